@@ -120,6 +120,7 @@ use jj_lib::store::Store;
 use jj_lib::str_util::StringExpression;
 use jj_lib::str_util::StringMatcher;
 use jj_lib::str_util::StringPattern;
+use jj_lib::time_util::DatePatternContext;
 use jj_lib::transaction::Transaction;
 use jj_lib::working_copy;
 use jj_lib::working_copy::CheckoutStats;
@@ -353,6 +354,11 @@ impl CommandHelper {
         &self.data.settings
     }
 
+    /// Context to parse date patterns in revsets and operation expressions.
+    pub fn date_pattern_context(&self) -> DatePatternContext {
+        date_pattern_context(self.settings())
+    }
+
     /// Resolves configuration for new workspace located at the specified path.
     pub fn settings_for_new_workspace(
         &self,
@@ -640,7 +646,8 @@ impl CommandHelper {
         repo_loader: &RepoLoader,
     ) -> Result<Operation, CommandError> {
         if let Some(op_str) = &self.data.global_args.at_operation {
-            Ok(op_walk::resolve_op_for_load(repo_loader, op_str)?)
+            let context = self.date_pattern_context();
+            Ok(op_walk::resolve_op_for_load(repo_loader, op_str, &context)?)
         } else {
             op_heads_store::resolve_op_heads(
                 repo_loader.op_heads_store().as_ref(),
@@ -650,11 +657,53 @@ impl CommandHelper {
                         ui.status(),
                         "Concurrent modification detected, resolving automatically.",
                     )?;
+                    if let Some(mut formatter) = ui.status_formatter() {
+                        for op in &op_heads {
+                            write!(formatter, "Merging operation ")?;
+                            write!(
+                                formatter.labeled("operation").labeled("id"),
+                                "{}",
+                                short_operation_hash(op.id())
+                            )?;
+                            writeln!(
+                                formatter,
+                                ": {}",
+                                op.metadata().description.lines().next().unwrap_or_default()
+                            )?;
+                        }
+                    }
                     let base_repo = repo_loader.load_at(&op_heads[0])?;
-                    // TODO: It may be helpful to print each operation we're merging here
                     let mut tx = start_repo_transaction(&base_repo, &self.data.string_args);
                     for other_op_head in op_heads.into_iter().skip(1) {
-                        tx.merge_operation(other_op_head)?;
+                        let conflicts = tx.merge_operation(other_op_head)?;
+                        if !conflicts.bookmarks.is_empty() {
+                            writeln!(
+                                ui.warning_default(),
+                                "Concurrent operations moved bookmarks to different commits: {}",
+                                conflicts
+                                    .bookmarks
+                                    .iter()
+                                    .map(|name| name.as_symbol())
+                                    .join(", ")
+                            )?;
+                        }
+                        if !conflicts.workspaces.is_empty() {
+                            writeln!(
+                                ui.warning_default(),
+                                "Concurrent operations changed the working-copy commit of \
+                                 workspaces: {}",
+                                conflicts
+                                    .workspaces
+                                    .iter()
+                                    .map(|name| name.as_symbol())
+                                    .join(", ")
+                            )?;
+                            writeln!(
+                                ui.hint_default(),
+                                "Kept the working-copy commit of the operation {}",
+                                short_operation_hash(tx.base_repo().op_id())
+                            )?;
+                        }
                         let num_rebased = tx.repo_mut().rebase_descendants()?;
                         if num_rebased > 0 {
                             writeln!(
@@ -833,18 +882,11 @@ impl WorkspaceCommandEnvironment {
             path_converter: &self.path_converter,
             workspace_name: &self.workspace_name,
         };
-        let now = if let Some(timestamp) = self.settings.commit_timestamp() {
-            chrono::Local
-                .timestamp_millis_opt(timestamp.timestamp.0)
-                .unwrap()
-        } else {
-            chrono::Local::now()
-        };
         RevsetParseContext {
             aliases_map: &self.revset_aliases_map,
             local_variables: HashMap::new(),
             user_email: self.settings.user_email(),
-            date_pattern_context: now.into(),
+            date_pattern_context: date_pattern_context(&self.settings),
             default_ignored_remote: self.default_ignored_remote,
             extensions: self.command.revset_extensions(),
             workspace: Some(workspace_context),
@@ -937,6 +979,7 @@ impl WorkspaceCommandEnvironment {
             repo,
             self.command.revset_extensions().clone(),
             &id_prefix_context,
+            date_pattern_context(&self.settings),
             immutable_expression,
         )
         .resolve()
@@ -1549,7 +1592,8 @@ to the current parents may contain changes from multiple commits.
     }
 
     pub fn resolve_single_op(&self, op_str: &str) -> Result<Operation, OpsetEvaluationError> {
-        op_walk::resolve_op_with_repo(self.repo(), op_str)
+        let context = date_pattern_context(self.settings());
+        op_walk::resolve_op_with_repo(self.repo(), op_str, &context)
     }
 
     /// Resolve a revset to a single revision. Return an error if the revset is
@@ -1655,6 +1699,7 @@ to the current parents may contain changes from multiple commits.
             self.repo().as_ref(),
             self.env.command.revset_extensions().clone(),
             self.id_prefix_context(),
+            date_pattern_context(self.settings()),
             expression,
         )
     }
@@ -1817,6 +1862,7 @@ to the current parents may contain changes from multiple commits.
                 repo,
                 self.env.command.revset_extensions().clone(),
                 &id_prefix_context,
+                date_pattern_context(self.settings()),
                 self.env.immutable_expression(),
             )
             .resolve()?
@@ -2027,7 +2073,10 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
     }
 
     pub fn start_transaction(&mut self) -> WorkspaceCommandTransaction<'_> {
-        let tx = start_repo_transaction(self.repo(), self.env.command.string_args());
+        let mut tx = start_repo_transaction(self.repo(), self.env.command.string_args());
+        for (key, value) in &self.env.command.global_args().op_tag {
+            tx.set_tag(key.clone(), value.clone());
+        }
         let id_prefix_context = mem::take(&mut self.user_repo.id_prefix_context);
         WorkspaceCommandTransaction {
             helper: self,
@@ -2873,6 +2922,19 @@ pub fn update_working_copy(
     Ok(stats)
 }
 
+/// Creates a context to parse date patterns relative to the current time, or
+/// to the `debug.commit-timestamp` if configured.
+pub fn date_pattern_context(settings: &UserSettings) -> DatePatternContext {
+    if let Some(timestamp) = settings.commit_timestamp() {
+        chrono::Local
+            .timestamp_millis_opt(timestamp.timestamp.0)
+            .unwrap()
+            .into()
+    } else {
+        chrono::Local::now().into()
+    }
+}
+
 /// Returns the special remote name that should be ignored by default.
 pub fn default_ignored_remote_name(store: &Store) -> Option<&'static RemoteName> {
     #[cfg(feature = "git")]
@@ -3244,7 +3306,9 @@ pub struct GlobalArgs {
     /// that divergent operations will never be merged.
     ///
     /// Use `jj op log` to find the operation ID you want. Any unambiguous
-    /// prefix of the operation ID is enough.
+    /// prefix of the operation ID is enough. You can also use
+    /// `--at-op='at:"<date>"'` to load the repo at the latest operation at or
+    /// before the given date.
     ///
     /// When loading the repo at an earlier operation, the working copy will be
     /// ignored, as if `--ignore-working-copy` had been specified.
@@ -3260,6 +3324,17 @@ pub struct GlobalArgs {
         add = ArgValueCandidates::new(complete::operations),
     )]
     pub at_operation: Option<String>,
+    /// Add a tag to the operation created by this command (can be repeated)
+    ///
+    /// Tags are shown in `jj op log`, and operations can be selected by their
+    /// tags with the `tags(KEY=VALUE)` operation set function.
+    #[arg(
+        long,
+        value_name = "KEY=VALUE",
+        global = true,
+        value_parser = parse_op_tag_arg,
+    )]
+    pub op_tag: Vec<(String, String)>,
     /// Enable debug logging
     #[arg(long, global = true)]
     pub debug: bool,
@@ -3357,6 +3432,17 @@ impl ValueParserFactory for RevisionArg {
     }
 }
 
+/// Parses `KEY=VALUE` argument of operation tag.
+pub fn parse_op_tag_arg(s: &str) -> Result<(String, String), String> {
+    let Some((key, value)) = s.split_once('=') else {
+        return Err("expected KEY=VALUE".to_owned());
+    };
+    if key.is_empty() {
+        return Err("tag name must not be empty".to_owned());
+    }
+    Ok((key.to_owned(), value.to_owned()))
+}
+
 /// Merges multiple clap args in order of appearance.
 ///
 /// The `id_values` is a list of `(id, values)` pairs, where `id` is the name of
@@ -36,11 +36,14 @@ use jj_lib::fileset::FilesetParseErrorKind;
 use jj_lib::fix::FixError;
 use jj_lib::gitignore::GitIgnoreError;
 use jj_lib::index::IndexError;
+use jj_lib::op_bundle::OpBundleError;
 use jj_lib::op_heads_store::OpHeadResolutionError;
 use jj_lib::op_heads_store::OpHeadsStoreError;
 use jj_lib::op_store::OpStoreError;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
+use jj_lib::opset::OpsetParseError;
+use jj_lib::opset::OpsetParseErrorKind;
 use jj_lib::repo::CheckOutCommitError;
 use jj_lib::repo::EditCommitError;
 use jj_lib::repo::RepoLoaderError;
@@ -335,6 +338,20 @@ impl From<IndexError> for CommandError {
     }
 }
 
+impl From<OpBundleError> for CommandError {
+    fn from(err: OpBundleError) -> Self {
+        match err {
+            OpBundleError::InvalidHeader
+            | OpBundleError::Decode(_)
+            | OpBundleError::MissingCommit(_)
+            | OpBundleError::IdMismatch { .. } => user_error(err),
+            OpBundleError::Backend(err) => err.into(),
+            OpBundleError::OpStore(err) => err.into(),
+            OpBundleError::RevsetEvaluation(err) => err.into(),
+        }
+    }
+}
+
 impl From<OpHeadsStoreError> for CommandError {
     fn from(err: OpHeadsStoreError) -> Self {
         internal_error_with_message("Unexpected error from operation heads store", err)
@@ -382,6 +399,7 @@ impl From<OpHeadResolutionError> for CommandError {
 impl From<OpsetEvaluationError> for CommandError {
     fn from(err: OpsetEvaluationError) -> Self {
         match err {
+            OpsetEvaluationError::OpsetParse(err) => err.into(),
             OpsetEvaluationError::OpsetResolution(err) => {
                 let hint = opset_resolution_error_hint(&err);
                 let mut cmd_err = user_error(err);
@@ -461,9 +479,11 @@ impl From<ConflictResolveError> for CommandError {
             ConflictResolveError::Io(err) => err.into(),
             _ => {
                 let hint = match &err {
-                    ConflictResolveError::ConflictTooComplicated { .. } => {
-                        Some("Edit the conflict markers manually to resolve this.".to_owned())
-                    }
+                    ConflictResolveError::ConflictTooComplicated { .. } => Some(
+                        "Edit the conflict markers manually or use the built-in merge editor \
+                             (`--tool=:builtin`) to resolve this."
+                            .to_owned(),
+                    ),
                     ConflictResolveError::ExecutableConflict { .. } => {
                         Some("Use `jj file chmod` to update the executable bit.".to_owned())
                     }
@@ -618,6 +638,7 @@ jj currently does not support partial clones. To use jj with this repository, tr
                 ),
                 GitPushError::Subprocess(_) => user_error(err),
                 GitPushError::UnexpectedBackend(_) => user_error(err),
+                GitPushError::ObsolescenceMarkers(_) => user_error(err),
             }
         }
     }
@@ -657,6 +678,16 @@ impl From<FilesetParseError> for CommandError {
     }
 }
 
+impl From<OpsetParseError> for CommandError {
+    fn from(err: OpsetParseError) -> Self {
+        let hint = opset_parse_error_hint(&err);
+        let mut cmd_err =
+            user_error_with_message(format!("Failed to parse opset: {}", err.kind()), err);
+        cmd_err.extend_hints(hint);
+        cmd_err
+    }
+}
+
 impl From<RecoverWorkspaceError> for CommandError {
     fn from(err: RecoverWorkspaceError) -> Self {
         match err {
@@ -855,6 +886,22 @@ fn fileset_parse_error_hint(err: &FilesetParseError) -> Option<String> {
     }
 }
 
+fn opset_parse_error_hint(err: &OpsetParseError) -> Option<String> {
+    match err.kind() {
+        OpsetParseErrorKind::SyntaxError => Some(String::from(
+            "See https://jj-vcs.github.io/jj/latest/operation-log/#operation-sets for opsets \
+             syntax.",
+        )),
+        OpsetParseErrorKind::NoSuchFunction {
+            name: _,
+            candidates,
+        } => format_similarity_hint(candidates),
+        OpsetParseErrorKind::InvalidArguments { .. } | OpsetParseErrorKind::Expression(_) => {
+            find_source_parse_error_hint(&err)
+        }
+    }
+}
+
 fn opset_resolution_error_hint(err: &OpsetResolutionError) -> Option<String> {
     match err {
         OpsetResolutionError::MultipleOperations {
@@ -25,6 +25,7 @@ use crate::cli_util::RevisionArg;
 use crate::cli_util::print_updated_commits;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::diff_util::DiffAlgorithmArg;
 use crate::diff_util::DiffFormat;
 use crate::ui::Ui;
 
@@ -66,6 +67,11 @@ pub(crate) struct AbsorbArgs {
         add = ArgValueCompleter::new(complete::modified_from_files),
     )]
     paths: Vec<String>,
+    /// Algorithm to find unchanged lines
+    ///
+    /// Defaults to the `diff.algorithm` config.
+    #[arg(long, value_enum)]
+    diff_algorithm: Option<DiffAlgorithmArg>,
 }
 
 #[instrument(skip_all)]
@@ -87,7 +93,12 @@ pub(crate) fn cmd_absorb(
 
     let repo = workspace_command.repo().as_ref();
     let source = AbsorbSource::from_commit(repo, source_commit)?;
-    let selected_trees = split_hunks_to_trees(repo, &source, &destinations, &matcher).block_on()?;
+    let diff_algorithm = match args.diff_algorithm {
+        Some(algorithm) => algorithm.into(),
+        None => workspace_command.settings().get("diff.algorithm")?,
+    };
+    let selected_trees =
+        split_hunks_to_trees(repo, &source, &destinations, &matcher, diff_algorithm).block_on()?;
 
     let path_converter = workspace_command.path_converter();
     for (path, reason) in selected_trees.skipped_paths {
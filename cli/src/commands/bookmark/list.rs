@@ -36,6 +36,8 @@ use crate::cli_util::default_ignored_remote_name;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitRef;
 use crate::complete;
+use crate::output_format::OutputFormatArgs;
+use crate::output_format::SerializedItemWriter;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -127,6 +129,8 @@ pub struct BookmarkListArgs {
     /// This defaults to the `ui.bookmark-list-sort-keys` setting.
     #[arg(long, value_name = "SORT_KEY", value_enum, value_delimiter = ',')]
     sort: Vec<SortKey>,
+    #[command(flatten)]
+    output_format: OutputFormatArgs,
 }
 
 pub fn cmd_bookmark_list(
@@ -134,6 +138,8 @@ pub fn cmd_bookmark_list(
     command: &CommandHelper,
     args: &BookmarkListArgs,
 ) -> Result<(), CommandError> {
+    let output_format = args.output_format.format();
+    output_format.check_text_option("--template", args.template.is_some())?;
     let workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo();
     let view = repo.view();
@@ -267,10 +273,16 @@ pub fn cmd_bookmark_list(
 
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
-    bookmark_list_items
+    let mut commit_refs = bookmark_list_items
         .iter()
-        .flat_map(|item| itertools::chain([&item.primary], &item.tracked))
-        .try_for_each(|commit_ref| template.format(commit_ref, formatter.as_mut()))?;
+        .flat_map(|item| itertools::chain([&item.primary], &item.tracked));
+    if output_format.is_serialized() {
+        let mut writer = SerializedItemWriter::new(formatter.as_mut(), output_format)?;
+        commit_refs.try_for_each(|commit_ref| writer.write_item(commit_ref.as_ref()))?;
+        writer.finish()?;
+    } else {
+        commit_refs.try_for_each(|commit_ref| template.format(commit_ref, formatter.as_mut()))?;
+    }
     drop(formatter);
 
     #[cfg(feature = "git")]
@@ -67,6 +67,7 @@ pub fn cmd_debug_revset(
         repo,
         command.revset_extensions().symbol_resolvers(),
         workspace_command.id_prefix_context(),
+        command.date_pattern_context(),
     );
     let mut expression = expression.resolve_user_expression(repo, &symbol_resolver)?;
     writeln!(ui.stdout(), "-- Resolved:")?;
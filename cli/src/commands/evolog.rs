@@ -34,6 +34,8 @@ use crate::complete;
 use crate::diff_util::DiffFormatArgs;
 use crate::graphlog::GraphStyle;
 use crate::graphlog::get_graphlog;
+use crate::output_format::OutputFormatArgs;
+use crate::output_format::SerializedItemWriter;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -88,6 +90,8 @@ pub(crate) struct EvologArgs {
     patch: bool,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
+    #[command(flatten)]
+    output_format: OutputFormatArgs,
 }
 
 #[instrument(skip_all)]
@@ -103,7 +107,10 @@ pub(crate) fn cmd_evolog(
         .evaluate_to_commit_ids()?
         .try_collect()?;
 
+    let output_format = args.output_format.format();
+    output_format.check_text_option("--template", args.template.is_some())?;
     let diff_renderer = workspace_command.diff_renderer_for_log(&args.diff_format, args.patch)?;
+    output_format.check_text_option("--patch", diff_renderer.is_some())?;
     let graph_style = GraphStyle::from_settings(workspace_command.settings())?;
     let with_content_format = LogContentFormat::new(ui, workspace_command.settings())?;
 
@@ -136,7 +143,7 @@ pub(crate) fn cmd_evolog(
 
     let repo = workspace_command.repo();
     let evolution_entries = walk_predecessors(repo, &start_commit_ids);
-    if !args.no_graph {
+    if !args.no_graph && !output_format.is_serialized() {
         let mut raw_output = formatter.raw()?;
         let mut graph = get_graphlog(graph_style, raw_output.as_mut());
 
@@ -208,22 +215,31 @@ pub(crate) fn cmd_evolog(
             Box::new(evolution_entries)
         };
 
-        for entry in evolution_entries {
-            let entry = entry?;
-            with_content_format.write(formatter, |formatter| template.format(&entry, formatter))?;
-            if let Some(renderer) = &diff_renderer {
-                let predecessors: Vec<_> = entry.predecessors().try_collect()?;
-                let width = ui.term_width();
-                renderer
-                    .show_inter_diff(
-                        ui,
-                        formatter,
-                        &predecessors,
-                        &entry.commit,
-                        &EverythingMatcher,
-                        width,
-                    )
-                    .block_on()?;
+        if output_format.is_serialized() {
+            let mut writer = SerializedItemWriter::new(formatter, output_format)?;
+            for entry in evolution_entries {
+                writer.write_item(&entry?)?;
+            }
+            writer.finish()?;
+        } else {
+            for entry in evolution_entries {
+                let entry = entry?;
+                with_content_format
+                    .write(formatter, |formatter| template.format(&entry, formatter))?;
+                if let Some(renderer) = &diff_renderer {
+                    let predecessors: Vec<_> = entry.predecessors().try_collect()?;
+                    let width = ui.term_width();
+                    renderer
+                        .show_inter_diff(
+                            ui,
+                            formatter,
+                            &predecessors,
+                            &entry.commit,
+                            &EverythingMatcher,
+                            width,
+                        )
+                        .block_on()?;
+                }
             }
         }
     }
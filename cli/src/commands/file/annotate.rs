@@ -27,6 +27,7 @@ use crate::command_error::CommandError;
 use crate::command_error::user_error;
 use crate::commit_templater::AnnotationLine;
 use crate::complete;
+use crate::diff_util::DiffAlgorithmArg;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -66,6 +67,11 @@ pub(crate) struct FileAnnotateArgs {
     ///     https://jj-vcs.github.io/jj/latest/templates/
     #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::template_aliases))]
     template: Option<String>,
+    /// Algorithm to find unchanged lines
+    ///
+    /// Defaults to the `diff.algorithm` config.
+    #[arg(long, value_enum)]
+    diff_algorithm: Option<DiffAlgorithmArg>,
 }
 
 #[instrument(skip_all)]
@@ -104,6 +110,11 @@ pub(crate) fn cmd_file_annotate(
     // exclude the revisions, but will ignore diffs in those revisions as if
     // ancestor revisions had new content.
     let mut annotator = FileAnnotator::from_commit(&starting_commit, &file_path)?;
+    let diff_algorithm = match args.diff_algorithm {
+        Some(algorithm) => algorithm.into(),
+        None => workspace_command.settings().get("diff.algorithm")?,
+    };
+    annotator.set_diff_algorithm(diff_algorithm);
     annotator.compute(repo.as_ref(), &RevsetExpression::all())?;
     let annotation = annotator.to_annotation();
 
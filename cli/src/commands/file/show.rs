@@ -21,6 +21,7 @@ use jj_lib::backend::BackendResult;
 use jj_lib::conflicts::ConflictMaterializeOptions;
 use jj_lib::conflicts::MaterializedTreeValue;
 use jj_lib::conflicts::materialize_merge_result;
+use jj_lib::conflicts::materialize_merge_result_to_bytes;
 use jj_lib::conflicts::materialize_tree_value;
 use jj_lib::file_util::copy_async_to_sync;
 use jj_lib::fileset::FilePattern;
@@ -38,6 +39,8 @@ use crate::command_error::CommandError;
 use crate::command_error::user_error;
 use crate::commit_templater::TreeEntry;
 use crate::complete;
+use crate::syntax_highlight;
+use crate::syntax_highlight::SyntaxHighlighter;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -159,6 +162,8 @@ fn write_tree_entries(
     for entry in entries {
         let entry = entry?;
         template.format(&entry, ui.stdout_formatter().as_mut())?;
+        let highlighter =
+            SyntaxHighlighter::for_formatter(ui.stdout_formatter().as_ref(), &entry.path);
         let materialized =
             materialize_tree_value(repo.store(), &entry.path, entry.value).block_on()?;
         match materialized {
@@ -170,8 +175,14 @@ fn write_tree_entries(
                     "Path '{ui_path}' exists but access is denied: {err}"
                 )?;
             }
-            MaterializedTreeValue::File(file) => {
-                copy_async_to_sync(file.reader, ui.stdout_formatter().as_mut()).block_on()?;
+            MaterializedTreeValue::File(mut file) => {
+                if let Some(highlighter) = &highlighter {
+                    let content = file.read_all(&entry.path).block_on()?;
+                    let mut formatter = ui.stdout_formatter();
+                    syntax_highlight::write_highlighted(formatter.as_mut(), highlighter, &content)?;
+                } else {
+                    copy_async_to_sync(file.reader, ui.stdout_formatter().as_mut()).block_on()?;
+                }
             }
             MaterializedTreeValue::FileConflict(file) => {
                 let options = ConflictMaterializeOptions {
@@ -179,7 +190,13 @@ fn write_tree_entries(
                     marker_len: None,
                     merge: repo.store().merge_options().clone(),
                 };
-                materialize_merge_result(&file.contents, &mut ui.stdout_formatter(), &options)?;
+                if let Some(highlighter) = &highlighter {
+                    let content = materialize_merge_result_to_bytes(&file.contents, &options);
+                    let mut formatter = ui.stdout_formatter();
+                    syntax_highlight::write_highlighted(formatter.as_mut(), highlighter, &content)?;
+                } else {
+                    materialize_merge_result(&file.contents, &mut ui.stdout_formatter(), &options)?;
+                }
             }
             MaterializedTreeValue::OtherConflict { id } => {
                 ui.stdout_formatter().write_all(id.describe().as_bytes())?;
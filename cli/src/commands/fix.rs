@@ -29,6 +29,7 @@ use jj_lib::fix::FixError;
 use jj_lib::fix::ParallelFileFixer;
 use jj_lib::fix::fix_files;
 use jj_lib::matchers::Matcher;
+use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::settings::UserSettings;
 use jj_lib::store::Store;
@@ -213,13 +214,69 @@ async fn fix_one_file(
             .read_file(&file_to_fix.repo_path, &file_to_fix.file_id)
             .await?;
         read.read_to_end(&mut old_content).await?;
-        let new_content = matching_tools.fold(old_content.clone(), |prev_content, tool_config| {
+        let new_content = run_tools(
+            ui,
+            workspace_root,
+            path_converter,
+            matching_tools,
+            &file_to_fix.repo_path,
+            old_content.clone(),
+        );
+        if new_content != old_content {
+            // TODO: send futures back over channel
+            let new_file_id = store
+                .write_file(&file_to_fix.repo_path, &mut new_content.as_slice())
+                .await?;
+            return Ok(Some(new_file_id));
+        }
+    }
+    Ok(None)
+}
+
+/// Invokes all matching tools to fix the given file content. Returns the
+/// content unchanged if no tools match.
+///
+/// The matching tools are invoked in order, with the result of one tool feeding
+/// into the next tool. If a tool invocation fails, the tool is skipped.
+pub(crate) fn fix_file_content(
+    ui: &Ui,
+    workspace_root: &Path,
+    path_converter: &RepoPathUiConverter,
+    tools_config: &ToolsConfig,
+    repo_path: &RepoPath,
+    content: Vec<u8>,
+) -> Vec<u8> {
+    let matching_tools = tools_config
+        .tools
+        .iter()
+        .filter(|tool_config| tool_config.matcher.matches(repo_path));
+    run_tools(
+        ui,
+        workspace_root,
+        path_converter,
+        matching_tools,
+        repo_path,
+        content,
+    )
+}
+
+fn run_tools<'a>(
+    ui: &Ui,
+    workspace_root: &Path,
+    path_converter: &RepoPathUiConverter,
+    tools: impl IntoIterator<Item = &'a ToolConfig>,
+    repo_path: &RepoPath,
+    content: Vec<u8>,
+) -> Vec<u8> {
+    tools
+        .into_iter()
+        .fold(content, |prev_content, tool_config| {
             match run_tool(
                 ui,
                 workspace_root,
                 path_converter,
                 &tool_config.command,
-                file_to_fix,
+                repo_path,
                 &prev_content,
             ) {
                 Ok(next_content) => next_content,
@@ -228,21 +285,12 @@ async fn fix_one_file(
                 // name, etc.
                 Err(_) => prev_content,
             }
-        });
-        if new_content != old_content {
-            // TODO: send futures back over channel
-            let new_file_id = store
-                .write_file(&file_to_fix.repo_path, &mut new_content.as_slice())
-                .await?;
-            return Ok(Some(new_file_id));
-        }
-    }
-    Ok(None)
+        })
 }
 
 /// Runs the `tool_command` to fix the given file content.
 ///
-/// The `old_content` is assumed to be that of the `file_to_fix`'s `FileId`, but
+/// The `old_content` is assumed to be that of the file at `repo_path`, but
 /// this is not verified.
 ///
 /// Returns the new file content, whose value will be the same as `old_content`
@@ -253,11 +301,11 @@ fn run_tool(
     workspace_root: &Path,
     path_converter: &RepoPathUiConverter,
     tool_command: &CommandNameAndArgs,
-    file_to_fix: &FileToFix,
+    repo_path: &RepoPath,
     old_content: &[u8],
 ) -> Result<Vec<u8>, ()> {
     let mut vars: HashMap<&str, &str> = HashMap::new();
-    vars.insert("path", file_to_fix.repo_path.as_internal_file_string());
+    vars.insert("path", repo_path.as_internal_file_string());
     // TODO: workspace_root.to_str() returns None if the workspace path is not
     // UTF-8, but we ignore that failure so `jj fix` still runs in that
     // situation. Maybe we should do something like substituting bytes instead
@@ -266,7 +314,7 @@ fn run_tool(
         vars.insert("root", root);
     }
     let mut command = tool_command.to_command_with_variables(&vars);
-    tracing::debug!(?command, ?file_to_fix.repo_path, "spawning fix tool");
+    tracing::debug!(?command, ?repo_path, "spawning fix tool");
     let mut child = command
         .current_dir(workspace_root)
         .stdin(Stdio::piped())
@@ -285,12 +333,7 @@ fn run_tool(
     tracing::debug!(?command, ?output.status, "fix tool exited:");
     if !output.stderr.is_empty() {
         let mut stderr = ui.stderr();
-        writeln!(
-            stderr,
-            "{}:",
-            path_converter.format_file_path(&file_to_fix.repo_path)
-        )
-        .ok();
+        writeln!(stderr, "{}:", path_converter.format_file_path(repo_path)).ok();
         stderr.write_all(&output.stderr).ok();
         writeln!(stderr).ok();
     }
@@ -314,7 +357,7 @@ struct ToolConfig {
 }
 
 /// Represents the `fix.tools` config table.
-struct ToolsConfig {
+pub(crate) struct ToolsConfig {
     /// Some tools, stored in the order they will be executed if more than one
     /// of them matches the same file.
     tools: Vec<ToolConfig>,
@@ -339,7 +382,10 @@ fn default_tool_enabled() -> bool {
 /// Fails if any of the commands or patterns are obviously unusable, but does
 /// not check for issues that might still occur later like missing executables.
 /// This is a place where we could fail earlier in some cases, though.
-fn get_tools_config(ui: &mut Ui, settings: &UserSettings) -> Result<ToolsConfig, CommandError> {
+pub(crate) fn get_tools_config(
+    ui: &mut Ui,
+    settings: &UserSettings,
+) -> Result<ToolsConfig, CommandError> {
     let mut tools: Vec<ToolConfig> = settings
         .table_keys("fix.tools")
         // Sort keys early so errors are deterministic.
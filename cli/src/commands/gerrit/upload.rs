@@ -378,7 +378,8 @@ pub fn cmd_gerrit_upload(
         .map_err(|err| match err {
             git::GitPushError::NoSuchRemote(_)
             | git::GitPushError::RemoteName(_)
-            | git::GitPushError::UnexpectedBackend(_) => user_error(err),
+            | git::GitPushError::UnexpectedBackend(_)
+            | git::GitPushError::ObsolescenceMarkers(_) => user_error(err),
             git::GitPushError::Subprocess(_) => {
                 user_error_with_message("Internal git error while pushing to gerrit", err)
             }
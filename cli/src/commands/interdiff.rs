@@ -15,12 +15,20 @@
 use std::slice;
 
 use clap::ArgGroup;
+use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::evolution::walk_predecessors;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo;
+use jj_lib::rewrite::rebase_to_dest_parent;
 use pollster::FutureExt as _;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
@@ -31,8 +39,18 @@ use crate::ui::Ui;
 /// This excludes changes from other commits by temporarily rebasing `--from`
 /// onto `--to`'s parents. If you wish to compare the same change across
 /// versions, consider `jj evolog -p` instead.
+///
+/// With `--since-op`, each of the `--revisions` is compared with its previous
+/// version that was visible at the given operation. This is useful to review
+/// what changed in a stack of revisions since the last time you looked at it.
+/// Revisions that were only rebased are omitted.
 #[derive(clap::Args, Clone, Debug)]
-#[command(group(ArgGroup::new("to_diff").args(&["from", "to"]).multiple(true).required(true)))]
+#[command(group(
+    ArgGroup::new("to_diff")
+        .args(&["from", "to", "since_op"])
+        .multiple(true)
+        .required(true)
+))]
 #[command(mut_arg("ignore_all_space", |a| a.short('w')))]
 #[command(mut_arg("ignore_space_change", |a| a.short('b')))]
 pub(crate) struct InterdiffArgs {
@@ -52,6 +70,23 @@ pub(crate) struct InterdiffArgs {
         add = ArgValueCompleter::new(complete::revset_expression_all),
     )]
     to: Option<RevisionArg>,
+    /// Show changes to the revisions since this operation
+    #[arg(
+        long,
+        value_name = "OPERATION",
+        conflicts_with_all = ["from", "to"],
+        add = ArgValueCandidates::new(complete::operations),
+    )]
+    since_op: Option<String>,
+    /// Revisions to compare with their versions at `--since-op` [default: @]
+    #[arg(
+        long,
+        short,
+        value_name = "REVSETS",
+        conflicts_with_all = ["from", "to"],
+        add = ArgValueCompleter::new(complete::revset_expression_all),
+    )]
+    revisions: Vec<RevisionArg>,
     /// Restrict the diff to these paths
     #[arg(
         value_name = "FILESETS",
@@ -70,6 +105,9 @@ pub(crate) fn cmd_interdiff(
     args: &InterdiffArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
+    if let Some(op_str) = &args.since_op {
+        return cmd_interdiff_since_op(ui, &workspace_command, args, op_str);
+    }
     let from =
         workspace_command.resolve_single_rev(ui, args.from.as_ref().unwrap_or(&RevisionArg::AT))?;
     let to =
@@ -91,3 +129,113 @@ pub(crate) fn cmd_interdiff(
         .block_on()?;
     Ok(())
 }
+
+fn cmd_interdiff_since_op(
+    ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    args: &InterdiffArgs,
+    op_str: &str,
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo();
+    let old_op = workspace_command.resolve_single_op(op_str)?;
+    let old_repo = repo.loader().load_at(&old_op)?;
+    let revision_args = if args.revisions.is_empty() {
+        slice::from_ref(&RevisionArg::AT)
+    } else {
+        &args.revisions
+    };
+    let commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, revision_args)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let matcher = workspace_command
+        .parse_file_patterns(ui, &args.paths)?
+        .to_matcher();
+    let diff_renderer = workspace_command.diff_renderer_for(&args.format)?;
+    let template = workspace_command.commit_summary_template();
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    let mut num_rebased = 0;
+    for commit in &commits {
+        let Some(old_commit) = find_predecessor_at(repo, &old_repo, commit)? else {
+            write!(formatter, "New revision ")?;
+            template.format(commit, formatter.as_mut())?;
+            writeln!(formatter)?;
+            diff_renderer
+                .show_patch(
+                    ui,
+                    formatter.as_mut(),
+                    commit,
+                    matcher.as_ref(),
+                    ui.term_width(),
+                )
+                .block_on()?;
+            continue;
+        };
+        if old_commit.id() == commit.id() {
+            continue;
+        }
+        if is_rebased_only(repo.as_ref(), &old_commit, commit)? {
+            num_rebased += 1;
+            continue;
+        }
+        write!(formatter, "Changes to ")?;
+        template.format(commit, formatter.as_mut())?;
+        writeln!(formatter)?;
+        diff_renderer
+            .show_inter_diff(
+                ui,
+                formatter.as_mut(),
+                slice::from_ref(&old_commit),
+                commit,
+                matcher.as_ref(),
+                ui.term_width(),
+            )
+            .block_on()?;
+    }
+    drop(formatter);
+    if num_rebased > 0 {
+        let (revisions, were) = if num_rebased == 1 {
+            ("revision", "was")
+        } else {
+            ("revisions", "were")
+        };
+        writeln!(
+            ui.status(),
+            "Omitted {num_rebased} {revisions} that {were} only rebased."
+        )?;
+    }
+    Ok(())
+}
+
+/// Walks the evolution history of `commit` to find the latest predecessor that
+/// was visible at the `old_repo` operation.
+fn find_predecessor_at(
+    repo: &ReadonlyRepo,
+    old_repo: &ReadonlyRepo,
+    commit: &Commit,
+) -> Result<Option<Commit>, CommandError> {
+    for entry in walk_predecessors(repo, slice::from_ref(commit.id())) {
+        let entry = entry?;
+        let visible_ids = old_repo.resolve_change_id(entry.commit.change_id())?;
+        if visible_ids.is_some_and(|ids| ids.contains(entry.commit.id())) {
+            return Ok(Some(entry.commit));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns true if `commit` has the same changes and description as
+/// `old_commit` after rebasing `old_commit` onto the parents of `commit`.
+fn is_rebased_only(
+    repo: &dyn Repo,
+    old_commit: &Commit,
+    commit: &Commit,
+) -> Result<bool, CommandError> {
+    if old_commit.description() != commit.description() {
+        return Ok(false);
+    }
+    let rebased_tree = rebase_to_dest_parent(repo, slice::from_ref(old_commit), commit)?;
+    Ok(rebased_tree.id() == *commit.tree_id())
+}
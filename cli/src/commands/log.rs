@@ -39,6 +39,8 @@ use crate::diff_util::DiffFormatArgs;
 use crate::formatter::FormatterExt as _;
 use crate::graphlog::GraphStyle;
 use crate::graphlog::get_graphlog;
+use crate::output_format::OutputFormatArgs;
+use crate::output_format::SerializedItemWriter;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -118,6 +120,8 @@ pub(crate) struct LogArgs {
     patch: bool,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
+    #[command(flatten)]
+    output_format: OutputFormatArgs,
 }
 
 #[instrument(skip_all)]
@@ -152,12 +156,16 @@ pub(crate) fn cmd_log(
     let prio_revset = settings.get_string("revsets.log-graph-prioritize")?;
     let prio_revset = workspace_command.parse_revset(ui, &RevisionArg::from(prio_revset))?;
 
+    let output_format = args.output_format.format();
+    output_format.check_text_option("--template", args.template.is_some())?;
+
     let repo = workspace_command.repo();
     let matcher = fileset_expression.to_matcher();
     let revset = revset_expression.evaluate()?;
 
     let store = repo.store();
     let diff_renderer = workspace_command.diff_renderer_for_log(&args.diff_format, args.patch)?;
+    output_format.check_text_option("--patch", diff_renderer.is_some())?;
     let graph_style = GraphStyle::from_settings(settings)?;
 
     let use_elided_nodes = settings.get_bool("ui.log-synthetic-elided-nodes")?;
@@ -184,7 +192,7 @@ pub(crate) fn cmd_log(
         let mut formatter = ui.stdout_formatter();
         let formatter = formatter.as_mut();
 
-        if !args.no_graph {
+        if !args.no_graph && !output_format.is_serialized() {
             let mut raw_output = formatter.raw()?;
             let mut graph = get_graphlog(graph_style, raw_output.as_mut());
             let iter: Box<dyn Iterator<Item = _>> = {
@@ -299,15 +307,23 @@ pub(crate) fn cmd_log(
                     Box::new(forward_iter)
                 }
             };
-            for commit_or_error in iter.commits(store) {
-                let commit = commit_or_error?;
-                with_content_format
-                    .write(formatter, |formatter| template.format(&commit, formatter))?;
-                if let Some(renderer) = &diff_renderer {
-                    let width = ui.term_width();
-                    renderer
-                        .show_patch(ui, formatter, &commit, matcher.as_ref(), width)
-                        .block_on()?;
+            if output_format.is_serialized() {
+                let mut writer = SerializedItemWriter::new(formatter, output_format)?;
+                for commit_or_error in iter.commits(store) {
+                    writer.write_item(&commit_or_error?)?;
+                }
+                writer.finish()?;
+            } else {
+                for commit_or_error in iter.commits(store) {
+                    let commit = commit_or_error?;
+                    with_content_format
+                        .write(formatter, |formatter| template.format(&commit, formatter))?;
+                    if let Some(renderer) = &diff_renderer {
+                        let width = ui.term_width();
+                        renderer
+                            .show_patch(ui, formatter, &commit, matcher.as_ref(), width)
+                            .block_on()?;
+                    }
                 }
             }
         }
@@ -45,6 +45,7 @@ mod prev;
 mod rebase;
 mod redo;
 mod resolve;
+mod resolve_divergence;
 mod restore;
 mod revert;
 mod root;
@@ -135,6 +136,7 @@ enum Command {
     Rebase(rebase::RebaseArgs),
     Redo(redo::RedoArgs),
     Resolve(resolve::ResolveArgs),
+    ResolveDivergence(resolve_divergence::ResolveDivergenceArgs),
     Restore(restore::RestoreArgs),
     Revert(revert::RevertArgs),
     Root(root::RootArgs),
@@ -201,6 +203,9 @@ pub fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<(), Co
         Command::Rebase(args) => rebase::cmd_rebase(ui, command_helper, args),
         Command::Redo(args) => redo::cmd_redo(ui, command_helper, args),
         Command::Resolve(args) => resolve::cmd_resolve(ui, command_helper, args),
+        Command::ResolveDivergence(args) => {
+            resolve_divergence::cmd_resolve_divergence(ui, command_helper, args)
+        }
         Command::Restore(args) => restore::cmd_restore(ui, command_helper, args),
         Command::Revert(args) => revert::cmd_revert(ui, command_helper, args),
         Command::Root(args) => root::cmd_root(ui, command_helper, args),
@@ -12,15 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::io::Write as _;
 use std::iter;
-use std::slice;
+use std::sync::Arc;
 
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
+use jj_lib::op_store::OpStore;
+use jj_lib::op_store::OperationId;
 use jj_lib::op_walk;
-use pollster::FutureExt as _;
+use jj_lib::operation::Operation;
+use jj_lib::opset;
+use jj_lib::time_util::DatePatternContext;
 
+use super::update_rewritten_op_heads;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::short_operation_hash;
 use crate::command_error::CommandError;
@@ -38,14 +44,20 @@ use crate::ui::Ui;
 /// To discard recent operations, use `jj op restore <operation ID>` followed
 /// by `jj op abandon <operation ID>..@-`.
 ///
+/// Any set of operations can be abandoned by using operation set expressions.
+/// For example, `jj op abandon 'snapshots() & ..@-'` will abandon all
+/// snapshot operations but the current one. The descendants of the abandoned
+/// operations will be reparented onto their nearest ancestors which aren't
+/// abandoned.
+///
 /// Previous versions of a change (or predecessors) are also discarded if they
 /// become unreachable from the operation history. The abandoned operations,
 /// commits, and other unreachable objects can later be garbage collected by
 /// using `jj util gc` command.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationAbandonArgs {
-    /// The operation or operation range to abandon
-    #[arg(add = ArgValueCandidates::new(complete::operations))]
+    /// The operation, operation range, or operation set to abandon
+    #[arg(value_name = "OPSET", add = ArgValueCandidates::new(complete::operations))]
     operation: String,
 }
 
@@ -66,56 +78,66 @@ pub fn cmd_op_abandon(
         return Err(cli_error("--at-op is not respected"));
     }
     let current_head_ops = op_walk::get_current_head_ops(op_store, op_heads_store.as_ref())?;
-    let resolve_op = |op_str| op_walk::resolve_op_at(op_store, &current_head_ops, op_str);
-    let (abandon_root_op, abandon_head_ops) =
-        if let Some((root_op_str, head_op_str)) = args.operation.split_once("..") {
-            let root_op = if root_op_str.is_empty() {
-                repo_loader.root_operation()
-            } else {
-                resolve_op(root_op_str)?
-            };
-            let head_ops = if head_op_str.is_empty() {
-                current_head_ops.clone()
+    let date_pattern_context = command.date_pattern_context();
+    let resolve_op =
+        |op_str| op_walk::resolve_op_at(op_store, &current_head_ops, op_str, &date_pattern_context);
+    let stats = if let Some(abandoned_ids) = resolve_opset_to_abandon(
+        op_store,
+        &current_head_ops,
+        &args.operation,
+        &date_pattern_context,
+    )? {
+        if let Some(op) = current_head_ops
+            .iter()
+            .find(|op| abandoned_ids.contains(op.id()))
+        {
+            return Err(current_op_error(op, &current_head_ops));
+        }
+        op_walk::abandon_operations(op_store.as_ref(), &abandoned_ids, &current_head_ops)?
+    } else {
+        let (abandon_root_op, abandon_head_ops) =
+            if let Some((root_op_str, head_op_str)) = args.operation.split_once("..") {
+                let root_op = if root_op_str.is_empty() {
+                    repo_loader.root_operation()
+                } else {
+                    resolve_op(root_op_str)?
+                };
+                let head_ops = if head_op_str.is_empty() {
+                    current_head_ops.clone()
+                } else {
+                    vec![resolve_op(head_op_str)?]
+                };
+                (root_op, head_ops)
             } else {
-                vec![resolve_op(head_op_str)?]
+                let op = resolve_op(&args.operation)?;
+                let parent_ops: Vec<_> = op.parents().try_collect()?;
+                let parent_op = match parent_ops.len() {
+                    0 => return Err(user_error("Cannot abandon the root operation")),
+                    1 => parent_ops.into_iter().next().unwrap(),
+                    _ => return Err(user_error("Cannot abandon a merge operation")),
+                };
+                (parent_op, vec![op])
             };
-            (root_op, head_ops)
-        } else {
-            let op = resolve_op(&args.operation)?;
-            let parent_ops: Vec<_> = op.parents().try_collect()?;
-            let parent_op = match parent_ops.len() {
-                0 => return Err(user_error("Cannot abandon the root operation")),
-                1 => parent_ops.into_iter().next().unwrap(),
-                _ => return Err(user_error("Cannot abandon a merge operation")),
-            };
-            (parent_op, vec![op])
-        };
 
-    if let Some(op) = abandon_head_ops
-        .iter()
-        .find(|op| current_head_ops.contains(op))
-    {
-        let mut err = user_error(format!(
-            "Cannot abandon the current operation {}",
-            short_operation_hash(op.id())
-        ));
-        if current_head_ops.len() == 1 {
-            err.add_hint("Run `jj undo` to revert the current operation, then use `jj op abandon`");
+        if let Some(op) = abandon_head_ops
+            .iter()
+            .find(|op| current_head_ops.contains(op))
+        {
+            return Err(current_op_error(op, &current_head_ops));
         }
-        return Err(err);
-    }
 
-    // Reparent descendants, count the number of abandoned operations.
-    let stats = op_walk::reparent_range(
-        op_store.as_ref(),
-        &abandon_head_ops,
-        &current_head_ops,
-        &abandon_root_op,
-    )?;
+        // Reparent descendants, count the number of abandoned operations.
+        op_walk::reparent_range(
+            op_store.as_ref(),
+            &abandon_head_ops,
+            &current_head_ops,
+            &abandon_root_op,
+        )?
+    };
     assert_eq!(
         current_head_ops.len(),
         stats.new_head_ids.len(),
-        "all current_head_ops should be reparented as they aren't included in abandon_head_ops"
+        "all current_head_ops should be reparented as they aren't abandoned"
     );
     let reparented_head_ops = || iter::zip(&current_head_ops, &stats.new_head_ids);
     if reparented_head_ops().all(|(old, new_id)| old.id() == new_id) {
@@ -128,29 +150,53 @@ pub fn cmd_op_abandon(
         stats.unreachable_count,
         stats.rewritten_count,
     )?;
-    for (old, new_id) in reparented_head_ops().filter(|&(old, new_id)| old.id() != new_id) {
-        op_heads_store
-            .update_op_heads(slice::from_ref(old.id()), new_id)
-            .block_on()?;
-    }
-    // Remap the operation id of the current workspace. If there were any
-    // divergent operations, user will need to re-abandon their ancestors.
-    if !command.global_args().ignore_working_copy {
-        let mut locked_ws = workspace.start_working_copy_mutation()?;
-        let old_op_id = locked_ws.locked_wc().old_operation_id();
-        if let Some((_, new_id)) = reparented_head_ops().find(|(old, _)| old.id() == old_op_id) {
-            locked_ws.finish(new_id.clone())?;
-        } else {
-            writeln!(
-                ui.warning_default(),
-                "The working copy operation {} is not updated because it differs from the repo {}.",
-                short_operation_hash(old_op_id),
-                current_head_ops
-                    .iter()
-                    .map(|op| short_operation_hash(op.id()))
-                    .join(", "),
-            )?;
+    update_rewritten_op_heads(
+        ui,
+        command,
+        &mut workspace,
+        &current_head_ops,
+        &stats.new_head_ids,
+    )?;
+    Ok(())
+}
+
+/// Resolves the operations to abandon if the `op_str` isn't a single operation
+/// or a range of operations, which can be abandoned more efficiently.
+fn resolve_opset_to_abandon(
+    op_store: &Arc<dyn OpStore>,
+    current_head_ops: &[Operation],
+    op_str: &str,
+    date_pattern_context: &DatePatternContext,
+) -> Result<Option<HashSet<OperationId>>, CommandError> {
+    let is_simple = match op_str.split_once("..") {
+        Some((root_op_str, head_op_str)) => {
+            op_walk::is_simple_op_expression(root_op_str)
+                && op_walk::is_simple_op_expression(head_op_str)
         }
+        None => op_walk::is_simple_op_expression(op_str),
+    };
+    if is_simple {
+        return Ok(None);
     }
-    Ok(())
+    let expression = opset::parse(op_str, date_pattern_context)?;
+    let opset = expression.resolve(op_store, current_head_ops)?;
+    // The root operation can't be abandoned, but it's included in ranges like
+    // "..x".
+    let abandoned_ids = opset
+        .evaluate()
+        .filter_ok(|op| op.id() != op_store.root_operation_id())
+        .map_ok(|op| op.id().clone())
+        .try_collect()?;
+    Ok(Some(abandoned_ids))
+}
+
+fn current_op_error(op: &Operation, current_head_ops: &[Operation]) -> CommandError {
+    let mut err = user_error(format!(
+        "Cannot abandon the current operation {}",
+        short_operation_hash(op.id())
+    ));
+    if current_head_ops.len() == 1 {
+        err.add_hint("Run `jj undo` to revert the current operation, then use `jj op abandon`");
+    }
+    err
 }
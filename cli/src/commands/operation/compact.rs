@@ -0,0 +1,103 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use jj_lib::backend::MillisSinceEpoch;
+use jj_lib::op_walk;
+
+use super::update_rewritten_op_heads;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::cli_error;
+use crate::command_error::user_error_with_message;
+use crate::ui::Ui;
+
+/// Fold runs of consecutive snapshot operations
+///
+/// Commands run by editors and shell prompts may fill the operation log with
+/// snapshot operations. This command folds each run of consecutive snapshot
+/// operations into a single operation, which has the repo state of the last
+/// snapshot in the run. The descendant operations are reparented onto the
+/// folded operations.
+///
+/// The previous versions of the working-copy commit recorded by the folded
+/// operations are preserved, so they are still shown by `jj evolog`.
+///
+/// Set `gc.compact-snapshots = true` to also compact snapshot operations older
+/// than the `jj util gc` threshold when running `jj util gc`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationCompactArgs {
+    /// Only fold snapshot operations older than the given date
+    ///
+    /// The date can be specified in the same forms as date patterns in
+    /// revsets, such as `2024-02-01 12:00:00` or `2 weeks ago`. By default,
+    /// all snapshot operations are folded.
+    #[arg(long, value_name = "DATE")]
+    older_than: Option<String>,
+}
+
+pub fn cmd_op_compact(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationCompactArgs,
+) -> Result<(), CommandError> {
+    let cutoff = if let Some(date) = &args.older_than {
+        command
+            .date_pattern_context()
+            .parse_instant(date)
+            .map_err(|err| user_error_with_message(format!("Invalid date `{date}`"), err))?
+    } else {
+        MillisSinceEpoch(i64::MAX)
+    };
+    let folded_count = compact_snapshot_operations(ui, command, cutoff)?;
+    if folded_count == 0 {
+        writeln!(ui.status(), "Nothing changed.")?;
+    } else {
+        writeln!(ui.status(), "Folded {folded_count} snapshot operations.")?;
+    }
+    Ok(())
+}
+
+/// Folds runs of snapshot operations which ended before the `cutoff`, and
+/// returns the number of folded operations.
+pub(crate) fn compact_snapshot_operations(
+    ui: &Ui,
+    command: &CommandHelper,
+    cutoff: MillisSinceEpoch,
+) -> Result<usize, CommandError> {
+    // Don't load the repo so that the operations can be rewritten without
+    // snapshotting the working copy.
+    let mut workspace = command.load_workspace()?;
+    // It doesn't make sense to create divergent operations that will be merged
+    // with the current head.
+    if command.global_args().at_operation.is_some() {
+        return Err(cli_error("--at-op is not respected"));
+    }
+    let repo_loader = workspace.repo_loader();
+    let op_store = repo_loader.op_store().clone();
+    let current_head_ops =
+        op_walk::get_current_head_ops(&op_store, repo_loader.op_heads_store().as_ref())?;
+    let stats = op_walk::compact_snapshots(op_store.as_ref(), &current_head_ops, cutoff)?;
+    if stats.unreachable_count > 0 {
+        update_rewritten_op_heads(
+            ui,
+            command,
+            &mut workspace,
+            &current_head_ops,
+            &stats.new_head_ids,
+        )?;
+    }
+    Ok(stats.unreachable_count)
+}
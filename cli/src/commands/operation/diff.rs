@@ -14,6 +14,7 @@
 
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::fmt;
 use std::slice;
 use std::sync::Arc;
 
@@ -33,11 +34,14 @@ use jj_lib::refs::diff_named_ref_targets;
 use jj_lib::refs::diff_named_remote_refs;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
+use jj_lib::revset::ResolvedRevsetExpression;
 use jj_lib::revset::RevsetExpression;
+use jj_lib::str_util::StringPattern;
 use pollster::FutureExt as _;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
+use crate::cli_util::RevisionArg;
 use crate::cli_util::default_ignored_remote_name;
 use crate::command_error::CommandError;
 use crate::complete;
@@ -85,10 +89,61 @@ pub struct OperationDiffArgs {
     /// contaminated by unrelated changes.
     #[arg(long, short = 'p')]
     patch: bool,
+    /// Only show changes to commits in the given revisions
+    ///
+    /// Abandoned commits are matched by their old versions. Filters like
+    /// `description()` match abandoned commits as well, but abandoned commits
+    /// aren't included in the ancestors of visible commits.
+    #[arg(long, short, value_name = "REVSETS")]
+    revisions: Option<Vec<RevisionArg>>,
+    /// Only show changes to bookmarks and tags matching the given name
+    /// patterns
+    ///
+    /// By default, the specified pattern matches names exactly. Use `glob:`
+    /// prefix to select refs by [wildcard pattern].
+    ///
+    /// [wildcard pattern]:
+    ///     https://jj-vcs.github.io/jj/latest/revsets/#string-patterns
+    #[arg(long, value_name = "PATTERN", value_parser = StringPattern::parse)]
+    ref_pattern: Option<Vec<StringPattern>>,
+    /// Show the numbers of new, rewritten, and abandoned commits instead of
+    /// listing them
+    ///
+    /// The numbers are also shown for each changed bookmark, counting the
+    /// commits between its old and new targets.
+    #[arg(long, conflicts_with_all = ["no_graph", "patch", "DiffFormatArgs", "template"])]
+    commit_stat: bool,
+    /// Render each changed commit using the given template
+    ///
+    /// The template is used instead of `templates.commit_summary`. All
+    /// 0-argument methods of the [`Commit` type] are available as keywords in
+    /// the template expression.
+    ///
+    /// [`Commit` type]:
+    ///     https://jj-vcs.github.io/jj/latest/templates/#commit-type
+    #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::template_aliases))]
+    template: Option<String>,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
 }
 
+/// Filters the changes shown by [`show_op_diff()`].
+#[derive(Clone, Debug, Default)]
+pub struct OpDiffFilter {
+    /// Only show changes to commits in this revset.
+    pub revset: Option<Arc<ResolvedRevsetExpression>>,
+    /// Only show changes to refs matching any of these patterns.
+    pub ref_patterns: Option<Vec<StringPattern>>,
+}
+
+impl OpDiffFilter {
+    fn matches_ref(&self, name: &str) -> bool {
+        self.ref_patterns
+            .as_ref()
+            .is_none_or(|patterns| patterns.iter().any(|pattern| pattern.is_match(name)))
+    }
+}
+
 pub fn cmd_op_diff(
     ui: &mut Ui,
     command: &CommandHelper,
@@ -131,12 +186,28 @@ pub fn cmd_op_diff(
     let id_prefix_context = workspace_env.new_id_prefix_context();
     let commit_summary_template = {
         let language = workspace_env.commit_template_language(merged_repo, &id_prefix_context);
-        let text = settings.get_string("templates.commit_summary")?;
+        let text = match &args.template {
+            Some(value) => value.to_owned(),
+            None => settings.get_string("templates.commit_summary")?,
+        };
         workspace_env
             .parse_template(ui, &language, &text)?
             .labeled(["op_diff", "commit"])
     };
 
+    let revset = match &args.revisions {
+        Some(revisions) => Some(
+            workspace_command
+                .parse_union_revsets(ui, revisions)?
+                .resolve()?,
+        ),
+        None => None,
+    };
+    let filter = OpDiffFilter {
+        revset,
+        ref_patterns: args.ref_pattern.clone(),
+    };
+
     let op_summary_template = workspace_command
         .operation_summary_template()
         .labeled(["op_diff"]);
@@ -152,6 +223,15 @@ pub fn cmd_op_diff(
     op_summary_template.format(&to_op, &mut *formatter)?;
     writeln!(formatter)?;
 
+    if args.commit_stat {
+        return show_op_diff_stat(
+            formatter.as_mut(),
+            merged_repo,
+            &from_repo,
+            &to_repo,
+            &filter,
+        );
+    }
     show_op_diff(
         ui,
         formatter.as_mut(),
@@ -162,6 +242,7 @@ pub fn cmd_op_diff(
         (!args.no_graph).then_some(graph_style),
         &with_content_format,
         diff_renderer.as_ref(),
+        &filter,
     )
 }
 
@@ -180,8 +261,9 @@ pub fn show_op_diff(
     graph_style: Option<GraphStyle>,
     with_content_format: &LogContentFormat,
     diff_renderer: Option<&DiffRenderer>,
+    filter: &OpDiffFilter,
 ) -> Result<(), CommandError> {
-    let changes = compute_operation_commits_diff(current_repo, from_repo, to_repo)?;
+    let changes = compute_filtered_commits_diff(current_repo, from_repo, to_repo, filter)?;
     if !changes.is_empty() {
         let revset =
             RevsetExpression::commits(changes.keys().cloned().collect()).evaluate(current_repo)?;
@@ -290,6 +372,7 @@ pub fn show_op_diff(
         from_repo.view().local_bookmarks(),
         to_repo.view().local_bookmarks(),
     )
+    .filter(|(name, _)| filter.matches_ref(name.as_str()))
     .collect_vec();
     if !changed_local_bookmarks.is_empty() {
         writeln!(formatter)?;
@@ -321,6 +404,7 @@ pub fn show_op_diff(
 
     let changed_local_tags =
         diff_named_ref_targets(from_repo.view().local_tags(), to_repo.view().local_tags())
+            .filter(|(name, _)| filter.matches_ref(name.as_str()))
             .collect_vec();
     if !changed_local_tags.is_empty() {
         writeln!(formatter)?;
@@ -357,6 +441,7 @@ pub fn show_op_diff(
     // Skip updates to the local git repo, since they should typically be covered in
     // local branches.
     .filter(|(symbol, _)| ignored_remote.is_none_or(|ignored| symbol.remote != ignored))
+    .filter(|(symbol, _)| filter.matches_ref(symbol.name.as_str()))
     .collect_vec();
     if !changed_remote_bookmarks.is_empty() {
         writeln!(formatter)?;
@@ -393,6 +478,109 @@ pub fn show_op_diff(
     Ok(())
 }
 
+/// Shows the numbers of new, rewritten, and abandoned commits between two
+/// operations, in total and for each changed bookmark.
+///
+/// `current_repo` should contain a `Repo` with the indices of both repos merged
+/// into it.
+pub fn show_op_diff_stat(
+    formatter: &mut dyn Formatter,
+    current_repo: &dyn Repo,
+    from_repo: &Arc<ReadonlyRepo>,
+    to_repo: &Arc<ReadonlyRepo>,
+    filter: &OpDiffFilter,
+) -> Result<(), CommandError> {
+    let changes = compute_filtered_commits_diff(current_repo, from_repo, to_repo, filter)?;
+    let mut total_counts = ChangeCounts::default();
+    for change in changes.values() {
+        total_counts.add(change);
+    }
+    writeln!(formatter)?;
+    writeln!(formatter, "Changed commits: {total_counts}")?;
+
+    // Counts the changes between the old and new targets of a ref.
+    let count_ref_changes = |from_target: &RefTarget, to_target: &RefTarget| {
+        let from_expr = RevsetExpression::commits(from_target.added_ids().cloned().collect());
+        let to_expr = RevsetExpression::commits(to_target.added_ids().cloned().collect());
+        let mut counts = ChangeCounts::default();
+        for commit_id in from_expr.range(&to_expr).evaluate(current_repo)?.iter() {
+            if let Some(change @ ModifiedChange::Existing { .. }) = changes.get(&commit_id?) {
+                counts.add(change);
+            }
+        }
+        for commit_id in to_expr.range(&from_expr).evaluate(current_repo)?.iter() {
+            if let Some(change @ ModifiedChange::Abandoned { .. }) = changes.get(&commit_id?) {
+                counts.add(change);
+            }
+        }
+        Ok::<_, CommandError>(counts)
+    };
+
+    let changed_local_bookmarks = diff_named_ref_targets(
+        from_repo.view().local_bookmarks(),
+        to_repo.view().local_bookmarks(),
+    )
+    .filter(|(name, _)| filter.matches_ref(name.as_str()))
+    .collect_vec();
+    if !changed_local_bookmarks.is_empty() {
+        writeln!(formatter)?;
+        writeln!(formatter, "Changed local bookmarks:")?;
+        for (name, (from_target, to_target)) in changed_local_bookmarks {
+            let counts = count_ref_changes(from_target, to_target)?;
+            writeln!(formatter, "{name}: {counts}", name = name.as_symbol())?;
+        }
+    }
+
+    let ignored_remote = default_ignored_remote_name(current_repo.store());
+    let changed_remote_bookmarks = diff_named_remote_refs(
+        from_repo.view().all_remote_bookmarks(),
+        to_repo.view().all_remote_bookmarks(),
+    )
+    .filter(|(symbol, _)| ignored_remote.is_none_or(|ignored| symbol.remote != ignored))
+    .filter(|(symbol, _)| filter.matches_ref(symbol.name.as_str()))
+    .collect_vec();
+    if !changed_remote_bookmarks.is_empty() {
+        writeln!(formatter)?;
+        writeln!(formatter, "Changed remote bookmarks:")?;
+        for (symbol, (from_ref, to_ref)) in changed_remote_bookmarks {
+            let counts = count_ref_changes(&from_ref.target, &to_ref.target)?;
+            writeln!(formatter, "{symbol}: {counts}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Numbers of new, rewritten, and abandoned commits.
+#[derive(Clone, Copy, Debug, Default)]
+struct ChangeCounts {
+    new: usize,
+    rewritten: usize,
+    abandoned: usize,
+}
+
+impl ChangeCounts {
+    fn add(&mut self, change: &ModifiedChange) {
+        match change {
+            ModifiedChange::Existing { predecessors, .. } if predecessors.is_empty() => {
+                self.new += 1;
+            }
+            ModifiedChange::Existing { .. } => self.rewritten += 1,
+            ModifiedChange::Abandoned { .. } => self.abandoned += 1,
+        }
+    }
+}
+
+impl fmt::Display for ChangeCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            new,
+            rewritten,
+            abandoned,
+        } = self;
+        write!(f, "{new} new, {rewritten} rewritten, {abandoned} abandoned")
+    }
+}
+
 /// Writes a summary for the given `ModifiedChange`.
 fn write_modified_change_summary(
     formatter: &mut dyn Formatter,
@@ -564,6 +752,28 @@ fn compute_operation_commits_diff(
     Ok(changes)
 }
 
+/// Computes the changes between two operations, and removes the changes to
+/// commits which don't match the `filter`.
+fn compute_filtered_commits_diff(
+    repo: &dyn Repo,
+    from_repo: &ReadonlyRepo,
+    to_repo: &ReadonlyRepo,
+    filter: &OpDiffFilter,
+) -> Result<HashMap<CommitId, ModifiedChange>, CommandError> {
+    let mut changes = compute_operation_commits_diff(repo, from_repo, to_repo)?;
+    let Some(expression) = &filter.revset else {
+        return Ok(changes);
+    };
+    let commit_ids = changes.keys().cloned().collect();
+    let matched_ids: HashSet<CommitId> = RevsetExpression::commits(commit_ids)
+        .intersection(expression)
+        .evaluate(repo)?
+        .iter()
+        .try_collect()?;
+    changes.retain(|commit_id, _| matched_ids.contains(commit_id));
+    Ok(changes)
+}
+
 /// Displays the diffs of a modified change.
 ///
 /// For created/rewritten commits, the diff is shown between the old (or
@@ -0,0 +1,66 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+use std::slice;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::op_bundle;
+use jj_lib::opset;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Export operations to a portable bundle
+///
+/// The bundle contains the specified operations, their views, and the
+/// commits, trees, and files referenced by the views. It is written to the
+/// standard output, and can be imported to another repo by `jj op import`.
+///
+/// All ancestors of the referenced commits and of the recorded predecessors
+/// are included, so the bundle can be imported to any repo using the same
+/// commit backend.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationExportArgs {
+    /// The operations to export
+    #[arg(
+        long,
+        short,
+        value_name = "OPSET",
+        default_value = "..@",
+        add = ArgValueCandidates::new(complete::operations),
+    )]
+    range: String,
+}
+
+pub fn cmd_op_export(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationExportArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    let repo = workspace_command.repo();
+    let expression = opset::parse(&args.range, &command.date_pattern_context())?;
+    let ops: Vec<_> = expression
+        .resolve(repo.op_store(), slice::from_ref(repo.operation()))?
+        .evaluate()
+        .try_collect()?;
+    let data = op_bundle::export_bundle(repo, &ops)?;
+    ui.stdout().write_all(&data)?;
+    Ok(())
+}
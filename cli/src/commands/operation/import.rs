@@ -0,0 +1,74 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use itertools::Itertools as _;
+use jj_lib::op_bundle;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::short_operation_hash;
+use crate::command_error::CommandError;
+use crate::command_error::cli_error;
+use crate::command_error::user_error_with_message;
+use crate::ui::Ui;
+
+/// Import operations from a bundle created by `jj op export`
+///
+/// The imported operations are stored in the repo, but they aren't added to
+/// the operation log, so the current repo state isn't changed. Use `jj --at-op`
+/// to inspect them, and `jj op restore` to restore the repo to the imported
+/// state. Imported operations which aren't restored may be removed by
+/// `jj util gc`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationImportArgs {
+    /// Path to the bundle file
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    path: PathBuf,
+}
+
+pub fn cmd_op_import(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationImportArgs,
+) -> Result<(), CommandError> {
+    // Don't load the repo so that the working copy isn't snapshotted.
+    let workspace = command.load_workspace()?;
+    if command.global_args().at_operation.is_some() {
+        return Err(cli_error("--at-op is not respected"));
+    }
+    let data = fs::read(&args.path).map_err(|err| {
+        user_error_with_message(format!("Failed to read {}", args.path.display()), err)
+    })?;
+    let stats = op_bundle::import_bundle(workspace.repo_loader(), &data)?;
+    if stats.head_ids.is_empty() {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+    writeln!(
+        ui.status(),
+        "Imported {} operations and {} commits as operation {}",
+        stats.operation_count,
+        stats.commit_count,
+        stats.head_ids.iter().map(short_operation_hash).join(", "),
+    )?;
+    writeln!(
+        ui.hint_default(),
+        "Use `jj op restore {}` to restore the repo to the imported operation.",
+        short_operation_hash(&stats.head_ids[0]),
+    )?;
+    Ok(())
+}
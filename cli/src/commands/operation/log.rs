@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::slice;
 
 use clap_complete::ArgValueCandidates;
@@ -19,10 +21,14 @@ use itertools::Itertools as _;
 use jj_lib::graph::GraphEdge;
 use jj_lib::graph::reverse_graph;
 use jj_lib::op_store::OpStoreError;
+use jj_lib::op_store::OperationId;
 use jj_lib::op_walk;
 use jj_lib::operation::Operation;
+use jj_lib::opset;
+use jj_lib::opset::ResolvedOpset;
 use jj_lib::repo::RepoLoader;
 
+use super::diff::OpDiffFilter;
 use super::diff::show_op_diff;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
@@ -37,6 +43,8 @@ use crate::formatter::Formatter;
 use crate::graphlog::GraphStyle;
 use crate::graphlog::get_graphlog;
 use crate::operation_templater::OperationTemplateLanguage;
+use crate::output_format::OutputFormatArgs;
+use crate::output_format::SerializedItemWriter;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -47,6 +55,15 @@ use crate::ui::Ui;
 /// to inspect the current state without mutation.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationLogArgs {
+    /// Which operations to show
+    ///
+    /// Operations can be selected by using functions such as
+    /// `description(glob:"git fetch*")`, `snapshots()`, or
+    /// `time(after:"yesterday")`, and ranges such as `x..y`. See
+    /// https://jj-vcs.github.io/jj/latest/operation-log/#operation-sets for
+    /// details.
+    #[arg(long = "operations", short = 'r', value_name = "OPSET")]
+    operations: Option<String>,
     /// Limit number of operations to show
     ///
     /// Applied after operations are reordered topologically, but before being
@@ -84,6 +101,8 @@ pub struct OperationLogArgs {
     patch: bool,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
+    #[command(flatten)]
+    output_format: OutputFormatArgs,
 }
 
 pub fn cmd_op_log(
@@ -115,6 +134,8 @@ fn do_op_log(
     current_op: &Operation,
     args: &OperationLogArgs,
 ) -> Result<(), CommandError> {
+    let output_format = args.output_format.format();
+    output_format.check_text_option("--template", args.template.is_some())?;
     let settings = repo_loader.settings();
     let graph_style = GraphStyle::from_settings(settings)?;
     let with_content_format = LogContentFormat::new(ui, settings)?;
@@ -144,6 +165,7 @@ fn do_op_log(
     }
 
     let diff_formats = diff_formats_for_log(settings, &args.diff_format, args.patch)?;
+    output_format.check_text_option("--op-diff", args.op_diff || !diff_formats.is_empty())?;
     let maybe_show_op_diff = if args.op_diff || !diff_formats.is_empty() {
         let template_text = settings.get_string("templates.commit_summary")?;
         let show = move |ui: &Ui,
@@ -189,6 +211,7 @@ fn do_op_log(
                 (!args.no_graph).then_some(graph_style),
                 with_content_format,
                 diff_renderer.as_ref(),
+                &OpDiffFilter::default(),
             )
         };
         Some(show)
@@ -196,21 +219,26 @@ fn do_op_log(
         None
     };
 
+    let opset = args
+        .operations
+        .as_ref()
+        .map(|text| -> Result<_, CommandError> {
+            let expression = opset::parse(
+                text,
+                &workspace_env.revset_parse_context().date_pattern_context,
+            )?;
+            Ok(expression.resolve(repo_loader.op_store(), slice::from_ref(current_op))?)
+        })
+        .transpose()?;
+
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
-    let iter =
-        op_walk::walk_ancestors(slice::from_ref(current_op)).take(args.limit.unwrap_or(usize::MAX));
+    let iter = walk_op_graph(current_op, opset.as_ref())?.take(args.limit.unwrap_or(usize::MAX));
 
-    if !args.no_graph {
+    if !args.no_graph && !output_format.is_serialized() {
         let mut raw_output = formatter.raw()?;
         let mut graph = get_graphlog(graph_style, raw_output.as_mut());
-        let iter = iter.map(|op| -> Result<_, OpStoreError> {
-            let op = op?;
-            let ids = op.parent_ids();
-            let edges = ids.iter().cloned().map(GraphEdge::direct).collect();
-            Ok((op, edges))
-        });
         let iter_nodes: Box<dyn Iterator<Item = _>> = if args.reversed {
             Box::new(reverse_graph(iter, Operation::id)?.into_iter().map(Ok))
         } else {
@@ -239,19 +267,83 @@ fn do_op_log(
             )?;
         }
     } else {
+        let iter = iter.map_ok(|(op, _)| op);
         let iter: Box<dyn Iterator<Item = _>> = if args.reversed {
             Box::new(iter.collect_vec().into_iter().rev())
         } else {
             Box::new(iter)
         };
-        for op in iter {
-            let op = op?;
-            with_content_format.write(formatter, |formatter| template.format(&op, formatter))?;
-            if let Some(show) = &maybe_show_op_diff {
-                show(ui, formatter, &op, &with_content_format)?;
+        if output_format.is_serialized() {
+            let mut writer = SerializedItemWriter::new(formatter, output_format)?;
+            for op in iter {
+                writer.write_item(&op?)?;
+            }
+            writer.finish()?;
+        } else {
+            for op in iter {
+                let op = op?;
+                with_content_format
+                    .write(formatter, |formatter| template.format(&op, formatter))?;
+                if let Some(show) = &maybe_show_op_diff {
+                    show(ui, formatter, &op, &with_content_format)?;
+                }
             }
         }
     }
 
     Ok(())
 }
+
+type OpGraphNode = (Operation, Vec<GraphEdge<OperationId>>);
+
+/// Walks ancestors of the `current_op` in reverse topological order. If the
+/// `opset` is specified, operations not in the set are omitted, and edges are
+/// drawn to the nearest ancestors in the set.
+fn walk_op_graph(
+    current_op: &Operation,
+    opset: Option<&ResolvedOpset>,
+) -> Result<Box<dyn Iterator<Item = Result<OpGraphNode, OpStoreError>>>, OpStoreError> {
+    let iter = op_walk::walk_ancestors(slice::from_ref(current_op));
+    let Some(opset) = opset else {
+        return Ok(Box::new(iter.map_ok(|op| {
+            let edges = op
+                .parent_ids()
+                .iter()
+                .cloned()
+                .map(GraphEdge::direct)
+                .collect();
+            (op, edges)
+        })));
+    };
+    let ops: Vec<_> = iter.try_collect()?;
+    let mut included_ids = HashSet::new();
+    // Indirect edges from the omitted operations to their nearest ancestors in
+    // the set.
+    let mut omitted_edges: HashMap<OperationId, Vec<GraphEdge<OperationId>>> = HashMap::new();
+    let mut nodes = vec![];
+    for op in ops.into_iter().rev() {
+        let edges = op
+            .parent_ids()
+            .iter()
+            .flat_map(|id| {
+                if included_ids.contains(id) {
+                    vec![GraphEdge::direct(id.clone())]
+                } else {
+                    omitted_edges.get(id).cloned().unwrap_or_default()
+                }
+            })
+            .unique_by(|edge| edge.target.clone())
+            .collect_vec();
+        if opset.contains(&op) {
+            included_ids.insert(op.id().clone());
+            nodes.push((op, edges));
+        } else {
+            let edges = edges
+                .into_iter()
+                .map(|edge| GraphEdge::indirect(edge.target))
+                .collect();
+            omitted_edges.insert(op.id().clone(), edges);
+        }
+    }
+    Ok(Box::new(nodes.into_iter().rev().map(Ok)))
+}
@@ -13,27 +13,51 @@
 // limitations under the License.
 
 mod abandon;
+mod compact;
 mod diff;
+mod export;
+mod import;
 mod log;
 mod restore;
 pub mod revert;
 mod show;
+mod tag;
+
+use std::iter;
+use std::slice;
 
 use abandon::OperationAbandonArgs;
 use abandon::cmd_op_abandon;
 use clap::Subcommand;
+use compact::OperationCompactArgs;
+use compact::cmd_op_compact;
+pub(crate) use compact::compact_snapshot_operations;
+pub(crate) use diff::OpDiffFilter;
 use diff::OperationDiffArgs;
 use diff::cmd_op_diff;
+pub(crate) use diff::show_op_diff;
+use export::OperationExportArgs;
+use export::cmd_op_export;
+use import::OperationImportArgs;
+use import::cmd_op_import;
+use itertools::Itertools as _;
+use jj_lib::op_store::OperationId;
+use jj_lib::operation::Operation;
+use jj_lib::workspace::Workspace;
 use log::OperationLogArgs;
 use log::cmd_op_log;
+use pollster::FutureExt as _;
 use restore::OperationRestoreArgs;
 use restore::cmd_op_restore;
 use revert::OperationRevertArgs;
 use revert::cmd_op_revert;
 use show::OperationShowArgs;
 use show::cmd_op_show;
+use tag::OperationTagArgs;
+use tag::cmd_op_tag;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::short_operation_hash;
 use crate::command_error::CommandError;
 use crate::commands::renamed_cmd;
 use crate::ui::Ui;
@@ -47,11 +71,15 @@ use crate::ui::Ui;
 #[derive(Subcommand, Clone, Debug)]
 pub enum OperationCommand {
     Abandon(OperationAbandonArgs),
+    Compact(OperationCompactArgs),
     Diff(OperationDiffArgs),
+    Export(OperationExportArgs),
+    Import(OperationImportArgs),
     Log(OperationLogArgs),
     Restore(OperationRestoreArgs),
     Revert(OperationRevertArgs),
     Show(OperationShowArgs),
+    Tag(OperationTagArgs),
     // TODO: Delete in jj 0.39.0+
     #[command(hide = true)]
     Undo(OperationRevertArgs),
@@ -64,11 +92,15 @@ pub fn cmd_operation(
 ) -> Result<(), CommandError> {
     match subcommand {
         OperationCommand::Abandon(args) => cmd_op_abandon(ui, command, args),
+        OperationCommand::Compact(args) => cmd_op_compact(ui, command, args),
         OperationCommand::Diff(args) => cmd_op_diff(ui, command, args),
+        OperationCommand::Export(args) => cmd_op_export(ui, command, args),
+        OperationCommand::Import(args) => cmd_op_import(ui, command, args),
         OperationCommand::Log(args) => cmd_op_log(ui, command, args),
         OperationCommand::Restore(args) => cmd_op_restore(ui, command, args),
         OperationCommand::Revert(args) => cmd_op_revert(ui, command, args),
         OperationCommand::Show(args) => cmd_op_show(ui, command, args),
+        OperationCommand::Tag(args) => cmd_op_tag(ui, command, args),
         OperationCommand::Undo(args) => {
             let cmd = renamed_cmd("op undo", "op revert", cmd_op_revert);
             cmd(ui, command, args)
@@ -118,3 +150,41 @@ pub(crate) fn view_with_desired_portions_restored(
         wc_commit_ids: repo_source.wc_commit_ids.clone(),
     }
 }
+
+/// Updates the operation heads and the operation of the current workspace
+/// after the `current_head_ops` were rewritten to the `new_head_ids`.
+pub(crate) fn update_rewritten_op_heads(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace: &mut Workspace,
+    current_head_ops: &[Operation],
+    new_head_ids: &[OperationId],
+) -> Result<(), CommandError> {
+    let op_heads_store = workspace.repo_loader().op_heads_store().clone();
+    let rewritten_head_ops = || iter::zip(current_head_ops, new_head_ids);
+    for (old, new_id) in rewritten_head_ops().filter(|&(old, new_id)| old.id() != new_id) {
+        op_heads_store
+            .update_op_heads(slice::from_ref(old.id()), new_id)
+            .block_on()?;
+    }
+    // Remap the operation id of the current workspace. If there were any
+    // divergent operations, user will need to re-abandon their ancestors.
+    if !command.global_args().ignore_working_copy {
+        let mut locked_ws = workspace.start_working_copy_mutation()?;
+        let old_op_id = locked_ws.locked_wc().old_operation_id();
+        if let Some((_, new_id)) = rewritten_head_ops().find(|(old, _)| old.id() == old_op_id) {
+            locked_ws.finish(new_id.clone())?;
+        } else {
+            writeln!(
+                ui.warning_default(),
+                "The working copy operation {} is not updated because it differs from the repo {}.",
+                short_operation_hash(old_op_id),
+                current_head_ops
+                    .iter()
+                    .map(|op| short_operation_hash(op.id()))
+                    .join(", "),
+            )?;
+        }
+    }
+    Ok(())
+}
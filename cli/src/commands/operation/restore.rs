@@ -12,14 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::slice;
+
 use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::operation::Operation;
+use jj_lib::opset::OpsetExpression;
+use jj_lib::repo::Repo as _;
 
 use super::DEFAULT_REVERT_WHAT;
 use super::RevertWhatToRestore;
 use super::view_with_desired_portions_restored;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::cli_util::date_pattern_context;
 use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
 use crate::complete;
 use crate::ui::Ui;
 
@@ -34,8 +44,18 @@ pub struct OperationRestoreArgs {
     /// Use `jj op log` to find an operation to restore to. Use e.g. `jj
     /// --at-op=<operation ID> log` before restoring to an operation to see the
     /// state of the repo at that operation.
-    #[arg(add = ArgValueCandidates::new(complete::operations))]
-    operation: String,
+    #[arg(
+        required_unless_present = "to_time",
+        add = ArgValueCandidates::new(complete::operations)
+    )]
+    operation: Option<String>,
+
+    /// Restore to the latest operation at or before the given date
+    ///
+    /// The date can be specified in the same forms as date patterns in
+    /// revsets, such as `2024-02-01 12:00:00` or `2 hours ago`.
+    #[arg(long, value_name = "DATE", conflicts_with = "operation")]
+    to_time: Option<String>,
 
     /// What portions of the local state to restore (can be repeated)
     ///
@@ -50,10 +70,26 @@ pub fn cmd_op_restore(
     args: &OperationRestoreArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
-    let target_op = workspace_command.resolve_single_op(&args.operation)?;
+    let target_op = if let Some(to_time) = &args.to_time {
+        resolve_op_at_time(&workspace_command, to_time)?
+    } else {
+        workspace_command.resolve_single_op(args.operation.as_deref().unwrap())?
+    };
     let mut tx = workspace_command.start_transaction();
+    // The target operation may not be an ancestor of the current operation,
+    // e.g. if it was imported by `jj op import`.
+    let target_view = target_op.view()?;
+    let index = tx.base_repo().index();
+    if !target_view
+        .heads()
+        .iter()
+        .all(|id| index.has_id(id).unwrap_or(false))
+    {
+        let target_repo = tx.base_repo().loader().load_at(&target_op)?;
+        tx.repo_mut().merge_index(&target_repo)?;
+    }
     let new_view = view_with_desired_portions_restored(
-        target_op.view()?.store_view(),
+        target_view.store_view(),
         tx.base_repo().view().store_view(),
         &args.what,
     );
@@ -68,3 +104,20 @@ pub fn cmd_op_restore(
 
     Ok(())
 }
+
+fn resolve_op_at_time(
+    workspace_command: &WorkspaceCommandHelper,
+    date: &str,
+) -> Result<Operation, CommandError> {
+    let time = date_pattern_context(workspace_command.settings())
+        .parse_instant(date)
+        .map_err(|err| user_error_with_message(format!("Invalid date `{date}`"), err))?;
+    let repo = workspace_command.repo();
+    let ops: Vec<_> = OpsetExpression::at_time(time)
+        .resolve(repo.op_store(), slice::from_ref(repo.operation()))?
+        .evaluate()
+        .try_collect()?;
+    ops.into_iter()
+        .next()
+        .ok_or_else(|| user_error(format!("No operation found at or before `{date}`")))
+}
@@ -0,0 +1,96 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::op_walk;
+
+use super::update_rewritten_op_heads;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::parse_op_tag_arg;
+use crate::cli_util::short_operation_hash;
+use crate::command_error::CommandError;
+use crate::command_error::cli_error;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Add tags to an operation
+///
+/// Operations are immutable, so the operation is rewritten with the new tags,
+/// and its descendants are reparented onto the rewritten operation. Existing
+/// tags of the same names are overwritten.
+///
+/// Tags are shown in `jj op log`, and operations can be selected by their tags
+/// with the `tags(KEY=VALUE)` operation set function. Use the global
+/// `--op-tag` option to tag the operation created by a command.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationTagArgs {
+    /// The operation to tag
+    #[arg(add = ArgValueCandidates::new(complete::operations))]
+    operation: String,
+    /// The tags to add
+    #[arg(required = true, value_name = "KEY=VALUE", value_parser = parse_op_tag_arg)]
+    tags: Vec<(String, String)>,
+}
+
+pub fn cmd_op_tag(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationTagArgs,
+) -> Result<(), CommandError> {
+    // Don't load the repo so that the operation can be rewritten without
+    // snapshotting the working copy.
+    let mut workspace = command.load_workspace()?;
+    // It doesn't make sense to create divergent operations that will be merged
+    // with the current head.
+    if command.global_args().at_operation.is_some() {
+        return Err(cli_error("--at-op is not respected"));
+    }
+    let repo_loader = workspace.repo_loader();
+    let op_store = repo_loader.op_store().clone();
+    let current_head_ops =
+        op_walk::get_current_head_ops(&op_store, repo_loader.op_heads_store().as_ref())?;
+    let op = op_walk::resolve_op_at(
+        &op_store,
+        &current_head_ops,
+        &args.operation,
+        &command.date_pattern_context(),
+    )?;
+    if op.id() == op_store.root_operation_id() {
+        return Err(user_error("Cannot tag the root operation"));
+    }
+    let tags: HashMap<_, _> = args.tags.iter().cloned().collect();
+    let stats = op_walk::set_operation_tags(op_store.as_ref(), &op, &tags, &current_head_ops)?;
+    if stats.rewritten_count == 0 {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+    writeln!(
+        ui.status(),
+        "Tagged operation {} and reparented {} descendant operations.",
+        short_operation_hash(op.id()),
+        stats.rewritten_count - 1,
+    )?;
+    update_rewritten_op_heads(
+        ui,
+        command,
+        &mut workspace,
+        &current_head_ops,
+        &stats.new_head_ids,
+    )?;
+    Ok(())
+}
@@ -15,6 +15,8 @@
 use itertools::Itertools as _;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::OperationId;
+use jj_lib::operation::Operation;
+use jj_lib::repo::RepoLoader;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
@@ -23,6 +25,7 @@ use crate::command_error::user_error;
 use crate::commands::operation::DEFAULT_REVERT_WHAT;
 use crate::commands::operation::view_with_desired_portions_restored;
 use crate::commands::undo::UNDO_OP_DESC_PREFIX;
+use crate::commands::undo::show_restore_preview;
 use crate::ui::Ui;
 
 /// Redo the most recently undone operation
@@ -35,14 +38,26 @@ use crate::ui::Ui;
 /// restore` to explicitly restore an older operation by its id (available in
 /// the operation log).
 #[derive(clap::Args, Clone, Debug)]
-pub struct RedoArgs {}
+pub struct RedoArgs {
+    /// Number of operations to redo
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    steps: usize,
+
+    /// Show what would be restored without redoing anything
+    #[arg(long)]
+    preview: bool,
+}
 
 const REDO_OP_DESC_PREFIX: &str = "redo: restore to operation ";
 
-pub fn cmd_redo(ui: &mut Ui, command: &CommandHelper, _: &RedoArgs) -> Result<(), CommandError> {
+pub fn cmd_redo(ui: &mut Ui, command: &CommandHelper, args: &RedoArgs) -> Result<(), CommandError> {
+    if args.steps == 0 {
+        return Err(user_error(
+            "The number of operations to redo must be positive",
+        ));
+    }
     let mut workspace_command = command.workspace_helper(ui)?;
-
-    let mut op_to_redo = workspace_command.repo().operation().clone();
+    let repo_loader = workspace_command.repo().loader();
 
     // Growing the "redo-stack" works very similar to the
     // [undo-stack](./undo.rs). `jj redo` and `jj undo` track their stacks
@@ -91,59 +106,42 @@ pub fn cmd_redo(ui: &mut Ui, command: &CommandHelper, _: &RedoArgs) -> Result<()
     // - H is a redo-operation restoring to D, so attempt to redo D.
     // - D is an undo-operation. Redo it by restoring its parent C.
     //
-    if let Some(id_of_restored_op) = op_to_redo
-        .metadata()
-        .description
-        .strip_prefix(REDO_OP_DESC_PREFIX)
-    {
-        let Some(id_of_restored_op) = OperationId::try_from_hex(id_of_restored_op) else {
-            return Err(internal_error(
-                "Failed to parse ID of restored operation in redo-stack",
-            ));
-        };
-        op_to_redo = workspace_command
-            .repo()
-            .loader()
-            .load_operation(&id_of_restored_op)?;
-    }
-
-    if !op_to_redo
-        .metadata()
-        .description
-        .starts_with(UNDO_OP_DESC_PREFIX)
-    {
-        // cannot redo a non-undo-operation
-        return Err(user_error("Nothing to redo"));
-    }
+    // Redoing multiple operations at once walks the redo-stack in the same way
+    // as repeated `redo`s would, without recording the intermediate
+    // redo-operations.
+    //
+    let mut op_to_restore = workspace_command.repo().operation().clone();
+    for _ in 0..args.steps {
+        let op_to_redo = resolve_redo_operation(repo_loader, op_to_restore)?;
 
-    let mut op_to_restore = match op_to_redo.parents().at_most_one().ok().flatten() {
-        Some(parent_of_op_to_redo) => parent_of_op_to_redo?,
-        None => {
-            return Err(internal_error("Undo operation should have a single parent"));
+        if !op_to_redo
+            .metadata()
+            .description
+            .starts_with(UNDO_OP_DESC_PREFIX)
+        {
+            // cannot redo a non-undo-operation
+            return Err(user_error("Nothing to redo"));
         }
-    };
 
-    // Avoid the creation of a linked list by restoring to the original
-    // operation directly, if we're about to restore a redo-operation. If
-    // we didn't to this, repeated calls of `jj undo ; jj redo` would create
-    // an ever-growing linked list of redo-operations that restore each
-    // other. Calling `jj redo` one more time would have to redo a potential
-    // undo-operation at the very beginning of the linked list, which would
-    // require walking the entire thing unnecessarily.
-    if let Some(original_op) = op_to_restore
-        .metadata()
-        .description
-        .strip_prefix(REDO_OP_DESC_PREFIX)
-    {
-        let Some(id_of_original_op) = OperationId::try_from_hex(original_op) else {
-            return Err(internal_error(
-                "Failed to parse ID of restored operation in redo-stack",
-            ));
+        let parent_of_op_to_redo = match op_to_redo.parents().at_most_one().ok().flatten() {
+            Some(parent_of_op_to_redo) => parent_of_op_to_redo?,
+            None => {
+                return Err(internal_error("Undo operation should have a single parent"));
+            }
         };
-        op_to_restore = workspace_command
-            .repo()
-            .loader()
-            .load_operation(&id_of_original_op)?;
+
+        // Avoid the creation of a linked list by restoring to the original
+        // operation directly, if we're about to restore a redo-operation. If
+        // we didn't to this, repeated calls of `jj undo ; jj redo` would create
+        // an ever-growing linked list of redo-operations that restore each
+        // other. Calling `jj redo` one more time would have to redo a potential
+        // undo-operation at the very beginning of the linked list, which would
+        // require walking the entire thing unnecessarily.
+        op_to_restore = resolve_redo_operation(repo_loader, parent_of_op_to_redo)?;
+    }
+
+    if args.preview {
+        return show_restore_preview(ui, &workspace_command, &op_to_restore);
     }
 
     let mut tx = workspace_command.start_transaction();
@@ -166,3 +164,21 @@ pub fn cmd_redo(ui: &mut Ui, command: &CommandHelper, _: &RedoArgs) -> Result<()
 
     Ok(())
 }
+
+/// Returns the operation restored by `op` if it is a redo-operation, or `op`
+/// itself otherwise.
+fn resolve_redo_operation(
+    repo_loader: &RepoLoader,
+    op: Operation,
+) -> Result<Operation, CommandError> {
+    let Some(id_of_restored_op) = op.metadata().description.strip_prefix(REDO_OP_DESC_PREFIX)
+    else {
+        return Ok(op);
+    };
+    let Some(id_of_restored_op) = OperationId::try_from_hex(id_of_restored_op) else {
+        return Err(internal_error(
+            "Failed to parse ID of restored operation in redo-stack",
+        ));
+    };
+    Ok(repo_loader.load_operation(&id_of_restored_op)?)
+}
@@ -15,14 +15,29 @@
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
+use jj_lib::backend::CopyId;
+use jj_lib::backend::TreeValue;
+use jj_lib::conflicts::try_materialize_file_conflict_value;
+use jj_lib::diff::CompareBytesIgnoreAllWhitespace;
+use jj_lib::files;
+use jj_lib::merge::Merge;
+use jj_lib::merge::SameChange;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::tree_merge::MergeOptions;
+use pollster::FutureExt as _;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::print_conflicted_paths;
 use crate::command_error::CommandError;
 use crate::command_error::cli_error;
+use crate::commands::fix::fix_file_content;
+use crate::commands::fix::get_tools_config;
 use crate::complete;
 use crate::formatter::FormatterExt as _;
 use crate::ui::Ui;
@@ -35,6 +50,12 @@ use crate::ui::Ui;
 /// resolved. To stop resolving conflicts, exit the merge tool without making
 /// any changes.
 ///
+/// With `--auto`, conflicts are instead resolved without a merge tool where
+/// possible. Hunks in which the sides are equal to the base or to each other
+/// are merged, lines that differ only in whitespace are considered the same,
+/// and each side is formatted with the matching `fix.tools` before merging.
+/// Files that can't be resolved this way are left as is.
+///
 /// Note that conflicts can also be resolved without using this command. You may
 /// edit the conflict markers in the conflicted file directly with a text
 /// editor.
@@ -70,6 +91,10 @@ pub(crate) struct ResolveArgs {
         add = ArgValueCandidates::new(complete::merge_editors),
     )]
     tool: Option<String>,
+    /// Resolve conflicts automatically where possible, without invoking a merge
+    /// tool
+    #[arg(long, conflicts_with_all = ["list", "tool"])]
+    auto: bool,
     /// Only resolve conflicts in these paths. You can use the `--list` argument
     /// to find paths to use here.
     #[arg(
@@ -116,10 +141,37 @@ pub(crate) fn cmd_resolve(
         .map(|(path, _)| path.as_ref())
         .collect_vec();
     workspace_command.check_rewritable([commit.id()])?;
-    let merge_editor = workspace_command.merge_editor(ui, args.tool.as_deref())?;
+    let (new_tree_id, partial_resolution_error) = if args.auto {
+        let mut tree_builder = MergedTreeBuilder::new(tree.id());
+        let resolved_paths = auto_resolve_files(
+            ui,
+            &workspace_command,
+            &tree,
+            &repo_paths,
+            &mut tree_builder,
+        )?;
+        if resolved_paths.is_empty() {
+            writeln!(ui.status(), "No conflicts could be resolved automatically.")?;
+            return Ok(());
+        }
+        if let Some(mut formatter) = ui.status_formatter() {
+            writeln!(formatter, "Resolved conflicts automatically in:")?;
+            for (path, resolver) in &resolved_paths {
+                write!(formatter, "  ")?;
+                write!(
+                    formatter.labeled("path"),
+                    "{}",
+                    workspace_command.format_file_path(path)
+                )?;
+                writeln!(formatter, " ({})", resolver.description())?;
+            }
+        }
+        (tree_builder.write_tree(tree.store())?, None)
+    } else {
+        let merge_editor = workspace_command.merge_editor(ui, args.tool.as_deref())?;
+        merge_editor.edit_files(ui, &tree, &repo_paths)?
+    };
     let mut tx = workspace_command.start_transaction();
-    let (new_tree_id, partial_resolution_error) =
-        merge_editor.edit_files(ui, &tree, &repo_paths)?;
     let new_commit = tx
         .repo_mut()
         .rewrite_commit(&commit)
@@ -151,3 +203,97 @@ pub(crate) fn cmd_resolve(
     }
     Ok(())
 }
+
+/// Heuristic used to resolve a conflict automatically.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum AutoResolver {
+    /// Sides equal to the base or to each other were merged.
+    Trivial,
+    /// Lines were compared ignoring whitespace.
+    Whitespace,
+    /// Sides were formatted with `fix.tools` before merging.
+    FixTools,
+}
+
+impl AutoResolver {
+    fn description(self) -> &'static str {
+        match self {
+            Self::Trivial => "sides equal to base or to each other",
+            Self::Whitespace => "whitespace-only changes",
+            Self::FixTools => "identical after running fix tools",
+        }
+    }
+}
+
+/// Tries to resolve conflicts at `repo_paths` with the [`AutoResolver`]s, and
+/// records the resolved files in `tree_builder`. Returns the resolved paths.
+fn auto_resolve_files<'a>(
+    ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    tree: &MergedTree,
+    repo_paths: &[&'a RepoPath],
+    tree_builder: &mut MergedTreeBuilder,
+) -> Result<Vec<(&'a RepoPath, AutoResolver)>, CommandError> {
+    let store = tree.store();
+    let settings = workspace_command.settings();
+    let tools_config = if settings.table_keys("fix.tools").next().is_some() {
+        Some(get_tools_config(ui, settings)?)
+    } else {
+        None
+    };
+    let merge_options = MergeOptions {
+        same_change: SameChange::Accept,
+        ..store.merge_options().clone()
+    };
+    let mut resolved_paths = vec![];
+    for &repo_path in repo_paths {
+        let conflict = tree.path_value(repo_path)?;
+        let Some(file) =
+            try_materialize_file_conflict_value(store, repo_path, &conflict).block_on()?
+        else {
+            continue;
+        };
+        let Some(executable) = file.executable else {
+            continue;
+        };
+        let contents = file.contents.simplify();
+        let resolved = if let Some(content) = files::try_merge(&contents, &merge_options) {
+            Some((content, AutoResolver::Trivial))
+        } else if let Some(content) = files::try_merge_with_compare(
+            &contents,
+            CompareBytesIgnoreAllWhitespace,
+            merge_options.same_change,
+        ) {
+            Some((content, AutoResolver::Whitespace))
+        } else if let Some(tools_config) = &tools_config {
+            let fixed_contents = contents.map(|content| {
+                fix_file_content(
+                    ui,
+                    workspace_command.workspace_root(),
+                    workspace_command.path_converter(),
+                    tools_config,
+                    repo_path,
+                    content.to_vec(),
+                )
+            });
+            files::try_merge(&fixed_contents, &merge_options)
+                .map(|content| (content, AutoResolver::FixTools))
+        } else {
+            None
+        };
+        let Some((content, resolver)) = resolved else {
+            continue;
+        };
+        let id = store
+            .write_file(repo_path, &mut content.as_slice())
+            .block_on()?;
+        let new_value = TreeValue::File {
+            id,
+            executable,
+            copy_id: file.copy_id.unwrap_or_else(CopyId::placeholder),
+        };
+        tree_builder.set_or_remove(repo_path.to_owned(), Merge::normal(new_value));
+        resolved_paths.push((repo_path, resolver));
+    }
+    Ok(resolved_paths)
+}
@@ -0,0 +1,361 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::slice;
+
+use clap_complete::ArgValueCompleter;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::evolution::walk_predecessors;
+use jj_lib::merge::Merge;
+use jj_lib::merge::SameChange;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::repo::MutableRepo;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::rewrite::rebase_to_dest_parent;
+use pollster::FutureExt as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::cli_util::short_change_hash;
+use crate::cli_util::short_commit_hash;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Resolve a divergent change by keeping or merging its commits
+///
+/// A change is divergent when it has multiple visible commits. This command
+/// shows how each of the divergent commits evolved since their common
+/// predecessor, and prompts for one of them to keep, or whether to merge them.
+///
+/// When merging, the changes each divergent commit made relative to their
+/// common predecessor are combined into a single commit, which is created on
+/// top of the parents of the first of the given revisions. If the commits have
+/// no common predecessor, their changes relative to their parents are combined
+/// instead.
+///
+/// The other divergent commits are abandoned, and their descendants are
+/// rebased onto the resulting commit.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct ResolveDivergenceArgs {
+    /// A revision of the divergent change, such as `change_id(CHANGE_ID)`
+    #[arg(
+        default_value = "@",
+        value_name = "REVSET",
+        add = ArgValueCompleter::new(complete::revset_expression_mutable),
+    )]
+    revision: RevisionArg,
+    /// Keep this commit and abandon the other divergent commits
+    #[arg(
+        long,
+        value_name = "REVSET",
+        add = ArgValueCompleter::new(complete::revset_expression_mutable),
+    )]
+    keep: Option<RevisionArg>,
+    /// Merge the divergent commits instead of keeping one of them
+    #[arg(long, conflicts_with = "keep")]
+    merge: bool,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_resolve_divergence(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ResolveDivergenceArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, slice::from_ref(&args.revision))?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let Some(first_commit) = commits.first() else {
+        return Err(user_error("Empty revision set"));
+    };
+    let change_id = first_commit.change_id().clone();
+    if commits
+        .iter()
+        .any(|commit| *commit.change_id() != change_id)
+    {
+        return Err(user_error("The revisions must belong to a single change"));
+    }
+    let repo = workspace_command.repo().clone();
+    let mut sides = vec![first_commit.clone()];
+    for id in repo.resolve_change_id(&change_id)?.unwrap_or_default() {
+        if id != *first_commit.id() {
+            sides.push(repo.store().get_commit(&id)?);
+        }
+    }
+    if sides.len() < 2 {
+        return Err(user_error(format!(
+            "Change {} is not divergent",
+            short_change_hash(&change_id)
+        )));
+    }
+    workspace_command.check_rewritable(sides.iter().map(Commit::id))?;
+
+    let evolution = DivergentEvolution::new(&repo, &sides)?;
+    let kept_commit = if let Some(keep) = &args.keep {
+        let commit = workspace_command.resolve_single_rev(ui, keep)?;
+        if !sides.contains(&commit) {
+            return Err(user_error(format!(
+                "Commit {} is not one of the divergent commits",
+                short_commit_hash(commit.id())
+            )));
+        }
+        Some(commit)
+    } else if args.merge {
+        None
+    } else {
+        match choose_resolution(ui, &workspace_command, &sides, &evolution)? {
+            Some(Resolution::Keep(commit)) => Some(commit),
+            Some(Resolution::Merge) => None,
+            None => {
+                writeln!(ui.status(), "Nothing changed.")?;
+                return Ok(());
+            }
+        }
+    };
+
+    let mut tx = workspace_command.start_transaction();
+    let (new_commit, other_sides) = if let Some(kept_commit) = kept_commit {
+        let other_sides = sides
+            .iter()
+            .filter(|&side| *side != kept_commit)
+            .collect_vec();
+        (kept_commit, other_sides)
+    } else {
+        let index = repo.index();
+        for (side1, side2) in sides.iter().tuple_combinations() {
+            if index.is_ancestor(side1.id(), side2.id())?
+                || index.is_ancestor(side2.id(), side1.id())?
+            {
+                return Err(user_error_with_hint(
+                    "Cannot merge divergent commits that are ancestors of each other",
+                    "Use `--keep` to keep one of them instead.",
+                ));
+            }
+        }
+        let base = evolution
+            .common_predecessor()
+            .map(|id| repo.store().get_commit(id))
+            .transpose()?;
+        let new_commit = merge_divergent_commits(tx.repo_mut(), &sides, base.as_ref())?;
+        (new_commit, sides[1..].iter().collect())
+    };
+    for side in other_sides {
+        if tx.repo().index().is_ancestor(side.id(), new_commit.id())? {
+            tx.repo_mut().record_abandoned_commit(side);
+        } else {
+            tx.repo_mut()
+                .set_rewritten_commit(side.id().clone(), new_commit.id().clone());
+        }
+    }
+    let num_rebased = tx.repo_mut().rebase_descendants()?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Resolved divergence with commit ")?;
+        tx.write_commit_summary(formatter.as_mut(), &new_commit)?;
+        writeln!(formatter)?;
+        if num_rebased > 0 {
+            writeln!(formatter, "Rebased {num_rebased} descendant commits")?;
+        }
+    }
+    tx.finish(
+        ui,
+        format!("resolve divergence of change {}", change_id.reverse_hex()),
+    )?;
+    Ok(())
+}
+
+/// How the divergent commits of a change evolved.
+struct DivergentEvolution {
+    /// Predecessors of each divergent commit, including itself, in reverse
+    /// topological order.
+    side_predecessors: Vec<Vec<CommitId>>,
+    common_predecessor: Option<CommitId>,
+}
+
+impl DivergentEvolution {
+    fn new(repo: &ReadonlyRepo, sides: &[Commit]) -> Result<Self, CommandError> {
+        let side_ids = sides.iter().map(|side| side.id().clone()).collect_vec();
+        let mut walk_order = vec![];
+        let mut predecessors: HashMap<CommitId, Vec<CommitId>> = HashMap::new();
+        for entry in walk_predecessors(repo, &side_ids) {
+            let entry = entry?;
+            walk_order.push(entry.commit.id().clone());
+            predecessors.insert(entry.commit.id().clone(), entry.predecessor_ids().to_vec());
+        }
+        let side_predecessors = side_ids
+            .iter()
+            .map(|side_id| {
+                let mut reachable = HashSet::from([side_id]);
+                let mut to_visit = vec![side_id];
+                while let Some(id) = to_visit.pop() {
+                    for predecessor_id in predecessors.get(id).into_iter().flatten() {
+                        if reachable.insert(predecessor_id) {
+                            to_visit.push(predecessor_id);
+                        }
+                    }
+                }
+                walk_order
+                    .iter()
+                    .filter(|id| reachable.contains(id))
+                    .cloned()
+                    .collect_vec()
+            })
+            .collect_vec();
+        let common_predecessor = walk_order
+            .iter()
+            .find(|id| side_predecessors.iter().all(|ids| ids.contains(id)))
+            .cloned();
+        Ok(Self {
+            side_predecessors,
+            common_predecessor,
+        })
+    }
+
+    fn common_predecessor(&self) -> Option<&CommitId> {
+        self.common_predecessor.as_ref()
+    }
+
+    /// Predecessors of the `index`-th divergent commit, excluding itself, up
+    /// to the common predecessor.
+    fn predecessors_since_common(&self, index: usize) -> &[CommitId] {
+        let ids = &self.side_predecessors[index][1..];
+        match ids
+            .iter()
+            .position(|id| Some(id) == self.common_predecessor.as_ref())
+        {
+            Some(position) => &ids[..position],
+            None => ids,
+        }
+    }
+}
+
+/// How the divergence should be resolved.
+enum Resolution {
+    /// Keep the commit and abandon the other divergent commits.
+    Keep(Commit),
+    /// Merge the divergent commits into one.
+    Merge,
+}
+
+/// Shows the evolution of the divergent commits, and asks which one to keep
+/// or whether to merge them. Returns `None` if the user quit the prompt.
+fn choose_resolution(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    sides: &[Commit],
+    evolution: &DivergentEvolution,
+) -> Result<Option<Resolution>, CommandError> {
+    let store = workspace_command.repo().store();
+    let template = workspace_command.commit_summary_template();
+    let mut formatter = ui.stderr_formatter();
+    writeln!(
+        formatter,
+        "The change has {} divergent commits:",
+        sides.len()
+    )?;
+    let mut choices = vec![];
+    for (i, side) in sides.iter().enumerate() {
+        write!(formatter, "{}: ", i + 1)?;
+        template.format(side, formatter.as_mut())?;
+        writeln!(formatter)?;
+        for id in evolution.predecessors_since_common(i) {
+            write!(formatter, "   rewritten from ")?;
+            template.format(&store.get_commit(id)?, formatter.as_mut())?;
+            writeln!(formatter)?;
+        }
+        choices.push(format!("{}", i + 1));
+    }
+    if let Some(id) = evolution.common_predecessor() {
+        write!(formatter, "Common predecessor: ")?;
+        template.format(&store.get_commit(id)?, formatter.as_mut())?;
+        writeln!(formatter)?;
+    }
+    writeln!(formatter, "m: merge the divergent commits")?;
+    choices.push("m".to_owned());
+    writeln!(formatter, "q: quit the prompt")?;
+    choices.push("q".to_owned());
+    drop(formatter);
+
+    let index = ui.prompt_choice(
+        "enter the index of the commit to keep, or `m` to merge them",
+        &choices,
+        None,
+    )?;
+    if let Some(side) = sides.get(index) {
+        Ok(Some(Resolution::Keep(side.clone())))
+    } else if index == sides.len() {
+        Ok(Some(Resolution::Merge))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Writes a commit which combines the changes the `sides` made relative to the
+/// `base`, on top of the parents of the first of the `sides`.
+fn merge_divergent_commits(
+    mut_repo: &mut MutableRepo,
+    sides: &[Commit],
+    base: Option<&Commit>,
+) -> Result<Commit, CommandError> {
+    let destination = &sides[0];
+    let base_tree = match base {
+        Some(base) => rebase_to_dest_parent(mut_repo, slice::from_ref(base), destination)?,
+        None => destination.parent_tree(mut_repo)?,
+    };
+    let side_trees: Vec<MergedTree> = sides
+        .iter()
+        .map(|side| rebase_to_dest_parent(mut_repo, slice::from_ref(side), destination))
+        .try_collect()?;
+    let tree_merge = Merge::from_removes_adds(
+        vec![base_tree.into_merge(); sides.len() - 1],
+        side_trees.into_iter().map(MergedTree::into_merge),
+    );
+    let new_tree = MergedTree::new(tree_merge.flatten().simplify())
+        .resolve()
+        .block_on()?;
+
+    let base_description = base.map_or("", |base| base.description());
+    let description_merge = Merge::from_removes_adds(
+        vec![base_description; sides.len() - 1],
+        sides.iter().map(|side| side.description()),
+    );
+    let description = match description_merge.resolve_trivial(SameChange::Accept) {
+        Some(description) => description.to_string(),
+        None => sides
+            .iter()
+            .map(|side| side.description())
+            .filter(|description| !description.is_empty())
+            .unique()
+            .join("\n"),
+    };
+
+    let new_commit = mut_repo
+        .rewrite_commit(destination)
+        .set_tree_id(new_tree.id())
+        .set_description(description)
+        .set_predecessors(sides.iter().map(|side| side.id().clone()).collect())
+        .write()?;
+    Ok(new_commit)
+}
@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use futures::StreamExt as _;
+use futures::TryStreamExt as _;
 use itertools::Itertools as _;
+use jj_lib::commit::Commit;
 use jj_lib::copies::CopyRecords;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::repo::Repo as _;
@@ -27,9 +30,12 @@ use crate::cli_util::CommandHelper;
 use crate::cli_util::print_conflicted_paths;
 use crate::cli_util::print_snapshot_stats;
 use crate::command_error::CommandError;
+use crate::commit_templater::TreeDiffEntry;
 use crate::diff_util::DiffFormat;
 use crate::diff_util::get_copy_records;
 use crate::formatter::FormatterExt as _;
+use crate::output_format::OutputFormatArgs;
+use crate::output_format::SerializedItemWriter;
 use crate::ui::Ui;
 
 /// Show high-level repo status [default alias: st]
@@ -50,6 +56,17 @@ pub(crate) struct StatusArgs {
     /// Restrict the status display to these paths
     #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
+    #[command(flatten)]
+    output_format: OutputFormatArgs,
+}
+
+/// Working-copy status serialized by `--output`.
+#[derive(serde::Serialize)]
+struct StatusItem {
+    working_copy: Option<Commit>,
+    parents: Vec<Commit>,
+    changes: Vec<TreeDiffEntry>,
+    untracked_paths: Vec<RepoPathBuf>,
 }
 
 #[instrument(skip_all)]
@@ -72,6 +89,35 @@ pub(crate) fn cmd_status(
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
+    let output_format = args.output_format.format();
+    if output_format.is_serialized() {
+        let mut status = StatusItem {
+            working_copy: maybe_wc_commit.clone(),
+            parents: vec![],
+            changes: vec![],
+            untracked_paths: snapshot_stats.untracked_paths.keys().cloned().collect(),
+        };
+        if let Some(wc_commit) = &maybe_wc_commit {
+            status.parents = wc_commit.parents().try_collect()?;
+            let mut copy_records = CopyRecords::default();
+            for parent in wc_commit.parent_ids() {
+                let records = get_copy_records(repo.store(), parent, wc_commit.id(), &matcher)?;
+                copy_records.add_records(records)?;
+            }
+            status.changes = wc_commit
+                .parent_tree(repo.as_ref())?
+                .diff_stream_with_copies(&wc_commit.tree()?, &matcher, &copy_records)
+                .map(TreeDiffEntry::from_backend_entry_with_copies)
+                .try_collect()
+                .block_on()?;
+        }
+        ui.request_pager();
+        let mut formatter = ui.stdout_formatter();
+        let mut writer = SerializedItemWriter::new(formatter.as_mut(), output_format)?;
+        writer.write_item(&status)?;
+        writer.finish()?;
+        return Ok(());
+    }
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
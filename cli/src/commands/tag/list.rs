@@ -21,6 +21,8 @@ use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitRef;
 use crate::complete;
+use crate::output_format::OutputFormatArgs;
+use crate::output_format::SerializedItemWriter;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -49,6 +51,8 @@ pub struct TagListArgs {
     ///     https://jj-vcs.github.io/jj/latest/templates/
     #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::template_aliases))]
     template: Option<String>,
+    #[command(flatten)]
+    output_format: OutputFormatArgs,
 }
 
 pub fn cmd_tag_list(
@@ -56,6 +60,8 @@ pub fn cmd_tag_list(
     command: &CommandHelper,
     args: &TagListArgs,
 ) -> Result<(), CommandError> {
+    let output_format = args.output_format.format();
+    output_format.check_text_option("--template", args.template.is_some())?;
     let workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo();
     let view = repo.view();
@@ -74,17 +80,26 @@ pub fn cmd_tag_list(
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
 
-    for (name, target) in view.local_tags() {
-        if !args.names.is_empty()
-            && !args
-                .names
-                .iter()
-                .any(|pattern| pattern.is_match(name.as_str()))
-        {
-            continue;
+    let commit_refs = view
+        .local_tags()
+        .filter(|(name, _)| {
+            args.names.is_empty()
+                || args
+                    .names
+                    .iter()
+                    .any(|pattern| pattern.is_match(name.as_str()))
+        })
+        .map(|(name, target)| CommitRef::local_only(name, target.clone()));
+    if output_format.is_serialized() {
+        let mut writer = SerializedItemWriter::new(formatter.as_mut(), output_format)?;
+        for commit_ref in commit_refs {
+            writer.write_item(commit_ref.as_ref())?;
+        }
+        writer.finish()?;
+    } else {
+        for commit_ref in commit_refs {
+            template.format(&commit_ref, formatter.as_mut())?;
         }
-        let commit_ref = CommitRef::local_only(name, target.clone());
-        template.format(&commit_ref, formatter.as_mut())?;
     }
 
     Ok(())
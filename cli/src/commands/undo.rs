@@ -16,8 +16,12 @@ use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::OperationId;
+use jj_lib::operation::Operation;
+use jj_lib::repo::RepoLoader;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::LogContentFormat;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::CommandError;
 use crate::command_error::internal_error;
 use crate::command_error::user_error;
@@ -25,11 +29,14 @@ use crate::command_error::user_error_with_hint;
 #[cfg(feature = "git")]
 use crate::commands::git::is_push_operation;
 use crate::commands::operation::DEFAULT_REVERT_WHAT;
+use crate::commands::operation::OpDiffFilter;
 use crate::commands::operation::RevertWhatToRestore;
 use crate::commands::operation::revert::OperationRevertArgs;
 use crate::commands::operation::revert::cmd_op_revert;
+use crate::commands::operation::show_op_diff;
 use crate::commands::operation::view_with_desired_portions_restored;
 use crate::complete;
+use crate::graphlog::GraphStyle;
 use crate::ui::Ui;
 
 /// Undo the last operation
@@ -41,6 +48,13 @@ use crate::ui::Ui;
 /// There is also a complementary `jj redo` command that would instead move in
 /// the direction of the future after one or more `jj undo`s.
 ///
+/// `jj undo --steps N` undoes the last N operations at once, skipping snapshot
+/// operations. This is recorded as a single operation, which a single `jj
+/// redo` will redo.
+///
+/// Undoing a `jj git push` operation requires `--force`, since the state of the
+/// remote can't be undone.
+///
 /// Use `jj op log` to visualize the log of past operations, including a
 /// detailed description of any past undo/redo operations. See also `jj op
 /// restore` to explicitly restore an older operation by its id (available in
@@ -56,6 +70,13 @@ pub struct UndoArgs {
     #[arg(default_value = "@", add = ArgValueCandidates::new(complete::operations))]
     operation: String,
 
+    /// Number of operations to undo
+    ///
+    /// Snapshot operations are undone along with the operations in between,
+    /// but they aren't counted.
+    #[arg(long, value_name = "N", conflicts_with = "operation")]
+    steps: Option<usize>,
+
     /// (deprecated, use `jj op revert --what`)
     ///
     /// What portions of the local state to restore (can be repeated)
@@ -63,6 +84,17 @@ pub struct UndoArgs {
     /// This option is EXPERIMENTAL.
     #[arg(long, value_enum, hide = true, default_values_t = DEFAULT_REVERT_WHAT)]
     what: Vec<RevertWhatToRestore>,
+
+    /// Show what would be restored without undoing anything
+    #[arg(long)]
+    preview: bool,
+
+    /// Undo push operations
+    ///
+    /// The state of the remote can't be undone, so undoing a push operation
+    /// often leads to conflicted bookmarks.
+    #[arg(long)]
+    force: bool,
 }
 
 pub(crate) const UNDO_OP_DESC_PREFIX: &str = "undo: restore to operation ";
@@ -90,10 +122,14 @@ pub fn cmd_undo(ui: &mut Ui, command: &CommandHelper, args: &UndoArgs) -> Result
         };
         return cmd_op_revert(ui, command, &args);
     }
+    if args.steps == Some(0) {
+        return Err(user_error(
+            "The number of operations to undo must be positive",
+        ));
+    }
 
     let mut workspace_command = command.workspace_helper(ui)?;
-
-    let mut op_to_undo = workspace_command.resolve_single_op(&args.operation)?;
+    let repo_loader = workspace_command.repo().loader();
 
     // Growing the "undo-stack" works as follows. See also the
     // [redo-stack](./redo.rs), which works in a similar way.
@@ -135,62 +171,59 @@ pub fn cmd_undo(ui: &mut Ui, command: &CommandHelper, args: &UndoArgs) -> Result
     // restored (as opposed to C). The undo-stack spanning from F to B was
     // "jumped over".
     //
-    if let Some(id_of_restored_op) = op_to_undo
-        .metadata()
-        .description
-        .strip_prefix(UNDO_OP_DESC_PREFIX)
-    {
-        let Some(id_of_restored_op) = OperationId::try_from_hex(id_of_restored_op) else {
-            return Err(internal_error(
-                "Failed to parse ID of restored operation in undo-stack",
-            ));
-        };
-        op_to_undo = workspace_command
-            .repo()
-            .loader()
-            .load_operation(&id_of_restored_op)?;
-    }
-    #[cfg(feature = "git")]
-    if is_push_operation(&op_to_undo) {
-        writeln!(
-            ui.warning_default(),
-            "Undoing a push operation often leads to conflicted bookmarks."
-        )?;
-        writeln!(ui.hint_default(), "To avoid this, run `jj redo` now.")?;
-    };
+    // Undoing multiple operations at once walks the undo-stack in the same way
+    // as repeated `undo`s would, without recording the intermediate
+    // undo-operations.
+    //
+    let mut op_to_restore = workspace_command.repo().operation().clone();
+    for _ in 0..args.steps.unwrap_or(1) {
+        loop {
+            let op_to_undo = resolve_undo_operation(repo_loader, op_to_restore)?;
+            #[cfg(feature = "git")]
+            if is_push_operation(&op_to_undo) {
+                if !args.force {
+                    return Err(user_error_with_hint(
+                        "Refusing to undo a push operation since the state of the remote can't \
+                         be undone",
+                        "Use --force to undo it anyway",
+                    ));
+                }
+                writeln!(
+                    ui.warning_default(),
+                    "Undoing a push operation often leads to conflicted bookmarks."
+                )?;
+                writeln!(ui.hint_default(), "To avoid this, run `jj redo` now.")?;
+            };
 
-    let mut op_to_restore = match op_to_undo.parents().at_most_one() {
-        Ok(Some(parent_of_op_to_undo)) => parent_of_op_to_undo?,
-        Ok(None) => return Err(user_error("Cannot undo root operation")),
-        Err(_) => {
-            return Err(user_error_with_hint(
-                "Cannot undo a merge operation",
-                "Consider using `jj op restore` instead",
-            ));
+            let parent_of_op_to_undo = match op_to_undo.parents().at_most_one() {
+                Ok(Some(parent_of_op_to_undo)) => parent_of_op_to_undo?,
+                Ok(None) => return Err(user_error("Cannot undo root operation")),
+                Err(_) => {
+                    return Err(user_error_with_hint(
+                        "Cannot undo a merge operation",
+                        "Consider using `jj op restore` instead",
+                    ));
+                }
+            };
+
+            // Avoid the creation of a linked list by restoring to the original
+            // operation directly, if we're about to restore an undo-operation.
+            // If we didn't to this, repeated calls of `jj new ; jj undo` would
+            // create an ever-growing linked list of undo-operations that
+            // restore each other. Calling `jj undo` one more time would have to
+            // restore to the operation at the very beginning of the linked
+            // list, which would require walking the entire thing unnecessarily.
+            op_to_restore = resolve_undo_operation(repo_loader, parent_of_op_to_undo)?;
+
+            // Snapshots aren't counted if the number of operations is given.
+            if args.steps.is_none() || !op_to_undo.metadata().is_snapshot {
+                break;
+            }
         }
-    };
+    }
 
-    // Avoid the creation of a linked list by restoring to the original
-    // operation directly, if we're about to restore an undo-operation. If we
-    // didn't to this, repeated calls of `jj new ; jj undo` would create an
-    // ever-growing linked list of undo-operations that restore each other.
-    // Calling `jj undo` one more time would have to restore to the operation
-    // at the very beginning of the linked list, which would require walking the
-    // entire thing unnecessarily.
-    if let Some(original_op) = op_to_restore
-        .metadata()
-        .description
-        .strip_prefix(UNDO_OP_DESC_PREFIX)
-    {
-        let Some(id_of_original_op) = OperationId::try_from_hex(original_op) else {
-            return Err(internal_error(
-                "Failed to parse ID of restored operation in undo-stack",
-            ));
-        };
-        op_to_restore = workspace_command
-            .repo()
-            .loader()
-            .load_operation(&id_of_original_op)?;
+    if args.preview {
+        return show_restore_preview(ui, &workspace_command, &op_to_restore);
     }
 
     let mut tx = workspace_command.start_transaction();
@@ -213,3 +246,68 @@ pub fn cmd_undo(ui: &mut Ui, command: &CommandHelper, args: &UndoArgs) -> Result
 
     Ok(())
 }
+
+/// Returns the operation restored by `op` if it is an undo-operation, or `op`
+/// itself otherwise.
+fn resolve_undo_operation(
+    repo_loader: &RepoLoader,
+    op: Operation,
+) -> Result<Operation, CommandError> {
+    let Some(id_of_restored_op) = op.metadata().description.strip_prefix(UNDO_OP_DESC_PREFIX)
+    else {
+        return Ok(op);
+    };
+    let Some(id_of_restored_op) = OperationId::try_from_hex(id_of_restored_op) else {
+        return Err(internal_error(
+            "Failed to parse ID of restored operation in undo-stack",
+        ));
+    };
+    Ok(repo_loader.load_operation(&id_of_restored_op)?)
+}
+
+/// Shows the changes that restoring `op_to_restore` would make to the repo.
+pub(crate) fn show_restore_preview(
+    ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    op_to_restore: &Operation,
+) -> Result<(), CommandError> {
+    let workspace_env = workspace_command.env();
+    let settings = workspace_command.settings();
+    let current_repo = workspace_command.repo();
+    let restored_repo = current_repo.loader().load_at(op_to_restore)?;
+
+    let id_prefix_context = workspace_env.new_id_prefix_context();
+    let commit_summary_template = {
+        let language =
+            workspace_env.commit_template_language(current_repo.as_ref(), &id_prefix_context);
+        let text = settings.get_string("templates.commit_summary")?;
+        workspace_env
+            .parse_template(ui, &language, &text)?
+            .labeled(["op_diff", "commit"])
+    };
+    let graph_style = GraphStyle::from_settings(settings)?;
+    let with_content_format = LogContentFormat::new(ui, settings)?;
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    write!(formatter, "Would restore to operation: ")?;
+    workspace_command
+        .operation_summary_template()
+        .labeled(["op_diff"])
+        .format(op_to_restore, formatter.as_mut())?;
+    writeln!(formatter)?;
+    // The restored operation is an ancestor of the current operation, so the
+    // current index contains all commits of both repos.
+    show_op_diff(
+        ui,
+        formatter.as_mut(),
+        current_repo.as_ref(),
+        current_repo,
+        &restored_repo,
+        &commit_summary_template,
+        Some(graph_style),
+        &with_content_format,
+        None,
+        &OpDiffFilter::default(),
+    )
+}
@@ -12,21 +12,49 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
 use std::slice;
 use std::time::Duration;
 use std::time::SystemTime;
 
+use jj_lib::backend::MillisSinceEpoch;
+use jj_lib::backend::Timestamp;
+use jj_lib::op_walk;
+use jj_lib::op_walk::OperationRetentionPolicy;
 use jj_lib::repo::Repo as _;
+use jj_lib::settings::HumanByteSize;
+use jj_lib::transaction::CONFLICTED_BOOKMARKS_TAG;
+use jj_lib::transaction::CONFLICTED_WORKSPACES_TAG;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::CommandError;
 use crate::command_error::user_error;
+use crate::commands::operation::compact_snapshot_operations;
+use crate::commands::operation::update_rewritten_op_heads;
 use crate::ui::Ui;
 
 /// Run backend-dependent garbage collection.
 ///
 /// To garbage-collect old operations and the commits/objects referenced by
 /// them, run `jj op abandon ..<some old operation>` before `jj util gc`.
+///
+/// If `gc.compact-snapshots` is enabled, runs of consecutive snapshot
+/// operations older than the time threshold are folded first, as if by
+/// `jj op compact`.
+///
+/// If `gc.operations.prune` is enabled or `--prune-operations` is specified,
+/// old operations are abandoned according to the retention policy configured
+/// by `gc.operations.keep-all-days` and `gc.operations.keep-daily-weeks`.
+/// The retention period is counted from the current time, or from the
+/// `debug.operation-timestamp` if configured. Tagged operations and the
+/// operation of the current workspace are always kept. Since the operations
+/// the other workspaces are at aren't known, operations aren't pruned if the
+/// repo has more than one workspace.
 #[derive(clap::Args, Clone, Debug)]
 pub struct UtilGcArgs {
     /// Time threshold
@@ -39,6 +67,10 @@ pub struct UtilGcArgs {
     /// release.
     #[arg(long)]
     expire: Option<String>,
+
+    /// Abandon old operations according to the retention policy
+    #[arg(long)]
+    prune_operations: bool,
 }
 
 pub fn cmd_util_gc(
@@ -56,11 +88,139 @@ pub fn cmd_util_gc(
         Some("now") => SystemTime::now() - Duration::ZERO,
         _ => return Err(user_error("--expire only accepts 'now'")),
     };
+    if command.settings().get_bool("gc.compact-snapshots")? {
+        let cutoff = keep_newer
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        let folded_count =
+            compact_snapshot_operations(ui, command, MillisSinceEpoch(cutoff.try_into().unwrap()))?;
+        if folded_count > 0 {
+            writeln!(ui.status(), "Folded {folded_count} snapshot operations.")?;
+        }
+    }
+    if args.prune_operations || command.settings().get_bool("gc.operations.prune")? {
+        prune_operations(ui, command, args.prune_operations)?;
+    }
     let workspace_command = command.workspace_helper(ui)?;
 
     let repo = workspace_command.repo();
+    let size_paths = storage_paths(&workspace_command);
+    let old_size = disk_usage(&size_paths)?;
     repo.op_store()
         .gc(slice::from_ref(repo.op_id()), keep_newer)?;
     repo.store().gc(repo.index(), keep_newer)?;
+    let new_size = disk_usage(&size_paths)?;
+    writeln!(
+        ui.status(),
+        "Reclaimed {} of disk space.",
+        HumanByteSize(old_size.saturating_sub(new_size))
+    )?;
+    Ok(())
+}
+
+/// Abandons the operations which aren't retained by the configured policy.
+///
+/// If the repo has more than one workspace, fails if `explicit`, or prints a
+/// warning and prunes nothing otherwise.
+fn prune_operations(ui: &Ui, command: &CommandHelper, explicit: bool) -> Result<(), CommandError> {
+    let settings = command.settings();
+    let days = |key: &'static str| -> Result<u64, CommandError> {
+        let value: u64 = settings.get(key)?;
+        Ok(value.saturating_mul(86400))
+    };
+    let now = settings
+        .operation_timestamp()
+        .unwrap_or_else(Timestamp::now)
+        .timestamp;
+    let threshold = |secs: u64| {
+        let millis = i64::try_from(secs.saturating_mul(1000)).unwrap_or(i64::MAX);
+        MillisSinceEpoch(now.0.saturating_sub(millis))
+    };
+    let keep_all_secs = days("gc.operations.keep-all-days")?;
+    let keep_daily_secs =
+        keep_all_secs.saturating_add(days("gc.operations.keep-daily-weeks")?.saturating_mul(7));
+
+    // Don't load the repo so that the operations can be rewritten without
+    // snapshotting the working copy.
+    let mut workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let op_store = repo_loader.op_store().clone();
+    let current_head_ops =
+        op_walk::get_current_head_ops(&op_store, repo_loader.op_heads_store().as_ref())?;
+    // The working-copy operations of the other workspaces are recorded in
+    // their working copies, so they can't be protected from here.
+    for op in &current_head_ops {
+        if op.view()?.wc_commit_ids().len() > 1 {
+            let message = "Cannot prune operations in a repo with multiple workspaces";
+            if explicit {
+                return Err(user_error(message));
+            }
+            writeln!(ui.warning_default(), "{message}")?;
+            return Ok(());
+        }
+    }
+    let policy = OperationRetentionPolicy {
+        keep_all_since: threshold(keep_all_secs),
+        keep_daily_since: threshold(keep_daily_secs),
+        // These tags are recorded automatically, so the operations aren't
+        // considered tagged by the user.
+        ignored_tag_names: HashSet::from([
+            "args".to_owned(),
+            CONFLICTED_BOOKMARKS_TAG.to_owned(),
+            CONFLICTED_WORKSPACES_TAG.to_owned(),
+        ]),
+        kept_ids: HashSet::from([workspace.working_copy().operation_id().clone()]),
+    };
+    let expired_ids =
+        op_walk::select_expired_operations(op_store.as_ref(), &current_head_ops, &policy)?;
+    if expired_ids.is_empty() {
+        return Ok(());
+    }
+    let stats = op_walk::abandon_operations(op_store.as_ref(), &expired_ids, &current_head_ops)?;
+    writeln!(
+        ui.status(),
+        "Pruned {} operations and reparented {} descendant operations.",
+        stats.unreachable_count,
+        stats.rewritten_count,
+    )?;
+    update_rewritten_op_heads(
+        ui,
+        command,
+        &mut workspace,
+        &current_head_ops,
+        &stats.new_head_ids,
+    )?;
     Ok(())
 }
+
+/// Returns the directories where the repo data is stored.
+fn storage_paths(workspace_command: &WorkspaceCommandHelper) -> Vec<&Path> {
+    let repo_path = workspace_command.repo_path();
+    let mut paths = vec![repo_path];
+    // The backing Git repo may be located outside of the repo directory.
+    if let Ok(git_backend) = jj_lib::git::get_git_backend(workspace_command.repo().store()) {
+        let git_repo_path = git_backend.git_repo_path();
+        if !git_repo_path.starts_with(repo_path) {
+            paths.push(git_repo_path);
+        }
+    }
+    paths
+}
+
+fn disk_usage(paths: &[&Path]) -> io::Result<u64> {
+    fn dir_size(path: &Path) -> io::Result<u64> {
+        let mut size = 0;
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                size += dir_size(&entry.path())?;
+            } else {
+                size += metadata.len();
+            }
+        }
+        Ok(size)
+    }
+    paths.iter().map(|path| dir_size(path)).sum()
+}
@@ -0,0 +1,129 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io::Write as _;
+use std::iter;
+
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::op_store::OpStore;
+use jj_lib::op_store::RootOperationData;
+use jj_lib::op_walk;
+use jj_lib::simple_op_store::SimpleOpStore;
+use jj_lib::table_op_store::TableOpStore;
+use pollster::FutureExt as _;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::cli_error;
+use crate::command_error::internal_error;
+use crate::commands::operation::update_rewritten_op_heads;
+use crate::ui::Ui;
+
+/// Convert the operation log to another storage format
+///
+/// The format of new repos can be chosen by the `operation.store` setting.
+/// For example, `jj git init --config operation.store=table` creates a repo
+/// which stores the operation log in the "table" format.
+///
+/// Other jj commands shouldn't be run in the repo while the operation log is
+/// being converted. Operations which aren't reachable from the current
+/// operation heads aren't copied.
+#[derive(clap::Args, Clone, Debug)]
+pub struct UtilMigrateOpStoreArgs {
+    /// The storage format to convert the operation log to
+    #[arg(value_enum)]
+    format: OpStoreFormat,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum OpStoreFormat {
+    /// Store each operation and view in a separate file
+    Simple,
+    /// Store operations and views in a small number of segment files
+    Table,
+}
+
+pub fn cmd_util_migrate_op_store(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &UtilMigrateOpStoreArgs,
+) -> Result<(), CommandError> {
+    // Don't load the repo so that the operation log isn't modified while
+    // copying.
+    let mut workspace = command.load_workspace()?;
+    if command.global_args().at_operation.is_some() {
+        return Err(cli_error("--at-op is not respected"));
+    }
+    let repo_loader = workspace.repo_loader();
+    let op_store = repo_loader.op_store();
+    let store_name = match args.format {
+        OpStoreFormat::Simple => SimpleOpStore::name(),
+        OpStoreFormat::Table => TableOpStore::name(),
+    };
+    if op_store.name() == store_name {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+    // Block concurrent commands until the operation heads are updated.
+    let op_heads_store = repo_loader.op_heads_store().clone();
+    let _lock = op_heads_store.lock().block_on()?;
+    let current_head_ops = op_walk::get_current_head_ops(op_store, op_heads_store.as_ref())?;
+
+    let repo_path = workspace.repo_path();
+    let store_path = repo_path.join("op_store");
+    let new_store_path = repo_path.join("op_store.new");
+    let old_store_path = repo_path.join("op_store.old");
+    fs::create_dir(&new_store_path).context(&new_store_path)?;
+    let root_data = RootOperationData {
+        root_commit_id: repo_loader.store().root_commit_id().clone(),
+    };
+    let new_store: Box<dyn OpStore> = match args.format {
+        OpStoreFormat::Simple => {
+            Box::new(SimpleOpStore::init(&new_store_path, root_data).map_err(internal_error)?)
+        }
+        OpStoreFormat::Table => {
+            Box::new(TableOpStore::init(&new_store_path, root_data).map_err(internal_error)?)
+        }
+    };
+    let type_path = new_store_path.join("type");
+    fs::write(&type_path, new_store.name()).context(&type_path)?;
+    let stats = op_walk::copy_operations(&current_head_ops, new_store.as_ref())?;
+    drop(new_store);
+
+    // If interrupted, the swap will be finished or rolled back when the repo
+    // is loaded next time. See RepoLoader::init_from_file_system().
+    fs::rename(&store_path, &old_store_path).context(&store_path)?;
+    fs::rename(&new_store_path, &store_path).context(&new_store_path)?;
+    if iter::zip(&current_head_ops, &stats.new_head_ids).any(|(old, new_id)| old.id() != new_id) {
+        update_rewritten_op_heads(
+            ui,
+            command,
+            &mut workspace,
+            &current_head_ops,
+            &stats.new_head_ids,
+        )?;
+    }
+    fs::remove_dir_all(&old_store_path).context(&old_store_path)?;
+    writeln!(
+        ui.status(),
+        "Converted {} operations to the {} format.",
+        stats.copied_count,
+        match args.format {
+            OpStoreFormat::Simple => "simple",
+            OpStoreFormat::Table => "table",
+        },
+    )?;
+    Ok(())
+}
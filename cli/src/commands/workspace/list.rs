@@ -20,6 +20,8 @@ use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::commit_templater::WorkspaceRef;
 use crate::complete;
+use crate::output_format::OutputFormatArgs;
+use crate::output_format::SerializedItemWriter;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -39,6 +41,8 @@ pub struct WorkspaceListArgs {
     ///     https://jj-vcs.github.io/jj/latest/templates/
     #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::template_aliases))]
     template: Option<String>,
+    #[command(flatten)]
+    output_format: OutputFormatArgs,
 }
 
 #[instrument(skip_all)]
@@ -47,6 +51,8 @@ pub fn cmd_workspace_list(
     command: &CommandHelper,
     args: &WorkspaceListArgs,
 ) -> Result<(), CommandError> {
+    let output_format = args.output_format.format();
+    output_format.check_text_option("--template", args.template.is_some())?;
     let workspace_command = command.workspace_helper(ui)?;
 
     let template: TemplateRenderer<WorkspaceRef> = {
@@ -67,11 +73,24 @@ pub fn cmd_workspace_list(
     let repo = workspace_command.repo();
     let mut formatter = ui.stdout_formatter();
 
-    for (name, wc_commit_id) in repo.view().wc_commit_ids() {
-        let commit = repo.store().get_commit(wc_commit_id)?;
-        let ws_ref = WorkspaceRef::new(name.clone(), commit);
-
-        template.format(&ws_ref, formatter.as_mut())?;
+    let ws_refs = repo
+        .view()
+        .wc_commit_ids()
+        .iter()
+        .map(|(name, wc_commit_id)| {
+            let commit = repo.store().get_commit(wc_commit_id)?;
+            Ok::<_, CommandError>(WorkspaceRef::new(name.clone(), commit))
+        });
+    if output_format.is_serialized() {
+        let mut writer = SerializedItemWriter::new(formatter.as_mut(), output_format)?;
+        for ws_ref in ws_refs {
+            writer.write_item(&ws_ref?)?;
+        }
+        writer.finish()?;
+    } else {
+        for ws_ref in ws_refs {
+            template.format(&ws_ref?, formatter.as_mut())?;
+        }
     }
 
     Ok(())
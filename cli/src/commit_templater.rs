@@ -36,6 +36,10 @@ use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
 use jj_lib::conflicts;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::ConflictMaterializeOptions;
+use jj_lib::conflicts::MaterializedTreeValue;
+use jj_lib::conflicts::materialize_merge_result_to_bytes;
+use jj_lib::conflicts::materialize_tree_value;
 use jj_lib::copies::CopiesTreeDiffEntry;
 use jj_lib::copies::CopiesTreeDiffEntryPath;
 use jj_lib::copies::CopyRecords;
@@ -96,10 +100,13 @@ use crate::template_builder::BuildContext;
 use crate::template_builder::CoreTemplateBuildFnTable;
 use crate::template_builder::CoreTemplatePropertyKind;
 use crate::template_builder::CoreTemplatePropertyVar;
+use crate::template_builder::SharedPropertyFn;
 use crate::template_builder::TemplateBuildMethodFnMap;
 use crate::template_builder::TemplateLanguage;
 use crate::template_builder::expect_stringify_expression;
 use crate::template_builder::merge_fn_map;
+use crate::template_builder::share_memoized_property;
+use crate::template_builder::share_property;
 use crate::template_parser;
 use crate::template_parser::ExpressionNode;
 use crate::template_parser::FunctionCallNode;
@@ -334,6 +341,13 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(self, diagnostics, build_ctx, property, function)
             }
+            CommitTemplatePropertyKind::TreeEntryOpt(property) => {
+                let type_name = "TreeEntry";
+                let table = &self.build_fn_table.tree_entry_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                let inner_property = property.try_unwrap(type_name).into_dyn();
+                build(self, diagnostics, build_ctx, inner_property, function)
+            }
             CommitTemplatePropertyKind::TreeEntryList(property) => {
                 let table = &self.build_fn_table.tree_entry_list_methods;
                 let build = template_parser::lookup_method(type_name, table, function)?;
@@ -429,6 +443,7 @@ pub enum CommitTemplatePropertyKind<'repo> {
     TreeDiffEntry(BoxedTemplateProperty<'repo, TreeDiffEntry>),
     TreeDiffEntryList(BoxedTemplateProperty<'repo, Vec<TreeDiffEntry>>),
     TreeEntry(BoxedTemplateProperty<'repo, TreeEntry>),
+    TreeEntryOpt(BoxedTemplateProperty<'repo, Option<TreeEntry>>),
     TreeEntryList(BoxedTemplateProperty<'repo, Vec<TreeEntry>>),
     DiffStats(BoxedTemplateProperty<'repo, DiffStatsFormatted<'repo>>),
     CryptographicSignatureOpt(BoxedTemplateProperty<'repo, Option<CryptographicSignature>>),
@@ -461,6 +476,7 @@ template_builder::impl_property_wrappers!(<'repo> CommitTemplatePropertyKind<'re
     TreeDiffEntry(TreeDiffEntry),
     TreeDiffEntryList(Vec<TreeDiffEntry>),
     TreeEntry(TreeEntry),
+    TreeEntryOpt(Option<TreeEntry>),
     TreeEntryList(Vec<TreeEntry>),
     DiffStats(DiffStatsFormatted<'repo>),
     CryptographicSignatureOpt(Option<CryptographicSignature>),
@@ -503,6 +519,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::TreeDiffEntry(_) => "TreeDiffEntry",
             Self::TreeDiffEntryList(_) => "List<TreeDiffEntry>",
             Self::TreeEntry(_) => "TreeEntry",
+            Self::TreeEntryOpt(_) => "Option<TreeEntry>",
             Self::TreeEntryList(_) => "List<TreeEntry>",
             Self::DiffStats(_) => "DiffStats",
             Self::CryptographicSignatureOpt(_) => "Option<CryptographicSignature>",
@@ -539,6 +556,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::TreeDiffEntry(_) => None,
             Self::TreeDiffEntryList(property) => Some(property.map(|l| !l.is_empty()).into_dyn()),
             Self::TreeEntry(_) => None,
+            Self::TreeEntryOpt(property) => Some(property.map(|opt| opt.is_some()).into_dyn()),
             Self::TreeEntryList(property) => Some(property.map(|l| !l.is_empty()).into_dyn()),
             Self::DiffStats(_) => None,
             Self::CryptographicSignatureOpt(property) => {
@@ -597,10 +615,11 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::CommitId(property) => Some(property.into_serialize()),
             Self::ShortestIdPrefix(property) => Some(property.into_serialize()),
             Self::TreeDiff(_) => None,
-            Self::TreeDiffEntry(_) => None,
-            Self::TreeDiffEntryList(_) => None,
-            Self::TreeEntry(_) => None,
-            Self::TreeEntryList(_) => None,
+            Self::TreeDiffEntry(property) => Some(property.into_serialize()),
+            Self::TreeDiffEntryList(property) => Some(property.into_serialize()),
+            Self::TreeEntry(property) => Some(property.into_serialize()),
+            Self::TreeEntryOpt(property) => Some(property.into_serialize()),
+            Self::TreeEntryList(property) => Some(property.into_serialize()),
             Self::DiffStats(_) => None,
             Self::CryptographicSignatureOpt(_) => None,
             Self::AnnotationLine(_) => None,
@@ -634,6 +653,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::TreeDiffEntry(_) => None,
             Self::TreeDiffEntryList(_) => None,
             Self::TreeEntry(_) => None,
+            Self::TreeEntryOpt(_) => None,
             Self::TreeEntryList(_) => None,
             Self::DiffStats(property) => Some(property.into_template()),
             Self::CryptographicSignatureOpt(_) => None,
@@ -701,6 +721,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             (Self::TreeDiffEntry(_), _) => None,
             (Self::TreeDiffEntryList(_), _) => None,
             (Self::TreeEntry(_), _) => None,
+            (Self::TreeEntryOpt(_), _) => None,
             (Self::TreeEntryList(_), _) => None,
             (Self::DiffStats(_), _) => None,
             (Self::CryptographicSignatureOpt(_), _) => None,
@@ -741,6 +762,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             (Self::TreeDiffEntry(_), _) => None,
             (Self::TreeDiffEntryList(_), _) => None,
             (Self::TreeEntry(_), _) => None,
+            (Self::TreeEntryOpt(_), _) => None,
             (Self::TreeEntryList(_), _) => None,
             (Self::DiffStats(_), _) => None,
             (Self::CryptographicSignatureOpt(_), _) => None,
@@ -749,6 +771,63 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             (Self::TrailerList(_), _) => None,
         }
     }
+
+    fn into_shared(self) -> SharedPropertyFn<'repo, Self> {
+        match self {
+            Self::Core(property) => {
+                let make = property.into_shared();
+                Box::new(move || Self::Core(make()))
+            }
+            Self::Operation(property) => {
+                let make = property.into_shared();
+                Box::new(move || Self::Operation(make()))
+            }
+            Self::Commit(property) => share_memoized_property(property, Self::Commit),
+            Self::CommitOpt(property) => share_memoized_property(property, Self::CommitOpt),
+            Self::CommitList(property) => share_memoized_property(property, Self::CommitList),
+            Self::CommitEvolutionEntry(property) => {
+                share_memoized_property(property, Self::CommitEvolutionEntry)
+            }
+            Self::CommitRef(property) => share_memoized_property(property, Self::CommitRef),
+            Self::CommitRefOpt(property) => share_memoized_property(property, Self::CommitRefOpt),
+            Self::CommitRefList(property) => share_memoized_property(property, Self::CommitRefList),
+            Self::WorkspaceRef(property) => share_memoized_property(property, Self::WorkspaceRef),
+            Self::WorkspaceRefOpt(property) => {
+                share_memoized_property(property, Self::WorkspaceRefOpt)
+            }
+            Self::WorkspaceRefList(property) => {
+                share_memoized_property(property, Self::WorkspaceRefList)
+            }
+            Self::RefSymbol(property) => share_memoized_property(property, Self::RefSymbol),
+            Self::RefSymbolOpt(property) => share_memoized_property(property, Self::RefSymbolOpt),
+            Self::RepoPath(property) => share_memoized_property(property, Self::RepoPath),
+            Self::RepoPathOpt(property) => share_memoized_property(property, Self::RepoPathOpt),
+            Self::ChangeId(property) => share_memoized_property(property, Self::ChangeId),
+            Self::CommitId(property) => share_memoized_property(property, Self::CommitId),
+            Self::ShortestIdPrefix(property) => {
+                share_memoized_property(property, Self::ShortestIdPrefix)
+            }
+            // TreeDiff isn't cloneable, but its evaluation is cheap as the
+            // diff is computed lazily.
+            Self::TreeDiff(property) => share_property(property, Self::TreeDiff),
+            Self::TreeDiffEntry(property) => share_memoized_property(property, Self::TreeDiffEntry),
+            Self::TreeDiffEntryList(property) => {
+                share_memoized_property(property, Self::TreeDiffEntryList)
+            }
+            Self::TreeEntry(property) => share_memoized_property(property, Self::TreeEntry),
+            Self::TreeEntryOpt(property) => share_memoized_property(property, Self::TreeEntryOpt),
+            Self::TreeEntryList(property) => share_memoized_property(property, Self::TreeEntryList),
+            Self::DiffStats(property) => share_memoized_property(property, Self::DiffStats),
+            Self::CryptographicSignatureOpt(property) => {
+                share_memoized_property(property, Self::CryptographicSignatureOpt)
+            }
+            Self::AnnotationLine(property) => {
+                share_memoized_property(property, Self::AnnotationLine)
+            }
+            Self::Trailer(property) => share_memoized_property(property, Self::Trailer),
+            Self::TrailerList(property) => share_memoized_property(property, Self::TrailerList),
+        }
+    }
 }
 
 impl<'repo> OperationTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo> {}
@@ -1253,6 +1332,22 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "file",
+        |language, diagnostics, _build_ctx, self_property, function| {
+            let [path_node] = function.expect_exact_arguments()?;
+            let path = expect_file_path_literal(diagnostics, path_node, language.path_converter)?;
+            let out_property = self_property.and_then(move |commit| {
+                let value = commit.tree()?.path_value(&path)?;
+                let entry = TreeEntry {
+                    path: path.clone(),
+                    value,
+                };
+                Ok((!entry.value.is_absent()).then_some(entry))
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "root",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -1299,6 +1394,19 @@ fn expect_fileset_literal(
     })
 }
 
+fn expect_file_path_literal(
+    diagnostics: &mut TemplateDiagnostics,
+    node: &ExpressionNode,
+    path_converter: &RepoPathUiConverter,
+) -> Result<RepoPathBuf, TemplateParseError> {
+    template_parser::catch_aliases(diagnostics, node, |_diagnostics, node| {
+        let text = template_parser::expect_string_literal(node)?;
+        path_converter.parse_file_path(text).map_err(|err| {
+            TemplateParseError::expression("Invalid file path", node.span).with_source(err)
+        })
+    })
+}
+
 fn evaluate_revset_expression<'repo>(
     language: &CommitTemplateLanguage<'repo>,
     span: pest::Span<'_>,
@@ -1310,6 +1418,7 @@ fn evaluate_revset_expression<'repo>(
         repo,
         language.revset_parse_context.extensions.symbol_resolvers(),
         language.id_prefix_context,
+        language.revset_parse_context.date_pattern_context,
     );
     let revset = expression
         .resolve_user_expression(repo, &symbol_resolver)
@@ -2286,8 +2395,11 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                 })
                 .transpose()?;
             let path_converter = language.path_converter;
-            // No user configuration exists for diff stat.
-            let options = diff_util::DiffStatOptions::default();
+            let options =
+                diff_util::DiffStatOptions::from_settings(language.settings()).map_err(|err| {
+                    let message = "Failed to load diff settings";
+                    TemplateParseError::expression(message, function.name_span).with_source(err)
+                })?;
             let conflict_marker_style = language.conflict_marker_style;
             // TODO: cache and reuse stats within the current evaluation?
             let out_property = (self_property, width_property).and_then(move |(diff, width)| {
@@ -2360,6 +2472,23 @@ impl TreeDiffEntry {
     }
 }
 
+impl serde::Serialize for TreeDiffEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct as _;
+        // Absent source or target entries are serialized as null.
+        let present = |entry: TreeEntry| (!entry.value.is_absent()).then_some(entry);
+        let mut state = serializer.serialize_struct("TreeDiffEntry", 4)?;
+        state.serialize_field("path", &self.path.target)?;
+        state.serialize_field("status", self.status_label())?;
+        state.serialize_field("source", &present(self.clone().into_source_entry()))?;
+        state.serialize_field("target", &present(self.clone().into_target_entry()))?;
+        state.end()
+    }
+}
+
 fn builtin_tree_diff_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, TreeDiffEntry>
 {
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -2408,6 +2537,24 @@ pub struct TreeEntry {
     pub value: MergedTreeValue,
 }
 
+impl serde::Serialize for TreeEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct as _;
+        let mut state = serializer.serialize_struct("TreeEntry", 4)?;
+        state.serialize_field("path", &self.path)?;
+        state.serialize_field("conflict", &!self.value.is_resolved())?;
+        state.serialize_field("file_type", describe_file_type(&self.value))?;
+        state.serialize_field(
+            "executable",
+            &is_executable_file(&self.value).unwrap_or_default(),
+        )?;
+        state.end()
+    }
+}
+
 fn builtin_tree_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, TreeEntry> {
     // Not using maplit::hashmap!{} or custom declarative macro here because
     // code completion inside macro is quite restricted.
@@ -2446,9 +2593,62 @@ fn builtin_tree_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo,
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "content",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let store = language.repo.store();
+            let marker_style = language.conflict_marker_style;
+            let out_property = self_property
+                .and_then(move |entry| read_tree_entry_content(store, entry, marker_style));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "lines",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let store = language.repo.store();
+            let marker_style = language.conflict_marker_style;
+            let out_property = self_property.and_then(move |entry| {
+                let content = read_tree_entry_content(store, entry, marker_style)?;
+                Ok(content.lines().map(|l| l.to_owned()).collect_vec())
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
+/// Reads the file content of the `entry`. Conflicts are materialized with
+/// markers. Absent entry is read as empty.
+fn read_tree_entry_content(
+    store: &Store,
+    entry: TreeEntry,
+    marker_style: ConflictMarkerStyle,
+) -> Result<String, TemplatePropertyError> {
+    let materialized = materialize_tree_value(store, &entry.path, entry.value).block_on()?;
+    let content = match materialized {
+        MaterializedTreeValue::Absent => vec![],
+        MaterializedTreeValue::AccessDenied(err) => return Err(TemplatePropertyError(err)),
+        MaterializedTreeValue::File(mut file) => file.read_all(&entry.path).block_on()?,
+        MaterializedTreeValue::Symlink { target, .. } => target.into_bytes(),
+        MaterializedTreeValue::FileConflict(file) => {
+            let options = ConflictMaterializeOptions {
+                marker_style,
+                marker_len: None,
+                merge: store.merge_options().clone(),
+            };
+            materialize_merge_result_to_bytes(&file.contents, &options).into()
+        }
+        MaterializedTreeValue::OtherConflict { id } => id.describe().into_bytes(),
+        MaterializedTreeValue::GitSubmodule(_) | MaterializedTreeValue::Tree(_) => {
+            return Err(TemplatePropertyError("Not a file".into()));
+        }
+    };
+    Ok(String::from_utf8_lossy(&content).into_owned())
+}
+
 fn describe_file_type(value: &MergedTreeValue) -> &'static str {
     match value.as_resolved() {
         Some(Some(TreeValue::File { .. })) => "file",
@@ -2510,7 +2710,7 @@ fn builtin_diff_stats_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo,
     map
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct CryptographicSignature {
     commit: Commit,
 }
@@ -14,7 +14,10 @@
 
 use std::borrow::Cow;
 use std::cmp::max;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::io;
+use std::io::Cursor;
 use std::iter;
 use std::ops::Range;
 use std::path::Path;
@@ -22,10 +25,12 @@ use std::path::PathBuf;
 
 use bstr::BStr;
 use bstr::BString;
+use bstr::ByteSlice as _;
 use clap_complete::ArgValueCandidates;
 use futures::StreamExt as _;
 use futures::TryStreamExt as _;
 use futures::executor::block_on_stream;
+use futures::stream;
 use futures::stream::BoxStream;
 use itertools::Itertools as _;
 use jj_lib::backend::BackendError;
@@ -46,6 +51,7 @@ use jj_lib::copies::CopiesTreeDiffEntryPath;
 use jj_lib::copies::CopyOperation;
 use jj_lib::copies::CopyRecords;
 use jj_lib::diff::ContentDiff;
+use jj_lib::diff::DiffAlgorithm;
 use jj_lib::diff::DiffHunk;
 use jj_lib::diff::DiffHunkKind;
 use jj_lib::diff_presentation::DiffTokenType;
@@ -53,6 +59,7 @@ use jj_lib::diff_presentation::FileContent;
 use jj_lib::diff_presentation::LineCompareMode;
 use jj_lib::diff_presentation::diff_by_line;
 use jj_lib::diff_presentation::file_content_for_diff;
+use jj_lib::diff_presentation::find_moved_lines_by_mode;
 use jj_lib::diff_presentation::unified::DiffLineType;
 use jj_lib::diff_presentation::unified::UnifiedDiffError;
 use jj_lib::diff_presentation::unified::git_diff_part;
@@ -72,6 +79,7 @@ use jj_lib::merged_tree::MergedTree;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::InvalidRepoPathError;
 use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::rewrite::rebase_to_dest_parent;
 use jj_lib::settings::UserSettings;
@@ -79,6 +87,7 @@ use jj_lib::store::Store;
 use pollster::FutureExt as _;
 use thiserror::Error;
 use tracing::instrument;
+use unicode_width::UnicodeWidthChar as _;
 use unicode_width::UnicodeWidthStr as _;
 
 use crate::command_error::CommandError;
@@ -94,6 +103,11 @@ use crate::merge_tools::ExternalMergeTool;
 use crate::merge_tools::generate_diff;
 use crate::merge_tools::invoke_external_diff;
 use crate::merge_tools::new_utf8_temp_dir;
+use crate::syntax_highlight;
+use crate::syntax_highlight::DiffHighlighter;
+use crate::syntax_highlight::LineHighlighter;
+use crate::syntax_highlight::SyntaxHighlighter;
+use crate::syntax_highlight::SyntaxKind;
 use crate::templater::TemplateRenderer;
 use crate::text_util;
 use crate::ui::Ui;
@@ -101,7 +115,7 @@ use crate::ui::Ui;
 #[derive(clap::Args, Clone, Debug)]
 #[command(next_help_heading = "Diff Formatting Options")]
 #[command(group(clap::ArgGroup::new("short-format").args(&["summary", "stat", "types", "name_only"])))]
-#[command(group(clap::ArgGroup::new("long-format").args(&["git", "color_words"])))]
+#[command(group(clap::ArgGroup::new("long-format").args(&["git", "color_words", "side_by_side"])))]
 pub struct DiffFormatArgs {
     /// For each path, show only whether it was modified, added, or deleted
     #[arg(long, short)]
@@ -130,6 +144,9 @@ pub struct DiffFormatArgs {
     /// Show a word-level diff with changes indicated only by color
     #[arg(long)]
     pub color_words: bool,
+    /// Show a two-column diff with the left and right contents side by side
+    #[arg(long)]
+    pub side_by_side: bool,
     /// Generate diff by external command
     ///
     /// A builtin format can also be specified as `:<name>`. For example,
@@ -150,6 +167,45 @@ pub struct DiffFormatArgs {
     /// Ignore changes in amount of whitespace when comparing lines.
     #[arg(long, conflicts_with = "ignore_all_space")] // short = 'b'
     ignore_space_change: bool,
+    /// Don't count lines moved from one place to another in `--stat`
+    #[arg(long)]
+    ignore_moved: bool,
+    /// Label lines moved from one place to another in `--git` and
+    /// `--color-words` diffs
+    ///
+    /// Defaults to the `diff.git.color-moved` and
+    /// `diff.color-words.color-moved` config.
+    #[arg(long)]
+    color_moved: bool,
+    /// Algorithm to find unchanged lines
+    ///
+    /// Defaults to the `diff.algorithm` config.
+    #[arg(long, value_enum)]
+    diff_algorithm: Option<DiffAlgorithmArg>,
+}
+
+/// Algorithm to find unchanged lines.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+pub(crate) enum DiffAlgorithmArg {
+    /// Anchor on the least frequent lines
+    Default,
+    /// Anchor on lines that occur exactly once on both sides
+    Patience,
+    /// Anchor on the common region containing the least frequent lines
+    Histogram,
+    /// Find the minimal number of changed lines
+    Minimal,
+}
+
+impl From<DiffAlgorithmArg> for DiffAlgorithm {
+    fn from(arg: DiffAlgorithmArg) -> Self {
+        match arg {
+            DiffAlgorithmArg::Default => Self::Default,
+            DiffAlgorithmArg::Patience => Self::Patience,
+            DiffAlgorithmArg::Histogram => Self::Histogram,
+            DiffAlgorithmArg::Minimal => Self::Minimal,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -161,6 +217,7 @@ pub enum DiffFormat {
     NameOnly,
     Git(Box<UnifiedDiffOptions>),
     ColorWords(Box<ColorWordsDiffOptions>),
+    SideBySide(Box<SideBySideDiffOptions>),
     Tool(Box<ExternalMergeTool>),
 }
 
@@ -172,6 +229,7 @@ enum BuiltinFormatKind {
     NameOnly,
     Git,
     ColorWords,
+    SideBySide,
 }
 
 impl BuiltinFormatKind {
@@ -185,6 +243,7 @@ impl BuiltinFormatKind {
         Self::NameOnly,
         Self::Git,
         Self::ColorWords,
+        Self::SideBySide,
     ];
 
     fn from_name(name: &str) -> Result<Self, String> {
@@ -195,6 +254,7 @@ impl BuiltinFormatKind {
             "name-only" => Ok(Self::NameOnly),
             "git" => Ok(Self::Git),
             "color-words" => Ok(Self::ColorWords),
+            "side-by-side" => Ok(Self::SideBySide),
             _ => Err(format!("Invalid builtin diff format: {name}")),
         }
     }
@@ -218,6 +278,8 @@ impl BuiltinFormatKind {
             Some(Self::Git)
         } else if args.color_words {
             Some(Self::ColorWords)
+        } else if args.side_by_side {
+            Some(Self::SideBySide)
         } else {
             None
         }
@@ -226,7 +288,7 @@ impl BuiltinFormatKind {
     fn is_short(self) -> bool {
         match self {
             Self::Summary | Self::Stat | Self::Types | Self::NameOnly => true,
-            Self::Git | Self::ColorWords => false,
+            Self::Git | Self::ColorWords | Self::SideBySide => false,
         }
     }
 
@@ -238,6 +300,7 @@ impl BuiltinFormatKind {
             Self::NameOnly => "name-only",
             Self::Git => "git",
             Self::ColorWords => "color-words",
+            Self::SideBySide => "side-by-side",
         }
     }
 
@@ -249,7 +312,7 @@ impl BuiltinFormatKind {
         match self {
             Self::Summary => Ok(DiffFormat::Summary),
             Self::Stat => {
-                let mut options = DiffStatOptions::default();
+                let mut options = DiffStatOptions::from_settings(settings)?;
                 options.merge_args(args);
                 Ok(DiffFormat::Stat(Box::new(options)))
             }
@@ -265,6 +328,11 @@ impl BuiltinFormatKind {
                 options.merge_args(args);
                 Ok(DiffFormat::ColorWords(Box::new(options)))
             }
+            Self::SideBySide => {
+                let mut options = SideBySideDiffOptions::from_settings(settings)?;
+                options.merge_args(args);
+                Ok(DiffFormat::SideBySide(Box::new(options)))
+            }
         }
     }
 }
@@ -298,11 +366,13 @@ pub fn diff_formats_for(
     args: &DiffFormatArgs,
 ) -> Result<Vec<DiffFormat>, CommandError> {
     let formats = diff_formats_from_args(settings, args)?;
-    if formats.iter().all(|f| f.is_none()) {
-        Ok(vec![default_diff_format(settings, args)?])
+    let formats = if formats.iter().all(|f| f.is_none()) {
+        vec![default_diff_format(settings, args)?]
     } else {
-        Ok(formats.into_iter().flatten().collect())
-    }
+        formats.into_iter().flatten().collect()
+    };
+    ensure_format_args_used(args, &formats)?;
+    Ok(formats)
 }
 
 /// Returns a list of requested diff formats for log-like commands, which may be
@@ -322,7 +392,29 @@ pub fn diff_formats_for_log(
             long_format = Some(default_format);
         }
     }
-    Ok([short_format, long_format].into_iter().flatten().collect())
+    let formats = [short_format, long_format]
+        .into_iter()
+        .flatten()
+        .collect_vec();
+    ensure_format_args_used(args, &formats)?;
+    Ok(formats)
+}
+
+/// Rejects flags that wouldn't affect any of the requested `formats`.
+fn ensure_format_args_used(
+    args: &DiffFormatArgs,
+    formats: &[DiffFormat],
+) -> Result<(), CommandError> {
+    if args.ignore_moved && !formats.iter().any(|f| matches!(f, DiffFormat::Stat(_))) {
+        return Err(cli_error("--ignore-moved can only be used with --stat"));
+    }
+    let labels_moved = |f: &DiffFormat| matches!(f, DiffFormat::Git(_) | DiffFormat::ColorWords(_));
+    if args.color_moved && !formats.iter().any(labels_moved) {
+        return Err(cli_error(
+            "--color-moved can only be used with --git or --color-words",
+        ));
+    }
+    Ok(())
 }
 
 fn diff_formats_from_args(
@@ -514,6 +606,20 @@ impl<'a> DiffRenderer<'a> {
                     )
                     .await?;
                 }
+                DiffFormat::SideBySide(options) => {
+                    let tree_diff =
+                        from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
+                    show_side_by_side_diff(
+                        formatter,
+                        store,
+                        tree_diff,
+                        path_converter,
+                        options,
+                        self.conflict_marker_style,
+                        width,
+                    )
+                    .await?;
+                }
                 DiffFormat::Tool(tool) => {
                     match tool.diff_invocation_mode {
                         DiffToolMode::FileByFile => {
@@ -555,6 +661,7 @@ impl<'a> DiffRenderer<'a> {
         &self,
         formatter: &mut dyn Formatter,
         [from_description, to_description]: [&Merge<&str>; 2],
+        width: usize,
     ) -> Result<(), DiffRenderError> {
         if from_description == to_description {
             return Ok(());
@@ -590,6 +697,27 @@ impl<'a> DiffRenderer<'a> {
                         [from_description, to_description],
                         options,
                         &materialize_options,
+                        None,
+                        None,
+                    )?;
+                }
+                DiffFormat::SideBySide(options) => {
+                    writeln!(formatter.labeled("header"), "Modified commit description:")?;
+                    let [left, right] = [from_description, to_description].map(|content| {
+                        match content.as_resolved() {
+                            Some(text) => Cow::Borrowed(BStr::new(text)),
+                            None => Cow::Owned(materialize_merge_result_to_bytes(
+                                content,
+                                &materialize_options,
+                            )),
+                        }
+                    });
+                    show_side_by_side_diff_hunks(
+                        formatter,
+                        [left.as_ref(), right.as_ref()],
+                        options,
+                        width,
+                        None,
                     )?;
                 }
                 DiffFormat::Tool(_) => {
@@ -628,7 +756,11 @@ impl<'a> DiffRenderer<'a> {
         let from_tree = rebase_to_dest_parent(self.repo, from_commits, to_commit)?;
         let to_tree = to_commit.tree_async().await?;
         let copy_records = CopyRecords::default(); // TODO
-        self.show_diff_commit_descriptions(*formatter, [&from_description, &to_description])?;
+        self.show_diff_commit_descriptions(
+            *formatter,
+            [&from_description, &to_description],
+            width,
+        )?;
         self.show_diff_trees(
             ui,
             *formatter,
@@ -695,11 +827,23 @@ pub enum ConflictDiffMethod {
 pub struct LineDiffOptions {
     /// How equivalence of lines is tested.
     pub compare_mode: LineCompareMode,
+    /// How unchanged lines are found.
+    pub algorithm: DiffAlgorithm,
     // TODO: add --ignore-blank-lines, etc. which aren't mutually exclusive.
 }
 
 impl LineDiffOptions {
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(Self {
+            compare_mode: LineCompareMode::default(),
+            algorithm: settings.get("diff.algorithm")?,
+        })
+    }
+
     fn merge_args(&mut self, args: &DiffFormatArgs) {
+        if let Some(algorithm) = args.diff_algorithm {
+            self.algorithm = algorithm.into();
+        }
         self.compare_mode = if args.ignore_all_space {
             LineCompareMode::IgnoreAllSpace
         } else if args.ignore_space_change {
@@ -720,6 +864,8 @@ pub struct ColorWordsDiffOptions {
     pub line_diff: LineDiffOptions,
     /// Maximum number of removed/added word alternation to inline.
     pub max_inline_alternation: Option<usize>,
+    /// Whether to label lines moved from one place to another.
+    pub color_moved: bool,
 }
 
 impl ColorWordsDiffOptions {
@@ -738,8 +884,9 @@ impl ColorWordsDiffOptions {
         Ok(Self {
             conflict: settings.get("diff.color-words.conflict")?,
             context: settings.get("diff.color-words.context")?,
-            line_diff: LineDiffOptions::default(),
+            line_diff: LineDiffOptions::from_settings(settings)?,
             max_inline_alternation,
+            color_moved: settings.get("diff.color-words.color-moved")?,
         })
     }
 
@@ -748,6 +895,7 @@ impl ColorWordsDiffOptions {
             self.context = context;
         }
         self.line_diff.merge_args(args);
+        self.color_moved |= args.color_moved;
     }
 }
 
@@ -756,12 +904,23 @@ fn show_color_words_diff_hunks<T: AsRef<[u8]>>(
     [lefts, rights]: [&Merge<T>; 2],
     options: &ColorWordsDiffOptions,
     materialize_options: &ConflictMaterializeOptions,
+    highlighter: Option<&SyntaxHighlighter>,
+    moved: Option<&MovedFileLines>,
 ) -> io::Result<()> {
     let line_number = DiffLineNumber { left: 1, right: 1 };
     let labels = ["removed", "added"];
     if let (Some(left), Some(right)) = (lefts.as_resolved(), rights.as_resolved()) {
         let contents = [left, right].map(BStr::new);
-        show_color_words_resolved_hunks(formatter, contents, line_number, labels, options)?;
+        let highlighter = highlighter.map(|h| DiffHighlighter::new(h, contents));
+        show_color_words_resolved_hunks(
+            formatter,
+            contents,
+            line_number,
+            labels,
+            options,
+            highlighter.as_ref(),
+            moved,
+        )?;
         return Ok(());
     }
     match options.conflict {
@@ -769,13 +928,32 @@ fn show_color_words_diff_hunks<T: AsRef<[u8]>>(
             let left = materialize_merge_result_to_bytes(lefts, materialize_options);
             let right = materialize_merge_result_to_bytes(rights, materialize_options);
             let contents = [&left, &right].map(BStr::new);
-            show_color_words_resolved_hunks(formatter, contents, line_number, labels, options)?;
+            let highlighter = highlighter.map(|h| DiffHighlighter::new(h, contents));
+            show_color_words_resolved_hunks(
+                formatter,
+                contents,
+                line_number,
+                labels,
+                options,
+                highlighter.as_ref(),
+                moved,
+            )?;
         }
         ConflictDiffMethod::Pair => {
             let lefts = files::merge(lefts, &materialize_options.merge);
             let rights = files::merge(rights, &materialize_options.merge);
             let contents = [&lefts, &rights];
-            show_color_words_conflict_hunks(formatter, contents, line_number, labels, options)?;
+            // The line numbers don't correspond to the conflicted contents, so
+            // constructs spanning multiple lines aren't tracked.
+            let highlighter = highlighter.map(|h| DiffHighlighter::new(h, [b"", b""]));
+            show_color_words_conflict_hunks(
+                formatter,
+                contents,
+                line_number,
+                labels,
+                options,
+                highlighter.as_ref(),
+            )?;
         }
     }
     Ok(())
@@ -787,11 +965,13 @@ fn show_color_words_conflict_hunks(
     mut line_number: DiffLineNumber,
     labels: [&str; 2],
     options: &ColorWordsDiffOptions,
+    highlighter: Option<&DiffHighlighter>,
 ) -> io::Result<DiffLineNumber> {
     let num_lefts = lefts.as_slice().len();
     let line_diff = diff_by_line(
         lefts.iter().chain(rights.iter()),
         &options.line_diff.compare_mode,
+        options.line_diff.algorithm,
     );
     // Matching entries shouldn't appear consecutively in diff of two inputs.
     // However, if the inputs have conflicts, there may be a hunk that can be
@@ -815,6 +995,7 @@ fn show_color_words_conflict_hunks(
                     line_number,
                     labels,
                     options,
+                    highlighter,
                     num_after,
                     num_before,
                 )?;
@@ -824,7 +1005,15 @@ fn show_color_words_conflict_hunks(
                     (hunk.lefts.as_resolved(), hunk.rights.as_resolved())
                 {
                     let contents = [left, right];
-                    show_color_words_diff_lines(formatter, contents, line_number, labels, options)?
+                    show_color_words_diff_lines(
+                        formatter,
+                        contents,
+                        line_number,
+                        labels,
+                        options,
+                        highlighter,
+                        None,
+                    )?
                 } else {
                     show_color_words_unresolved_hunk(
                         formatter,
@@ -832,6 +1021,7 @@ fn show_color_words_conflict_hunks(
                         line_number,
                         labels,
                         options,
+                        highlighter,
                     )?
                 }
             }
@@ -846,6 +1036,7 @@ fn show_color_words_conflict_hunks(
         line_number,
         labels,
         options,
+        highlighter,
         num_after,
         num_before,
     )
@@ -857,6 +1048,7 @@ fn show_color_words_unresolved_hunk(
     line_number: DiffLineNumber,
     [label1, label2]: [&str; 2],
     options: &ColorWordsDiffOptions,
+    highlighter: Option<&DiffHighlighter>,
 ) -> io::Result<DiffLineNumber> {
     let hunk_desc = if hunk.lefts.is_resolved() {
         "Created conflict"
@@ -898,8 +1090,15 @@ fn show_color_words_unresolved_hunk(
             false => [label2, label1],
         };
         // Individual hunk pair may be largely the same, so diff it again.
-        let new_line_number =
-            show_color_words_resolved_hunks(formatter, contents, line_number, labels, options)?;
+        let new_line_number = show_color_words_resolved_hunks(
+            formatter,
+            contents,
+            line_number,
+            labels,
+            options,
+            highlighter,
+            None,
+        )?;
         // Take max to assign unique line numbers to trailing hunks. The line
         // numbers can't be real anyway because preceding conflict hunks might
         // have been resolved.
@@ -917,8 +1116,14 @@ fn show_color_words_resolved_hunks(
     mut line_number: DiffLineNumber,
     labels: [&str; 2],
     options: &ColorWordsDiffOptions,
+    highlighter: Option<&DiffHighlighter>,
+    moved: Option<&MovedFileLines>,
 ) -> io::Result<DiffLineNumber> {
-    let line_diff = diff_by_line(contents, &options.line_diff.compare_mode);
+    let line_diff = diff_by_line(
+        contents,
+        &options.line_diff.compare_mode,
+        options.line_diff.algorithm,
+    );
     // Matching entries shouldn't appear consecutively in diff of two inputs.
     let mut context: Option<[&BStr; 2]> = None;
     let mut emitted = false;
@@ -938,6 +1143,7 @@ fn show_color_words_resolved_hunks(
                     line_number,
                     labels,
                     options,
+                    highlighter,
                     num_after,
                     num_before,
                 )?;
@@ -949,6 +1155,8 @@ fn show_color_words_resolved_hunks(
                     line_number,
                     labels,
                     options,
+                    highlighter,
+                    moved,
                 )?;
             }
         }
@@ -962,18 +1170,21 @@ fn show_color_words_resolved_hunks(
         line_number,
         labels,
         options,
+        highlighter,
         num_after,
         num_before,
     )
 }
 
 /// Prints `num_after` lines, ellipsis, and `num_before` lines.
+#[expect(clippy::too_many_arguments)]
 fn show_color_words_context_lines(
     formatter: &mut dyn Formatter,
     contexts: &[[&BStr; 2]],
     mut line_number: DiffLineNumber,
     labels: [&str; 2],
     options: &ColorWordsDiffOptions,
+    highlighter: Option<&DiffHighlighter>,
     num_after: usize,
     num_before: usize,
 ) -> io::Result<DiffLineNumber> {
@@ -997,11 +1208,14 @@ fn show_color_words_context_lines(
                     formatter,
                     [Some(line_number.left), Some(line_number.right)],
                     labels,
+                    [false, false],
                 )?;
                 show_color_words_inline_hunks(
                     formatter,
                     &[(DiffLineHunkSide::Both, line.as_ref())],
                     labels,
+                    [false, false],
+                    highlighter.map(|h| h.line(1, line_number.right)),
                 )?;
                 line_number.left += 1;
                 line_number.right += 1;
@@ -1016,6 +1230,8 @@ fn show_color_words_context_lines(
                 line_number,
                 labels,
                 options,
+                highlighter,
+                None,
             )
         }
     };
@@ -1048,6 +1264,8 @@ fn show_color_words_diff_lines(
     mut line_number: DiffLineNumber,
     labels: [&str; 2],
     options: &ColorWordsDiffOptions,
+    highlighter: Option<&DiffHighlighter>,
+    moved: Option<&MovedFileLines>,
 ) -> io::Result<DiffLineNumber> {
     let word_diff_hunks = ContentDiff::by_word(contents).hunks().collect_vec();
     let can_inline = match options.max_inline_alternation {
@@ -1062,32 +1280,61 @@ fn show_color_words_diff_lines(
         let mut diff_line_iter =
             DiffLineIterator::with_line_number(word_diff_hunks.iter(), line_number);
         for diff_line in diff_line_iter.by_ref() {
-            show_color_words_line_number(
+            let line_numbers = [
+                diff_line
+                    .has_left_content()
+                    .then_some(diff_line.line_number.left),
+                diff_line
+                    .has_right_content()
+                    .then_some(diff_line.line_number.right),
+            ];
+            let moved_sides = [0, 1].map(|side| {
+                line_numbers[side].is_some_and(|n| is_line_moved(moved, side, n as usize - 1))
+            });
+            show_color_words_line_number(formatter, line_numbers, labels, moved_sides)?;
+            let line_highlighter = highlighter.map(|h| {
+                if diff_line.has_right_content() {
+                    h.line(1, diff_line.line_number.right)
+                } else {
+                    h.line(0, diff_line.line_number.left)
+                }
+            });
+            show_color_words_inline_hunks(
                 formatter,
-                [
-                    diff_line
-                        .has_left_content()
-                        .then_some(diff_line.line_number.left),
-                    diff_line
-                        .has_right_content()
-                        .then_some(diff_line.line_number.right),
-                ],
+                &diff_line.hunks,
                 labels,
+                moved_sides,
+                line_highlighter,
             )?;
-            show_color_words_inline_hunks(formatter, &diff_line.hunks, labels)?;
         }
         line_number = diff_line_iter.next_line_number();
     } else {
         let [left_lines, right_lines] = unzip_diff_hunks_to_lines(&word_diff_hunks);
         let [left_label, right_label] = labels;
         for tokens in &left_lines {
-            show_color_words_line_number(formatter, [Some(line_number.left), None], labels)?;
-            show_color_words_single_sided_line(formatter, tokens, left_label)?;
+            let moved = is_line_moved(moved, 0, line_number.left as usize - 1);
+            show_color_words_line_number(
+                formatter,
+                [Some(line_number.left), None],
+                labels,
+                [moved, false],
+            )?;
+            let labels = moved_line_labels(left_label, moved);
+            let highlighter = highlighter.map(|h| h.line(0, line_number.left));
+            show_color_words_single_sided_line(formatter, tokens, &labels, highlighter)?;
             line_number.left += 1;
         }
         for tokens in &right_lines {
-            show_color_words_line_number(formatter, [None, Some(line_number.right)], labels)?;
-            show_color_words_single_sided_line(formatter, tokens, right_label)?;
+            let moved = is_line_moved(moved, 1, line_number.right as usize - 1);
+            show_color_words_line_number(
+                formatter,
+                [None, Some(line_number.right)],
+                labels,
+                [false, moved],
+            )?;
+            let labels = moved_line_labels(right_label, moved);
+            let highlighter = highlighter.map(|h| h.line(1, line_number.right));
+            show_color_words_single_sided_line(formatter, tokens, &labels, highlighter)?;
             line_number.right += 1;
         }
     }
@@ -1098,21 +1345,28 @@ fn show_color_words_line_number(
     formatter: &mut dyn Formatter,
     [left_line_number, right_line_number]: [Option<u32>; 2],
     [left_label, right_label]: [&str; 2],
+    [left_moved, right_moved]: [bool; 2],
 ) -> io::Result<()> {
     if let Some(line_number) = left_line_number {
-        write!(
-            formatter.labeled(left_label).labeled("line_number"),
-            "{line_number:>4}"
-        )?;
+        let labels = [
+            moved_line_labels(left_label, left_moved),
+            vec!["line_number"],
+        ]
+        .concat();
+        let text = format!("{line_number:>4}");
+        syntax_highlight::write_labeled(formatter, None, &labels, text.as_bytes())?;
         write!(formatter, " ")?;
     } else {
         write!(formatter, "     ")?;
     }
     if let Some(line_number) = right_line_number {
-        write!(
-            formatter.labeled(right_label).labeled("line_number"),
-            "{line_number:>4}"
-        )?;
+        let labels = [
+            moved_line_labels(right_label, right_moved),
+            vec!["line_number"],
+        ]
+        .concat();
+        let text = format!("{line_number:>4}");
+        syntax_highlight::write_labeled(formatter, None, &labels, text.as_bytes())?;
         write!(formatter, ": ")?;
     } else {
         write!(formatter, "    : ")?;
@@ -1125,19 +1379,23 @@ fn show_color_words_inline_hunks(
     formatter: &mut dyn Formatter,
     line_hunks: &[(DiffLineHunkSide, &BStr)],
     [left_label, right_label]: [&str; 2],
+    [left_moved, right_moved]: [bool; 2],
+    highlighter: Option<LineHighlighter<'_>>,
 ) -> io::Result<()> {
-    for (side, data) in line_hunks {
-        let label = match side {
-            DiffLineHunkSide::Both => None,
-            DiffLineHunkSide::Left => Some(left_label),
-            DiffLineHunkSide::Right => Some(right_label),
-        };
-        if let Some(label) = label {
-            formatter.labeled(label).labeled("token").write_all(data)?;
-        } else {
-            formatter.write_all(data)?;
-        }
-    }
+    let left_labels = [moved_line_labels(left_label, left_moved), vec!["token"]].concat();
+    let right_labels = [moved_line_labels(right_label, right_moved), vec!["token"]].concat();
+    let pieces = line_hunks
+        .iter()
+        .map(|&(side, data)| {
+            let labels: &[&str] = match side {
+                DiffLineHunkSide::Both => &[],
+                DiffLineHunkSide::Left => &left_labels,
+                DiffLineHunkSide::Right => &right_labels,
+            };
+            (labels, data.as_ref())
+        })
+        .collect_vec();
+    syntax_highlight::write_line_pieces(formatter, highlighter, &pieces)?;
     let (_, data) = line_hunks.last().expect("diff line must not be empty");
     if !data.ends_with(b"\n") {
         writeln!(formatter)?;
@@ -1145,13 +1403,14 @@ fn show_color_words_inline_hunks(
     Ok(())
 }
 
-/// Prints left/right-only line tokens with the given label.
+/// Prints left/right-only line tokens with the given labels.
 fn show_color_words_single_sided_line(
     formatter: &mut dyn Formatter,
     tokens: &[(DiffTokenType, &[u8])],
-    label: &str,
+    labels: &[&str],
+    highlighter: Option<LineHighlighter<'_>>,
 ) -> io::Result<()> {
-    show_diff_line_tokens(*formatter.labeled(label), tokens)?;
+    show_diff_line_tokens(formatter, tokens, labels, highlighter)?;
     let (_, data) = tokens.last().expect("diff line must not be empty");
     if !data.ends_with(b"\n") {
         writeln!(formatter)?;
@@ -1257,6 +1516,153 @@ fn diff_content_with<T>(
     }
 }
 
+/// Materializes contents of the both sides of the diff entry.
+fn diff_contents_for_entry(
+    MaterializedTreeDiffEntry { path, values }: MaterializedTreeDiffEntry,
+    materialize_options: &ConflictMaterializeOptions,
+) -> BackendResult<(CopiesTreeDiffEntryPath, [FileContent<BString>; 2])> {
+    let (left, right) = values?;
+    let left_content = diff_content(path.source(), left, materialize_options)?;
+    let right_content = diff_content(path.target(), right, materialize_options)?;
+    Ok((path, [left_content, right_content]))
+}
+
+/// Reads the file content of the `value` so it can be inspected before the
+/// diff is formatted. The returned value reads the content from memory.
+///
+/// Values other than files and file conflicts have no lines to be moved, and
+/// are returned as empty content.
+fn buffer_diff_content(
+    path: &RepoPath,
+    value: MaterializedTreeValue,
+    materialize_options: &ConflictMaterializeOptions,
+) -> BackendResult<(FileContent<BString>, MaterializedTreeValue)> {
+    match value {
+        MaterializedTreeValue::File(mut file) => {
+            let content = file_content_for_diff(path, &mut file, |content| content)?;
+            file.reader = Box::pin(Cursor::new(content.contents.to_vec()));
+            Ok((content, MaterializedTreeValue::File(file)))
+        }
+        MaterializedTreeValue::FileConflict(file) => {
+            let content = FileContent {
+                is_binary: false,
+                contents: materialize_merge_result_to_bytes(&file.contents, materialize_options),
+            };
+            Ok((content, MaterializedTreeValue::FileConflict(file)))
+        }
+        value => {
+            let content = FileContent {
+                is_binary: false,
+                contents: BString::default(),
+            };
+            Ok((content, value))
+        }
+    }
+}
+
+/// Indices of moved lines in the left and right contents of a file.
+type MovedFileLines = [HashSet<usize>; 2];
+
+/// Lines moved from one place to another within a diff, keyed by target path.
+#[derive(Clone, Debug, Default)]
+struct MovedLines {
+    files: HashMap<RepoPathBuf, MovedFileLines>,
+}
+
+impl MovedLines {
+    /// Detects moved lines in the `tree_diff`. Since the whole diff has to be
+    /// scanned in advance, the materialized entries are returned as a new
+    /// stream, with the file contents kept in memory.
+    async fn detect<'a>(
+        store: &Store,
+        tree_diff: BoxStream<'a, CopiesTreeDiffEntry>,
+        line_diff: &LineDiffOptions,
+        materialize_options: &ConflictMaterializeOptions,
+    ) -> BackendResult<(BoxStream<'a, MaterializedTreeDiffEntry>, Self)> {
+        let mut entries = Vec::new();
+        let mut files = Vec::new();
+        let mut diff_stream = materialized_diff_stream(store, tree_diff);
+        while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
+            let (left_value, right_value) = values?;
+            let (left_content, left_value) =
+                buffer_diff_content(path.source(), left_value, materialize_options)?;
+            let (right_content, right_value) =
+                buffer_diff_content(path.target(), right_value, materialize_options)?;
+            files.push((path.clone(), [left_content, right_content]));
+            entries.push(MaterializedTreeDiffEntry {
+                path,
+                values: Ok((left_value, right_value)),
+            });
+        }
+        let moved_lines = Self::from_contents(&files, line_diff);
+        Ok((stream::iter(entries).boxed(), moved_lines))
+    }
+
+    fn from_contents(
+        files: &[(CopiesTreeDiffEntryPath, [FileContent<BString>; 2])],
+        line_diff: &LineDiffOptions,
+    ) -> Self {
+        // Changed blocks of lines, and their (file index, start line index).
+        let mut blocks: [Vec<Vec<&[u8]>>; 2] = Default::default();
+        let mut block_origins: [Vec<(usize, usize)>; 2] = Default::default();
+        for (file_index, (_, contents)) in files.iter().enumerate() {
+            if contents.iter().any(|content| content.is_binary) {
+                continue;
+            }
+            let diff = diff_by_line(
+                contents.each_ref().map(|c| &c.contents),
+                &line_diff.compare_mode,
+                line_diff.algorithm,
+            );
+            let mut line_indices = [0, 0];
+            for hunk in diff.hunks() {
+                for (side, content) in hunk.contents.iter().enumerate() {
+                    let lines = content.split_inclusive(|b| *b == b'\n').collect_vec();
+                    let num_lines = lines.len();
+                    if hunk.kind == DiffHunkKind::Different && num_lines > 0 {
+                        blocks[side].push(lines);
+                        block_origins[side].push((file_index, line_indices[side]));
+                    }
+                    line_indices[side] += num_lines;
+                }
+            }
+        }
+
+        let mut moved_files: HashMap<RepoPathBuf, MovedFileLines> = HashMap::new();
+        let [removed_blocks, added_blocks] = &blocks;
+        let flags = find_moved_lines_by_mode(removed_blocks, added_blocks, &line_diff.compare_mode);
+        for (side, (side_flags, origins)) in iter::zip(flags, &block_origins).enumerate() {
+            for (block_flags, &(file_index, start)) in iter::zip(side_flags, origins) {
+                if !block_flags.contains(&true) {
+                    continue;
+                }
+                let (path, _) = &files[file_index];
+                let moved_file = moved_files.entry(path.target().to_owned()).or_default();
+                let moved_indices = block_flags.iter().positions(|&moved| moved);
+                moved_file[side].extend(moved_indices.map(|index| start + index));
+            }
+        }
+        Self { files: moved_files }
+    }
+
+    fn get(&self, path: &RepoPath) -> Option<&MovedFileLines> {
+        self.files.get(path)
+    }
+}
+
+fn is_line_moved(moved: Option<&MovedFileLines>, side: usize, index: usize) -> bool {
+    moved.is_some_and(|moved| moved[side].contains(&index))
+}
+
+/// Returns labels for a removed or added line.
+fn moved_line_labels(label: &str, moved: bool) -> Vec<&str> {
+    if moved {
+        vec![label, "moved"]
+    } else {
+        vec![label]
+    }
+}
+
 fn basic_diff_file_type(value: &MaterializedTreeValue) -> &'static str {
     match value {
         MaterializedTreeValue::Absent => {
@@ -1279,6 +1685,52 @@ fn basic_diff_file_type(value: &MaterializedTreeValue) -> &'static str {
     }
 }
 
+/// Describes how a path present on both sides changed, for use in diff headers.
+fn modified_diff_description(
+    left_value: &MaterializedTreeValue,
+    right_value: &MaterializedTreeValue,
+) -> String {
+    match (left_value, right_value) {
+        (MaterializedTreeValue::File(left), MaterializedTreeValue::File(right)) => {
+            if left.executable && right.executable {
+                "Modified executable file".to_string()
+            } else if left.executable {
+                "Executable file became non-executable at".to_string()
+            } else if right.executable {
+                "Non-executable file became executable at".to_string()
+            } else {
+                "Modified regular file".to_string()
+            }
+        }
+        (
+            MaterializedTreeValue::FileConflict(_) | MaterializedTreeValue::OtherConflict { .. },
+            MaterializedTreeValue::FileConflict(_) | MaterializedTreeValue::OtherConflict { .. },
+        ) => "Modified conflict in".to_string(),
+        (
+            MaterializedTreeValue::FileConflict(_) | MaterializedTreeValue::OtherConflict { .. },
+            _,
+        ) => "Resolved conflict in".to_string(),
+        (
+            _,
+            MaterializedTreeValue::FileConflict(_) | MaterializedTreeValue::OtherConflict { .. },
+        ) => "Created conflict in".to_string(),
+        (MaterializedTreeValue::Symlink { .. }, MaterializedTreeValue::Symlink { .. }) => {
+            "Symlink target changed at".to_string()
+        }
+        (_, _) => {
+            let left_type = basic_diff_file_type(left_value);
+            let right_type = basic_diff_file_type(right_value);
+            let (first, rest) = left_type.split_at(1);
+            format!(
+                "{}{} became {} at",
+                first.to_ascii_uppercase(),
+                rest,
+                right_type
+            )
+        }
+    }
+}
+
 pub async fn show_color_words_diff(
     formatter: &mut dyn Formatter,
     store: &Store,
@@ -1293,13 +1745,20 @@ pub async fn show_color_words_diff(
         merge: store.merge_options().clone(),
     };
     let empty_content = || Merge::resolved(BString::default());
-    let mut diff_stream = materialized_diff_stream(store, tree_diff);
+    let (mut diff_stream, moved_lines) = if options.color_moved {
+        MovedLines::detect(store, tree_diff, &options.line_diff, &materialize_options).await?
+    } else {
+        let diff_stream = materialized_diff_stream(store, tree_diff).boxed();
+        (diff_stream, MovedLines::default())
+    };
     while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
         let left_path = path.source();
         let right_path = path.target();
         let left_ui_path = path_converter.format_file_path(left_path);
         let right_ui_path = path_converter.format_file_path(right_path);
         let (left_value, right_value) = values?;
+        let highlighter = SyntaxHighlighter::for_formatter(formatter, right_path);
+        let moved = moved_lines.get(right_path);
 
         match (&left_value, &right_value) {
             (MaterializedTreeValue::AccessDenied(source), _) => {
@@ -1337,52 +1796,12 @@ pub async fn show_color_words_diff(
                     [&empty_content(), &right_content.contents],
                     options,
                     &materialize_options,
+                    highlighter.as_ref(),
+                    moved,
                 )?;
             }
         } else if right_value.is_present() {
-            let description = match (&left_value, &right_value) {
-                (MaterializedTreeValue::File(left), MaterializedTreeValue::File(right)) => {
-                    if left.executable && right.executable {
-                        "Modified executable file".to_string()
-                    } else if left.executable {
-                        "Executable file became non-executable at".to_string()
-                    } else if right.executable {
-                        "Non-executable file became executable at".to_string()
-                    } else {
-                        "Modified regular file".to_string()
-                    }
-                }
-                (
-                    MaterializedTreeValue::FileConflict(_)
-                    | MaterializedTreeValue::OtherConflict { .. },
-                    MaterializedTreeValue::FileConflict(_)
-                    | MaterializedTreeValue::OtherConflict { .. },
-                ) => "Modified conflict in".to_string(),
-                (
-                    MaterializedTreeValue::FileConflict(_)
-                    | MaterializedTreeValue::OtherConflict { .. },
-                    _,
-                ) => "Resolved conflict in".to_string(),
-                (
-                    _,
-                    MaterializedTreeValue::FileConflict(_)
-                    | MaterializedTreeValue::OtherConflict { .. },
-                ) => "Created conflict in".to_string(),
-                (MaterializedTreeValue::Symlink { .. }, MaterializedTreeValue::Symlink { .. }) => {
-                    "Symlink target changed at".to_string()
-                }
-                (_, _) => {
-                    let left_type = basic_diff_file_type(&left_value);
-                    let right_type = basic_diff_file_type(&right_value);
-                    let (first, rest) = left_type.split_at(1);
-                    format!(
-                        "{}{} became {} at",
-                        first.to_ascii_uppercase(),
-                        rest,
-                        right_type
-                    )
-                }
-            };
+            let description = modified_diff_description(&left_value, &right_value);
             let left_content = diff_content_as_merge(left_path, left_value)?;
             let right_content = diff_content_as_merge(right_path, right_value)?;
             if left_path == right_path {
@@ -1404,6 +1823,8 @@ pub async fn show_color_words_diff(
                     [&left_content.contents, &right_content.contents],
                     options,
                     &materialize_options,
+                    highlighter.as_ref(),
+                    moved,
                 )?;
             }
         } else {
@@ -1423,13 +1844,424 @@ pub async fn show_color_words_diff(
                     [&left_content.contents, &empty_content()],
                     options,
                     &materialize_options,
+                    highlighter.as_ref(),
+                    moved,
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SideBySideDiffOptions {
+    /// Number of context lines to show.
+    pub context: usize,
+    /// How lines are tokenized and compared.
+    pub line_diff: LineDiffOptions,
+}
+
+impl SideBySideDiffOptions {
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(Self {
+            context: settings.get("diff.side-by-side.context")?,
+            line_diff: LineDiffOptions::from_settings(settings)?,
+        })
+    }
+
+    fn merge_args(&mut self, args: &DiffFormatArgs) {
+        if let Some(context) = args.context {
+            self.context = context;
+        }
+        self.line_diff.merge_args(args);
+    }
+}
+
+pub async fn show_side_by_side_diff(
+    formatter: &mut dyn Formatter,
+    store: &Store,
+    tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
+    path_converter: &RepoPathUiConverter,
+    options: &SideBySideDiffOptions,
+    marker_style: ConflictMarkerStyle,
+    width: usize,
+) -> Result<(), DiffRenderError> {
+    let materialize_options = ConflictMaterializeOptions {
+        marker_style,
+        marker_len: None,
+        merge: store.merge_options().clone(),
+    };
+    let mut diff_stream = materialized_diff_stream(store, tree_diff);
+    while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
+        let left_path = path.source();
+        let right_path = path.target();
+        let left_ui_path = path_converter.format_file_path(left_path);
+        let right_ui_path = path_converter.format_file_path(right_path);
+        let (left_value, right_value) = values?;
+        let highlighter = SyntaxHighlighter::for_formatter(formatter, right_path);
+
+        match (&left_value, &right_value) {
+            (MaterializedTreeValue::AccessDenied(source), _) => {
+                write!(
+                    formatter.labeled("access-denied"),
+                    "Access denied to {left_ui_path}:"
+                )?;
+                writeln!(formatter, " {source}")?;
+                continue;
+            }
+            (_, MaterializedTreeValue::AccessDenied(source)) => {
+                write!(
+                    formatter.labeled("access-denied"),
+                    "Access denied to {right_ui_path}:"
+                )?;
+                writeln!(formatter, " {source}")?;
+                continue;
+            }
+            _ => {}
+        }
+        let is_modified = left_value.is_present() && right_value.is_present();
+        if left_value.is_absent() {
+            let description = basic_diff_file_type(&right_value);
+            writeln!(
+                formatter.labeled("header"),
+                "Added {description} {right_ui_path}:"
+            )?;
+        } else if is_modified {
+            let description = modified_diff_description(&left_value, &right_value);
+            if left_path == right_path {
+                writeln!(
+                    formatter.labeled("header"),
+                    "{description} {right_ui_path}:"
+                )?;
+            } else {
+                writeln!(
+                    formatter.labeled("header"),
+                    "{description} {right_ui_path} ({left_ui_path} => {right_ui_path}):"
                 )?;
             }
+        } else {
+            let description = basic_diff_file_type(&left_value);
+            writeln!(
+                formatter.labeled("header"),
+                "Removed {description} {right_ui_path}:"
+            )?;
+        }
+        // Conflicts are compared as materialized contents so that both sides
+        // can be laid out line by line.
+        let left_content = diff_content(left_path, left_value, &materialize_options)?;
+        let right_content = diff_content(right_path, right_value, &materialize_options)?;
+        if left_content.is_binary || right_content.is_binary {
+            writeln!(formatter.labeled("binary"), "    (binary)")?;
+        } else if left_content.contents != right_content.contents {
+            show_side_by_side_diff_hunks(
+                formatter,
+                [&left_content.contents, &right_content.contents].map(BStr::new),
+                options,
+                width,
+                highlighter.as_ref(),
+            )?;
+        } else if !is_modified {
+            writeln!(formatter.labeled("empty"), "    (empty)")?;
         }
     }
     Ok(())
 }
 
+/// Minimum width of each side-by-side column, used if the terminal is narrow.
+const SIDE_BY_SIDE_MIN_COLUMN_WIDTH: usize = 20;
+/// Minimum number of digits of the line numbers in front of each column.
+const SIDE_BY_SIDE_MIN_LINE_NUMBER_WIDTH: usize = 4;
+const SIDE_BY_SIDE_SEPARATOR: &str = " │ ";
+const SIDE_BY_SIDE_TAB_WIDTH: usize = 4;
+
+type DiffToken<'content> = (DiffTokenType, &'content [u8]);
+
+fn show_side_by_side_diff_hunks(
+    formatter: &mut dyn Formatter,
+    contents: [&BStr; 2],
+    options: &SideBySideDiffOptions,
+    width: usize,
+    highlighter: Option<&SyntaxHighlighter>,
+) -> io::Result<()> {
+    const SKIPPED_CONTEXT_LINE: &str = "    ...\n";
+    // The gutter is wide enough to fit the largest line number of either side.
+    let line_number_width = {
+        let num_lines = contents
+            .iter()
+            .map(|content| content.split_inclusive(|b| *b == b'\n').count())
+            .max()
+            .unwrap_or(0);
+        max(
+            num_lines.to_string().len(),
+            SIDE_BY_SIDE_MIN_LINE_NUMBER_WIDTH,
+        )
+    };
+    let column_width = {
+        let gutters = 2 * (line_number_width + 1) + SIDE_BY_SIDE_SEPARATOR.width();
+        max(
+            width.saturating_sub(gutters) / 2,
+            SIDE_BY_SIDE_MIN_COLUMN_WIDTH,
+        )
+    };
+    let line_diff = diff_by_line(
+        contents,
+        &options.line_diff.compare_mode,
+        options.line_diff.algorithm,
+    );
+    let hunks = line_diff.hunks().collect_vec();
+    let highlighter = highlighter.map(|h| DiffHighlighter::new(h, contents));
+    let highlighter = highlighter.as_ref();
+    let mut line_number = DiffLineNumber { left: 1, right: 1 };
+    for (i, hunk) in hunks.iter().enumerate() {
+        match hunk.kind {
+            DiffHunkKind::Matching => {
+                // Lines may differ in whitespace depending on the compare
+                // mode, so show both sides.
+                let lines = iter::zip(
+                    hunk.contents[0].split_inclusive(|b| *b == b'\n'),
+                    hunk.contents[1].split_inclusive(|b| *b == b'\n'),
+                )
+                .collect_vec();
+                let num_after = if i == 0 { 0 } else { options.context };
+                let num_before = if i + 1 == hunks.len() {
+                    0
+                } else {
+                    options.context
+                };
+                if lines.len() > num_after + num_before {
+                    let (after_lines, rest) = lines.split_at(num_after);
+                    let (skipped_lines, before_lines) = rest.split_at(rest.len() - num_before);
+                    line_number = show_side_by_side_context_lines(
+                        formatter,
+                        after_lines,
+                        line_number,
+                        column_width,
+                        line_number_width,
+                        highlighter,
+                    )?;
+                    write!(formatter, "{SKIPPED_CONTEXT_LINE}")?;
+                    let num_skipped = u32::try_from(skipped_lines.len()).unwrap();
+                    line_number.left += num_skipped;
+                    line_number.right += num_skipped;
+                    line_number = show_side_by_side_context_lines(
+                        formatter,
+                        before_lines,
+                        line_number,
+                        column_width,
+                        line_number_width,
+                        highlighter,
+                    )?;
+                } else {
+                    line_number = show_side_by_side_context_lines(
+                        formatter,
+                        &lines,
+                        line_number,
+                        column_width,
+                        line_number_width,
+                        highlighter,
+                    )?;
+                }
+            }
+            DiffHunkKind::Different => {
+                let word_diff_hunks = ContentDiff::by_word([hunk.contents[0], hunk.contents[1]])
+                    .hunks()
+                    .collect_vec();
+                let [left_lines, right_lines] = unzip_diff_hunks_to_lines(&word_diff_hunks);
+                for pair in left_lines.iter().zip_longest(&right_lines) {
+                    let (left, right) = pair.left_and_right();
+                    show_side_by_side_line(
+                        formatter,
+                        [
+                            left.map(|tokens| (line_number.left, &tokens[..])),
+                            right.map(|tokens| (line_number.right, &tokens[..])),
+                        ],
+                        [Some("removed"), Some("added")],
+                        column_width,
+                        line_number_width,
+                        highlighter,
+                    )?;
+                    line_number.left += u32::from(left.is_some());
+                    line_number.right += u32::from(right.is_some());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn show_side_by_side_context_lines(
+    formatter: &mut dyn Formatter,
+    lines: &[(&[u8], &[u8])],
+    mut line_number: DiffLineNumber,
+    column_width: usize,
+    line_number_width: usize,
+    highlighter: Option<&DiffHighlighter>,
+) -> io::Result<DiffLineNumber> {
+    for &(left, right) in lines {
+        let [left_tokens, right_tokens] =
+            [left, right].map(|line| [(DiffTokenType::Matching, line)]);
+        show_side_by_side_line(
+            formatter,
+            [
+                Some((line_number.left, &left_tokens[..])),
+                Some((line_number.right, &right_tokens[..])),
+            ],
+            [None, None],
+            column_width,
+            line_number_width,
+            highlighter,
+        )?;
+        line_number.left += 1;
+        line_number.right += 1;
+    }
+    Ok(line_number)
+}
+
+/// Prints a pair of left/right lines, wrapping them to the `column_width`.
+fn show_side_by_side_line(
+    formatter: &mut dyn Formatter,
+    sides: [Option<(u32, &[DiffToken])>; 2],
+    labels: [Option<&str>; 2],
+    column_width: usize,
+    line_number_width: usize,
+    highlighter: Option<&DiffHighlighter>,
+) -> io::Result<()> {
+    let [left_rows, right_rows] = [0, 1].map(|side_index| {
+        sides[side_index].map(|(line_number, tokens)| {
+            let highlighter = highlighter.map(|h| h.line(side_index, line_number));
+            wrap_side_by_side_tokens(tokens, column_width, highlighter)
+        })
+    });
+    let num_rows = max(
+        left_rows.as_ref().map_or(0, |rows| rows.len()),
+        right_rows.as_ref().map_or(0, |rows| rows.len()),
+    );
+    for row_index in 0..num_rows {
+        for (side_index, rows) in [&left_rows, &right_rows].into_iter().enumerate() {
+            let line_number = sides[side_index]
+                .filter(|_| row_index == 0)
+                .map(|(line_number, _)| line_number);
+            let row = rows.as_ref().and_then(|rows| rows.get(row_index));
+            let is_last_column = side_index == 1;
+            if is_last_column {
+                if row.is_none() {
+                    // Don't emit trailing spaces.
+                    write!(formatter, "{}", SIDE_BY_SIDE_SEPARATOR.trim_end())?;
+                    continue;
+                }
+                write!(formatter, "{SIDE_BY_SIDE_SEPARATOR}")?;
+            }
+            let label = labels[side_index].filter(|_| rows.is_some());
+            let row_width =
+                show_side_by_side_cell(formatter, label, line_number, line_number_width, row)?;
+            if !is_last_column {
+                let padding = column_width.saturating_sub(row_width);
+                write!(formatter, "{:padding$}", "")?;
+            }
+        }
+        writeln!(formatter)?;
+    }
+    Ok(())
+}
+
+/// Prints a line number and a wrapped row of a side-by-side column. Returns
+/// the display width of the row content.
+fn show_side_by_side_cell(
+    formatter: &mut dyn Formatter,
+    label: Option<&str>,
+    line_number: Option<u32>,
+    line_number_width: usize,
+    row: Option<&Vec<SideBySideSegment>>,
+) -> io::Result<usize> {
+    let write_gutter = |formatter: &mut dyn Formatter| {
+        if let Some(line_number) = line_number {
+            write!(
+                formatter.labeled("line_number"),
+                "{line_number:>line_number_width$}"
+            )?;
+            write!(formatter, " ")
+        } else {
+            write!(formatter, "{:width$}", "", width = line_number_width + 1)
+        }
+    };
+    match label {
+        Some(label) => write_gutter(*formatter.labeled(label))?,
+        None => write_gutter(formatter)?,
+    }
+    let Some(row) = row else {
+        return Ok(0);
+    };
+    let token_labels: Vec<&str> = label.into_iter().chain(["token"]).collect();
+    for (token_type, kind, text) in row {
+        let labels = match token_type {
+            DiffTokenType::Matching => &token_labels[..token_labels.len() - 1],
+            DiffTokenType::Different => &token_labels[..],
+        };
+        syntax_highlight::write_labeled(formatter, *kind, labels, text.as_bytes())?;
+    }
+    Ok(row.iter().map(|(_, _, text)| text.width()).sum())
+}
+
+type SideBySideSegment = (DiffTokenType, Option<SyntaxKind>, String);
+
+/// Splits line tokens into rows of at most `width` columns. Tabs are expanded
+/// to spaces, and the trailing newline is removed.
+fn wrap_side_by_side_tokens(
+    tokens: &[DiffToken],
+    width: usize,
+    highlighter: Option<LineHighlighter<'_>>,
+) -> Vec<Vec<SideBySideSegment>> {
+    let spans = highlighter.map_or_else(Vec::new, |(highlighter, state)| {
+        let line = tokens
+            .iter()
+            .flat_map(|(_, data)| *data)
+            .copied()
+            .collect_vec();
+        let (spans, _) = highlighter.highlight_line(state, &line);
+        spans
+    });
+    let mut spans_iter = spans.iter().peekable();
+    let mut rows: Vec<Vec<SideBySideSegment>> = vec![vec![]];
+    let mut row_width = 0;
+    let mut line_width = 0;
+    let mut token_start = 0;
+    for &(token_type, data) in tokens {
+        for (start, _, c) in data.char_indices() {
+            let pos = token_start + start;
+            while spans_iter.next_if(|(range, _)| range.end <= pos).is_some() {}
+            let kind = spans_iter
+                .peek()
+                .filter(|(range, _)| range.start <= pos)
+                .map(|(_, kind)| *kind);
+            let (text, char_width) = match c {
+                '\n' | '\r' => continue,
+                '\t' => {
+                    let n = SIDE_BY_SIDE_TAB_WIDTH - line_width % SIDE_BY_SIDE_TAB_WIDTH;
+                    (" ".repeat(n), n)
+                }
+                c => (c.to_string(), c.width().unwrap_or(0)),
+            };
+            if row_width > 0 && row_width + char_width > width {
+                rows.push(vec![]);
+                row_width = 0;
+            }
+            let row = rows.last_mut().unwrap();
+            match row.last_mut() {
+                Some((last_type, last_kind, last_text))
+                    if *last_type == token_type && *last_kind == kind =>
+                {
+                    last_text.push_str(&text);
+                }
+                _ => row.push((token_type, kind, text)),
+            }
+            row_width += char_width;
+            line_width += char_width;
+        }
+        token_start += data.len();
+    }
+    rows
+}
+
 #[expect(clippy::too_many_arguments)]
 pub async fn show_file_by_file_diff(
     ui: &Ui,
@@ -1518,13 +2350,16 @@ pub struct UnifiedDiffOptions {
     pub context: usize,
     /// How lines are tokenized and compared.
     pub line_diff: LineDiffOptions,
+    /// Whether to label lines moved from one place to another.
+    pub color_moved: bool,
 }
 
 impl UnifiedDiffOptions {
     pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
         Ok(Self {
             context: settings.get("diff.git.context")?,
-            line_diff: LineDiffOptions::default(),
+            line_diff: LineDiffOptions::from_settings(settings)?,
+            color_moved: settings.get("diff.git.color-moved")?,
         })
     }
 
@@ -1533,6 +2368,7 @@ impl UnifiedDiffOptions {
             self.context = context;
         }
         self.line_diff.merge_args(args);
+        self.color_moved |= args.color_moved;
     }
 }
 
@@ -1540,6 +2376,8 @@ fn show_unified_diff_hunks(
     formatter: &mut dyn Formatter,
     contents: [&BStr; 2],
     options: &UnifiedDiffOptions,
+    highlighter: Option<&SyntaxHighlighter>,
+    moved: Option<&MovedFileLines>,
 ) -> io::Result<()> {
     // "If the chunk size is 0, the first number is one lower than one would
     // expect." - https://www.artima.com/weblogs/viewpost.jsp?thread=164293
@@ -1556,7 +2394,14 @@ fn show_unified_diff_hunks(
         }
     }
 
-    for hunk in unified_diff_hunks(contents, options.context, options.line_diff.compare_mode) {
+    let hunks = unified_diff_hunks(
+        contents,
+        options.context,
+        options.line_diff.compare_mode,
+        options.line_diff.algorithm,
+    );
+    let highlighter = highlighter.map(|h| DiffHighlighter::new(h, contents));
+    for hunk in hunks {
         writeln!(
             formatter.labeled("hunk_header"),
             "@@ -{},{} +{},{} @@",
@@ -1565,14 +2410,38 @@ fn show_unified_diff_hunks(
             to_line_number(hunk.right_line_range.clone()),
             hunk.right_line_range.len()
         )?;
+        let mut line_indices = [hunk.left_line_range.start, hunk.right_line_range.start];
         for (line_type, tokens) in &hunk.lines {
-            let (label, sigil) = match line_type {
-                DiffLineType::Context => ("context", " "),
-                DiffLineType::Removed => ("removed", "-"),
-                DiffLineType::Added => ("added", "+"),
+            let (label, sigil, moved, side) = match line_type {
+                DiffLineType::Context => {
+                    line_indices[0] += 1;
+                    line_indices[1] += 1;
+                    ("context", " ", false, 1)
+                }
+                DiffLineType::Removed => {
+                    line_indices[0] += 1;
+                    (
+                        "removed",
+                        "-",
+                        is_line_moved(moved, 0, line_indices[0] - 1),
+                        0,
+                    )
+                }
+                DiffLineType::Added => {
+                    line_indices[1] += 1;
+                    (
+                        "added",
+                        "+",
+                        is_line_moved(moved, 1, line_indices[1] - 1),
+                        1,
+                    )
+                }
             };
-            write!(formatter.labeled(label), "{sigil}")?;
-            show_diff_line_tokens(*formatter.labeled(label), tokens)?;
+            let labels = moved_line_labels(label, moved);
+            syntax_highlight::write_labeled(formatter, None, &labels, sigil.as_bytes())?;
+            let line_number = u32::try_from(line_indices[side]).unwrap();
+            let highlighter = highlighter.as_ref().map(|h| h.line(side, line_number));
+            show_diff_line_tokens(formatter, tokens, &labels, highlighter)?;
             let (_, content) = tokens.last().expect("hunk line must not be empty");
             if !content.ends_with(b"\n") {
                 write!(formatter, "\n\\ No newline at end of file\n")?;
@@ -1585,14 +2454,18 @@ fn show_unified_diff_hunks(
 fn show_diff_line_tokens(
     formatter: &mut dyn Formatter,
     tokens: &[(DiffTokenType, &[u8])],
+    labels: &[&str],
+    highlighter: Option<LineHighlighter<'_>>,
 ) -> io::Result<()> {
-    for (token_type, content) in tokens {
-        match token_type {
-            DiffTokenType::Matching => formatter.write_all(content)?,
-            DiffTokenType::Different => formatter.labeled("token").write_all(content)?,
-        }
-    }
-    Ok(())
+    let token_labels = [labels, &["token"]].concat();
+    let pieces = tokens
+        .iter()
+        .map(|&(token_type, content)| match token_type {
+            DiffTokenType::Matching => (labels, content),
+            DiffTokenType::Different => (&token_labels[..], content),
+        })
+        .collect_vec();
+    syntax_highlight::write_line_pieces(formatter, highlighter, &pieces)
 }
 
 pub async fn show_git_diff(
@@ -1607,12 +2480,19 @@ pub async fn show_git_diff(
         marker_len: None,
         merge: store.merge_options().clone(),
     };
-    let mut diff_stream = materialized_diff_stream(store, tree_diff);
+    let (mut diff_stream, moved_lines) = if options.color_moved {
+        MovedLines::detect(store, tree_diff, &options.line_diff, &materialize_options).await?
+    } else {
+        let diff_stream = materialized_diff_stream(store, tree_diff).boxed();
+        (diff_stream, MovedLines::default())
+    };
     while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
         let left_path = path.source();
         let right_path = path.target();
         let left_path_string = left_path.as_internal_file_string();
         let right_path_string = right_path.as_internal_file_string();
+        let highlighter = SyntaxHighlighter::for_formatter(formatter, right_path);
+        let moved = moved_lines.get(right_path);
         let (left_value, right_value) = values?;
 
         let left_part = git_diff_part(left_path, left_value, &materialize_options)?;
@@ -1684,6 +2564,8 @@ pub async fn show_git_diff(
                 formatter,
                 [&left_part.content.contents, &right_part.content.contents].map(BStr::new),
                 options,
+                highlighter.as_ref(),
+                moved,
             )?;
         }
     }
@@ -1711,7 +2593,13 @@ fn show_git_diff_texts<T: AsRef<[u8]>>(
             materialize_options,
         )),
     });
-    show_unified_diff_hunks(formatter, [left.as_ref(), right.as_ref()], options)
+    show_unified_diff_hunks(
+        formatter,
+        [left.as_ref(), right.as_ref()],
+        options,
+        None,
+        None,
+    )
 }
 
 #[instrument(skip_all)]
@@ -1756,11 +2644,21 @@ pub fn diff_status_label_and_char(
 pub struct DiffStatOptions {
     /// How lines are tokenized and compared.
     pub line_diff: LineDiffOptions,
+    /// Whether to exclude lines moved from one place to another.
+    pub ignore_moved: bool,
 }
 
 impl DiffStatOptions {
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(Self {
+            line_diff: LineDiffOptions::from_settings(settings)?,
+            ignore_moved: false,
+        })
+    }
+
     fn merge_args(&mut self, args: &DiffFormatArgs) {
         self.line_diff.merge_args(args);
+        self.ignore_moved = args.ignore_moved;
     }
 }
 
@@ -1782,16 +2680,27 @@ impl DiffStats {
             marker_len: None,
             merge: store.merge_options().clone(),
         };
-        let entries = materialized_diff_stream(store, tree_diff)
-            .map(|MaterializedTreeDiffEntry { path, values }| {
-                let (left, right) = values?;
-                let left_content = diff_content(path.source(), left, &materialize_options)?;
-                let right_content = diff_content(path.target(), right, &materialize_options)?;
-                let stat = get_diff_stat_entry(path, [&left_content, &right_content], options);
-                BackendResult::Ok(stat)
-            })
-            .try_collect()
-            .await?;
+        let files = materialized_diff_stream(store, tree_diff)
+            .map(|entry| diff_contents_for_entry(entry, &materialize_options));
+        let entries = if options.ignore_moved {
+            // Moved lines can only be detected after scanning all files.
+            let files: Vec<_> = files.try_collect().await?;
+            let moved_lines = MovedLines::from_contents(&files, &options.line_diff);
+            files
+                .into_iter()
+                .map(|(path, contents)| {
+                    let moved = moved_lines.get(path.target());
+                    get_diff_stat_entry(path, contents.each_ref(), options, moved)
+                })
+                .collect()
+        } else {
+            files
+                .map_ok(|(path, contents)| {
+                    get_diff_stat_entry(path, contents.each_ref(), options, None)
+                })
+                .try_collect()
+                .await?
+        };
         Ok(Self { entries })
     }
 
@@ -1830,6 +2739,7 @@ fn get_diff_stat_entry(
     path: CopiesTreeDiffEntryPath,
     contents: [&FileContent<BString>; 2],
     options: &DiffStatOptions,
+    moved: Option<&MovedFileLines>,
 ) -> DiffStatEntry {
     let [left_content, right_content] = contents;
     let added_removed = if left_content.is_binary || right_content.is_binary {
@@ -1838,19 +2748,26 @@ fn get_diff_stat_entry(
         let diff = diff_by_line(
             contents.map(|content| &content.contents),
             &options.line_diff.compare_mode,
+            options.line_diff.algorithm,
         );
-        let mut added = 0;
-        let mut removed = 0;
+        let mut counts = [0, 0];
+        let mut line_indices = [0, 0];
         for hunk in diff.hunks() {
-            match hunk.kind {
-                DiffHunkKind::Matching => {}
-                DiffHunkKind::Different => {
-                    let [left, right] = hunk.contents[..].try_into().unwrap();
-                    removed += left.split_inclusive(|b| *b == b'\n').count();
-                    added += right.split_inclusive(|b| *b == b'\n').count();
+            for (side, content) in hunk.contents.iter().enumerate() {
+                let num_lines = content.split_inclusive(|b| *b == b'\n').count();
+                let start = line_indices[side];
+                line_indices[side] += num_lines;
+                match hunk.kind {
+                    DiffHunkKind::Matching => {}
+                    DiffHunkKind::Different => {
+                        counts[side] += (start..start + num_lines)
+                            .filter(|&index| !is_line_moved(moved, side, index))
+                            .count();
+                    }
                 }
             }
         }
+        let [removed, added] = counts;
         Some((added, removed))
     };
 
@@ -45,6 +45,16 @@ pub trait Formatter: Write {
     fn push_label(&mut self, label: &str);
 
     fn pop_label(&mut self);
+
+    /// Starts a hyperlink to the `url`. The subsequent text will be linked
+    /// until the matching `pop_hyperlink()` call. Formatters that don't
+    /// support hyperlinks print the text only.
+    fn push_hyperlink(&mut self, url: &str);
+
+    fn pop_hyperlink(&mut self);
+
+    /// Returns true if file contents should be syntax highlighted.
+    fn syntax_highlighting(&self) -> bool;
 }
 
 impl<T: Formatter + ?Sized> Formatter for &mut T {
@@ -59,6 +69,18 @@ impl<T: Formatter + ?Sized> Formatter for &mut T {
     fn pop_label(&mut self) {
         <T as Formatter>::pop_label(self);
     }
+
+    fn push_hyperlink(&mut self, url: &str) {
+        <T as Formatter>::push_hyperlink(self, url);
+    }
+
+    fn pop_hyperlink(&mut self) {
+        <T as Formatter>::pop_hyperlink(self);
+    }
+
+    fn syntax_highlighting(&self) -> bool {
+        <T as Formatter>::syntax_highlighting(self)
+    }
 }
 
 impl<T: Formatter + ?Sized> Formatter for Box<T> {
@@ -73,6 +95,18 @@ impl<T: Formatter + ?Sized> Formatter for Box<T> {
     fn pop_label(&mut self) {
         <T as Formatter>::pop_label(self);
     }
+
+    fn push_hyperlink(&mut self, url: &str) {
+        <T as Formatter>::push_hyperlink(self, url);
+    }
+
+    fn pop_hyperlink(&mut self) {
+        <T as Formatter>::pop_hyperlink(self);
+    }
+
+    fn syntax_highlighting(&self) -> bool {
+        <T as Formatter>::syntax_highlighting(self)
+    }
 }
 
 /// [`Formatter`] adapters.
@@ -172,7 +206,12 @@ pub struct FormatterFactory {
 enum FormatterFactoryKind {
     PlainText,
     Sanitized,
-    Color { rules: Arc<Rules>, debug: bool },
+    Color {
+        rules: Arc<Rules>,
+        debug: bool,
+        hyperlinks: bool,
+        syntax_highlighting: bool,
+    },
 }
 
 impl FormatterFactory {
@@ -186,9 +225,19 @@ impl FormatterFactory {
         Self { kind }
     }
 
-    pub fn color(config: &StackedConfig, debug: bool) -> Result<Self, ConfigGetError> {
+    pub fn color(
+        config: &StackedConfig,
+        debug: bool,
+        hyperlinks: bool,
+        syntax_highlighting: bool,
+    ) -> Result<Self, ConfigGetError> {
         let rules = Arc::new(rules_from_config(config)?);
-        let kind = FormatterFactoryKind::Color { rules, debug };
+        let kind = FormatterFactoryKind::Color {
+            rules,
+            debug,
+            hyperlinks,
+            syntax_highlighting,
+        };
         Ok(Self { kind })
     }
 
@@ -199,9 +248,16 @@ impl FormatterFactory {
         match &self.kind {
             FormatterFactoryKind::PlainText => Box::new(PlainTextFormatter::new(output)),
             FormatterFactoryKind::Sanitized => Box::new(SanitizingFormatter::new(output)),
-            FormatterFactoryKind::Color { rules, debug } => {
-                Box::new(ColorFormatter::new(output, rules.clone(), *debug))
-            }
+            FormatterFactoryKind::Color {
+                rules,
+                debug,
+                hyperlinks,
+                syntax_highlighting,
+            } => Box::new(
+                ColorFormatter::new(output, rules.clone(), *debug)
+                    .with_hyperlinks(*hyperlinks)
+                    .with_syntax_highlighting(*syntax_highlighting),
+            ),
         }
     }
 
@@ -238,6 +294,14 @@ impl<W: Write> Formatter for PlainTextFormatter<W> {
     fn push_label(&mut self, _label: &str) {}
 
     fn pop_label(&mut self) {}
+
+    fn push_hyperlink(&mut self, _url: &str) {}
+
+    fn pop_hyperlink(&mut self) {}
+
+    fn syntax_highlighting(&self) -> bool {
+        false
+    }
 }
 
 pub struct SanitizingFormatter<W> {
@@ -269,6 +333,14 @@ impl<W: Write> Formatter for SanitizingFormatter<W> {
     fn push_label(&mut self, _label: &str) {}
 
     fn pop_label(&mut self) {}
+
+    fn push_hyperlink(&mut self, _url: &str) {}
+
+    fn pop_hyperlink(&mut self) {}
+
+    fn syntax_highlighting(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
@@ -308,6 +380,15 @@ pub struct ColorFormatter<W: Write> {
     /// The debug string (space-separated labels) we last wrote to the output.
     /// Initialize to None to turn debug strings off.
     current_debug: Option<String>,
+    /// Whether to emit hyperlinks as OSC 8 escape sequences.
+    hyperlinks_enabled: bool,
+    /// The stack of currently requested hyperlink URLs. The innermost one is
+    /// applied.
+    hyperlinks: Vec<String>,
+    /// The hyperlink URL we last wrote to the output.
+    current_hyperlink: Option<String>,
+    /// Whether to highlight syntax of file contents.
+    syntax_highlighting: bool,
 }
 
 impl<W: Write> ColorFormatter<W> {
@@ -319,9 +400,26 @@ impl<W: Write> ColorFormatter<W> {
             cached_styles: HashMap::new(),
             current_style: Style::default(),
             current_debug: debug.then(String::new),
+            hyperlinks_enabled: true,
+            hyperlinks: vec![],
+            current_hyperlink: None,
+            syntax_highlighting: false,
         }
     }
 
+    /// Enables or disables hyperlinks. If disabled, the linked text is printed
+    /// without escape sequences.
+    pub fn with_hyperlinks(mut self, enabled: bool) -> Self {
+        self.hyperlinks_enabled = enabled;
+        self
+    }
+
+    /// Enables or disables syntax highlighting of file contents.
+    pub fn with_syntax_highlighting(mut self, enabled: bool) -> Self {
+        self.syntax_highlighting = enabled;
+        self
+    }
+
     pub fn for_config(
         output: W,
         config: &StackedConfig,
@@ -440,6 +538,22 @@ impl<W: Write> ColorFormatter<W> {
             }
             self.current_debug = Some(d);
         }
+        self.write_new_hyperlink()
+    }
+
+    fn write_new_hyperlink(&mut self) -> io::Result<()> {
+        let new_hyperlink = self
+            .hyperlinks
+            .last()
+            .filter(|url| self.hyperlinks_enabled && !url.is_empty());
+        if new_hyperlink == self.current_hyperlink.as_ref() {
+            return Ok(());
+        }
+        // OSC 8 ; params ; URI ST. Links can't be nested, so the new link
+        // implicitly terminates the current one.
+        let url = new_hyperlink.map_or("", |url| url);
+        write!(self.output, "\x1b]8;;{url}\x1b\\")?;
+        self.current_hyperlink = new_hyperlink.cloned();
         Ok(())
     }
 }
@@ -572,9 +686,11 @@ impl<W: Write> Write for ColorFormatter<W> {
                 self.write_new_style()?;
                 write_sanitized(&mut self.output, &line[..line.len() - 1])?;
                 let labels = mem::take(&mut self.labels);
+                let hyperlinks = mem::take(&mut self.hyperlinks);
                 self.write_new_style()?;
                 self.output.write_all(b"\n")?;
                 self.labels = labels;
+                self.hyperlinks = hyperlinks;
             } else {
                 self.write_new_style()?;
                 write_sanitized(&mut self.output, line)?;
@@ -603,6 +719,20 @@ impl<W: Write> Formatter for ColorFormatter<W> {
     fn pop_label(&mut self) {
         self.labels.pop();
     }
+
+    fn push_hyperlink(&mut self, url: &str) {
+        // Control characters would terminate the escape sequence
+        let url = url.chars().filter(|c| !c.is_control()).collect();
+        self.hyperlinks.push(url);
+    }
+
+    fn pop_hyperlink(&mut self) {
+        self.hyperlinks.pop();
+    }
+
+    fn syntax_highlighting(&self) -> bool {
+        self.syntax_highlighting
+    }
 }
 
 impl<W: Write> Drop for ColorFormatter<W> {
@@ -610,6 +740,7 @@ impl<W: Write> Drop for ColorFormatter<W> {
         // If a `ColorFormatter` was dropped without flushing, let's try to
         // reset any currently active style.
         self.labels.clear();
+        self.hyperlinks.clear();
         self.write_new_style().ok();
     }
 }
@@ -631,6 +762,8 @@ pub struct FormatRecorder {
 enum FormatOp {
     PushLabel(String),
     PopLabel,
+    PushHyperlink(String),
+    PopHyperlink,
     RawEscapeSequence(Vec<u8>),
 }
 
@@ -679,6 +812,8 @@ impl FormatRecorder {
             match op {
                 FormatOp::PushLabel(label) => formatter.push_label(label),
                 FormatOp::PopLabel => formatter.pop_label(),
+                FormatOp::PushHyperlink(url) => formatter.push_hyperlink(url),
+                FormatOp::PopHyperlink => formatter.pop_hyperlink(),
                 FormatOp::RawEscapeSequence(raw_escape_sequence) => {
                     formatter.raw()?.write_all(raw_escape_sequence)?;
                 }
@@ -724,6 +859,18 @@ impl Formatter for FormatRecorder {
     fn pop_label(&mut self) {
         self.push_op(FormatOp::PopLabel);
     }
+
+    fn push_hyperlink(&mut self, url: &str) {
+        self.push_op(FormatOp::PushHyperlink(url.to_owned()));
+    }
+
+    fn pop_hyperlink(&mut self) {
+        self.push_op(FormatOp::PopHyperlink);
+    }
+
+    fn syntax_highlighting(&self) -> bool {
+        false
+    }
 }
 
 fn write_sanitized(output: &mut impl Write, buf: &[u8]) -> Result<(), Error> {
@@ -1067,6 +1214,65 @@ mod tests {
             to_snapshot_string(output), @"[38;5;1mfoo[39m[38;5;1mbar[39m[EOF]");
     }
 
+    #[test]
+    fn test_color_formatter_hyperlink() {
+        let config = config_from_string("colors.red = 'red'");
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = ColorFormatter::for_config(&mut output, &config, false).unwrap();
+        write!(formatter, "before ").unwrap();
+        formatter.push_hyperlink("https://example.com/a");
+        formatter.push_label("red");
+        write!(formatter, "link").unwrap();
+        formatter.pop_label();
+        formatter.push_hyperlink("https://example.com/b");
+        write!(formatter, "nested").unwrap();
+        formatter.pop_hyperlink();
+        write!(formatter, " multi\nline").unwrap();
+        formatter.pop_hyperlink();
+        write!(formatter, " after").unwrap();
+        drop(formatter);
+        insta::assert_snapshot!(to_snapshot_string(output), @r"
+        before [38;5;1m]8;;https://example.com/a\link[39m]8;;https://example.com/b\nested]8;;https://example.com/a\ multi]8;;\
+        ]8;;https://example.com/a\line]8;;\ after[EOF]
+        ");
+
+        // Control characters should be removed from the URL
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = ColorFormatter::for_config(&mut output, &config, false).unwrap();
+        formatter.push_hyperlink("https://example.com/\x1b\x07x");
+        write!(formatter, "link").unwrap();
+        formatter.pop_hyperlink();
+        drop(formatter);
+        insta::assert_snapshot!(to_snapshot_string(output), @r"]8;;https://example.com/x\link]8;;\[EOF]");
+
+        // Empty hyperlink shouldn't be emitted
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = ColorFormatter::for_config(&mut output, &config, false).unwrap();
+        formatter.push_hyperlink("https://example.com/");
+        formatter.pop_hyperlink();
+        write!(formatter, "text").unwrap();
+        drop(formatter);
+        insta::assert_snapshot!(to_snapshot_string(output), @"text[EOF]");
+
+        // Disabled hyperlinks should print the text only
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = ColorFormatter::for_config(&mut output, &config, false)
+            .unwrap()
+            .with_hyperlinks(false);
+        formatter.push_hyperlink("https://example.com/");
+        write!(formatter, "link").unwrap();
+        formatter.pop_hyperlink();
+        drop(formatter);
+        insta::assert_snapshot!(to_snapshot_string(output), @"link[EOF]");
+
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = PlainTextFormatter::new(&mut output);
+        formatter.push_hyperlink("https://example.com/");
+        write!(formatter, "link").unwrap();
+        formatter.pop_hyperlink();
+        insta::assert_snapshot!(to_snapshot_string(output), @"link[EOF]");
+    }
+
     #[test]
     fn test_color_formatter_no_space() {
         // Test that two different colors can touch.
@@ -1461,6 +1667,22 @@ mod tests {
             @"<< outer1 >>[38;5;1m<< inner1  inner2 >>[39m<< outer2 >>[EOF]");
     }
 
+    #[test]
+    fn test_format_recorder_hyperlink() {
+        let mut recorder = FormatRecorder::new();
+        write!(recorder, " outer ").unwrap();
+        recorder.push_hyperlink("https://example.com/");
+        write!(recorder, " link ").unwrap();
+        recorder.pop_hyperlink();
+
+        let config = config_from_string("");
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = ColorFormatter::for_config(&mut output, &config, false).unwrap();
+        recorder.replay(&mut formatter).unwrap();
+        drop(formatter);
+        insta::assert_snapshot!(to_snapshot_string(output), @r" outer ]8;;https://example.com/\ link ]8;;\[EOF]");
+    }
+
     #[test]
     fn test_raw_format_recorder() {
         // Note: similar to test_format_recorder above
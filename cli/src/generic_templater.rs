@@ -22,7 +22,9 @@ use crate::template_builder::BuildContext;
 use crate::template_builder::CoreTemplateBuildFnTable;
 use crate::template_builder::CoreTemplatePropertyKind;
 use crate::template_builder::CoreTemplatePropertyVar;
+use crate::template_builder::SharedPropertyFn;
 use crate::template_builder::TemplateLanguage;
+use crate::template_builder::share_property;
 use crate::template_parser;
 use crate::template_parser::FunctionCallNode;
 use crate::template_parser::TemplateDiagnostics;
@@ -237,6 +239,17 @@ where
             (Self::Self_(_), _) => None,
         }
     }
+
+    fn into_shared(self) -> SharedPropertyFn<'a, Self> {
+        match self {
+            Self::Core(property) => {
+                let make = property.into_shared();
+                Box::new(move || Self::Core(make()))
+            }
+            // The self type isn't required to be cloneable.
+            Self::Self_(property) => share_property(property, Self::Self_),
+        }
+    }
 }
 
 /// Function that translates keyword (or 0-ary method call node of the self type
@@ -42,8 +42,10 @@ pub mod graphlog;
 pub mod merge_tools;
 pub mod movement_util;
 pub mod operation_templater;
+pub mod output_format;
 mod progress;
 pub mod revset_util;
+pub mod syntax_highlight;
 pub mod template_builder;
 pub mod template_parser;
 pub mod templater;
@@ -21,6 +21,7 @@ use jj_lib::copies::CopyRecords;
 use jj_lib::diff::ContentDiff;
 use jj_lib::diff::DiffHunkKind;
 use jj_lib::files;
+use jj_lib::files::FileMergeHunkLevel;
 use jj_lib::files::MergeResult;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Merge;
@@ -610,29 +611,31 @@ fn make_merge_sections(
                         }
                     }
                     Err(merge) => {
-                        let lines: Vec<scm_record::SectionChangedLine> = merge
-                            .iter()
-                            .zip(
-                                [
-                                    scm_record::ChangeType::Added,
-                                    scm_record::ChangeType::Removed,
-                                ]
-                                .into_iter()
-                                .cycle(),
-                            )
-                            .map(|(contents, change_type)| -> Result<_, BuiltinToolError> {
-                                let contents = str::from_utf8(contents).map_err(|err| {
-                                    BuiltinToolError::DecodeUtf8 {
-                                        source: err,
-                                        item: "conflicting hunk",
-                                    }
-                                })?;
-                                let changed_lines =
-                                    make_section_changed_lines(contents, change_type);
-                                Ok(changed_lines)
-                            })
-                            .flatten_ok()
-                            .try_collect()?;
+                        // Show the first side, the base, and the other sides.
+                        // The other bases are omitted so that the base is
+                        // kept only once if no side is selected.
+                        let mut adds = merge.adds();
+                        let first_add = adds.next().expect("conflict should have sides");
+                        let base = merge.removes().next().expect("conflict should have bases");
+                        let lines: Vec<scm_record::SectionChangedLine> = itertools::chain!(
+                            [
+                                (first_add, scm_record::ChangeType::Added),
+                                (base, scm_record::ChangeType::Removed),
+                            ],
+                            adds.map(|contents| (contents, scm_record::ChangeType::Added)),
+                        )
+                        .map(|(contents, change_type)| -> Result<_, BuiltinToolError> {
+                            let contents = str::from_utf8(contents).map_err(|err| {
+                                BuiltinToolError::DecodeUtf8 {
+                                    source: err,
+                                    item: "conflicting hunk",
+                                }
+                            })?;
+                            let changed_lines = make_section_changed_lines(contents, change_type);
+                            Ok(changed_lines)
+                        })
+                        .flatten_ok()
+                        .try_collect()?;
                         scm_record::Section::Changed { lines }
                     }
                 };
@@ -646,6 +649,7 @@ fn make_merge_sections(
 fn make_merge_file(
     merge_tool_file: &MergeToolFile,
     options: &MergeOptions,
+    hunk_level: FileMergeHunkLevel,
 ) -> Result<scm_record::File<'static>, BuiltinToolError> {
     let file = &merge_tool_file.file;
     let file_mode = if file.executable.expect("should have been resolved") {
@@ -655,7 +659,12 @@ fn make_merge_file(
     };
     // TODO: Maybe we should test binary contents here, and generate per-file
     // Binary section to select either "our" or "their" file.
-    let merge_result = files::merge_hunks(&file.contents, options);
+    let merge_result = match hunk_level {
+        FileMergeHunkLevel::Line => files::merge_hunks(&file.contents, options),
+        // Conflicting lines are split into words so that intra-line changes
+        // can be picked individually.
+        FileMergeHunkLevel::Word => files::merge_hunks_by_word(&file.contents, options),
+    };
     let sections = make_merge_sections(merge_result)?;
     Ok(scm_record::File {
         old_path: None,
@@ -673,6 +682,7 @@ fn make_merge_file(
 pub fn edit_merge_builtin(
     tree: &MergedTree,
     merge_tool_files: &[MergeToolFile],
+    hunk_level: FileMergeHunkLevel,
 ) -> Result<MergedTreeId, BuiltinToolError> {
     let store = tree.store();
     let mut input = scm_record::helpers::CrosstermInput;
@@ -681,7 +691,7 @@ pub fn edit_merge_builtin(
             is_read_only: false,
             files: merge_tool_files
                 .iter()
-                .map(|f| make_merge_file(f, store.merge_options()))
+                .map(|f| make_merge_file(f, store.merge_options(), hunk_level))
                 .try_collect()?,
             commits: Default::default(),
         },
@@ -721,8 +731,10 @@ mod tests {
 
     use jj_lib::backend::FileId;
     use jj_lib::conflicts::extract_as_single_hunk;
+    use jj_lib::diff::DiffAlgorithm;
     use jj_lib::matchers::EverythingMatcher;
     use jj_lib::matchers::FilesMatcher;
+    use jj_lib::merge::SameChange;
     use jj_lib::repo::Repo as _;
     use proptest::prelude::*;
     use proptest_state_machine::ReferenceStateMachine;
@@ -1903,6 +1915,143 @@ mod tests {
         "#);
     }
 
+    #[test]
+    fn test_make_merge_sections_multiple_sides() {
+        let options = MergeOptions {
+            hunk_level: FileMergeHunkLevel::Line,
+            same_change: SameChange::Accept,
+            diff_algorithm: DiffAlgorithm::Default,
+        };
+        let merge = Merge::from_vec(vec![
+            "left 1\nbase 2\n",
+            "base 1\nbase 2\n",
+            "middle 1\nbase 2\n",
+            "base 1\nbase 2\n",
+            "right 1\nbase 2\n",
+        ]);
+        let merge_result = files::merge_hunks(&merge, &options);
+        let sections = make_merge_sections(merge_result).unwrap();
+        insta::assert_debug_snapshot!(sections, @r#"
+        [
+            Changed {
+                lines: [
+                    SectionChangedLine {
+                        is_checked: false,
+                        change_type: Added,
+                        line: "left 1\n",
+                    },
+                    SectionChangedLine {
+                        is_checked: false,
+                        change_type: Removed,
+                        line: "base 1\n",
+                    },
+                    SectionChangedLine {
+                        is_checked: false,
+                        change_type: Added,
+                        line: "middle 1\n",
+                    },
+                    SectionChangedLine {
+                        is_checked: false,
+                        change_type: Added,
+                        line: "right 1\n",
+                    },
+                ],
+            },
+            Unchanged {
+                lines: [
+                    "base 2\n",
+                ],
+            },
+        ]
+        "#);
+
+        let selected_contents = |sections: &[scm_record::Section<'static>]| {
+            let file = scm_record::File {
+                old_path: None,
+                path: Cow::Borrowed(Path::new("file")),
+                file_mode: mode::NORMAL,
+                sections: sections.to_vec(),
+            };
+            match file.get_selected_contents().0.contents {
+                scm_record::SelectedContents::Text { contents } => contents,
+                contents => panic!("unexpected contents: {contents:?}"),
+            }
+        };
+        let select_lines = |selected: &[&str]| {
+            let mut sections = sections.clone();
+            for section in &mut sections {
+                if let scm_record::Section::Changed { lines } = section {
+                    for line in lines {
+                        line.is_checked = selected.contains(&line.line.as_ref());
+                    }
+                }
+            }
+            sections
+        };
+
+        // The base is kept once if nothing is selected
+        assert_eq!(selected_contents(&sections), "base 1\nbase 2\n");
+        // A side can replace the base
+        assert_eq!(
+            selected_contents(&select_lines(&["middle 1\n", "base 1\n"])),
+            "middle 1\nbase 2\n"
+        );
+        // Sides can be kept along with the base
+        assert_eq!(
+            selected_contents(&select_lines(&["left 1\n", "middle 1\n", "right 1\n"])),
+            "left 1\nbase 1\nmiddle 1\nright 1\nbase 2\n"
+        );
+    }
+
+    #[test]
+    fn test_make_merge_sections_by_word() {
+        let options = MergeOptions {
+            hunk_level: FileMergeHunkLevel::Word,
+            same_change: SameChange::Accept,
+            diff_algorithm: DiffAlgorithm::Default,
+        };
+        let merge = Merge::from_vec(vec![
+            "let x = left(1, 2);\n",
+            "let x = base(1, 2);\n",
+            "let x = right(1, 2);\n",
+        ]);
+        let merge_result = files::merge_hunks_by_word(&merge, &options);
+        let sections = make_merge_sections(merge_result).unwrap();
+        insta::assert_debug_snapshot!(sections, @r#"
+        [
+            Unchanged {
+                lines: [
+                    "let x = ",
+                ],
+            },
+            Changed {
+                lines: [
+                    SectionChangedLine {
+                        is_checked: false,
+                        change_type: Added,
+                        line: "left",
+                    },
+                    SectionChangedLine {
+                        is_checked: false,
+                        change_type: Removed,
+                        line: "base",
+                    },
+                    SectionChangedLine {
+                        is_checked: false,
+                        change_type: Added,
+                        line: "right",
+                    },
+                ],
+            },
+            Unchanged {
+                lines: [
+                    "(1, 2);\n",
+                ],
+            },
+        ]
+        "#);
+    }
+
     prop_state_machine! {
         #[test]
         fn test_edit_diff_builtin_all_or_nothing_proptest(
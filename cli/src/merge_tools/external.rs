@@ -338,6 +338,16 @@ pub fn run_mergetool_external(
     merge_tool_files: &[MergeToolFile],
     default_conflict_marker_style: ConflictMarkerStyle,
 ) -> Result<(MergedTreeId, Option<MergeToolPartialResolutionError>), ConflictResolveError> {
+    // We only support conflicts with 2 sides (3-way conflicts)
+    if let Some(file) = merge_tool_files
+        .iter()
+        .find(|file| file.file.ids.num_sides() > 2)
+    {
+        return Err(ConflictResolveError::ConflictTooComplicated {
+            path: file.repo_path.clone(),
+            sides: file.file.ids.num_sides(),
+        });
+    }
     // TODO: add support for "dir" invocation mode, similar to the
     // "diff-invocation-mode" config option for diffs
     let mut tree_builder = MergedTreeBuilder::new(tree.id());
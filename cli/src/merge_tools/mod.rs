@@ -29,6 +29,7 @@ use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::conflicts::MaterializedFileConflictValue;
 use jj_lib::conflicts::try_materialize_file_conflict_value;
+use jj_lib::files::FileMergeHunkLevel;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Merge;
@@ -102,7 +103,10 @@ pub enum ConflictResolveError {
         summary = summary.trim_end()
     )]
     NotNormalFiles { path: RepoPathBuf, summary: String },
-    #[error("The conflict at {path:?} has {sides} sides. At most 2 sides are supported.")]
+    #[error(
+        "The conflict at {path:?} has {sides} sides. At most 2 sides are supported by \
+         external merge tools."
+    )]
     ConflictTooComplicated { path: RepoPathBuf, sides: usize },
     #[error("{path:?} has conflicts in executable bit\n{summary}", summary = summary.trim_end())]
     ExecutableConflict { path: RepoPathBuf, summary: String },
@@ -344,13 +348,6 @@ impl MergeToolFile {
                 path: repo_path.to_owned(),
                 summary: conflict.describe(),
             })?;
-        // We only support conflicts with 2 sides (3-way conflicts)
-        if file.ids.num_sides() > 2 {
-            return Err(ConflictResolveError::ConflictTooComplicated {
-                path: repo_path.to_owned(),
-                sides: file.ids.num_sides(),
-            });
-        };
         if file.executable.is_none() {
             return Err(ConflictResolveError::ExecutableConflict {
                 path: repo_path.to_owned(),
@@ -371,6 +368,7 @@ pub struct MergeEditor {
     tool: MergeTool,
     path_converter: RepoPathUiConverter,
     conflict_marker_style: ConflictMarkerStyle,
+    hunk_level: FileMergeHunkLevel,
 }
 
 impl MergeEditor {
@@ -384,7 +382,7 @@ impl MergeEditor {
     ) -> Result<Self, MergeToolConfigError> {
         let tool = MergeTool::get_tool_config(settings, name)?
             .unwrap_or_else(|| MergeTool::external(ExternalMergeTool::with_program(name)));
-        Self::new_inner(name, tool, path_converter, conflict_marker_style)
+        Self::new_inner(name, tool, settings, path_converter, conflict_marker_style)
     }
 
     /// Loads the default 3-way merge editor from the settings.
@@ -401,12 +399,13 @@ impl MergeEditor {
             None
         }
         .unwrap_or_else(|| MergeTool::external(ExternalMergeTool::with_merge_args(&args)));
-        Self::new_inner(&args, tool, path_converter, conflict_marker_style)
+        Self::new_inner(&args, tool, settings, path_converter, conflict_marker_style)
     }
 
     fn new_inner(
         name: impl ToString,
         tool: MergeTool,
+        settings: &UserSettings,
         path_converter: RepoPathUiConverter,
         conflict_marker_style: ConflictMarkerStyle,
     ) -> Result<Self, MergeToolConfigError> {
@@ -421,6 +420,7 @@ impl MergeEditor {
             tool,
             path_converter,
             conflict_marker_style,
+            hunk_level: settings.get("ui.merge-editor-hunk-level")?,
         })
     }
 
@@ -438,7 +438,8 @@ impl MergeEditor {
 
         match &self.tool {
             MergeTool::Builtin => {
-                let tree_id = edit_merge_builtin(tree, &merge_tool_files).map_err(Box::new)?;
+                let tree_id = edit_merge_builtin(tree, &merge_tool_files, self.hunk_level)
+                    .map_err(Box::new)?;
                 Ok((tree_id, None))
             }
             MergeTool::Ours => {
@@ -32,9 +32,12 @@ use crate::template_builder::BuildContext;
 use crate::template_builder::CoreTemplateBuildFnTable;
 use crate::template_builder::CoreTemplatePropertyKind;
 use crate::template_builder::CoreTemplatePropertyVar;
+use crate::template_builder::SharedPropertyFn;
 use crate::template_builder::TemplateBuildMethodFnMap;
 use crate::template_builder::TemplateLanguage;
+use crate::template_builder::expect_stringify_expression;
 use crate::template_builder::merge_fn_map;
+use crate::template_builder::share_memoized_property;
 use crate::template_parser;
 use crate::template_parser::FunctionCallNode;
 use crate::template_parser::TemplateDiagnostics;
@@ -273,6 +276,15 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             (Self::OperationId(_), _) => None,
         }
     }
+
+    pub fn into_shared(self) -> SharedPropertyFn<'a, Self> {
+        match self {
+            Self::Operation(property) => share_memoized_property(property, Self::Operation),
+            Self::OperationOpt(property) => share_memoized_property(property, Self::OperationOpt),
+            Self::OperationList(property) => share_memoized_property(property, Self::OperationList),
+            Self::OperationId(property) => share_memoized_property(property, Self::OperationId),
+        }
+    }
 }
 
 /// Tagged property types available in [`OperationTemplateLanguage`].
@@ -354,6 +366,19 @@ impl CoreTemplatePropertyVar<'static> for OperationTemplateLanguagePropertyKind
             (Self::Operation(lhs), Self::Operation(rhs)) => lhs.try_into_cmp(rhs),
         }
     }
+
+    fn into_shared(self) -> SharedPropertyFn<'static, Self> {
+        match self {
+            Self::Core(property) => {
+                let make = property.into_shared();
+                Box::new(move || Self::Core(make()))
+            }
+            Self::Operation(property) => {
+                let make = property.into_shared();
+                Box::new(move || Self::Operation(make()))
+            }
+        }
+    }
 }
 
 impl OperationTemplatePropertyVar<'static> for OperationTemplateLanguagePropertyKind {}
@@ -511,12 +536,24 @@ where
                 op.metadata()
                     .tags
                     .iter()
+                    .sorted()
                     .map(|(key, value)| format!("{key}: {value}"))
                     .join("\n")
             });
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "tag",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [name_node] = function.expect_exact_arguments()?;
+            let name_property =
+                expect_stringify_expression(language, diagnostics, build_ctx, name_node)?;
+            let out_property = (self_property, name_property)
+                .map(|(op, name)| op.metadata().tags.get(&name).cloned().unwrap_or_default());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "snapshot",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -565,6 +602,22 @@ where
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "merged_from",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|op| {
+                // Operations with multiple parents are created by merging
+                // concurrent operations.
+                if op.parent_ids().len() < 2 {
+                    return Ok(vec![]);
+                }
+                let ops: Vec<_> = op.parents().try_collect()?;
+                Ok(ops)
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
@@ -0,0 +1,148 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Machine-readable output of listing commands.
+
+use std::io;
+
+use clap::ValueEnum as _;
+use serde::Serialize;
+
+use crate::command_error::CommandError;
+use crate::command_error::cli_error;
+use crate::formatter::Formatter;
+
+/// Version of the serialized output schema.
+///
+/// This should be incremented when fields are removed or changed
+/// incompatibly. Adding new fields doesn't require a new version.
+pub const OUTPUT_SCHEMA_VERSION: u32 = 1;
+
+/// Output format of listing commands.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OutputFormat {
+    /// Human-readable text rendered by templates
+    #[default]
+    Text,
+    /// JSON object with the schema version and the array of items
+    Json,
+    /// JSON object per line containing the schema version and the item
+    Jsonl,
+}
+
+impl OutputFormat {
+    /// Returns true if the items should be serialized instead of rendered by
+    /// templates.
+    pub fn is_serialized(self) -> bool {
+        self != Self::Text
+    }
+
+    /// Returns an error if the `option` which only affects the text output is
+    /// specified with a serialized format.
+    pub fn check_text_option(self, option: &str, is_set: bool) -> Result<(), CommandError> {
+        if self.is_serialized() && is_set {
+            let value = self.to_possible_value().unwrap();
+            Err(cli_error(format!(
+                "{option} cannot be used with --output {}",
+                value.get_name()
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Arguments to select the output format of listing commands.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OutputFormatArgs {
+    /// Print the listed items in machine-readable format
+    ///
+    /// The items are printed as JSON objects instead of being rendered by
+    /// templates. `json` prints a single JSON object containing the schema
+    /// `version` and the array of `items`, and `jsonl` prints each item on its
+    /// own line.
+    #[arg(long, value_name = "FORMAT")]
+    output: Option<OutputFormat>,
+}
+
+impl OutputFormatArgs {
+    /// Output format specified by `--output`.
+    pub fn format(&self) -> OutputFormat {
+        self.output.unwrap_or_default()
+    }
+}
+
+/// Line of JSON Lines output, which carries the schema version.
+#[derive(Serialize)]
+struct VersionedItem<'a, T> {
+    version: u32,
+    item: &'a T,
+}
+
+/// Writes serialized items in JSON or JSON Lines format.
+pub struct SerializedItemWriter<'a> {
+    formatter: &'a mut dyn Formatter,
+    format: OutputFormat,
+    count: usize,
+}
+
+impl<'a> SerializedItemWriter<'a> {
+    /// Starts writing items in the given `format`, which must be a serialized
+    /// format.
+    pub fn new(formatter: &'a mut dyn Formatter, format: OutputFormat) -> io::Result<Self> {
+        assert!(format.is_serialized());
+        if format == OutputFormat::Json {
+            write!(
+                formatter,
+                r#"{{"version":{OUTPUT_SCHEMA_VERSION},"items":["#
+            )?;
+        }
+        Ok(Self {
+            formatter,
+            format,
+            count: 0,
+        })
+    }
+
+    /// Writes the serialized `item`.
+    pub fn write_item(&mut self, item: &impl Serialize) -> io::Result<()> {
+        match self.format {
+            OutputFormat::Text => unreachable!(),
+            OutputFormat::Json => {
+                if self.count > 0 {
+                    write!(self.formatter, ",")?;
+                }
+                serde_json::to_writer(&mut *self.formatter, item)?;
+            }
+            OutputFormat::Jsonl => {
+                let record = VersionedItem {
+                    version: OUTPUT_SCHEMA_VERSION,
+                    item,
+                };
+                serde_json::to_writer(&mut *self.formatter, &record)?;
+                writeln!(self.formatter)?;
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Finishes writing items.
+    pub fn finish(self) -> io::Result<()> {
+        if self.format == OutputFormat::Json {
+            writeln!(self.formatter, "]}}")?;
+        }
+        Ok(())
+    }
+}
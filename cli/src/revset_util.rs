@@ -42,6 +42,7 @@ use jj_lib::revset::RevsetResolutionError;
 use jj_lib::revset::SymbolResolver;
 use jj_lib::revset::SymbolResolverExtension;
 use jj_lib::revset::UserRevsetExpression;
+use jj_lib::time_util::DatePatternContext;
 use thiserror::Error;
 
 use crate::command_error::CommandError;
@@ -65,6 +66,7 @@ pub struct RevsetExpressionEvaluator<'repo> {
     repo: &'repo dyn Repo,
     extensions: Arc<RevsetExtensions>,
     id_prefix_context: &'repo IdPrefixContext,
+    date_pattern_context: DatePatternContext,
     expression: Arc<UserRevsetExpression>,
 }
 
@@ -73,12 +75,14 @@ impl<'repo> RevsetExpressionEvaluator<'repo> {
         repo: &'repo dyn Repo,
         extensions: Arc<RevsetExtensions>,
         id_prefix_context: &'repo IdPrefixContext,
+        date_pattern_context: DatePatternContext,
         expression: Arc<UserRevsetExpression>,
     ) -> Self {
         Self {
             repo,
             extensions,
             id_prefix_context,
+            date_pattern_context,
             expression,
         }
     }
@@ -99,6 +103,7 @@ impl<'repo> RevsetExpressionEvaluator<'repo> {
             self.repo,
             self.extensions.symbol_resolvers(),
             self.id_prefix_context,
+            self.date_pattern_context,
         );
         self.expression
             .resolve_user_expression(self.repo, &symbol_resolver)
@@ -209,8 +214,11 @@ pub fn default_symbol_resolver<'a>(
     repo: &'a dyn Repo,
     extensions: &[impl AsRef<dyn SymbolResolverExtension>],
     id_prefix_context: &'a IdPrefixContext,
+    date_pattern_context: DatePatternContext,
 ) -> SymbolResolver<'a> {
-    SymbolResolver::new(repo, extensions).with_id_prefix_context(id_prefix_context)
+    SymbolResolver::new(repo, extensions)
+        .with_id_prefix_context(id_prefix_context)
+        .with_date_pattern_context(date_pattern_context)
 }
 
 /// Parses user-configured expression defining the heads of the immutable set.
@@ -244,7 +252,8 @@ pub(super) fn warn_unresolvable_trunk(
     };
     // Not using IdPrefixContext since trunk() revset shouldn't contain short
     // prefixes.
-    let symbol_resolver = SymbolResolver::new(repo, context.extensions.symbol_resolvers());
+    let symbol_resolver = SymbolResolver::new(repo, context.extensions.symbol_resolvers())
+        .with_date_pattern_context(context.date_pattern_context);
     if let Err(err) = expression.resolve_user_expression(repo, &symbol_resolver) {
         writeln!(
             ui.warning_default(),
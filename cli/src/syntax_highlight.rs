@@ -0,0 +1,943 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lightweight syntax highlighting of file contents.
+//!
+//! Lines are tokenized by simple per-language rules, and the tokens are
+//! emitted as `syntax <kind>` labels. Block comments and multi-line strings
+//! are tracked by the [`LineState`] carried over from the preceding line.
+
+use std::io;
+use std::iter;
+use std::ops::Range;
+
+use jj_lib::repo_path::RepoPath;
+
+use crate::formatter::Formatter;
+
+/// Kind of syntax token, which is mapped to a label.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SyntaxKind {
+    Comment,
+    Keyword,
+    Number,
+    String,
+}
+
+impl SyntaxKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Comment => "comment",
+            Self::Keyword => "keyword",
+            Self::Number => "number",
+            Self::String => "string",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Language {
+    extensions: &'static [&'static str],
+    keywords: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Delimiters of strings which may span multiple lines. These take
+    /// precedence over the `string_quotes`.
+    multiline_quotes: &'static [&'static str],
+    string_quotes: &'static [u8],
+    /// Whether `'` starts a character literal such as `'a'`, but may also
+    /// appear unpaired (e.g. Rust lifetimes.)
+    char_literals: bool,
+}
+
+const C_KEYWORDS: &[&str] = &[
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "constexpr",
+    "continue",
+    "default",
+    "delete",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "explicit",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "friend",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "namespace",
+    "new",
+    "nullptr",
+    "operator",
+    "private",
+    "protected",
+    "public",
+    "register",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "template",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typedef",
+    "typename",
+    "union",
+    "unsigned",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+];
+
+const LANGUAGES: &[Language] = &[
+    Language {
+        extensions: &["rs"],
+        keywords: &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+            "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+            "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super",
+            "trait", "true", "type", "unsafe", "use", "where", "while",
+        ],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        multiline_quotes: &[],
+        string_quotes: b"\"",
+        char_literals: true,
+    },
+    Language {
+        extensions: &["c", "h", "cc", "cpp", "cxx", "hh", "hpp", "hxx"],
+        keywords: C_KEYWORDS,
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        multiline_quotes: &[],
+        string_quotes: b"\"",
+        char_literals: true,
+    },
+    Language {
+        extensions: &["go"],
+        keywords: &[
+            "break",
+            "case",
+            "chan",
+            "const",
+            "continue",
+            "default",
+            "defer",
+            "else",
+            "fallthrough",
+            "false",
+            "for",
+            "func",
+            "go",
+            "goto",
+            "if",
+            "import",
+            "interface",
+            "map",
+            "nil",
+            "package",
+            "range",
+            "return",
+            "select",
+            "struct",
+            "switch",
+            "true",
+            "type",
+            "var",
+        ],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        multiline_quotes: &["`"],
+        string_quotes: b"\"",
+        char_literals: true,
+    },
+    Language {
+        extensions: &["java", "kt", "kts"],
+        keywords: &[
+            "abstract",
+            "boolean",
+            "break",
+            "case",
+            "catch",
+            "class",
+            "continue",
+            "default",
+            "do",
+            "else",
+            "enum",
+            "extends",
+            "false",
+            "final",
+            "finally",
+            "for",
+            "fun",
+            "if",
+            "implements",
+            "import",
+            "instanceof",
+            "interface",
+            "new",
+            "null",
+            "override",
+            "package",
+            "private",
+            "protected",
+            "public",
+            "return",
+            "static",
+            "super",
+            "switch",
+            "this",
+            "throw",
+            "throws",
+            "true",
+            "try",
+            "val",
+            "var",
+            "void",
+            "when",
+            "while",
+        ],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        multiline_quotes: &["\"\"\""],
+        string_quotes: b"\"",
+        char_literals: true,
+    },
+    Language {
+        extensions: &["js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts"],
+        keywords: &[
+            "async",
+            "await",
+            "break",
+            "case",
+            "catch",
+            "class",
+            "const",
+            "continue",
+            "default",
+            "delete",
+            "do",
+            "else",
+            "export",
+            "extends",
+            "false",
+            "finally",
+            "for",
+            "from",
+            "function",
+            "if",
+            "import",
+            "in",
+            "instanceof",
+            "interface",
+            "let",
+            "new",
+            "null",
+            "of",
+            "return",
+            "super",
+            "switch",
+            "this",
+            "throw",
+            "true",
+            "try",
+            "type",
+            "typeof",
+            "undefined",
+            "var",
+            "void",
+            "while",
+            "yield",
+        ],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        multiline_quotes: &["`"],
+        string_quotes: b"\"'",
+        char_literals: false,
+    },
+    Language {
+        extensions: &["py", "pyi"],
+        keywords: &[
+            "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+            "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global",
+            "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise",
+            "return", "try", "while", "with", "yield",
+        ],
+        line_comments: &["#"],
+        block_comment: None,
+        multiline_quotes: &["\"\"\"", "'''"],
+        string_quotes: b"\"'",
+        char_literals: false,
+    },
+    Language {
+        extensions: &["sh", "bash", "zsh"],
+        keywords: &[
+            "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+            "in", "local", "return", "then", "until", "while",
+        ],
+        line_comments: &["#"],
+        block_comment: None,
+        multiline_quotes: &[],
+        string_quotes: b"\"'",
+        char_literals: false,
+    },
+    Language {
+        extensions: &["toml"],
+        keywords: &["false", "true"],
+        line_comments: &["#"],
+        block_comment: None,
+        multiline_quotes: &["\"\"\"", "'''"],
+        string_quotes: b"\"'",
+        char_literals: false,
+    },
+];
+
+/// State of the constructs continued from the preceding line.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LineState {
+    #[default]
+    Code,
+    /// Inside a block comment.
+    BlockComment,
+    /// Inside a string which is closed by the given delimiter.
+    String(&'static str),
+}
+
+/// Tokenizes lines of a file in the language detected by file extension.
+#[derive(Clone, Copy, Debug)]
+pub struct SyntaxHighlighter {
+    language: &'static Language,
+}
+
+impl SyntaxHighlighter {
+    /// Returns highlighter for the given file path, or `None` if the language
+    /// isn't supported.
+    pub fn for_path(path: &RepoPath) -> Option<Self> {
+        let (_, file_name) = path.split()?;
+        let (_, extension) = file_name.as_internal_str().rsplit_once('.')?;
+        let language = LANGUAGES
+            .iter()
+            .find(|language| language.extensions.contains(&extension))?;
+        Some(Self { language })
+    }
+
+    /// Returns highlighter for the given file path if the `formatter` has
+    /// syntax highlighting enabled.
+    pub fn for_formatter(formatter: &dyn Formatter, path: &RepoPath) -> Option<Self> {
+        if formatter.syntax_highlighting() {
+            Self::for_path(path)
+        } else {
+            None
+        }
+    }
+
+    /// Splits the `line` into ranges of syntax tokens, starting with the
+    /// `state` of the preceding line. Bytes not covered by the returned ranges
+    /// are plain text. Returns the state at the end of the line.
+    pub fn highlight_line(
+        &self,
+        mut state: LineState,
+        line: &[u8],
+    ) -> (Vec<(Range<usize>, SyntaxKind)>, LineState) {
+        let language = self.language;
+        let end_of_line = line.trim_ascii_end().len();
+        let is_word_byte = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || !b.is_ascii();
+        let mut spans = vec![];
+        let mut pos = 0;
+        // Close the construct continued from the preceding line.
+        let continued = match state {
+            LineState::Code => None,
+            LineState::BlockComment => {
+                let (_, end) = language.block_comment.expect("language has block comments");
+                let len =
+                    find_subslice(&line[..end_of_line], end.as_bytes()).map(|n| n + end.len());
+                Some((len, SyntaxKind::Comment))
+            }
+            LineState::String(quote) => {
+                let len = find_closing_quote(&line[..end_of_line], quote.as_bytes());
+                Some((len, SyntaxKind::String))
+            }
+        };
+        if let Some((len, kind)) = continued {
+            pos = len.unwrap_or(end_of_line);
+            if len.is_some() {
+                state = LineState::Code;
+            }
+            if pos > 0 {
+                spans.push((0..pos, kind));
+            }
+        }
+        while pos < end_of_line {
+            let rest = &line[pos..end_of_line];
+            let b = rest[0];
+            let (len, kind) = if language
+                .line_comments
+                .iter()
+                .any(|prefix| rest.starts_with(prefix.as_bytes()))
+            {
+                (rest.len(), Some(SyntaxKind::Comment))
+            } else if let Some((start, end)) = language.block_comment
+                && rest.starts_with(start.as_bytes())
+            {
+                let len = find_subslice(&rest[start.len()..], end.as_bytes())
+                    .map(|n| start.len() + n + end.len());
+                if len.is_none() {
+                    state = LineState::BlockComment;
+                }
+                (len.unwrap_or(rest.len()), Some(SyntaxKind::Comment))
+            } else if let Some(&quote) = language
+                .multiline_quotes
+                .iter()
+                .find(|quote| rest.starts_with(quote.as_bytes()))
+            {
+                let len = find_closing_quote(&rest[quote.len()..], quote.as_bytes())
+                    .map(|n| quote.len() + n);
+                if len.is_none() {
+                    state = LineState::String(quote);
+                }
+                (len.unwrap_or(rest.len()), Some(SyntaxKind::String))
+            } else if language.string_quotes.contains(&b) {
+                let len = find_closing_quote(&rest[1..], &rest[..1]).map_or(rest.len(), |n| n + 1);
+                (len, Some(SyntaxKind::String))
+            } else if b == b'\'' && language.char_literals {
+                match char_literal_len(rest) {
+                    Some(len) => (len, Some(SyntaxKind::String)),
+                    None => (1, None),
+                }
+            } else if b.is_ascii_digit() {
+                let len = rest
+                    .iter()
+                    .position(|&b| !is_word_byte(b) && b != b'.')
+                    .unwrap_or(rest.len());
+                (len, Some(SyntaxKind::Number))
+            } else if is_word_byte(b) {
+                let len = rest
+                    .iter()
+                    .position(|&b| !is_word_byte(b))
+                    .unwrap_or(rest.len());
+                let word = &rest[..len];
+                let is_keyword = language.keywords.iter().any(|k| k.as_bytes() == word);
+                (len, is_keyword.then_some(SyntaxKind::Keyword))
+            } else {
+                (1, None)
+            };
+            if let Some(kind) = kind {
+                spans.push((pos..pos + len, kind));
+            }
+            pos += len;
+        }
+        (spans, state)
+    }
+
+    /// Returns the states at the start of each line of the `text`.
+    pub fn line_states(&self, text: &[u8]) -> Vec<LineState> {
+        let mut state = LineState::default();
+        text.split_inclusive(|b| *b == b'\n')
+            .map(|line| {
+                let line_state = state;
+                (_, state) = self.highlight_line(line_state, line);
+                line_state
+            })
+            .collect()
+    }
+}
+
+/// Highlights lines of the left and right contents of a diff.
+#[derive(Clone, Debug)]
+pub struct DiffHighlighter {
+    highlighter: SyntaxHighlighter,
+    line_states: [Vec<LineState>; 2],
+}
+
+impl DiffHighlighter {
+    /// Scans the left and right `contents` for constructs spanning multiple
+    /// lines.
+    pub fn new<T: AsRef<[u8]> + ?Sized>(
+        highlighter: &SyntaxHighlighter,
+        contents: [&T; 2],
+    ) -> Self {
+        Self {
+            highlighter: *highlighter,
+            line_states: contents.map(|text| highlighter.line_states(text.as_ref())),
+        }
+    }
+
+    /// Returns highlighter of the 1-based `line_number` at the `side` (0 for
+    /// left, 1 for right.)
+    pub fn line(&self, side: usize, line_number: u32) -> LineHighlighter<'_> {
+        let state = usize::try_from(line_number)
+            .ok()
+            .and_then(|n| self.line_states[side].get(n.checked_sub(1)?))
+            .copied()
+            .unwrap_or_default();
+        (&self.highlighter, state)
+    }
+}
+
+/// Highlighter and the state at the start of the line to be highlighted.
+pub type LineHighlighter<'a> = (&'a SyntaxHighlighter, LineState);
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Returns length of the string contents in `text` up to and including the
+/// closing `quote`, or `None` if the string isn't terminated.
+fn find_closing_quote(text: &[u8], quote: &[u8]) -> Option<usize> {
+    let mut pos = 0;
+    while pos < text.len() {
+        if text[pos] == b'\\' {
+            pos += 2;
+        } else if text[pos..].starts_with(quote) {
+            return Some(pos + quote.len());
+        } else {
+            pos += 1;
+        }
+    }
+    None
+}
+
+/// Returns length of the character literal such as `'a'` or `'\n'` at the
+/// start of `text`.
+fn char_literal_len(text: &[u8]) -> Option<usize> {
+    match text.get(1..)? {
+        [b'\\', rest @ ..] => rest
+            .iter()
+            .take(10)
+            .position(|&b| b == b'\'')
+            .map(|n| n + 3),
+        [_, b'\'', ..] => Some(3),
+        // Multi-byte UTF-8 character
+        rest => {
+            let len = std::str::from_utf8(&rest[..rest.len().min(4)])
+                .or_else(|err| std::str::from_utf8(&rest[..err.valid_up_to()]))
+                .ok()?
+                .chars()
+                .next()?
+                .len_utf8();
+            (len > 1 && rest.get(len) == Some(&b'\'')).then_some(len + 2)
+        }
+    }
+}
+
+/// Writes `data` with the given `labels`, and the syntax label of the `kind`
+/// outside of them.
+pub fn write_labeled(
+    formatter: &mut dyn Formatter,
+    kind: Option<SyntaxKind>,
+    labels: &[&str],
+    data: &[u8],
+) -> io::Result<()> {
+    if let Some(kind) = kind {
+        formatter.push_label("syntax");
+        formatter.push_label(kind.label());
+    }
+    for label in labels {
+        formatter.push_label(label);
+    }
+    let result = formatter.write_all(data);
+    for _ in labels {
+        formatter.pop_label();
+    }
+    if kind.is_some() {
+        formatter.pop_label();
+        formatter.pop_label();
+    }
+    result
+}
+
+/// Writes a line made of `pieces`, each of which is labeled with its own
+/// labels.
+///
+/// Syntax labels are applied outside of the piece labels, so styles of the
+/// piece labels (such as `removed` or `added`) take precedence.
+pub fn write_line_pieces(
+    formatter: &mut dyn Formatter,
+    highlighter: Option<LineHighlighter<'_>>,
+    pieces: &[(&[&str], &[u8])],
+) -> io::Result<()> {
+    let Some((highlighter, state)) = highlighter else {
+        for &(labels, data) in pieces {
+            write_labeled(formatter, None, labels, data)?;
+        }
+        return Ok(());
+    };
+    let line = pieces
+        .iter()
+        .flat_map(|(_, data)| *data)
+        .copied()
+        .collect::<Vec<_>>();
+    let (spans, _) = highlighter.highlight_line(state, &line);
+    let mut spans_iter = spans.iter().peekable();
+    let mut piece_start = 0;
+    for &(labels, data) in pieces {
+        let piece_end = piece_start + data.len();
+        let mut pos = piece_start;
+        while pos < piece_end {
+            while spans_iter.next_if(|(range, _)| range.end <= pos).is_some() {}
+            let (end, kind) = match spans_iter.peek() {
+                Some((range, kind)) if range.start <= pos => {
+                    (range.end.min(piece_end), Some(*kind))
+                }
+                Some((range, _)) => (range.start.min(piece_end), None),
+                None => (piece_end, None),
+            };
+            let chunk = &data[pos - piece_start..end - piece_start];
+            write_labeled(formatter, kind, labels, chunk)?;
+            pos = end;
+        }
+        piece_start = piece_end;
+    }
+    Ok(())
+}
+
+/// Writes possibly multi-line `text` highlighted line by line.
+pub fn write_highlighted(
+    formatter: &mut dyn Formatter,
+    highlighter: &SyntaxHighlighter,
+    text: &[u8],
+) -> io::Result<()> {
+    let line_states = highlighter.line_states(text);
+    for (line, state) in iter::zip(text.split_inclusive(|b| *b == b'\n'), line_states) {
+        write_line_pieces(formatter, Some((highlighter, state)), &[(&[], line)])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use jj_lib::config::StackedConfig;
+
+    use super::*;
+    use crate::formatter::ColorFormatter;
+    use crate::formatter::PlainTextFormatter;
+
+    fn highlighter(path: &str) -> SyntaxHighlighter {
+        SyntaxHighlighter::for_path(RepoPath::from_internal_string(path).unwrap()).unwrap()
+    }
+
+    fn highlight(path: &str, line: &str) -> Vec<(&'static str, String)> {
+        highlighter(path)
+            .highlight_line(LineState::default(), line.as_bytes())
+            .0
+            .into_iter()
+            .map(|(range, kind)| (kind.label(), line[range].to_owned()))
+            .collect()
+    }
+
+    fn highlight_lines(path: &str, text: &str) -> Vec<(usize, &'static str, String)> {
+        let highlighter = highlighter(path);
+        let mut state = LineState::default();
+        let mut spans = Vec::new();
+        for (line_index, line) in text.split_inclusive('\n').enumerate() {
+            let (ranges, next_state) = highlighter.highlight_line(state, line.as_bytes());
+            spans.extend(
+                ranges
+                    .into_iter()
+                    .map(|(range, kind)| (line_index, kind.label(), line[range].to_owned())),
+            );
+            state = next_state;
+        }
+        spans
+    }
+
+    #[test]
+    fn test_for_path() {
+        let for_path =
+            |path| SyntaxHighlighter::for_path(RepoPath::from_internal_string(path).unwrap());
+        assert!(for_path("src/main.rs").is_some());
+        assert!(for_path("script.py").is_some());
+        assert!(for_path("Makefile").is_none());
+        assert!(for_path("file.unknown").is_none());
+        assert!(for_path("dir.rs/file").is_none());
+        assert!(for_path("").is_none());
+    }
+
+    #[test]
+    fn test_highlight_line() {
+        insta::assert_debug_snapshot!(
+            highlight("a.rs", "let x = \"a \\\" b\"; // comment\n"), @r#"
+        [
+            (
+                "keyword",
+                "let",
+            ),
+            (
+                "string",
+                "\"a \\\" b\"",
+            ),
+            (
+                "comment",
+                "// comment",
+            ),
+        ]
+        "#);
+        insta::assert_debug_snapshot!(
+            highlight("a.rs", "fn f<'a>(c: char) { '\\n'; 'é'; 0x1f_u8; 1.5 /* x */ }"), @r#"
+        [
+            (
+                "keyword",
+                "fn",
+            ),
+            (
+                "string",
+                "'\\n'",
+            ),
+            (
+                "string",
+                "'é'",
+            ),
+            (
+                "number",
+                "0x1f_u8",
+            ),
+            (
+                "number",
+                "1.5",
+            ),
+            (
+                "comment",
+                "/* x */",
+            ),
+        ]
+        "#);
+        insta::assert_debug_snapshot!(highlight("a.py", "if x: return 'it''s' # done"), @r##"
+        [
+            (
+                "keyword",
+                "if",
+            ),
+            (
+                "keyword",
+                "return",
+            ),
+            (
+                "string",
+                "'it'",
+            ),
+            (
+                "string",
+                "'s'",
+            ),
+            (
+                "comment",
+                "# done",
+            ),
+        ]
+        "##);
+        // Keywords shouldn't be matched within identifiers
+        insta::assert_debug_snapshot!(highlight("a.py", "iffy = if_ + é_if"), @"[]");
+        // Unterminated constructs extend to the end of line
+        insta::assert_debug_snapshot!(highlight("a.c", "\"abc /* x"), @r#"
+        [
+            (
+                "string",
+                "\"abc /* x",
+            ),
+        ]
+        "#);
+    }
+
+    #[test]
+    fn test_highlight_multi_line() {
+        insta::assert_debug_snapshot!(
+            highlight_lines("a.rs", "let x = 1; /* a\nlet y\n*/ let z = 2;\n"), @r#"
+        [
+            (
+                0,
+                "keyword",
+                "let",
+            ),
+            (
+                0,
+                "number",
+                "1",
+            ),
+            (
+                0,
+                "comment",
+                "/* a",
+            ),
+            (
+                1,
+                "comment",
+                "let y",
+            ),
+            (
+                2,
+                "comment",
+                "*/",
+            ),
+            (
+                2,
+                "keyword",
+                "let",
+            ),
+            (
+                2,
+                "number",
+                "2",
+            ),
+        ]
+        "#);
+        insta::assert_debug_snapshot!(
+            highlight_lines("a.py", "x = \"\"\"a\nif b\n\"\"\" # c\nif d:\n"), @r##"
+        [
+            (
+                0,
+                "string",
+                "\"\"\"a",
+            ),
+            (
+                1,
+                "string",
+                "if b",
+            ),
+            (
+                2,
+                "string",
+                "\"\"\"",
+            ),
+            (
+                2,
+                "comment",
+                "# c",
+            ),
+            (
+                3,
+                "keyword",
+                "if",
+            ),
+        ]
+        "##);
+        // Quote characters of the other kind don't end a multi-line string
+        insta::assert_debug_snapshot!(
+            highlight_lines("a.py", "x = '''a\n\"\"\"\nif'''\n"), @r#"
+        [
+            (
+                0,
+                "string",
+                "'''a",
+            ),
+            (
+                1,
+                "string",
+                "\"\"\"",
+            ),
+            (
+                2,
+                "string",
+                "if'''",
+            ),
+        ]
+        "#);
+        // Escaped quotes within a template literal don't end it
+        insta::assert_debug_snapshot!(
+            highlight_lines("a.js", "x = `a\n\\` b`;\n"), @r#"
+        [
+            (
+                0,
+                "string",
+                "`a",
+            ),
+            (
+                1,
+                "string",
+                "\\` b`",
+            ),
+        ]
+        "#);
+    }
+
+    #[test]
+    fn test_line_states() {
+        let c = highlighter("a.c");
+        assert_eq!(
+            c.line_states(b"int a; /* b\n * c\n */ int d; /* e */\nint f;\n"),
+            [
+                LineState::Code,
+                LineState::BlockComment,
+                LineState::BlockComment,
+                LineState::Code,
+            ]
+        );
+        let python = highlighter("a.py");
+        assert_eq!(
+            python.line_states(b"x = \"\"\"\ny\n\"\"\"\n"),
+            [
+                LineState::Code,
+                LineState::String("\"\"\""),
+                LineState::String("\"\"\""),
+            ]
+        );
+
+        let diff = DiffHighlighter::new(&python, ["\"\"\"\nx\n", "y\n"]);
+        assert_eq!(diff.line(0, 2).1, LineState::String("\"\"\""));
+        assert_eq!(diff.line(1, 1).1, LineState::Code);
+        // Out-of-range line numbers fall back to the default state
+        assert_eq!(diff.line(0, 0).1, LineState::Code);
+        assert_eq!(diff.line(1, 5).1, LineState::Code);
+    }
+
+    #[test]
+    fn test_write_line_pieces() {
+        let highlighter = highlighter("a.rs");
+        let mut output = Vec::new();
+        let config = StackedConfig::empty();
+        let mut formatter = ColorFormatter::for_config(&mut output, &config, true).unwrap();
+        write_line_pieces(
+            &mut formatter,
+            Some((&highlighter, LineState::default())),
+            &[
+                (&["removed"], b"let x"),
+                (&["removed", "token"], b" = 1"),
+                (&[], b"; // c\n"),
+            ],
+        )
+        .unwrap();
+        drop(formatter);
+        insta::assert_snapshot!(String::from_utf8(output).unwrap(), @"<<syntax keyword removed::let>><<removed:: x>><<removed token:: = >><<syntax number removed token::1>>; <<syntax comment::// c>>");
+
+        let mut output = Vec::new();
+        let mut formatter = PlainTextFormatter::new(&mut output);
+        write_highlighted(&mut formatter, &highlighter, b"let x = 1;\nfn f() {}\n").unwrap();
+        insta::assert_snapshot!(String::from_utf8(output).unwrap(), @r"
+        let x = 1;
+        fn f() {}
+        ");
+    }
+}
@@ -16,6 +16,7 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::io;
 use std::iter;
+use std::rc::Rc;
 
 use itertools::Itertools as _;
 use jj_lib::backend::Signature;
@@ -39,6 +40,7 @@ use crate::template_parser::ExpressionKind;
 use crate::template_parser::ExpressionNode;
 use crate::template_parser::FunctionCallNode;
 use crate::template_parser::LambdaNode;
+use crate::template_parser::LetNode;
 use crate::template_parser::TemplateAliasesMap;
 use crate::template_parser::TemplateDiagnostics;
 use crate::template_parser::TemplateParseError;
@@ -51,10 +53,12 @@ use crate::templater::CoalesceTemplate;
 use crate::templater::ConcatTemplate;
 use crate::templater::ConditionalTemplate;
 use crate::templater::Email;
+use crate::templater::HyperlinkTemplate;
 use crate::templater::LabelTemplate;
 use crate::templater::ListPropertyTemplate;
 use crate::templater::ListTemplate;
 use crate::templater::Literal;
+use crate::templater::MemoizedProperty;
 use crate::templater::PlainTextFormattedProperty;
 use crate::templater::PropertyPlaceholder;
 use crate::templater::RawEscapeSequenceTemplate;
@@ -164,6 +168,7 @@ where
     Self: WrapTemplateProperty<'a, i64>,
     Self: WrapTemplateProperty<'a, Option<i64>>,
     Self: WrapTemplateProperty<'a, ConfigValue>,
+    Self: WrapTemplateProperty<'a, Option<ConfigValue>>,
     Self: WrapTemplateProperty<'a, Signature>,
     Self: WrapTemplateProperty<'a, Email>,
     Self: WrapTemplateProperty<'a, SizeHint>,
@@ -189,6 +194,38 @@ where
 
     /// Transforms into a property that will evaluate to an [`Ordering`].
     fn try_into_cmp(self, other: Self) -> Option<BoxedTemplateProperty<'a, Ordering>>;
+
+    /// Transforms into a function that creates copies of this property. The
+    /// copies share the value evaluated at most once per context if possible.
+    fn into_shared(self) -> SharedPropertyFn<'a, Self>;
+}
+
+/// Function that creates copies of shared property.
+pub type SharedPropertyFn<'a, P> = Box<dyn Fn() -> P + 'a>;
+
+/// Creates function that wraps copies of the memoized `property`.
+pub fn share_memoized_property<'a, O, P>(
+    property: BoxedTemplateProperty<'a, O>,
+    wrap: impl Fn(BoxedTemplateProperty<'a, O>) -> P + 'a,
+) -> SharedPropertyFn<'a, P>
+where
+    O: Clone + 'a,
+{
+    let property = Rc::new(MemoizedProperty::new(property));
+    Box::new(move || wrap(property.clone().into_dyn()))
+}
+
+/// Creates function that wraps copies of the `property`. The value will be
+/// evaluated for each copy since it isn't cloneable.
+pub fn share_property<'a, O, P>(
+    property: BoxedTemplateProperty<'a, O>,
+    wrap: impl Fn(BoxedTemplateProperty<'a, O>) -> P + 'a,
+) -> SharedPropertyFn<'a, P>
+where
+    O: 'a,
+{
+    let property: Rc<dyn TemplateProperty<Output = O> + 'a> = property.into();
+    Box::new(move || wrap(property.clone().into_dyn()))
 }
 
 pub enum CoreTemplatePropertyKind<'a> {
@@ -198,6 +235,7 @@ pub enum CoreTemplatePropertyKind<'a> {
     Integer(BoxedTemplateProperty<'a, i64>),
     IntegerOpt(BoxedTemplateProperty<'a, Option<i64>>),
     ConfigValue(BoxedTemplateProperty<'a, ConfigValue>),
+    ConfigValueOpt(BoxedTemplateProperty<'a, Option<ConfigValue>>),
     Signature(BoxedTemplateProperty<'a, Signature>),
     Email(BoxedTemplateProperty<'a, Email>),
     SizeHint(BoxedTemplateProperty<'a, SizeHint>),
@@ -231,6 +269,7 @@ macro_rules! impl_core_property_wrappers {
             Integer(i64),
             IntegerOpt(Option<i64>),
             ConfigValue(jj_lib::config::ConfigValue),
+            ConfigValueOpt(Option<jj_lib::config::ConfigValue>),
             Signature(jj_lib::backend::Signature),
             Email($crate::templater::Email),
             SizeHint($crate::templater::SizeHint),
@@ -261,6 +300,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::Integer(_) => "Integer",
             Self::IntegerOpt(_) => "Option<Integer>",
             Self::ConfigValue(_) => "ConfigValue",
+            Self::ConfigValueOpt(_) => "Option<ConfigValue>",
             Self::Signature(_) => "Signature",
             Self::Email(_) => "Email",
             Self::SizeHint(_) => "SizeHint",
@@ -279,6 +319,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::Integer(_) => None,
             Self::IntegerOpt(property) => Some(property.map(|opt| opt.is_some()).into_dyn()),
             Self::ConfigValue(_) => None,
+            Self::ConfigValueOpt(property) => Some(property.map(|opt| opt.is_some()).into_dyn()),
             Self::Signature(_) => None,
             Self::Email(property) => Some(property.map(|e| !e.0.is_empty()).into_dyn()),
             Self::SizeHint(_) => None,
@@ -320,6 +361,11 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::ConfigValue(property) => {
                 Some(property.map(config::to_serializable_value).into_serialize())
             }
+            Self::ConfigValueOpt(property) => Some(
+                property
+                    .map(|opt| opt.map(config::to_serializable_value))
+                    .into_serialize(),
+            ),
             Self::Signature(property) => Some(property.into_serialize()),
             Self::Email(property) => Some(property.into_serialize()),
             Self::SizeHint(property) => Some(property.into_serialize()),
@@ -338,6 +384,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::Integer(property) => Some(property.into_template()),
             Self::IntegerOpt(property) => Some(property.into_template()),
             Self::ConfigValue(property) => Some(property.into_template()),
+            Self::ConfigValueOpt(property) => Some(property.into_template()),
             Self::Signature(property) => Some(property.into_template()),
             Self::Email(property) => Some(property.into_template()),
             Self::SizeHint(_) => None,
@@ -383,6 +430,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             (Self::Integer(_), _) => None,
             (Self::IntegerOpt(_), _) => None,
             (Self::ConfigValue(_), _) => None,
+            (Self::ConfigValueOpt(_), _) => None,
             (Self::Signature(_), _) => None,
             (Self::Email(_), _) => None,
             (Self::SizeHint(_), _) => None,
@@ -407,12 +455,22 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             (Self::IntegerOpt(lhs), Self::IntegerOpt(rhs)) => {
                 Some((lhs, rhs).map(|(l, r)| l.cmp(&r)).into_dyn())
             }
+            (Self::String(lhs), Self::String(rhs)) => {
+                Some((lhs, rhs).map(|(l, r)| l.cmp(&r)).into_dyn())
+            }
+            // Timestamps are compared as instants regardless of time zones
+            (Self::Timestamp(lhs), Self::Timestamp(rhs)) => Some(
+                (lhs, rhs)
+                    .map(|(l, r)| l.timestamp.cmp(&r.timestamp))
+                    .into_dyn(),
+            ),
             (Self::String(_), _) => None,
             (Self::StringList(_), _) => None,
             (Self::Boolean(_), _) => None,
             (Self::Integer(_), _) => None,
             (Self::IntegerOpt(_), _) => None,
             (Self::ConfigValue(_), _) => None,
+            (Self::ConfigValueOpt(_), _) => None,
             (Self::Signature(_), _) => None,
             (Self::Email(_), _) => None,
             (Self::SizeHint(_), _) => None,
@@ -422,6 +480,37 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             (Self::ListTemplate(_), _) => None,
         }
     }
+
+    fn into_shared(self) -> SharedPropertyFn<'a, Self> {
+        match self {
+            Self::String(property) => share_memoized_property(property, Self::String),
+            Self::StringList(property) => share_memoized_property(property, Self::StringList),
+            Self::Boolean(property) => share_memoized_property(property, Self::Boolean),
+            Self::Integer(property) => share_memoized_property(property, Self::Integer),
+            Self::IntegerOpt(property) => share_memoized_property(property, Self::IntegerOpt),
+            Self::ConfigValue(property) => share_memoized_property(property, Self::ConfigValue),
+            Self::ConfigValueOpt(property) => {
+                share_memoized_property(property, Self::ConfigValueOpt)
+            }
+            Self::Signature(property) => share_memoized_property(property, Self::Signature),
+            Self::Email(property) => share_memoized_property(property, Self::Email),
+            Self::SizeHint(property) => share_memoized_property(property, Self::SizeHint),
+            Self::Timestamp(property) => share_memoized_property(property, Self::Timestamp),
+            Self::TimestampRange(property) => {
+                share_memoized_property(property, Self::TimestampRange)
+            }
+            // Templates are formatted for each copy. A list template can no
+            // longer be joined since the items are consumed by join().
+            Self::Template(template) => {
+                let template: Rc<dyn Template + 'a> = template.into();
+                Box::new(move || Self::Template(Box::new(template.clone())))
+            }
+            Self::ListTemplate(template) => {
+                let template: Rc<dyn Template + 'a> = Rc::new(template);
+                Box::new(move || Self::Template(Box::new(template.clone())))
+            }
+        }
+    }
 }
 
 /// Function that translates global function call node.
@@ -623,6 +712,13 @@ where
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(language, diagnostics, build_ctx, property, function)
             }
+            CoreTemplatePropertyKind::ConfigValueOpt(property) => {
+                let type_name = "ConfigValue";
+                let table = &self.config_value_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                let inner_property = property.try_unwrap(type_name).into_dyn();
+                build(language, diagnostics, build_ctx, inner_property, function)
+            }
             CoreTemplatePropertyKind::Signature(property) => {
                 let table = &self.signature_methods;
                 let build = template_parser::lookup_method(type_name, table, function)?;
@@ -723,6 +819,9 @@ impl<'a, P: CoreTemplatePropertyVar<'a>> Expression<P> {
 pub struct BuildContext<'i, P> {
     /// Map of functions to create `L::Property`.
     local_variables: HashMap<&'i str, &'i dyn Fn() -> P>,
+    /// Map of lambdas bound by `let`, and the environments where the lambdas
+    /// were defined.
+    local_functions: HashMap<&'i str, (&'i LambdaNode<'i>, &'i Self)>,
     /// Function to create `L::Property` representing `self`.
     ///
     /// This could be `local_variables["self"]`, but keyword lookup shouldn't be
@@ -1204,11 +1303,55 @@ fn builtin_config_value_methods<'a, L: TemplateLanguage<'a> + ?Sized>()
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "get",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [key_node] = function.expect_exact_arguments()?;
+            let key_property =
+                expect_stringify_expression(language, diagnostics, build_ctx, key_node)?;
+            let out_property = (self_property, key_property).and_then(|(value, key)| {
+                let mut table = extract_table(value)?;
+                // .decorated("", "") to trim leading/trailing whitespace
+                Ok(table.remove(&key).map(|value| value.decorated("", "")))
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "keys",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|value| {
+                let table = extract_table(value)?;
+                Ok(table.iter().map(|(key, _)| key.to_owned()).collect_vec())
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "contains_key",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [key_node] = function.expect_exact_arguments()?;
+            let key_property =
+                expect_stringify_expression(language, diagnostics, build_ctx, key_node)?;
+            let out_property = (self_property, key_property)
+                .and_then(|(value, key)| Ok(extract_table(value)?.contains_key(&key)));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     // TODO: add is_<type>() -> Boolean?
-    // TODO: add .get(key) -> ConfigValue or Option<ConfigValue>?
     map
 }
 
+fn extract_table(value: ConfigValue) -> Result<toml_edit::InlineTable, TemplatePropertyError> {
+    match value {
+        ConfigValue::InlineTable(table) => Ok(table),
+        _ => Err(TemplatePropertyError(
+            format!("Expected a table, but is {}", value.type_name()).into(),
+        )),
+    }
+}
+
 fn builtin_signature_methods<'a, L: TemplateLanguage<'a> + ?Sized>()
 -> TemplateBuildMethodFnMap<'a, L, Signature> {
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -1536,6 +1679,132 @@ where
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "sort_by",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let out_property =
+                build_sort_by_operation(language, diagnostics, build_ctx, self_property, function)?;
+            Ok(L::Property::wrap_property(out_property))
+        },
+    );
+    map.insert(
+        "reverse",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|mut items| {
+                items.reverse();
+                items
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "unique",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = build_unique_operation::<L, O>(self_property, function)?;
+            Ok(L::Property::wrap_property(out_property))
+        },
+    );
+    map.insert(
+        "first",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|items| {
+                items
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| TemplatePropertyError("List is empty".into()))
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "last",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|items| {
+                items
+                    .into_iter()
+                    .next_back()
+                    .ok_or_else(|| TemplatePropertyError("List is empty".into()))
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "get",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [index_node] = function.expect_exact_arguments()?;
+            let index = expect_integer_expression(language, diagnostics, build_ctx, index_node)?;
+            let out_property = (self_property, index).and_then(|(items, index)| {
+                usize::try_from(index)
+                    .ok()
+                    .and_then(|i| items.into_iter().nth(i))
+                    .ok_or_else(|| {
+                        TemplatePropertyError(format!("Index {index} is out of range").into())
+                    })
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "take",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [count_node] = function.expect_exact_arguments()?;
+            let count = expect_usize_expression(language, diagnostics, build_ctx, count_node)?;
+            let out_property = (self_property, count).map(|(mut items, count)| {
+                items.truncate(count);
+                items
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "skip",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [count_node] = function.expect_exact_arguments()?;
+            let count = expect_usize_expression(language, diagnostics, build_ctx, count_node)?;
+            let out_property = (self_property, count).map(|(mut items, count)| {
+                items.drain(..count.min(items.len()));
+                items
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "sum",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let values = build_integer_map_operation(
+                language,
+                diagnostics,
+                build_ctx,
+                self_property,
+                function,
+            )?;
+            let out_property = values.and_then(|values| {
+                values
+                    .into_iter()
+                    .try_fold(0_i64, i64::checked_add)
+                    .ok_or_else(|| TemplatePropertyError("Attempt to add with overflow".into()))
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "max",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let values = build_integer_map_operation(
+                language,
+                diagnostics,
+                build_ctx,
+                self_property,
+                function,
+            )?;
+            let out_property = values.map(|values| values.into_iter().max());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
@@ -1559,10 +1828,9 @@ where
     let item_placeholder = PropertyPlaceholder::new();
     let item_predicate =
         template_parser::catch_aliases(diagnostics, lambda_node, |diagnostics, node| {
-            let lambda = template_parser::expect_lambda(node)?;
-            build_lambda_expression(
+            build_lambda_argument(
                 build_ctx,
-                lambda,
+                node,
                 &[&|| item_placeholder.clone().into_dyn_wrapped()],
                 |build_ctx, body| expect_boolean_expression(language, diagnostics, build_ctx, body),
             )
@@ -1602,10 +1870,9 @@ where
     let item_placeholder = PropertyPlaceholder::new();
     let item_template =
         template_parser::catch_aliases(diagnostics, lambda_node, |diagnostics, node| {
-            let lambda = template_parser::expect_lambda(node)?;
-            build_lambda_expression(
+            build_lambda_argument(
                 build_ctx,
-                lambda,
+                node,
                 &[&|| item_placeholder.clone().into_dyn_wrapped()],
                 |build_ctx, body| {
                     expect_template_expression(language, diagnostics, build_ctx, body)
@@ -1642,10 +1909,9 @@ where
     let item_placeholder = PropertyPlaceholder::new();
     let item_predicate =
         template_parser::catch_aliases(diagnostics, lambda_node, |diagnostics, node| {
-            let lambda = template_parser::expect_lambda(node)?;
-            build_lambda_expression(
+            build_lambda_argument(
                 build_ctx,
-                lambda,
+                node,
                 &[&|| item_placeholder.clone().into_dyn_wrapped()],
                 |build_ctx, body| expect_boolean_expression(language, diagnostics, build_ctx, body),
             )
@@ -1680,10 +1946,9 @@ where
     let item_placeholder = PropertyPlaceholder::new();
     let item_predicate =
         template_parser::catch_aliases(diagnostics, lambda_node, |diagnostics, node| {
-            let lambda = template_parser::expect_lambda(node)?;
-            build_lambda_expression(
+            build_lambda_argument(
                 build_ctx,
-                lambda,
+                node,
                 &[&|| item_placeholder.clone().into_dyn_wrapped()],
                 |build_ctx, body| expect_boolean_expression(language, diagnostics, build_ctx, body),
             )
@@ -1698,6 +1963,142 @@ where
     Ok(out_property.into_dyn())
 }
 
+/// Builds expression that sorts items by the key evaluated by the lambda.
+fn build_sort_by_operation<'a, L, O, P>(
+    language: &L,
+    diagnostics: &mut TemplateDiagnostics,
+    build_ctx: &BuildContext<L::Property>,
+    self_property: P,
+    function: &FunctionCallNode,
+) -> TemplateParseResult<BoxedTemplateProperty<'a, Vec<O>>>
+where
+    L: TemplateLanguage<'a> + ?Sized,
+    L::Property: WrapTemplateProperty<'a, O>,
+    P: TemplateProperty<Output = Vec<O>> + 'a,
+    O: Clone + 'a,
+{
+    let [lambda_node] = function.expect_exact_arguments()?;
+    let lhs_placeholder = PropertyPlaceholder::new();
+    let rhs_placeholder = PropertyPlaceholder::new();
+    let key_cmp = template_parser::catch_aliases(diagnostics, lambda_node, |diagnostics, node| {
+        let lhs_key = build_lambda_argument(
+            build_ctx,
+            node,
+            &[&|| lhs_placeholder.clone().into_dyn_wrapped()],
+            |build_ctx, body| build_expression(language, diagnostics, build_ctx, body),
+        )?;
+        // The same lambda is built again for the other operand. Diagnostics
+        // are discarded since they would be duplicates.
+        let rhs_key = build_lambda_argument(
+            build_ctx,
+            node,
+            &[&|| rhs_placeholder.clone().into_dyn_wrapped()],
+            |build_ctx, body| {
+                build_expression(language, &mut TemplateDiagnostics::new(), build_ctx, body)
+            },
+        )?;
+        let key_type = lhs_key.type_name();
+        lhs_key.try_into_cmp(rhs_key).ok_or_else(|| {
+            let message = format!("Cannot sort by expression of type `{key_type}`");
+            TemplateParseError::expression(message, node.span)
+        })
+    })?;
+    let out_property = self_property.and_then(move |mut items| {
+        let mut result = Ok(());
+        // Stable sort to preserve the original order of items with equal keys
+        items.sort_by(|lhs, rhs| {
+            if result.is_err() {
+                return Ordering::Equal;
+            }
+            let ordering = lhs_placeholder.with_value(lhs.clone(), || {
+                rhs_placeholder.with_value(rhs.clone(), || key_cmp.extract())
+            });
+            ordering.unwrap_or_else(|err| {
+                result = Err(err);
+                Ordering::Equal
+            })
+        });
+        result.map(|()| items)
+    });
+    Ok(out_property.into_dyn())
+}
+
+/// Builds expression that removes duplicated items, keeping the first
+/// occurrences.
+fn build_unique_operation<'a, L, O>(
+    self_property: impl TemplateProperty<Output = Vec<O>> + 'a,
+    function: &FunctionCallNode,
+) -> TemplateParseResult<BoxedTemplateProperty<'a, Vec<O>>>
+where
+    L: TemplateLanguage<'a> + ?Sized,
+    L::Property: WrapTemplateProperty<'a, O>,
+    O: Clone + 'a,
+{
+    let lhs_placeholder = PropertyPlaceholder::new();
+    let rhs_placeholder = PropertyPlaceholder::new();
+    let lhs_item: L::Property = lhs_placeholder.clone().into_dyn_wrapped();
+    let rhs_item: L::Property = rhs_placeholder.clone().into_dyn_wrapped();
+    let item_type = lhs_item.type_name();
+    let item_eq = lhs_item.try_into_eq(rhs_item).ok_or_else(|| {
+        let message = format!("Cannot compare items of type `{item_type}`");
+        TemplateParseError::expression(message, function.name_span)
+    })?;
+    let out_property = self_property.and_then(move |items| {
+        let mut unique_items: Vec<O> = Vec::with_capacity(items.len());
+        for item in items {
+            let mut found = false;
+            for unique_item in &unique_items {
+                found = lhs_placeholder.with_value(unique_item.clone(), || {
+                    rhs_placeholder.with_value(item.clone(), || item_eq.extract())
+                })?;
+                if found {
+                    break;
+                }
+            }
+            if !found {
+                unique_items.push(item);
+            }
+        }
+        Ok(unique_items)
+    });
+    Ok(out_property.into_dyn())
+}
+
+/// Builds expression that evaluates the integer lambda for each item.
+fn build_integer_map_operation<'a, L, O, P>(
+    language: &L,
+    diagnostics: &mut TemplateDiagnostics,
+    build_ctx: &BuildContext<L::Property>,
+    self_property: P,
+    function: &FunctionCallNode,
+) -> TemplateParseResult<BoxedTemplateProperty<'a, Vec<i64>>>
+where
+    L: TemplateLanguage<'a> + ?Sized,
+    L::Property: WrapTemplateProperty<'a, O>,
+    P: TemplateProperty + 'a,
+    P::Output: IntoIterator<Item = O>,
+    O: Clone + 'a,
+{
+    let [lambda_node] = function.expect_exact_arguments()?;
+    let item_placeholder = PropertyPlaceholder::new();
+    let item_value =
+        template_parser::catch_aliases(diagnostics, lambda_node, |diagnostics, node| {
+            build_lambda_argument(
+                build_ctx,
+                node,
+                &[&|| item_placeholder.clone().into_dyn_wrapped()],
+                |build_ctx, body| expect_integer_expression(language, diagnostics, build_ctx, body),
+            )
+        })?;
+    let out_property = self_property.and_then(move |items| {
+        items
+            .into_iter()
+            .map(|item| item_placeholder.with_value(item, || item_value.extract()))
+            .try_collect()
+    });
+    Ok(out_property.into_dyn())
+}
+
 /// Builds lambda expression to be evaluated with the provided arguments.
 /// `arg_fns` is usually an array of wrapped [`PropertyPlaceholder`]s.
 fn build_lambda_expression<'i, P, T>(
@@ -1714,13 +2115,100 @@ fn build_lambda_expression<'i, P, T>(
     }
     let mut local_variables = build_ctx.local_variables.clone();
     local_variables.extend(iter::zip(&lambda.params, arg_fns));
+    let mut local_functions = build_ctx.local_functions.clone();
+    for name in &lambda.params {
+        local_functions.remove(name);
+    }
     let inner_build_ctx = BuildContext {
         local_variables,
+        local_functions,
         self_variable: build_ctx.self_variable,
     };
     build_body(&inner_build_ctx, &lambda.body)
 }
 
+/// Builds lambda expression passed as a function argument. The argument may
+/// be either a lambda or a name of lambda bound by `let`.
+fn build_lambda_argument<'i, P, T>(
+    build_ctx: &BuildContext<'i, P>,
+    node: &ExpressionNode<'i>,
+    arg_fns: &[&'i dyn Fn() -> P],
+    build_body: impl FnOnce(&BuildContext<'i, P>, &ExpressionNode<'i>) -> TemplateParseResult<T>,
+) -> TemplateParseResult<T> {
+    if let ExpressionKind::Identifier(name) = &node.kind
+        && let Some(&(lambda, lambda_build_ctx)) = build_ctx.local_functions.get(name)
+    {
+        build_lambda_expression(lambda_build_ctx, lambda, arg_fns, build_body)
+    } else {
+        let lambda = template_parser::expect_lambda(node)?;
+        build_lambda_expression(build_ctx, lambda, arg_fns, build_body)
+    }
+}
+
+/// Builds call of lambda bound by `let`. The arguments are built in the
+/// caller's environment, and the lambda body is built in the environment where
+/// the lambda was defined.
+fn build_local_function_call<'a, L: TemplateLanguage<'a> + ?Sized>(
+    language: &L,
+    diagnostics: &mut TemplateDiagnostics,
+    build_ctx: &BuildContext<L::Property>,
+    lambda: &LambdaNode,
+    lambda_build_ctx: &BuildContext<L::Property>,
+    function: &FunctionCallNode,
+) -> TemplateParseResult<Expression<L::Property>> {
+    let ([], args) = function.expect_some_arguments()?;
+    if args.len() != lambda.params.len() {
+        return Err(TemplateParseError::with_span(
+            TemplateParseErrorKind::InvalidArguments {
+                name: function.name.to_owned(),
+                message: format!("Expected {} arguments", lambda.params.len()),
+            },
+            function.args_span,
+        ));
+    }
+    // Each argument is evaluated once no matter how many times the parameter
+    // is referenced.
+    let arg_fns: Vec<_> = args
+        .iter()
+        .map(|node| {
+            let expression = build_expression(language, diagnostics, build_ctx, node)?;
+            Ok::<_, TemplateParseError>(expression.property.into_shared())
+        })
+        .try_collect()?;
+    let arg_refs = arg_fns.iter().map(|make| make.as_ref()).collect_vec();
+    build_lambda_expression(lambda_build_ctx, lambda, &arg_refs, |build_ctx, body| {
+        build_expression(language, diagnostics, build_ctx, body)
+    })
+}
+
+/// Builds `let` expression. The bound value is evaluated at most once per
+/// context, whereas the bound lambda is built at each call site.
+fn build_let_expression<'a, L: TemplateLanguage<'a> + ?Sized>(
+    language: &L,
+    diagnostics: &mut TemplateDiagnostics,
+    build_ctx: &BuildContext<L::Property>,
+    binding: &LetNode,
+) -> TemplateParseResult<Expression<L::Property>> {
+    let make_value;
+    let mut local_variables = build_ctx.local_variables.clone();
+    let mut local_functions = build_ctx.local_functions.clone();
+    if let ExpressionKind::Lambda(lambda) = &binding.value.kind {
+        local_variables.remove(binding.name);
+        local_functions.insert(binding.name, (lambda.as_ref(), build_ctx));
+    } else {
+        let value = build_expression(language, diagnostics, build_ctx, &binding.value)?;
+        make_value = value.property.into_shared();
+        local_variables.insert(binding.name, make_value.as_ref());
+        local_functions.remove(binding.name);
+    }
+    let inner_build_ctx = BuildContext {
+        local_variables,
+        local_functions,
+        self_variable: build_ctx.self_variable,
+    };
+    build_expression(language, diagnostics, &inner_build_ctx, &binding.body)
+}
+
 fn builtin_functions<'a, L: TemplateLanguage<'a> + ?Sized>() -> TemplateBuildFunctionFnMap<'a, L> {
     // Not using maplit::hashmap!{} or custom declarative macro here because
     // code completion inside macro is quite restricted.
@@ -1835,6 +2323,14 @@ fn builtin_functions<'a, L: TemplateLanguage<'a> + ?Sized>() -> TemplateBuildFun
             content, labels,
         ))))
     });
+    map.insert("hyperlink", |language, diagnostics, build_ctx, function| {
+        let [url_node, content_node] = function.expect_exact_arguments()?;
+        let url_property = expect_stringify_expression(language, diagnostics, build_ctx, url_node)?;
+        let content = expect_template_expression(language, diagnostics, build_ctx, content_node)?;
+        Ok(L::Property::wrap_template(Box::new(
+            HyperlinkTemplate::new(content, url_property),
+        )))
+    });
     map.insert(
         "raw_escape_sequence",
         |language, diagnostics, build_ctx, function| {
@@ -2013,6 +2509,11 @@ pub fn build_expression<'a, L: TemplateLanguage<'a> + ?Sized>(
             if let Some(make) = build_ctx.local_variables.get(name) {
                 // Don't label a local variable with its name
                 Ok(Expression::unlabeled(make()))
+            } else if build_ctx.local_functions.contains_key(name) {
+                Err(TemplateParseError::expression(
+                    format!("Lambda `{name}` must be called"),
+                    node.span,
+                ))
             } else if *name == "self" {
                 // "self" is a special variable, so don't label it
                 let make = build_ctx.self_variable;
@@ -2069,8 +2570,24 @@ pub fn build_expression<'a, L: TemplateLanguage<'a> + ?Sized>(
             Ok(Expression::unlabeled(property))
         }
         ExpressionKind::FunctionCall(function) => {
-            let property = language.build_function(diagnostics, build_ctx, function)?;
-            Ok(Expression::unlabeled(property))
+            if let Some(&(lambda, lambda_build_ctx)) = build_ctx.local_functions.get(function.name)
+            {
+                build_local_function_call(
+                    language,
+                    diagnostics,
+                    build_ctx,
+                    lambda,
+                    lambda_build_ctx,
+                    function,
+                )
+            } else {
+                let property = language
+                    .build_function(diagnostics, build_ctx, function)
+                    .map_err(|err| {
+                        err.extend_function_candidates(build_ctx.local_functions.keys().copied())
+                    })?;
+                Ok(Expression::unlabeled(property))
+            }
         }
         ExpressionKind::MethodCall(method) => {
             let mut expression =
@@ -2088,6 +2605,9 @@ pub fn build_expression<'a, L: TemplateLanguage<'a> + ?Sized>(
             "Lambda cannot be defined here",
             node.span,
         )),
+        ExpressionKind::Let(binding) => {
+            build_let_expression(language, diagnostics, build_ctx, binding)
+        }
         ExpressionKind::AliasExpanded(..) => unreachable!(),
     })
 }
@@ -2106,6 +2626,7 @@ where
     let self_placeholder = PropertyPlaceholder::new();
     let build_ctx = BuildContext {
         local_variables: HashMap::new(),
+        local_functions: HashMap::new(),
         self_variable: &|| self_placeholder.clone().into_dyn_wrapped(),
     };
     let template = expect_template_expression(language, diagnostics, &build_ctx, node)?;
@@ -2252,6 +2773,8 @@ fn expect_expression_of_type<'a, L: TemplateLanguage<'a> + ?Sized, T>(
 
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
+
     use jj_lib::backend::MillisSinceEpoch;
     use jj_lib::config::StackedConfig;
 
@@ -2690,7 +3213,7 @@ mod tests {
         ");
 
         // Optional integer can be converted to boolean, and Some(0) is truthy.
-        env.add_keyword("none_i64", || literal(None));
+        env.add_keyword("none_i64", || literal(None::<i64>));
         env.add_keyword("some_i64", || literal(Some(0)));
         insta::assert_snapshot!(env.render_ok(r#"if(none_i64, true, false)"#), @"false");
         insta::assert_snapshot!(env.render_ok(r#"if(some_i64, true, false)"#), @"true");
@@ -2723,7 +3246,7 @@ mod tests {
     #[test]
     fn test_arithmetic_operation() {
         let mut env = TestTemplateEnv::new();
-        env.add_keyword("none_i64", || literal(None));
+        env.add_keyword("none_i64", || literal(None::<i64>));
         env.add_keyword("some_i64", || literal(Some(1)));
         env.add_keyword("i64_min", || literal(i64::MIN));
         env.add_keyword("i64_max", || literal(i64::MAX));
@@ -2792,6 +3315,19 @@ mod tests {
         insta::assert_snapshot!(env.render_ok(r#"some_i64_0 > some_i64_1"#), @"false");
         insta::assert_snapshot!(env.render_ok(r#"none_i64 < 0"#), @"true");
         insta::assert_snapshot!(env.render_ok(r#"1 > some_i64_0"#), @"true");
+
+        // Strings are compared lexicographically
+        insta::assert_snapshot!(env.render_ok(r#"'a' < 'b'"#), @"true");
+        insta::assert_snapshot!(env.render_ok(r#"'ab' > 'b'"#), @"false");
+        insta::assert_snapshot!(env.render_ok(r#"'' <= 'a'"#), @"true");
+
+        // Timestamps are compared as instants
+        env.add_keyword("t0", || literal(new_timestamp(0, 0)));
+        env.add_keyword("t1", || literal(new_timestamp(1, 0)));
+        env.add_keyword("t1_tz", || literal(new_timestamp(1, 60)));
+        insta::assert_snapshot!(env.render_ok(r#"t0 < t1"#), @"true");
+        insta::assert_snapshot!(env.render_ok(r#"t1 >= t1_tz"#), @"true");
+        insta::assert_snapshot!(env.render_ok(r#"t1 > t1_tz"#), @"false");
     }
 
     #[test]
@@ -2843,6 +3379,163 @@ mod tests {
         insta::assert_snapshot!(env.render_ok(r#"true || bad_bool"#), @"true");
     }
 
+    #[test]
+    fn test_let_binding() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("hello", || literal("Hello".to_owned()));
+        env.add_keyword("words", || literal(vec!["a".to_owned(), "b".to_owned()]));
+
+        insta::assert_snapshot!(env.render_ok(r#"let x = hello; x ++ x"#), @"HelloHello");
+        insta::assert_snapshot!(env.render_ok(r#"let x = hello.upper(); x.lower()"#), @"hello");
+        insta::assert_snapshot!(env.render_ok(r#"let x = 1; let y = x + 1; x ++ y"#), @"12");
+        insta::assert_snapshot!(env.render_ok(r#"(let x = 1; x) ++ (let x = 2; x)"#), @"12");
+        insta::assert_snapshot!(
+            env.render_ok(r#"words.map(|w| let x = w.upper(); x ++ w)"#),
+            @"Aa Bb");
+        // Local variable shadows keyword and outer variable
+        insta::assert_snapshot!(env.render_ok(r#"let hello = "a"; hello"#), @"a");
+        insta::assert_snapshot!(env.render_ok(r#"let x = 1; let x = x + 1; x"#), @"2");
+        insta::assert_snapshot!(env.render_ok(r#"let w = "x"; words.map(|w| w)"#), @"a b");
+        // Template value can be bound
+        insta::assert_snapshot!(
+            env.render_ok(r#"let x = label("a", hello); x ++ x"#),
+            @"HelloHello");
+        insta::assert_snapshot!(env.render_ok(r#"let x = words.map(|w| w); x"#), @"a b");
+
+        // Lambda can be bound and called
+        insta::assert_snapshot!(
+            env.render_ok(r#"let f = |s| s.upper(); f(hello) ++ f("x")"#),
+            @"HELLOX");
+        insta::assert_snapshot!(
+            env.render_ok(r#"let f = |a, b| a ++ "-" ++ b; f(1, 2)"#),
+            @"1-2");
+        insta::assert_snapshot!(env.render_ok(r#"let f = || hello; f()"#), @"Hello");
+        // Bound lambda can be passed to method
+        insta::assert_snapshot!(
+            env.render_ok(r#"let f = |s| s.upper(); words.map(f)"#),
+            @"A B");
+        insta::assert_snapshot!(
+            env.render_ok(r#"let f = |s| s == "b"; words.filter(f) ++ words.any(f)"#),
+            @"btrue");
+        // Variables are resolved where the lambda is defined
+        insta::assert_snapshot!(
+            env.render_ok(r#"let x = "a"; let f = |s| s ++ x; let x = "b"; f(x)"#),
+            @"ba");
+        // Bound lambda shadows function, and is shadowed by variable
+        insta::assert_snapshot!(env.render_ok(r#"let json = |s| s; json(hello)"#), @"Hello");
+        insta::assert_snapshot!(
+            env.render_ok(r#"let f = |s| s; words.map(|f| f)"#),
+            @"a b");
+        insta::assert_snapshot!(env.render_ok(r#"let f = |s| s; let f = 1; f"#), @"1");
+        // Alias can be defined with bindings
+        env.add_alias("twice(s)", "let t = s; t ++ t");
+        insta::assert_snapshot!(env.render_ok(r#"twice(hello)"#), @"HelloHello");
+
+        insta::assert_snapshot!(env.parse_err(r#"let f = |s| s; f"#), @r"
+         --> 1:16
+          |
+        1 | let f = |s| s; f
+          |                ^
+          |
+          = Lambda `f` must be called
+        ");
+        insta::assert_snapshot!(env.parse_err(r#"let f = |s| s; f(1, 2)"#), @r"
+         --> 1:18
+          |
+        1 | let f = |s| s; f(1, 2)
+          |                  ^--^
+          |
+          = Function `f`: Expected 1 arguments
+        ");
+        insta::assert_snapshot!(env.parse_err(r#"let f = |s| s; f(s = 1)"#), @r"
+         --> 1:18
+          |
+        1 | let f = |s| s; f(s = 1)
+          |                  ^---^
+          |
+          = Function `f`: Unexpected keyword arguments
+        ");
+        insta::assert_snapshot!(env.parse_err(r#"let f = |s| s; words.map(|s| f)"#), @r"
+         --> 1:30
+          |
+        1 | let f = |s| s; words.map(|s| f)
+          |                              ^
+          |
+          = Lambda `f` must be called
+        ");
+        // Lambda can't be called recursively
+        insta::assert_snapshot!(env.parse_err(r#"let f = |s| f(s); f(1)"#), @r"
+         --> 1:13
+          |
+        1 | let f = |s| f(s); f(1)
+          |             ^
+          |
+          = Function `f` doesn't exist
+        ");
+        // Variable isn't visible outside of the body
+        insta::assert_snapshot!(env.parse_err(r#"(let x = 1; x) ++ x"#), @r"
+         --> 1:19
+          |
+        1 | (let x = 1; x) ++ x
+          |                   ^
+          |
+          = Keyword `x` doesn't exist
+        ");
+        // Error in bound value
+        insta::assert_snapshot!(env.parse_err(r#"let x = foo; x"#), @r"
+         --> 1:9
+          |
+        1 | let x = foo; x
+          |         ^-^
+          |
+          = Keyword `foo` doesn't exist
+        ");
+    }
+
+    #[test]
+    fn test_let_binding_evaluation() {
+        let counter = Rc::new(Cell::new(0));
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("count", {
+            let counter = counter.clone();
+            move || {
+                let counter = counter.clone();
+                Literal(())
+                    .map(move |()| {
+                        counter.set(counter.get() + 1);
+                        counter.get()
+                    })
+                    .into_dyn_wrapped()
+            }
+        });
+        env.add_keyword("words", || literal(vec!["a".to_owned(), "b".to_owned()]));
+
+        // Keyword is evaluated for each reference
+        insta::assert_snapshot!(env.render_ok(r#"count ++ count"#), @"12");
+        counter.set(0);
+
+        // Bound value is evaluated once per context
+        let template = env.parse(r#"let c = count; c ++ c ++ c"#).unwrap();
+        let render = || String::from_utf8(template.format_plain_text(&Context)).unwrap();
+        insta::assert_snapshot!(render(), @"111");
+        insta::assert_snapshot!(render(), @"222");
+        counter.set(0);
+
+        // Lambda arguments are evaluated once per call
+        insta::assert_snapshot!(env.render_ok(r#"let f = |x| x ++ x; f(count)"#), @"11");
+        counter.set(0);
+
+        // Bound value is re-evaluated per item of map() since the context
+        // changes, but is shared within the item
+        insta::assert_snapshot!(
+            env.render_ok(r#"let c = count; words.map(|w| c ++ w ++ c)"#),
+            @"1a1 2b2");
+        counter.set(0);
+        insta::assert_snapshot!(
+            env.render_ok(r#"words.map(|w| let c = count; c ++ w ++ c) ++ " " ++ count"#),
+            @"1a1 2b2 3");
+    }
+
     #[test]
     fn test_list_method() {
         let mut env = TestTemplateEnv::new();
@@ -3050,6 +3743,111 @@ mod tests {
         "#);
     }
 
+    #[test]
+    fn test_list_ordering_method() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("words", || {
+            literal(["bb", "a", "ccc", "a", "b"].map(str::to_owned).to_vec())
+        });
+
+        insta::assert_snapshot!(env.render_ok(r#"words.sort_by(|s| s)"#), @"a a b bb ccc");
+        // Sort is stable
+        insta::assert_snapshot!(env.render_ok(r#"words.sort_by(|s| s.len())"#), @"a a b bb ccc");
+        insta::assert_snapshot!(env.render_ok(r#"words.sort_by(|s| -s.len())"#), @"ccc bb a a b");
+        insta::assert_snapshot!(env.render_ok(r#"words.reverse()"#), @"b a ccc a bb");
+        insta::assert_snapshot!(
+            env.render_ok(r#"words.sort_by(|s| s).reverse().join(",")"#),
+            @"ccc,bb,b,a,a");
+        insta::assert_snapshot!(env.render_ok(r#"words.unique()"#), @"bb a ccc b");
+        insta::assert_snapshot!(env.render_ok(r#"words.unique().len()"#), @"4");
+        insta::assert_snapshot!(env.render_ok(r#""".lines().sort_by(|s| s).unique()"#), @"");
+
+        // Lambda bound by let can be used as key
+        insta::assert_snapshot!(
+            env.render_ok(r#"let key = |s| s.len(); words.sort_by(key).reverse()"#),
+            @"ccc bb b a a");
+
+        insta::assert_snapshot!(env.parse_err(r#"words.sort_by(|s| s.lines())"#), @r#"
+         --> 1:15
+          |
+        1 | words.sort_by(|s| s.lines())
+          |               ^-----------^
+          |
+          = Cannot sort by expression of type `List<String>`
+        "#);
+        insta::assert_snapshot!(env.parse_err(r#"words.sort_by(|a, b| a)"#), @r#"
+         --> 1:16
+          |
+        1 | words.sort_by(|a, b| a)
+          |                ^--^
+          |
+          = Expected 1 lambda parameters
+        "#);
+        insta::assert_snapshot!(env.parse_err(r#"words.reverse(1)"#), @r#"
+         --> 1:15
+          |
+        1 | words.reverse(1)
+          |               ^
+          |
+          = Function `reverse`: Expected 0 arguments
+        "#);
+    }
+
+    #[test]
+    fn test_list_slicing_method() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("words", || {
+            literal(["a", "b", "c"].map(str::to_owned).to_vec())
+        });
+
+        insta::assert_snapshot!(env.render_ok(r#"words.first() ++ words.last()"#), @"ac");
+        insta::assert_snapshot!(env.render_ok(r#"words.get(0) ++ words.get(2)"#), @"ac");
+        insta::assert_snapshot!(env.render_ok(r#"words.get(3)"#), @"<Error: Index 3 is out of range>");
+        insta::assert_snapshot!(
+            env.render_ok(r#"words.get(-1)"#),
+            @"<Error: Index -1 is out of range>");
+        insta::assert_snapshot!(env.render_ok(r#""".lines().first()"#), @"<Error: List is empty>");
+        insta::assert_snapshot!(env.render_ok(r#""".lines().last()"#), @"<Error: List is empty>");
+
+        insta::assert_snapshot!(env.render_ok(r#"words.take(2)"#), @"a b");
+        insta::assert_snapshot!(env.render_ok(r#"words.take(5)"#), @"a b c");
+        insta::assert_snapshot!(env.render_ok(r#"words.skip(2)"#), @"c");
+        insta::assert_snapshot!(env.render_ok(r#"words.skip(5).len()"#), @"0");
+        insta::assert_snapshot!(env.render_ok(r#"words.skip(1).take(1)"#), @"b");
+        insta::assert_snapshot!(
+            env.render_ok(r#"words.take(-1)"#),
+            @"<Error: out of range integral type conversion attempted>");
+    }
+
+    #[test]
+    fn test_list_aggregation_method() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("words", || {
+            literal(["bb", "a", "ccc"].map(str::to_owned).to_vec())
+        });
+        env.add_keyword("i64_max", || literal(i64::MAX));
+
+        insta::assert_snapshot!(env.render_ok(r#"words.sum(|s| s.len())"#), @"6");
+        insta::assert_snapshot!(env.render_ok(r#"words.max(|s| s.len())"#), @"3");
+        insta::assert_snapshot!(env.render_ok(r#""".lines().sum(|s| s.len())"#), @"0");
+        insta::assert_snapshot!(env.render_ok(r#""".lines().max(|s| s.len())"#), @"");
+        insta::assert_snapshot!(
+            env.render_ok(r#"if(words.max(|s| s.len()) > 2, "long")"#),
+            @"long");
+        insta::assert_snapshot!(
+            env.render_ok(r#"words.sum(|s| i64_max)"#),
+            @"<Error: Attempt to add with overflow>");
+
+        insta::assert_snapshot!(env.parse_err(r#"words.sum(|s| s)"#), @r#"
+         --> 1:15
+          |
+        1 | words.sum(|s| s)
+          |               ^
+          |
+          = Expected expression of type `Integer`, but actual type is `String`
+        "#);
+    }
+
     #[test]
     fn test_string_method() {
         let mut env = TestTemplateEnv::new();
@@ -3267,6 +4065,42 @@ mod tests {
             @"<Error: invalid type: sequence, expected a boolean>");
     }
 
+    #[test]
+    fn test_config_value_table_method() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("string", || literal(ConfigValue::from("foo")));
+        env.add_keyword("table", || {
+            literal(ConfigValue::from_iter([
+                ("src/net", "alice"),
+                ("lib", "bob"),
+            ]))
+        });
+        env.add_keyword("name", || literal("lib".to_owned()));
+
+        insta::assert_snapshot!(env.render_ok("table.keys()"), @"src/net lib");
+        insta::assert_snapshot!(env.render_ok("table.get('src/net')"), @r#""alice""#);
+        insta::assert_snapshot!(env.render_ok("table.get(name).as_string()"), @"bob");
+        insta::assert_snapshot!(env.render_ok("table.get('unknown')"), @"");
+        insta::assert_snapshot!(
+            env.render_ok("if(table.get('unknown'), 'some', 'none')"), @"none");
+        insta::assert_snapshot!(env.render_ok("table.get('unknown').as_string()"), @"<Error: No ConfigValue available>");
+        insta::assert_snapshot!(env.render_ok("table.contains_key('lib')"), @"true");
+        insta::assert_snapshot!(env.render_ok("table.contains_key('bin')"), @"false");
+        insta::assert_snapshot!(env.render_ok("json(table.get('lib'))"), @r#""bob""#);
+        insta::assert_snapshot!(env.render_ok("json(table.get('bin'))"), @"null");
+
+        insta::assert_snapshot!(env.render_ok("string.keys()"), @"<Error: Expected a table, but is string>");
+        insta::assert_snapshot!(env.render_ok("string.get('foo')"), @"<Error: Expected a table, but is string>");
+        insta::assert_snapshot!(env.parse_err("table.get()"), @r"
+         --> 1:11
+          |
+        1 | table.get()
+          |           ^
+          |
+          = Function `get`: Expected 1 arguments
+        ");
+    }
+
     #[test]
     fn test_signature() {
         let mut env = TestTemplateEnv::new();
@@ -3623,6 +4457,39 @@ mod tests {
             @"[38;5;1mtext[39m");
     }
 
+    #[test]
+    fn test_hyperlink_function() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("url", || literal("https://example.com/".to_owned()));
+        env.add_color("error", crossterm::style::Color::DarkRed);
+
+        insta::assert_snapshot!(
+            env.render_ok(r#"hyperlink("https://example.com/", "text")"#),
+            @r"]8;;https://example.com/\text]8;;\");
+        insta::assert_snapshot!(
+            env.render_ok(r#"hyperlink(url ++ "x", label("error", "text"))"#),
+            @r"[38;5;1m]8;;https://example.com/x\text[39m]8;;\");
+        // Padding and other text manipulation should apply to the link text
+        insta::assert_snapshot!(
+            env.render_ok(r#"fill(4, hyperlink(url, "a b c"))"#),
+            @r"
+        ]8;;https://example.com/\a b]8;;\
+        ]8;;https://example.com/\c]8;;\
+        ");
+        // Empty URL
+        insta::assert_snapshot!(env.render_ok(r#"hyperlink("", "text")"#), @"text");
+        insta::assert_snapshot!(
+            env.parse_err(r#"hyperlink(url)"#),
+            @r"
+         --> 1:11
+          |
+        1 | hyperlink(url)
+          |           ^-^
+          |
+          = Function `hyperlink`: Expected 2 arguments
+        ");
+    }
+
     #[test]
     fn test_raw_escape_sequence_function_strip_labels() {
         let mut env = TestTemplateEnv::new();
@@ -73,6 +73,7 @@ impl Rule {
             Self::any_string_literal => None,
             Self::integer_literal => None,
             Self::identifier => None,
+            Self::let_keyword => Some("let"),
             Self::concat_op => Some("++"),
             Self::logical_or_op => Some("||"),
             Self::logical_and_op => Some("&&"),
@@ -103,6 +104,7 @@ impl Rule {
             Self::primary => None,
             Self::term => None,
             Self::expression => None,
+            Self::let_binding => None,
             Self::template => None,
             Self::program => None,
             Self::function_alias_declaration => None,
@@ -301,6 +303,8 @@ pub enum ExpressionKind<'i> {
     FunctionCall(Box<FunctionCallNode<'i>>),
     MethodCall(Box<MethodCallNode<'i>>),
     Lambda(Box<LambdaNode<'i>>),
+    /// `let <name> = <value>; <body>`
+    Let(Box<LetNode<'i>>),
     /// Identity node to preserve the span in the source template text.
     AliasExpanded(AliasId<'i>, Box<ExpressionNode<'i>>),
 }
@@ -345,6 +349,15 @@ impl<'i> FoldableExpression<'i> for ExpressionKind<'i> {
                 });
                 Ok(Self::Lambda(lambda))
             }
+            Self::Let(binding) => {
+                let binding = Box::new(LetNode {
+                    name: binding.name,
+                    name_span: binding.name_span,
+                    value: folder.fold_expression(binding.value)?,
+                    body: folder.fold_expression(binding.body)?,
+                });
+                Ok(Self::Let(binding))
+            }
             Self::AliasExpanded(id, subst) => {
                 let subst = Box::new(folder.fold_expression(*subst)?);
                 Ok(Self::AliasExpanded(id, subst))
@@ -421,6 +434,14 @@ pub struct LambdaNode<'i> {
     pub body: ExpressionNode<'i>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct LetNode<'i> {
+    pub name: &'i str,
+    pub name_span: pest::Span<'i>,
+    pub value: ExpressionNode<'i>,
+    pub body: ExpressionNode<'i>,
+}
+
 fn parse_identifier_or_literal(pair: Pair<Rule>) -> ExpressionKind {
     assert_eq!(pair.as_rule(), Rule::identifier);
     match pair.as_str() {
@@ -607,10 +628,33 @@ fn parse_expression_node(pair: Pair<Rule>) -> TemplateParseResult<ExpressionNode
         .parse(pair.into_inner())
 }
 
+fn parse_let_binding(
+    pair: Pair<'_, Rule>,
+) -> TemplateParseResult<(&str, pest::Span<'_>, ExpressionNode<'_>)> {
+    assert_eq!(pair.as_rule(), Rule::let_binding);
+    let [keyword_pair, name_pair, value_pair] = pair.into_inner().collect_array().unwrap();
+    assert_eq!(keyword_pair.as_rule(), Rule::let_keyword);
+    let name_span = name_pair.as_span();
+    let name = parse_identifier_name(name_pair)?;
+    let value = parse_template_node(value_pair)?;
+    Ok((name, name_span, value))
+}
+
 fn parse_template_node(pair: Pair<Rule>) -> TemplateParseResult<ExpressionNode> {
     assert_eq!(pair.as_rule(), Rule::template);
     let span = pair.as_span();
-    let inner = pair.into_inner();
+    let mut inner = pair.into_inner().peekable();
+    let mut bindings = Vec::new();
+    while let Some(pair) = inner.next_if(|pair| pair.as_rule() == Rule::let_binding) {
+        bindings.push((pair.as_span(), parse_let_binding(pair)?));
+    }
+    // Concatenated expressions follow the let bindings
+    let body_span = inner
+        .peek()
+        .unwrap()
+        .as_span()
+        .start_pos()
+        .span(&span.end_pos());
     let mut nodes: Vec<_> = inner
         .filter_map(|pair| match pair.as_rule() {
             Rule::concat_op => None,
@@ -618,11 +662,27 @@ fn parse_template_node(pair: Pair<Rule>) -> TemplateParseResult<ExpressionNode>
             r => panic!("unexpected template item rule {r:?}"),
         })
         .try_collect()?;
-    if nodes.len() == 1 {
-        Ok(nodes.pop().unwrap())
+    let body = if nodes.len() == 1 {
+        nodes.pop().unwrap()
     } else {
-        Ok(ExpressionNode::new(ExpressionKind::Concat(nodes), span))
-    }
+        ExpressionNode::new(ExpressionKind::Concat(nodes), body_span)
+    };
+    // Inner bindings are nested in the body of the outer bindings
+    let node =
+        bindings
+            .into_iter()
+            .rev()
+            .fold(body, |body, (binding_span, (name, name_span, value))| {
+                let span = binding_span.start_pos().span(&span.end_pos());
+                let binding = Box::new(LetNode {
+                    name,
+                    name_span,
+                    value,
+                    body,
+                });
+                ExpressionNode::new(ExpressionKind::Let(binding), span)
+            });
+    Ok(node)
 }
 
 /// Parses text into AST nodes. No type/name checking is made at this stage.
@@ -919,6 +979,15 @@ mod tests {
                 });
                 ExpressionKind::Lambda(lambda)
             }
+            ExpressionKind::Let(binding) => {
+                let binding = Box::new(LetNode {
+                    name: binding.name,
+                    name_span: empty_span(),
+                    value: normalize_tree(binding.value),
+                    body: normalize_tree(binding.body),
+                });
+                ExpressionKind::Let(binding)
+            }
             ExpressionKind::AliasExpanded(_, subst) => normalize_tree(*subst).kind,
         };
         ExpressionNode {
@@ -1143,6 +1212,74 @@ mod tests {
         assert!(parse_template("|false| a").is_err());
     }
 
+    #[test]
+    fn test_let_syntax() {
+        fn unwrap_let(node: ExpressionNode<'_>) -> Box<LetNode<'_>> {
+            match node.kind {
+                ExpressionKind::Let(binding) => binding,
+                _ => panic!("unexpected expression: {node:?}"),
+            }
+        }
+
+        let binding = unwrap_let(parse_template("let x = a; x").unwrap());
+        assert_eq!(binding.name, "x");
+        assert_eq!(binding.value.kind, ExpressionKind::Identifier("a"));
+        assert_eq!(binding.body.kind, ExpressionKind::Identifier("x"));
+
+        // Value and body can be concatenated templates
+        assert_eq!(
+            parse_normalized("let x = a ++ b; x ++ c"),
+            parse_normalized("let x = (a ++ b); (x ++ c)"),
+        );
+        // Lambda can be bound
+        assert_eq!(
+            parse_normalized("let f = |x| x ++ a; f(b)"),
+            parse_normalized("let f = (|x| (x ++ a)); f(b)"),
+        );
+        // Multiple bindings are nested
+        let binding = unwrap_let(parse_template("let x = a; let y = x; y").unwrap());
+        assert_eq!(binding.name, "x");
+        let inner = unwrap_let(binding.body);
+        assert_eq!(inner.name, "y");
+        assert_eq!(inner.value.kind, ExpressionKind::Identifier("x"));
+        // Bindings can be placed in parenthesized template, lambda body, and
+        // function arguments
+        assert!(parse_template("a ++ (let x = b; x)").is_ok());
+        assert!(parse_template("l.map(|x| let y = x; y)").is_ok());
+        assert!(parse_template("f(let x = a; x, b)").is_ok());
+
+        // Binding must be followed by body
+        assert!(parse_template("let x = a;").is_err());
+        assert!(parse_template("let x = a").is_err());
+        // Binding can't be placed in the middle of template
+        assert!(parse_template("a ++ let x = b; x").is_err());
+        // Boolean literal cannot be used as a name
+        assert!(parse_template("let true = a; b").is_err());
+        // "let" is a keyword only if followed by a binding
+        assert_eq!(
+            parse_into_kind("let"),
+            Ok(ExpressionKind::Identifier("let"))
+        );
+        assert_eq!(
+            parse_into_kind("letter"),
+            Ok(ExpressionKind::Identifier("letter")),
+        );
+        assert!(parse_template("letx = a; x").is_err());
+
+        // Expression span (which includes trailing whitespace as concatenation
+        // does)
+        let node = parse_template(" let x = a; let y = x; y ++ z ").unwrap();
+        assert_eq!(node.span.as_str(), "let x = a; let y = x; y ++ z ");
+        let binding = unwrap_let(node);
+        assert_eq!(binding.name_span.as_str(), "x");
+        assert_eq!(binding.value.span.as_str(), "a");
+        assert_eq!(binding.body.span.as_str(), "let y = x; y ++ z ");
+        let inner = unwrap_let(binding.body);
+        assert_eq!(inner.body.span.as_str(), "y ++ z ");
+        let node = parse_template("let x = a ; x").unwrap();
+        assert_eq!(unwrap_let(node).body.span.as_str(), "x");
+    }
+
     #[test]
     fn test_keyword_literal() {
         assert_eq!(parse_into_kind("false"), Ok(ExpressionKind::Boolean(false)));
@@ -15,6 +15,7 @@
 //! Tools for lazily evaluating templates that produce text in a fallible
 //! manner.
 
+use std::cell::Cell;
 use std::cell::RefCell;
 use std::error;
 use std::fmt;
@@ -67,6 +68,12 @@ impl<T: Template + ?Sized> Template for Box<T> {
     }
 }
 
+impl<T: Template + ?Sized> Template for Rc<T> {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        <T as Template>::format(self, formatter)
+    }
+}
+
 // All optional printable types should be printable, and it's unlikely to
 // implement different formatting per type.
 impl<T: Template> Template for Option<T> {
@@ -207,6 +214,39 @@ where
     }
 }
 
+pub struct HyperlinkTemplate<T, U> {
+    content: T,
+    url: U,
+}
+
+impl<T, U> HyperlinkTemplate<T, U> {
+    pub fn new(content: T, url: U) -> Self
+    where
+        T: Template,
+        U: TemplateProperty<Output = String>,
+    {
+        Self { content, url }
+    }
+}
+
+impl<T, U> Template for HyperlinkTemplate<T, U>
+where
+    T: Template,
+    U: TemplateProperty<Output = String>,
+{
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        match self.url.extract() {
+            Ok(url) => {
+                formatter.push_hyperlink(&url);
+                self.content.format(formatter)?;
+                formatter.pop_hyperlink();
+                Ok(())
+            }
+            Err(err) => formatter.handle_error(err),
+        }
+    }
+}
+
 pub struct RawEscapeSequenceTemplate<T>(pub T);
 
 impl<T: Template> Template for RawEscapeSequenceTemplate<T> {
@@ -343,6 +383,14 @@ impl<P: TemplateProperty + ?Sized> TemplateProperty for Box<P> {
     }
 }
 
+impl<P: TemplateProperty + ?Sized> TemplateProperty for Rc<P> {
+    type Output = <P as TemplateProperty>::Output;
+
+    fn extract(&self) -> Result<Self::Output, TemplatePropertyError> {
+        <P as TemplateProperty>::extract(self)
+    }
+}
+
 impl<P: TemplateProperty> TemplateProperty for Option<P> {
     type Output = Option<P::Output>;
 
@@ -667,24 +715,57 @@ where
     }
 }
 
+thread_local! {
+    /// Pair of the current evaluation context id and the next id to be
+    /// allocated.
+    static CONTEXT_IDS: Cell<(u64, u64)> = const { Cell::new((0, 1)) };
+}
+
+/// Returns the id of the current evaluation context, which changes when a
+/// placeholder value is set or taken.
+fn current_context_id() -> u64 {
+    CONTEXT_IDS.get().0
+}
+
+/// Enters new evaluation context, and returns the id of the outer context.
+fn enter_new_context() -> u64 {
+    let (outer, next) = CONTEXT_IDS.get();
+    CONTEXT_IDS.set((next, next + 1));
+    outer
+}
+
+fn restore_context(outer: u64) {
+    let (_, next) = CONTEXT_IDS.get();
+    CONTEXT_IDS.set((outer, next));
+}
+
 /// Property which will be compiled into template once, and substituted later.
 #[derive(Clone, Debug)]
 pub struct PropertyPlaceholder<O> {
     value: Rc<RefCell<Option<O>>>,
+    outer_context_id: Rc<Cell<Option<u64>>>,
 }
 
 impl<O> PropertyPlaceholder<O> {
     pub fn new() -> Self {
         Self {
             value: Rc::new(RefCell::new(None)),
+            outer_context_id: Rc::new(Cell::new(None)),
         }
     }
 
     pub fn set(&self, value: O) {
         *self.value.borrow_mut() = Some(value);
+        let outer = enter_new_context();
+        // If the value is overwritten, the original outer context is kept.
+        self.outer_context_id
+            .set(Some(self.outer_context_id.get().unwrap_or(outer)));
     }
 
     pub fn take(&self) -> Option<O> {
+        if let Some(outer) = self.outer_context_id.take() {
+            restore_context(outer);
+        }
         self.value.borrow_mut().take()
     }
 
@@ -714,6 +795,45 @@ impl<O: Clone> TemplateProperty for PropertyPlaceholder<O> {
     }
 }
 
+/// Property that evaluates the inner property at most once per evaluation
+/// context.
+///
+/// The evaluation context changes when any [`PropertyPlaceholder`] value is
+/// set or taken. The last value is reused while the context stays the same.
+pub struct MemoizedProperty<P: TemplateProperty> {
+    property: P,
+    cache: RefCell<Option<(u64, P::Output)>>,
+}
+
+impl<P: TemplateProperty> MemoizedProperty<P> {
+    pub fn new(property: P) -> Self {
+        Self {
+            property,
+            cache: RefCell::new(None),
+        }
+    }
+}
+
+impl<P> TemplateProperty for MemoizedProperty<P>
+where
+    P: TemplateProperty,
+    P::Output: Clone,
+{
+    type Output = P::Output;
+
+    fn extract(&self) -> Result<Self::Output, TemplatePropertyError> {
+        let context_id = current_context_id();
+        if let Some((id, value)) = &*self.cache.borrow()
+            && *id == context_id
+        {
+            return Ok(value.clone());
+        }
+        let value = self.property.extract()?;
+        *self.cache.borrow_mut() = Some((context_id, value.clone()));
+        Ok(value)
+    }
+}
+
 /// Adapter that renders compiled `template` with the `placeholder` value set.
 pub struct TemplateRenderer<'a, C> {
     template: Box<dyn Template + 'a>,
@@ -800,6 +920,14 @@ impl<'a> TemplateFormatter<'a> {
         self.formatter.pop_label();
     }
 
+    pub fn push_hyperlink(&mut self, url: &str) {
+        self.formatter.push_hyperlink(url);
+    }
+
+    pub fn pop_hyperlink(&mut self) {
+        self.formatter.pop_hyperlink();
+    }
+
     pub fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> io::Result<()> {
         self.formatter.write_fmt(args)
     }
@@ -238,6 +238,15 @@ impl fmt::Display for ColorChoice {
     }
 }
 
+/// Whether to enable an optional feature of colorized output.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ColorFeatureChoice {
+    Always,
+    Never,
+    Auto,
+}
+
 fn prepare_formatter_factory(
     config: &StackedConfig,
     stdout: &Stdout,
@@ -250,7 +259,14 @@ fn prepare_formatter_factory(
         ColorChoice::Auto => (terminal, false),
     };
     if color {
-        FormatterFactory::color(config, debug)
+        let enabled = |choice| match choice {
+            ColorFeatureChoice::Always => true,
+            ColorFeatureChoice::Never => false,
+            ColorFeatureChoice::Auto => terminal,
+        };
+        let hyperlinks = enabled(config.get("ui.hyperlinks")?);
+        let syntax_highlighting = enabled(config.get("ui.syntax-highlighting")?);
+        FormatterFactory::color(config, debug, hyperlinks, syntax_highlighting)
     } else if terminal {
         // Sanitize ANSI escape codes if we're printing to a terminal. Doesn't
         // affect ANSI escape codes that originate from the formatter itself.
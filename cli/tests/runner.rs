@@ -64,6 +64,7 @@ mod test_parallelize_command;
 mod test_rebase_command;
 mod test_repo_change_report;
 mod test_resolve_command;
+mod test_resolve_divergence_command;
 mod test_restore_command;
 mod test_revert_command;
 mod test_revset_output;
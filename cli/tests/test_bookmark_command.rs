@@ -552,6 +552,9 @@ fn test_bookmark_move_conflicting() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 5ab2df217c33: create bookmark foo pointing to commit f7addb25e91d5dbc1f882e2ea8c6f977653214f3
+    Merging operation 81d44d6c3648: create bookmark foo pointing to commit 61b17b816638bd93b6f8f88444732801a50689ca
+    Warning: Concurrent operations moved bookmarks to different commits: foo
     [EOF]
     ");
 
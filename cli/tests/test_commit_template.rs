@@ -562,6 +562,10 @@ fn test_log_evolog_divergence() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation fec5a045b947: describe commit d0c049cd993a8d3a2e69ba6df98788e264ea9fa1
+    Merging operation f78801e95500: describe commit d0c049cd993a8d3a2e69ba6df98788e264ea9fa1
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation fec5a045b947
     [EOF]
     ");
 
@@ -1521,6 +1525,113 @@ fn test_file_list_symlink() {
     ");
 }
 
+#[test]
+fn test_file_content() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.create_dir("dir");
+    work_dir.write_file("VERSION", "1.0.0\n");
+    work_dir.write_file("dir/file", "a\nb\n");
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir.write_file("VERSION", "1.1.0\n");
+    work_dir.run_jj(["describe", "-m", "second"]).success();
+    work_dir.run_jj(["new", "root()", "-m", "other"]).success();
+    work_dir.write_file("VERSION", "2.0.0\n");
+    work_dir
+        .run_jj(["new", "visible_heads()", "-m", "merge"])
+        .success();
+
+    let template = r#"
+        separate(" ",
+          description.first_line(),
+          if(self.file("VERSION"), self.file("VERSION").lines().join("|"), "(none)"),
+        ) ++ "\n"
+    "#;
+    let output = work_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    @    merge <<<<<<< Conflict 1 of 1|%%%%%%% Changes from base to side #1|+2.0.0|+++++++ Contents of side #2|1.1.0|>>>>>>> Conflict 1 of 1 ends
+    ├─╮
+    │ ○  second 1.1.0
+    │ ○  first 1.0.0
+    ○ │  other 2.0.0
+    ├─╯
+    ◆  (none)
+    [EOF]
+    ");
+
+    let template = r#"self.file("dir/file").content()"#;
+    let output = work_dir.run_jj([
+        "log",
+        "-r",
+        "description(first)",
+        "--no-graph",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    a
+    b
+    [EOF]
+    ");
+
+    // Path is relative to the current directory
+    let sub_dir = work_dir.dir("dir");
+    let template = r#"self.file("file").path()"#;
+    let output = sub_dir.run_jj([
+        "log",
+        "-r",
+        "description(first)",
+        "--no-graph",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @"dir/file[EOF]");
+
+    // Directory has no content
+    let template = r#"self.file("dir").file_type() ++ " " ++ self.file("dir").content()"#;
+    let output = work_dir.run_jj([
+        "log",
+        "-r",
+        "description(first)",
+        "--no-graph",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @"tree <Error: Not a file>[EOF]");
+
+    // Missing file
+    let template = r#"self.file("missing").content()"#;
+    let output = work_dir.run_jj([
+        "log",
+        "-r",
+        "description(first)",
+        "--no-graph",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @"<Error: No TreeEntry available>[EOF]");
+
+    // Path outside the workspace
+    let output = work_dir.run_jj(["log", "-T", r#"self.file("../out")"#]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Failed to parse template: Invalid file path
+    Caused by:
+    1:  --> 1:11
+      |
+    1 | self.file("../out")
+      |           ^------^
+      |
+      = Invalid file path
+    2: Path "../out" is not in the repo "."
+    3: Invalid component ".." in repo-relative path "../out"
+    [EOF]
+    [exit status: 1]
+    "#);
+}
+
 #[test]
 fn test_signature_templates() {
     let test_env = TestEnvironment::default();
@@ -208,6 +208,7 @@ fn test_bookmark_names() {
     --ignore-working-copy	Don't snapshot the working copy, and don't update it
     --ignore-immutable	Allow rewriting immutable commits
     --at-operation	Operation to load the repo at
+    --op-tag	Add a tag to the operation created by this command (can be repeated)
     --debug	Enable debug logging
     --color	When to colorize output
     --quiet	Silence non-primary command output
@@ -1178,6 +1179,7 @@ fn test_merge_tools() {
     :name-only
     :git
     :color-words
+    :side-by-side
     diffedit3
     diffedit3-ssh
     difft
@@ -61,6 +61,10 @@ fn test_concurrent_operation_divergence() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation b2cffe4f3026: describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    Merging operation d8ced2ea64a8: describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation b2cffe4f3026
     [EOF]
     ");
 }
@@ -87,6 +91,10 @@ fn test_concurrent_operations_auto_rebase() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 5e615518d781: describe commit 7b22a8cbe888adcb4d5ff6dd46a38049e870c6ab
+    Merging operation 4b73b997dbdc: new empty commit
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation 5e615518d781
     Rebased 1 descendant commits onto commits rewritten by other operation
     [EOF]
     ");
@@ -117,6 +125,10 @@ fn test_concurrent_operations_wc_modified() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 05616ad7902a: new empty commit
+    Merging operation 7211c05f0480: new empty commit
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation 05616ad7902a
     [EOF]
     ");
     let output = work_dir.run_jj(["diff", "--git"]);
@@ -148,6 +160,66 @@ fn test_concurrent_operations_wc_modified() {
     ");
 }
 
+#[test]
+fn test_concurrent_operations_bookmark_conflict() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["new", "-m", "A"]).success();
+    work_dir.run_jj(["new", "-m", "B", "@-"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "foo"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "set", "-rdescription(A)", "foo"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "set", "-r@", "foo", "--at-op=@-"])
+        .success();
+
+    // The bookmark conflict is reported when the operations are merged
+    let output = work_dir.run_jj(["bookmark", "list"]);
+    insta::assert_snapshot!(output, @r"
+    foo (conflicted):
+      - qpvuntsm e8849ae1 (empty) (no description set)
+      + rlvkpnrz 8c12c2c0 (empty) A
+      + kkmpptxz bc433a37 (empty) B
+    [EOF]
+    ------- stderr -------
+    Concurrent modification detected, resolving automatically.
+    Merging operation b075e8f49de3: point bookmark foo to commit 8c12c2c019f54c6e3dfe2fcf6179a37546e5c5f1
+    Merging operation 12b78ee86e9b: point bookmark foo to commit bc433a372426c3fc7c922b526b6f8671e5faad45
+    Warning: Concurrent operations moved bookmarks to different commits: foo
+    [EOF]
+    ");
+
+    // The merge operation is marked in the op log
+    let output = work_dir.run_jj(["op", "log", "-n1"]);
+    insta::assert_snapshot!(output, @r"
+    @    b3f75f46329c test-username@host.example.com 2001-02-03 04:05:13.000 +07:00 - 2001-02-03 04:05:13.000 +07:00
+    ├─╮  reconcile divergent operations
+    │ │  merged from b075e8f49de3, 12b78ee86e9b
+    │ │  args: jj bookmark list
+    │ │  conflicted-bookmarks: foo
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "op",
+        "log",
+        "--no-graph",
+        "-n1",
+        "-T",
+        r#"merged_from.map(|op| op.description()).join("\n") ++ "\n""#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    point bookmark foo to commit 8c12c2c019f54c6e3dfe2fcf6179a37546e5c5f1
+    point bookmark foo to commit bc433a372426c3fc7c922b526b6f8671e5faad45
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_concurrent_snapshot_wc_reloadable() {
     let test_env = TestEnvironment::default();
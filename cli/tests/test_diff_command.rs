@@ -337,6 +337,7 @@ fn test_diff_basic() {
     error: the argument '--template <TEMPLATE>' cannot be used with:
       --git
       --color-words
+      --side-by-side
 
     Usage: jj diff --template <TEMPLATE> --git [FILESETS]...
 
@@ -1833,6 +1834,443 @@ fn test_color_words_diff_missing_newline() {
     ");
 }
 
+#[test]
+fn test_diff_side_by_side() {
+    let mut test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_env_var("COLUMNS", "60");
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n");
+    work_dir.write_file("file2", "foo\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file(
+        "file1",
+        "a\nb\nc\nd\nE\nf\ng\nh\ni\tj and a very long line to be wrapped\nk\n",
+    );
+    work_dir.remove_file("file2");
+    work_dir.write_file("file3", "");
+
+    let output = work_dir.run_jj(["diff", "--side-by-side"]);
+    insta::assert_snapshot!(output, @r"
+    Modified regular file file1:
+        ...
+       2 b                       │    2 b
+       3 c                       │    3 c
+       4 d                       │    4 d
+       5 e                       │    5 E
+       6 f                       │    6 f
+       7 g                       │    7 g
+       8 h                       │    8 h
+       9 i                       │    9 i   j and a very long l
+                                 │      ine to be wrapped
+      10 j                       │   10 k
+    Removed regular file file2:
+       1 foo                     │
+    Added regular file file3:
+        (empty)
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["diff", "--side-by-side", "--context=0", "--color=always"]);
+    insta::assert_snapshot!(output, @r"
+    [38;5;3mModified regular file file1:[39m
+        ...
+    [38;5;1m   5 [4me[24m[39m                       │ [38;5;2m   5 [4mE[24m[39m
+        ...
+    [38;5;1m   9 i[39m                       │ [38;5;2m   9 i[4m   [24mj[4m and a very long l[24m[39m
+    [38;5;1m     [39m                        │ [38;5;2m     [4mine to be wrapped[24m[39m
+    [38;5;1m  10 j[39m                       │ [38;5;2m  10 [4mk[24m[39m
+    [38;5;3mRemoved regular file file2:[39m
+    [38;5;1m   1 [4mfoo[24m[39m                     │
+    [38;5;3mAdded regular file file3:[39m
+    [38;5;6m    (empty)[39m
+    [EOF]
+    ");
+
+    // The format can also be selected by name
+    let output = work_dir.run_jj(["diff", "--tool=:side-by-side", "--context=1", "file1"]);
+    insta::assert_snapshot!(output, @r"
+    Modified regular file file1:
+        ...
+       4 d                       │    4 d
+       5 e                       │    5 E
+       6 f                       │    6 f
+        ...
+       8 h                       │    8 h
+       9 i                       │    9 i   j and a very long l
+                                 │      ine to be wrapped
+      10 j                       │   10 k
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_diff_side_by_side_large_line_numbers() {
+    let mut test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_env_var("COLUMNS", "60");
+    let work_dir = test_env.work_dir("repo");
+
+    let lines = (1..=100000).map(|i| format!("{i}\n")).collect_vec();
+    work_dir.write_file("file", lines.concat());
+    work_dir.run_jj(["new"]).success();
+    let mut lines = lines;
+    lines[99998] = "changed\n".to_owned();
+    work_dir.write_file("file", lines.concat());
+
+    // The gutter is widened to fit the line numbers
+    let output = work_dir.run_jj(["diff", "--side-by-side", "--context=1"]);
+    insta::assert_snapshot!(output, @r"
+    Modified regular file file:
+        ...
+     99998 99998                 │  99998 99998
+     99999 99999                 │  99999 changed
+    100000 100000                │ 100000 100000
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_diff_side_by_side_conflict() {
+    let mut test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_env_var("COLUMNS", "60");
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "base", &[], &[("file", "a\nb\n")]);
+    create_commit_with_files(&work_dir, "left", &["base"], &[("file", "a\nleft\n")]);
+    create_commit_with_files(&work_dir, "right", &["base"], &[("file", "a\nright\n")]);
+    create_commit_with_files(&work_dir, "merge", &["left", "right"], &[]);
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file", "a\nresolved\n");
+
+    let output = work_dir.run_jj(["diff", "--side-by-side", "--from=left", "--to=merge"]);
+    insta::assert_snapshot!(output, @r"
+    Created conflict in file:
+       1 a                       │    1 a
+       2 left                    │    2 <<<<<<< Conflict 1 of 1
+                                 │    3 %%%%%%% Changes from ba
+                                 │      se to side #1
+                                 │    4 -b
+                                 │    5 +left
+                                 │    6 +++++++ Contents of sid
+                                 │      e #2
+                                 │    7 right
+                                 │    8 >>>>>>> Conflict 1 of 1
+                                 │       ends
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["diff", "--side-by-side"]);
+    insta::assert_snapshot!(output, @r"
+    Resolved conflict in file:
+       1 a                       │    1 a
+       2 <<<<<<< Conflict 1 of 1 │    2 resolved
+       3 %%%%%%% Changes from ba │
+         se to side #1           │
+       4 -b                      │
+       5 +left                   │
+       6 +++++++ Contents of sid │
+         e #2                    │
+       7 right                   │
+       8 >>>>>>> Conflict 1 of 1 │
+          ends                   │
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_diff_moved_lines() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file(
+        "file1",
+        indoc! {"
+            fn first() {
+                println!(\"first function\");
+            }
+
+            fn second() {
+                println!(\"second function\");
+            }
+            }
+        "},
+    );
+    work_dir.write_file("file2", "a\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file(
+        "file1",
+        indoc! {"
+            fn second() {
+                println!(\"second function\");
+            }
+            }
+        "},
+    );
+    work_dir.write_file(
+        "file2",
+        indoc! {"
+            a
+            fn first() {
+                println!(\"first function\");
+            }
+            b
+        "},
+    );
+
+    // Moved lines aren't labeled by default
+    let output = work_dir.run_jj(["diff", "--color=debug"]);
+    insta::assert_snapshot!(output, @r#"
+    [38;5;3m<<diff header::Modified regular file file1:>>[39m
+    [38;5;1m<<diff removed line_number::   1>>[39m<<diff::     : >>[4m[38;5;1m<<diff removed token::fn first() {>>[24m[39m
+    [38;5;1m<<diff removed line_number::   2>>[39m<<diff::     : >>[4m[38;5;1m<<diff removed token::    println!("first function");>>[24m[39m
+    [38;5;1m<<diff removed line_number::   3>>[39m<<diff::     : >>[4m[38;5;1m<<diff removed token::}>>[24m[39m
+    [38;5;1m<<diff removed line_number::   4>>[39m<<diff::     : >>[4m[38;5;1m<<diff removed token::>>[24m[39m
+    [38;5;1m<<diff removed line_number::   5>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   1>>[39m<<diff::: fn second() {>>
+    [38;5;1m<<diff removed line_number::   6>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   2>>[39m<<diff:::     println!("second function");>>
+    [38;5;1m<<diff removed line_number::   7>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   3>>[39m<<diff::: }>>
+    [38;5;1m<<diff removed line_number::   8>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   4>>[39m<<diff::: }>>
+    [38;5;3m<<diff header::Modified regular file file2:>>[39m
+    [38;5;1m<<diff removed line_number::   1>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   1>>[39m<<diff::: a>>
+    <<diff::     >>[38;5;2m<<diff added line_number::   2>>[39m<<diff::: >>[4m[38;5;2m<<diff added token::fn first() {>>[24m[39m
+    <<diff::     >>[38;5;2m<<diff added line_number::   3>>[39m<<diff::: >>[4m[38;5;2m<<diff added token::    println!("first function");>>[24m[39m
+    <<diff::     >>[38;5;2m<<diff added line_number::   4>>[39m<<diff::: >>[4m[38;5;2m<<diff added token::}>>[24m[39m
+    <<diff::     >>[38;5;2m<<diff added line_number::   5>>[39m<<diff::: >>[4m[38;5;2m<<diff added token::b>>[24m[39m
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj([
+        "diff",
+        "--color=debug",
+        "--config=diff.color-words.color-moved=true",
+    ]);
+    insta::assert_snapshot!(output, @r#"
+    [38;5;3m<<diff header::Modified regular file file1:>>[39m
+    [38;5;5m<<diff removed moved line_number::   1>>[39m<<diff::     : >>[4m[38;5;5m<<diff removed moved token::fn first() {>>[24m[39m
+    [38;5;5m<<diff removed moved line_number::   2>>[39m<<diff::     : >>[4m[38;5;5m<<diff removed moved token::    println!("first function");>>[24m[39m
+    [38;5;5m<<diff removed moved line_number::   3>>[39m<<diff::     : >>[4m[38;5;5m<<diff removed moved token::}>>[24m[39m
+    [38;5;1m<<diff removed line_number::   4>>[39m<<diff::     : >>[4m[38;5;1m<<diff removed token::>>[24m[39m
+    [38;5;1m<<diff removed line_number::   5>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   1>>[39m<<diff::: fn second() {>>
+    [38;5;1m<<diff removed line_number::   6>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   2>>[39m<<diff:::     println!("second function");>>
+    [38;5;1m<<diff removed line_number::   7>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   3>>[39m<<diff::: }>>
+    [38;5;1m<<diff removed line_number::   8>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   4>>[39m<<diff::: }>>
+    [38;5;3m<<diff header::Modified regular file file2:>>[39m
+    [38;5;1m<<diff removed line_number::   1>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   1>>[39m<<diff::: a>>
+    <<diff::     >>[38;5;6m<<diff added moved line_number::   2>>[39m<<diff::: >>[4m[38;5;6m<<diff added moved token::fn first() {>>[24m[39m
+    <<diff::     >>[38;5;6m<<diff added moved line_number::   3>>[39m<<diff::: >>[4m[38;5;6m<<diff added moved token::    println!("first function");>>[24m[39m
+    <<diff::     >>[38;5;6m<<diff added moved line_number::   4>>[39m<<diff::: >>[4m[38;5;6m<<diff added moved token::}>>[24m[39m
+    <<diff::     >>[38;5;2m<<diff added line_number::   5>>[39m<<diff::: >>[4m[38;5;2m<<diff added token::b>>[24m[39m
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj([
+        "diff",
+        "--git",
+        "--color=debug",
+        "--config=diff.git.color-moved=true",
+    ]);
+    insta::assert_snapshot!(output, @r#"
+    [1m<<diff file_header::diff --git a/file1 b/file1>>[0m
+    [1m<<diff file_header::index 80b1f9787e..e4be826b66 100644>>[0m
+    [1m<<diff file_header::--- a/file1>>[0m
+    [1m<<diff file_header::+++ b/file1>>[0m
+    [38;5;6m<<diff hunk_header::@@ -1,7 +1,3 @@>>[39m
+    [38;5;5m<<diff removed moved::->>[4m<<diff removed moved token::fn first() {>>[24m[39m
+    [38;5;5m<<diff removed moved::->>[4m<<diff removed moved token::    println!("first function");>>[24m[39m
+    [38;5;5m<<diff removed moved::->>[4m<<diff removed moved token::}>>[24m[39m
+    [38;5;1m<<diff removed::->>[4m<<diff removed token::>>[24m[39m
+    <<diff context:: fn second() {>>
+    <<diff context::     println!("second function");>>
+    <<diff context:: }>>
+    [1m<<diff file_header::diff --git a/file2 b/file2>>[0m
+    [1m<<diff file_header::index 7898192261..29b598a963 100644>>[0m
+    [1m<<diff file_header::--- a/file2>>[0m
+    [1m<<diff file_header::+++ b/file2>>[0m
+    [38;5;6m<<diff hunk_header::@@ -1,1 +1,5 @@>>[39m
+    <<diff context:: a>>
+    [38;5;6m<<diff added moved::+>>[4m<<diff added moved token::fn first() {>>[24m[39m
+    [38;5;6m<<diff added moved::+>>[4m<<diff added moved token::    println!("first function");>>[24m[39m
+    [38;5;6m<<diff added moved::+>>[4m<<diff added moved token::}>>[24m[39m
+    [38;5;2m<<diff added::+>>[4m<<diff added token::b>>[24m[39m
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["diff", "--stat"]);
+    insta::assert_snapshot!(output, @r"
+    file1 | 4 ----
+    file2 | 4 ++++
+    2 files changed, 4 insertions(+), 4 deletions(-)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--stat", "--ignore-moved"]);
+    insta::assert_snapshot!(output, @r"
+    file1 | 1 -
+    file2 | 1 +
+    2 files changed, 1 insertion(+), 1 deletion(-)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--git", "--ignore-moved"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: --ignore-moved can only be used with --stat
+    [EOF]
+    [exit status: 2]
+    ");
+
+    // Moved lines can be labeled by command-line flag
+    let output = work_dir.run_jj(["diff", "--git", "--color=debug", "--color-moved"]);
+    insta::assert_snapshot!(output, @r#"
+    [1m<<diff file_header::diff --git a/file1 b/file1>>[0m
+    [1m<<diff file_header::index 80b1f9787e..e4be826b66 100644>>[0m
+    [1m<<diff file_header::--- a/file1>>[0m
+    [1m<<diff file_header::+++ b/file1>>[0m
+    [38;5;6m<<diff hunk_header::@@ -1,7 +1,3 @@>>[39m
+    [38;5;5m<<diff removed moved::->>[4m<<diff removed moved token::fn first() {>>[24m[39m
+    [38;5;5m<<diff removed moved::->>[4m<<diff removed moved token::    println!("first function");>>[24m[39m
+    [38;5;5m<<diff removed moved::->>[4m<<diff removed moved token::}>>[24m[39m
+    [38;5;1m<<diff removed::->>[4m<<diff removed token::>>[24m[39m
+    <<diff context:: fn second() {>>
+    <<diff context::     println!("second function");>>
+    <<diff context:: }>>
+    [1m<<diff file_header::diff --git a/file2 b/file2>>[0m
+    [1m<<diff file_header::index 7898192261..29b598a963 100644>>[0m
+    [1m<<diff file_header::--- a/file2>>[0m
+    [1m<<diff file_header::+++ b/file2>>[0m
+    [38;5;6m<<diff hunk_header::@@ -1,1 +1,5 @@>>[39m
+    <<diff context:: a>>
+    [38;5;6m<<diff added moved::+>>[4m<<diff added moved token::fn first() {>>[24m[39m
+    [38;5;6m<<diff added moved::+>>[4m<<diff added moved token::    println!("first function");>>[24m[39m
+    [38;5;6m<<diff added moved::+>>[4m<<diff added moved token::}>>[24m[39m
+    [38;5;2m<<diff added::+>>[4m<<diff added token::b>>[24m[39m
+    [EOF]
+    "#);
+    let output = work_dir.run_jj(["diff", "--stat", "--color-moved"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: --color-moved can only be used with --git or --color-words
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
+#[test]
+fn test_diff_syntax_highlighting() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file.rs", "// one\nlet x = 1;\n");
+    work_dir.write_file("file.txt", "let x = 1;\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file.rs", "// one\nlet x = \"two\";\n");
+    work_dir.write_file("file.txt", "let x = 2;\n");
+
+    // Disabled by default unless output is a terminal
+    let output = work_dir.run_jj(["diff", "--color=debug", "file.rs"]);
+    insta::assert_snapshot!(output, @r#"
+    [38;5;3m<<diff header::Modified regular file file.rs:>>[39m
+    [38;5;1m<<diff removed line_number::   1>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   1>>[39m<<diff::: // one>>
+    [38;5;1m<<diff removed line_number::   2>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   2>>[39m<<diff::: let x = >>[4m[38;5;1m<<diff removed token::1>>[38;5;2m<<diff added token::"two">>[24m[39m<<diff::;>>
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj([
+        "diff",
+        "--color=debug",
+        "--config=ui.syntax-highlighting=always",
+    ]);
+    insta::assert_snapshot!(output, @r#"
+    [38;5;3m<<diff header::Modified regular file file.rs:>>[39m
+    [38;5;1m<<diff removed line_number::   1>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   1>>[39m<<diff::: >>[38;5;8m<<diff syntax comment::// one>>[39m<<diff::>>
+    [38;5;1m<<diff removed line_number::   2>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   2>>[39m<<diff::: >>[38;5;5m<<diff syntax keyword::let>>[39m<<diff:: x = >>[4m[38;5;1m<<diff syntax number removed token::1>>[38;5;2m<<diff syntax string added token::"two">>[24m[39m<<diff::;>>
+    [38;5;3m<<diff header::Modified regular file file.txt:>>[39m
+    [38;5;1m<<diff removed line_number::   1>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   1>>[39m<<diff::: let x = >>[4m[38;5;1m<<diff removed token::1>>[38;5;2m<<diff added token::2>>[24m[39m<<diff::;>>
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj([
+        "diff",
+        "--git",
+        "--color=debug",
+        "--config=ui.syntax-highlighting=always",
+        "file.rs",
+    ]);
+    insta::assert_snapshot!(output, @r#"
+    [1m<<diff file_header::diff --git a/file.rs b/file.rs>>[0m
+    [1m<<diff file_header::index a76f4fafa2..7940f9fd85 100644>>[0m
+    [1m<<diff file_header::--- a/file.rs>>[0m
+    [1m<<diff file_header::+++ b/file.rs>>[0m
+    [38;5;6m<<diff hunk_header::@@ -1,2 +1,2 @@>>[39m
+    <<diff context:: >>[38;5;8m<<diff syntax comment context::// one>>[39m<<diff context::>>
+    [38;5;1m<<diff removed::->><<diff syntax keyword removed::let>><<diff removed:: x = >>[4m<<diff syntax number removed token::1>>[24m<<diff removed::;>>[39m
+    [38;5;2m<<diff added::+>><<diff syntax keyword added::let>><<diff added:: x = >>[4m<<diff syntax string added token::"two">>[24m<<diff added::;>>[39m
+    [EOF]
+    "#);
+
+    // Not highlighted without color
+    let output = work_dir.run_jj([
+        "diff",
+        "--git",
+        "--config=ui.syntax-highlighting=always",
+        "file.rs",
+    ]);
+    insta::assert_snapshot!(output, @r#"
+    diff --git a/file.rs b/file.rs
+    index a76f4fafa2..7940f9fd85 100644
+    --- a/file.rs
+    +++ b/file.rs
+    @@ -1,2 +1,2 @@
+     // one
+    -let x = 1;
+    +let x = "two";
+    [EOF]
+    "#);
+
+    // Block comments opened before the hunk are carried into it
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("block.rs", "/*\na\nb\nc\nd\nlet x = 1;\n*/\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("block.rs", "/*\na\nb\nc\nd\nlet x = 2;\n*/\n");
+    let output = work_dir.run_jj([
+        "diff",
+        "--git",
+        "--color=debug",
+        "--config=ui.syntax-highlighting=always",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    [1m<<diff file_header::diff --git a/block.rs b/block.rs>>[0m
+    [1m<<diff file_header::index bd6e25b78e..ce06cec5f9 100644>>[0m
+    [1m<<diff file_header::--- a/block.rs>>[0m
+    [1m<<diff file_header::+++ b/block.rs>>[0m
+    [38;5;6m<<diff hunk_header::@@ -3,5 +3,5 @@>>[39m
+    <<diff context:: >>[38;5;8m<<diff syntax comment context::b>>[39m<<diff context::>>
+    <<diff context:: >>[38;5;8m<<diff syntax comment context::c>>[39m<<diff context::>>
+    <<diff context:: >>[38;5;8m<<diff syntax comment context::d>>[39m<<diff context::>>
+    [38;5;1m<<diff removed::->><<diff syntax comment removed::let x = >>[4m<<diff syntax comment removed token::1>>[24m<<diff syntax comment removed::;>><<diff removed::>>[39m
+    [38;5;2m<<diff added::+>><<diff syntax comment added::let x = >>[4m<<diff syntax comment added token::2>>[24m<<diff syntax comment added::;>><<diff added::>>[39m
+    <<diff context:: >>[38;5;8m<<diff syntax comment context::*/>>[39m<<diff context::>>
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "diff",
+        "--color=debug",
+        "--config=ui.syntax-highlighting=always",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    [38;5;3m<<diff header::Modified regular file block.rs:>>[39m
+    <<diff::    ...>>
+    [38;5;1m<<diff removed line_number::   3>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   3>>[39m<<diff::: >>[38;5;8m<<diff syntax comment::b>>[39m<<diff::>>
+    [38;5;1m<<diff removed line_number::   4>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   4>>[39m<<diff::: >>[38;5;8m<<diff syntax comment::c>>[39m<<diff::>>
+    [38;5;1m<<diff removed line_number::   5>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   5>>[39m<<diff::: >>[38;5;8m<<diff syntax comment::d>>[39m<<diff::>>
+    [38;5;1m<<diff removed line_number::   6>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   6>>[39m<<diff::: >>[38;5;8m<<diff syntax comment::let x = >>[4m[38;5;1m<<diff syntax comment removed token::1>>[38;5;2m<<diff syntax comment added token::2>>[24m[38;5;8m<<diff syntax comment::;>>[39m<<diff::>>
+    [38;5;1m<<diff removed line_number::   7>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   7>>[39m<<diff::: >>[38;5;8m<<diff syntax comment::*/>>[39m<<diff::>>
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_diff_ignore_whitespace() {
     let test_env = TestEnvironment::default();
@@ -1971,6 +2409,74 @@ fn test_diff_ignore_whitespace() {
     ");
 }
 
+#[test]
+fn test_diff_algorithm() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file", "a\na\nb\nx\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file", "b\na\na\nx\n");
+
+    // The default algorithm anchors on the unique line "b"
+    let output = work_dir.run_jj(["diff", "--git"]);
+    insta::assert_snapshot!(output, @r"
+    diff --git a/file b/file
+    index 79647c5f6f..b6660ea397 100644
+    --- a/file
+    +++ b/file
+    @@ -1,4 +1,4 @@
+    -a
+    -a
+     b
+    +a
+    +a
+     x
+    [EOF]
+    ");
+
+    // The minimal diff keeps the lines "a"
+    let output = work_dir.run_jj(["diff", "--git", "--diff-algorithm=minimal"]);
+    insta::assert_snapshot!(output, @r"
+    diff --git a/file b/file
+    index 79647c5f6f..b6660ea397 100644
+    --- a/file
+    +++ b/file
+    @@ -1,4 +1,4 @@
+    +b
+     a
+     a
+    -b
+     x
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--stat", "--config=diff.algorithm=minimal"]);
+    insta::assert_snapshot!(output, @r"
+    file | 2 +-
+    1 file changed, 1 insertion(+), 1 deletion(-)
+    [EOF]
+    ");
+
+    // The command-line flag takes precedence over the config
+    let output = work_dir.run_jj([
+        "diff",
+        "--color-words",
+        "--config=diff.algorithm=minimal",
+        "--diff-algorithm=default",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    Modified regular file file:
+       1     : a
+       2     : a
+       3    1: b
+            2: a
+            3: a
+       4    4: x
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_diff_skipped_context() {
     let test_env = TestEnvironment::default();
@@ -212,6 +212,57 @@ fn test_annotate_abandoned() {
     ");
 }
 
+#[test]
+fn test_annotate_with_diff_algorithm() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file.txt", "a\na\nb\nx\n");
+    work_dir.run_jj(["describe", "-m=initial"]).success();
+    work_dir.run_jj(["new", "-m=next"]).success();
+    work_dir.write_file("file.txt", "b\na\na\nx\n");
+
+    let output = work_dir.run_jj(["file", "annotate", "file.txt"]);
+    insta::assert_snapshot!(output, @r"
+    qpvuntsm test.use 2001-02-03 08:05:08    1: b
+    kkmpptxz test.use 2001-02-03 08:05:10    2: a
+    kkmpptxz test.use 2001-02-03 08:05:10    3: a
+    qpvuntsm test.use 2001-02-03 08:05:08    4: x
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "file",
+        "annotate",
+        "file.txt",
+        "--config=diff.algorithm=minimal",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    kkmpptxz test.use 2001-02-03 08:05:10    1: b
+    qpvuntsm test.use 2001-02-03 08:05:08    2: a
+    qpvuntsm test.use 2001-02-03 08:05:08    3: a
+    qpvuntsm test.use 2001-02-03 08:05:08    4: x
+    [EOF]
+    ");
+
+    // The flag overrides the config
+    let output = work_dir.run_jj([
+        "file",
+        "annotate",
+        "file.txt",
+        "--config=diff.algorithm=minimal",
+        "--diff-algorithm=default",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    qpvuntsm test.use 2001-02-03 08:05:08    1: b
+    kkmpptxz test.use 2001-02-03 08:05:10    2: a
+    kkmpptxz test.use 2001-02-03 08:05:10    3: a
+    qpvuntsm test.use 2001-02-03 08:05:08    4: x
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_annotate_with_template() {
     let test_env = TestEnvironment::default();
@@ -116,6 +116,44 @@ fn test_show() {
     ");
 }
 
+#[test]
+fn test_show_syntax_highlighting() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file.py", "def f():\n    return 'a'  # comment\n");
+    work_dir.write_file("file.txt", "def f():\n");
+
+    let output = work_dir.run_jj([
+        "file",
+        "show",
+        "--color=debug",
+        "--config=ui.syntax-highlighting=always",
+        "file.py",
+        "file.txt",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    [38;5;5m<<syntax keyword::def>>[39m f():
+        [38;5;5m<<syntax keyword::return>>[39m [38;5;3m<<syntax string::'a'>>[39m  [38;5;8m<<syntax comment::# comment>>[39m
+    def f():
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "file",
+        "show",
+        "--color=debug",
+        "--config=ui.syntax-highlighting=never",
+        "file.py",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    def f():
+        return 'a'  # comment
+    [EOF]
+    ");
+}
+
 #[cfg(unix)]
 #[test]
 fn test_show_symlink() {
@@ -2047,3 +2047,95 @@ fn test_git_fetch_tracked_multiple_remotes() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_git_fetch_obsolescence_markers() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.exchange-obsolescence = true");
+    git::init_bare(test_env.env_root().join("origin"));
+    test_env
+        .run_jj_in(".", ["git", "clone", "origin", "alice"])
+        .success();
+    let alice_dir = test_env.work_dir("alice");
+    let template = r#"separate(" ", change_id.short(), description.first_line(), bookmarks) ++ "
+""#;
+
+    alice_dir.run_jj(["commit", "-m", "feature v1"]).success();
+    alice_dir
+        .run_jj(["bookmark", "create", "-r@-", "feature"])
+        .success();
+    alice_dir.run_jj(["git", "push", "--allow-new"]).success();
+
+    // Bob builds on top of the fetched commit
+    test_env
+        .run_jj_in(".", ["git", "clone", "origin", "bob"])
+        .success();
+    let bob_dir = test_env.work_dir("bob");
+    bob_dir
+        .run_jj(["bookmark", "track", "feature@origin"])
+        .success();
+    bob_dir
+        .run_jj(["new", "feature", "-m", "bob's work"])
+        .success();
+
+    // Alice rewrites the pushed commit, and the marker is pushed along with the
+    // bookmark
+    alice_dir
+        .run_jj(["describe", "feature", "-m", "feature v2"])
+        .success();
+    let output = alice_dir.run_jj(["git", "push"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Move sideways bookmark feature from fe661bdf7bed to aef4bb18b7a0
+    [EOF]
+    ");
+    let origin_repo = git::open(test_env.env_root().join("origin"));
+    let marker_names = origin_repo
+        .references()
+        .unwrap()
+        .prefixed("refs/jj/obsolete/")
+        .unwrap()
+        .map(|r| r.unwrap().name().as_bstr().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(marker_names.len(), 1);
+
+    // Bob's work is rebased onto the new version, and the old version is hidden
+    let output = bob_dir.run_jj(["git", "fetch"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    bookmark: feature@origin [updated] tracked
+    Replaced 1 commits with the versions rewritten on the remote.
+    Rebased 1 descendant commits
+    Working copy  (@) now at: yqosqzyt 2d2106cb (empty) bob's work
+    Parent commit (@-)      : qpvuntsm aef4bb18 feature | (empty) feature v2
+    [EOF]
+    ");
+    let output = bob_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    @  yqosqzytrlsw bob's work
+    ○  qpvuntsmwlqt feature v2 feature
+    ◆  zzzzzzzzzzzz
+    [EOF]
+    ");
+    let output = bob_dir.run_jj([
+        "evolog",
+        "-r",
+        "feature",
+        "-T",
+        r#"commit.description().first_line() ++ "\n""#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ○  feature v2
+    ○  feature v1
+    [EOF]
+    ");
+
+    // Nothing changes when fetching the same markers again
+    let output = bob_dir.run_jj(["git", "fetch"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+}
@@ -626,6 +626,90 @@ fn test_quiet() {
     insta::assert_snapshot!(output, @"");
 }
 
+#[test]
+fn test_output_format() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["describe", "-m=first"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "main"])
+        .success();
+    work_dir.run_jj(["new", "-m=second"]).success();
+    work_dir.write_file("file", "contents");
+
+    let output = work_dir.run_jj(["log", "-r@-", "--output=json"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"items":[{"commit_id":"68a505386f936fff6d718f55005e77ea72589bc1","parents":["0000000000000000000000000000000000000000"],"change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","description":"first\n","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"}}]}
+    [EOF]
+    "#);
+    let output = work_dir.run_jj(["log", "-r::@", "--output=jsonl", "--reversed"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"item":{"commit_id":"0000000000000000000000000000000000000000","parents":[],"change_id":"zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz","description":"","author":{"name":"","email":"","timestamp":"1970-01-01T00:00:00Z"},"committer":{"name":"","email":"","timestamp":"1970-01-01T00:00:00Z"}}}
+    {"version":1,"item":{"commit_id":"68a505386f936fff6d718f55005e77ea72589bc1","parents":["0000000000000000000000000000000000000000"],"change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","description":"first\n","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"}}}
+    {"version":1,"item":{"commit_id":"2a1c5cf316283009b46927ecd4070b748f4568f4","parents":["68a505386f936fff6d718f55005e77ea72589bc1"],"change_id":"zsuskulnrvyrovkzqrwmxqlsskqntxvp","description":"second\n","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:10+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:11+07:00"}}}
+    [EOF]
+    "#);
+    let output = work_dir.run_jj(["op", "log", "--limit=1", "--output=jsonl"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"item":{"id":"419c7fd724d9cb1a10da0011e753e3dfa8a1bdc1e31b75ca8c4f0b4b42afe78ad4da98a88ed8295b5474ade827e3c2f50d3d5bd870fbce35ffb2532645b8139d","parents":["e06f4d6073e53672a31b3c46bbb1457477e09f004283411f309a196909019a9b04bad5d8b65f37de3fea92e166a1c104f90b656441e052c1a6eb899e6bb23a07"],"time":{"start":"2001-02-03T04:05:11+07:00","end":"2001-02-03T04:05:11+07:00"},"description":"snapshot working copy","hostname":"host.example.com","username":"test-username","is_snapshot":true,"tags":{"args":"jj log -r@- '--output=json'"}}}
+    [EOF]
+    "#);
+    let output = work_dir.run_jj(["bookmark", "list", "--output=jsonl"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"item":{"name":"main","target":["68a505386f936fff6d718f55005e77ea72589bc1"]}}
+    [EOF]
+    "#);
+    let output = work_dir.run_jj(["workspace", "list", "--output=jsonl"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"item":{"name":"default","target":{"commit_id":"2a1c5cf316283009b46927ecd4070b748f4568f4","parents":["68a505386f936fff6d718f55005e77ea72589bc1"],"change_id":"zsuskulnrvyrovkzqrwmxqlsskqntxvp","description":"second\n","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:10+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:11+07:00"}}}}
+    [EOF]
+    "#);
+    let output = work_dir.run_jj(["status", "--output=json"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"items":[{"working_copy":{"commit_id":"2a1c5cf316283009b46927ecd4070b748f4568f4","parents":["68a505386f936fff6d718f55005e77ea72589bc1"],"change_id":"zsuskulnrvyrovkzqrwmxqlsskqntxvp","description":"second\n","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:10+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:11+07:00"}},"parents":[{"commit_id":"68a505386f936fff6d718f55005e77ea72589bc1","parents":["0000000000000000000000000000000000000000"],"change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","description":"first\n","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"}}],"changes":[{"path":"file","status":"added","source":null,"target":{"path":"file","conflict":false,"file_type":"file","executable":false}}],"untracked_paths":[]}]}
+    [EOF]
+    "#);
+
+    // Empty list
+    let output = work_dir.run_jj(["tag", "list", "--output=json"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"items":[]}
+    [EOF]
+    "#);
+
+    // Text options can't be combined with serialized output
+    let output = work_dir.run_jj(["log", "--output=json", "-T=commit_id"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: --template cannot be used with --output json
+    [EOF]
+    [exit status: 2]
+    ");
+    let output = work_dir.run_jj(["log", "--output=jsonl", "--patch"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: --patch cannot be used with --output jsonl
+    [EOF]
+    [exit status: 2]
+    ");
+
+    // Commands which don't list items don't accept --output
+    let output = work_dir.run_jj(["diff", "--output=json"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: unexpected argument '--output' found
+
+      tip: to pass '--output' as a value, use '-- --output'
+
+    Usage: jj diff [OPTIONS] [FILESETS]...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_early_args() {
     // Test that help output parses early args
@@ -1059,6 +1143,8 @@ fn test_help() {
           --ignore-working-copy          Don't snapshot the working copy, and don't update it
           --ignore-immutable             Allow rewriting immutable commits
           --at-operation <AT_OPERATION>  Operation to load the repo at [aliases: --at-op]
+          --op-tag <KEY=VALUE>           Add a tag to the operation created by this command (can be
+                                         repeated)
           --debug                        Enable debug logging
           --color <WHEN>                 When to colorize output [possible values: always, never, debug,
                                          auto]
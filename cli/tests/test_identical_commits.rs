@@ -67,6 +67,8 @@ fn test_identical_commits_concurrently() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation f184243937e9: describe commit 053222c21fa06b9492e22346f8f70e732231ad4f
+    Merging operation 4447ea62eb0c: describe commit 053222c21fa06b9492e22346f8f70e732231ad4f
     [EOF]
     ");
 }
@@ -195,3 +195,76 @@ fn test_interdiff_conflicting() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_interdiff_since_op() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-mtrunk"]).success();
+    work_dir.write_file("trunk", "trunk\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "trunk"])
+        .success();
+    work_dir.run_jj(["new", "-ma"]).success();
+    work_dir.write_file("a", "a\n");
+    work_dir.run_jj(["new", "-mb"]).success();
+    work_dir.write_file("b", "b\n");
+    work_dir.run_jj(["debug", "snapshot"]).success();
+    let setup_opid = work_dir.current_operation_id();
+
+    // Nothing changed since the operation
+    let output = work_dir.run_jj(["interdiff", "--since-op", &setup_opid, "-r", "trunk+::"]);
+    insta::assert_snapshot!(output, @"");
+
+    // Move trunk, rebase the stack, then update "b" and add "c"
+    work_dir.run_jj(["new", "trunk", "-mtrunk2"]).success();
+    work_dir.write_file("trunk", "trunk\ntrunk2\n");
+    work_dir
+        .run_jj(["bookmark", "set", "-r@", "trunk"])
+        .success();
+    work_dir
+        .run_jj(["rebase", "-s", "description(a)", "-d", "trunk"])
+        .success();
+    work_dir.run_jj(["edit", "description(b)"]).success();
+    work_dir.write_file("b", "b\nb2\n");
+    work_dir.run_jj(["new", "-mc"]).success();
+    work_dir.write_file("c", "c\n");
+
+    let output = work_dir.run_jj(["interdiff", "--since-op", &setup_opid, "-r", "trunk+::"]);
+    insta::assert_snapshot!(output, @r"
+    New revision kmkuslsw c387d56c c
+    Added regular file c:
+            1: c
+    Changes to mzvwutvl b142acb5 b
+    Modified regular file b:
+       1    1: b
+            2: b2
+    [EOF]
+    ------- stderr -------
+    Omitted 1 revision that was only rebased.
+    [EOF]
+    ");
+
+    // Default to the working-copy revision
+    let output = work_dir.run_jj(["interdiff", "--since-op", &setup_opid, "-s"]);
+    insta::assert_snapshot!(output, @r"
+    New revision kmkuslsw c387d56c c
+    A c
+    [EOF]
+    ");
+
+    // --revisions can only be used with --since-op
+    let output = work_dir.run_jj(["interdiff", "--from", "@", "-r", "@"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the argument '--from <REVSET>' cannot be used with '--revisions <REVSETS>'
+
+    Usage: jj interdiff <--from <REVSET>|--to <REVSET>|--since-op <OPERATION>> [FILESETS]...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
@@ -815,6 +815,10 @@ fn test_log_divergence() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation fec5a045b947: describe commit d0c049cd993a8d3a2e69ba6df98788e264ea9fa1
+    Merging operation f78801e95500: describe commit d0c049cd993a8d3a2e69ba6df98788e264ea9fa1
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation fec5a045b947
     [EOF]
     ");
 }
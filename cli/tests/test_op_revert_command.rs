@@ -47,6 +47,10 @@ fn test_revert_merge_operation() {
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation dbcb2561b6fe: new empty commit
+    Merging operation b6c93bcb296f: new empty commit
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation dbcb2561b6fe
     Error: Cannot revert a merge operation
     [EOF]
     [exit status: 1]
@@ -233,6 +233,248 @@ fn test_op_log_limit() {
     ");
 }
 
+#[test]
+fn test_op_log_opset() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["commit", "-m", "commit 1"]).success();
+    work_dir.write_file("file", "contents");
+    work_dir.run_jj(["describe", "-m", "commit 2"]).success();
+    work_dir.run_jj(["new"]).success();
+    let template = r#"id.short() ++ " " ++ description ++ "\n""#;
+
+    let output = work_dir.run_jj(["op", "log", "-T", template, "-r", "snapshots()"]);
+    insta::assert_snapshot!(output, @r"
+    ○  411f1beb7c66 snapshot working copy
+    [EOF]
+    ");
+
+    // Edges are drawn to the nearest ancestors in the set.
+    let output = work_dir.run_jj([
+        "op",
+        "log",
+        "-T",
+        template,
+        "-r",
+        r#"description(glob:"commit*") | description(exact:"new empty commit")"#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    @  77fc56e904b3 new empty commit
+    ○  c815486340d5 commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "log", "-T", template, "-r", "@---..@-", "--no-graph"]);
+    insta::assert_snapshot!(output, @r"
+    011040ee9d1b describe commit 729bf34644ecb0ca96f8431bd6a497a0aacc703b
+    411f1beb7c66 snapshot working copy
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "log", "-T", template, "-r", "latest(~snapshots(), 2)"]);
+    insta::assert_snapshot!(output, @r"
+    @  77fc56e904b3 new empty commit
+    ○  011040ee9d1b describe commit 729bf34644ecb0ca96f8431bd6a497a0aacc703b
+    [EOF]
+    ");
+
+    // The opset can also be used to specify a single operation.
+    let output = work_dir.run_jj([
+        "op",
+        "show",
+        "--no-op-diff",
+        "-T",
+        template,
+        r#"description("snapshot")+"#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    011040ee9d1b describe commit 729bf34644ecb0ca96f8431bd6a497a0aacc703b
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "log", "-r", "snapshot()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse opset: Function `snapshot` doesn't exist
+    Caused by:  --> 1:1
+      |
+    1 | snapshot()
+      | ^------^
+      |
+      = Function `snapshot` doesn't exist
+    Hint: Did you mean `snapshots`?
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["op", "log", "-r", "@-|"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse opset: Syntax error
+    Caused by:  --> 1:4
+      |
+    1 | @-|
+      |    ^---
+      |
+      = expected `..`, `..`, `~`, or <primary>
+    Hint: See https://jj-vcs.github.io/jj/latest/operation-log/#operation-sets for opsets syntax.
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["op", "show", "description(commit)"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: The "description(commit)" expression resolved to more than one operation
+    Hint: Try specifying one of the operations by ID: 77fc56e904b3, 011040ee9d1b, c815486340d5
+    [EOF]
+    [exit status: 1]
+    "#);
+}
+
+#[test]
+fn test_op_at_time() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["commit", "-m", "commit 1"]).success();
+    work_dir.run_jj(["commit", "-m", "commit 2"]).success();
+    let template = r#"id.short() ++ " " ++ time.end() ++ "\n""#;
+    let output = work_dir.run_jj(["op", "log", "-T", template, "--no-graph"]);
+    insta::assert_snapshot!(output, @r"
+    3fc56f6bb4db 2001-02-03 04:05:09.000 +07:00
+    c815486340d5 2001-02-03 04:05:08.000 +07:00
+    8f47435a3990 2001-02-03 04:05:07.000 +07:00
+    000000000000 1970-01-01 00:00:00.000 +00:00
+    [EOF]
+    ");
+
+    // The latest operation at or before the given time is selected.
+    let output = work_dir.run_jj([
+        "--at-op",
+        r#"at:"2001-02-03T04:05:08+07:00""#,
+        "log",
+        "-T",
+        "description",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    @
+    ○  commit 1
+    ◆
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "op",
+        "show",
+        "--no-op-diff",
+        "-T",
+        template,
+        r#"at:"2001-02-03T04:05:09.500+07:00""#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    3fc56f6bb4db 2001-02-03 04:05:09.000 +07:00
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "show", r#"at:"2000-01-01""#]);
+    insta::assert_snapshot!(output, @r"
+    000000000000 root()
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "show", r#"at:"not a date""#]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Failed to parse opset: Invalid date
+    Caused by:
+    1:  --> 1:1
+      |
+    1 | at:"not a date"
+      | ^-------------^
+      |
+      = Invalid date
+    2: expected unsupported identifier as position 0..3
+    [EOF]
+    [exit status: 1]
+    "#);
+
+    // Relative dates are resolved against the configured timestamp, so the
+    // same operation is selected by --at-op and op show
+    let output = work_dir.run_jj([
+        "--at-op",
+        r#"at:"6 seconds ago""#,
+        "op",
+        "show",
+        "--no-op-diff",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    3fc56f6bb4db 2001-02-03 04:05:09.000 +07:00
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "op",
+        "show",
+        "--no-op-diff",
+        "-T",
+        template,
+        r#"at:"6 seconds ago""#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    3fc56f6bb4db 2001-02-03 04:05:09.000 +07:00
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "show", "--no-op-diff", "-T", template, "@"]);
+    insta::assert_snapshot!(output, @r"
+    3fc56f6bb4db 2001-02-03 04:05:09.000 +07:00
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "restore", "--to-time", "9 seconds ago"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Restored to operation: 3fc56f6bb4db (2001-02-03 08:05:09) commit 4e0592f3dd52e7a4998a97d9a1f354e2727a856b
+    Nothing changed.
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "restore", "--to-time", "2001-02-03T04:05:08+07:00"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Restored to operation: c815486340d5 (2001-02-03 08:05:08) commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    Working copy  (@) now at: rlvkpnrz 4e0592f3 (empty) (no description set)
+    Parent commit (@-)      : qpvuntsm 4de4efb4 (empty) commit 1
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-T", "description"]);
+    insta::assert_snapshot!(output, @r"
+    @
+    ○  commit 1
+    ◆
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "restore", "--to-time", "2000-01-01"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Restored to operation: 000000000000 root()
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "restore"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the following required arguments were not provided:
+      <OPERATION>
+
+    Usage: jj operation restore <OPERATION>
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_op_log_no_graph() {
     let test_env = TestEnvironment::default();
@@ -289,7 +531,7 @@ fn test_op_log_reversed() {
 
     // Should be able to display log with fork and branch points
     let output = work_dir.run_jj(["op", "log", "--reversed"]);
-    insta::assert_snapshot!(output, @r"
+    insta::assert_snapshot!(output, @r###"
     ○  000000000000 root()
     ○    8f47435a3990 test-username@host.example.com 2001-02-03 04:05:07.000 +07:00 - 2001-02-03 04:05:07.000 +07:00
     ├─╮  add workspace 'default'
@@ -299,14 +541,20 @@ fn test_op_log_reversed() {
     ○ │  12f7cbba4278 test-username@host.example.com 2001-02-03 04:05:08.000 +07:00 - 2001-02-03 04:05:08.000 +07:00
     ├─╯  describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
     │    args: jj describe -m 'description 0'
-    @  fa6e12f12705 test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
+    @  63d8f69a6c7f test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
        reconcile divergent operations
+       merged from 12f7cbba4278, 39f59ea3ec6e
        args: jj op log --reversed
+       conflicted-workspaces: default
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 12f7cbba4278: describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    Merging operation 39f59ea3ec6e: describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation 12f7cbba4278
     [EOF]
-    ");
+    "###);
 
     // Should work correctly with `--no-graph`
     let output = work_dir.run_jj(["op", "log", "--reversed", "--no-graph"]);
@@ -320,9 +568,11 @@ fn test_op_log_reversed() {
     12f7cbba4278 test-username@host.example.com 2001-02-03 04:05:08.000 +07:00 - 2001-02-03 04:05:08.000 +07:00
     describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
     args: jj describe -m 'description 0'
-    fa6e12f12705 test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
+    63d8f69a6c7f test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
     reconcile divergent operations
+    merged from 12f7cbba4278, 39f59ea3ec6e
     args: jj op log --reversed
+    conflicted-workspaces: default
     [EOF]
     ");
 
@@ -335,9 +585,11 @@ fn test_op_log_reversed() {
     │ ○  12f7cbba4278 test-username@host.example.com 2001-02-03 04:05:08.000 +07:00 - 2001-02-03 04:05:08.000 +07:00
     ├─╯  describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
     │    args: jj describe -m 'description 0'
-    @  fa6e12f12705 test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
+    @  63d8f69a6c7f test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
        reconcile divergent operations
+       merged from 12f7cbba4278, 39f59ea3ec6e
        args: jj op log --reversed
+       conflicted-workspaces: default
     [EOF]
     ");
 
@@ -347,9 +599,11 @@ fn test_op_log_reversed() {
     12f7cbba4278 test-username@host.example.com 2001-02-03 04:05:08.000 +07:00 - 2001-02-03 04:05:08.000 +07:00
     describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
     args: jj describe -m 'description 0'
-    fa6e12f12705 test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
+    63d8f69a6c7f test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
     reconcile divergent operations
+    merged from 12f7cbba4278, 39f59ea3ec6e
     args: jj op log --reversed
+    conflicted-workspaces: default
     [EOF]
     ");
 }
@@ -768,6 +1022,376 @@ fn test_op_abandon_ancestors() {
     ");
 }
 
+#[test]
+fn test_op_abandon_opset() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "");
+    work_dir.run_jj(["commit", "-m", "commit 1"]).success();
+    work_dir.write_file("file2", "");
+    work_dir.run_jj(["commit", "-m", "commit 2"]).success();
+    work_dir.write_file("file3", "");
+    work_dir.run_jj(["commit", "-m", "commit 3"]).success();
+    let template = r#"id.short() ++ " " ++ description ++ "\n""#;
+    insta::assert_snapshot!(work_dir.run_jj(["op", "log", "-T", template]), @r"
+    @  1002562c7895 commit 5c5f4a73bb275d769786cb2115746923c4c2dacc
+    ○  a0a84246fb49 snapshot working copy
+    ○  91b69d8ec921 commit 4c75edc67bcd26ab3532193cf874228f415cdcc5
+    ○  923d1ce23163 snapshot working copy
+    ○  c63c841e8622 commit 093c3c9624b6cfe22b310586f5638792aa80e6d7
+    ○  be2519a73d90 snapshot working copy
+    ○  8f47435a3990 add workspace 'default'
+    ○  000000000000
+    [EOF]
+    ");
+
+    // Abandon snapshot operations except for the latest one.
+    let output = work_dir.run_jj(["op", "abandon", "snapshots() ~ latest(snapshots())"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Abandoned 2 operations and reparented 4 descendant operations.
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["op", "log", "-T", template]), @r"
+    @  66eb341cdef4 commit 5c5f4a73bb275d769786cb2115746923c4c2dacc
+    ○  baaca790d518 snapshot working copy
+    ○  c620d446c33b commit 4c75edc67bcd26ab3532193cf874228f415cdcc5
+    ○  4c5b851bfd66 commit 093c3c9624b6cfe22b310586f5638792aa80e6d7
+    ○  8f47435a3990 add workspace 'default'
+    ○  000000000000
+    [EOF]
+    ");
+
+    // The current operation can't be abandoned.
+    let output = work_dir.run_jj(["op", "abandon", "@ | @-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Cannot abandon the current operation 66eb341cdef4
+    Hint: Run `jj undo` to revert the current operation, then use `jj op abandon`
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["op", "abandon", "none()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_op_export_import() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.run_jj_in(".", ["git", "init", "other"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let other_dir = test_env.work_dir("other");
+
+    work_dir.write_file("file", "a");
+    work_dir.run_jj(["commit", "-m", "commit 1"]).success();
+    work_dir.write_file("file", "b");
+    work_dir.run_jj(["describe", "-m", "commit 2"]).success();
+    let bundle = test_env
+        .new_jj_cmd()
+        .current_dir(work_dir.root())
+        .args(["op", "export", "--range", "..@"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    other_dir.write_file("../bundle", bundle);
+
+    let output = other_dir.run_jj(["op", "import", "../bundle"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Imported 5 operations and 6 commits as operation c373a630ec13
+    Hint: Use `jj op restore c373a630ec13` to restore the repo to the imported operation.
+    [EOF]
+    ");
+    // The current repo state isn't changed
+    let template = r#"id.short() ++ " " ++ description ++ "\n""#;
+    insta::assert_snapshot!(other_dir.run_jj(["op", "log", "-T", template]), @r"
+    @  bb0f38a1f44d add workspace 'default'
+    ○  000000000000
+    [EOF]
+    ");
+    let output = other_dir.run_jj(["--at-op", "c373a630ec13", "op", "log", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    @  c373a630ec13 describe commit 11475bea92fbbabfb21208c28e4c627b33a71b38
+    ○  2dc2869a1c84 snapshot working copy
+    ○  c5009562777b commit 4b91e60ce12bd86aaac988a7a2ddf1806df054eb
+    ○  31d490533717 snapshot working copy
+    ○  8f47435a3990 add workspace 'default'
+    ○  000000000000
+    [EOF]
+    ");
+    let output = other_dir.run_jj(["op", "restore", "c373a630ec13"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Restored to operation: c373a630ec13 (2001-02-03 08:05:10) describe commit 11475bea92fbbabfb21208c28e4c627b33a71b38
+    Working copy  (@) now at: kkmpptxz 13a7175c commit 2
+    Parent commit (@-)      : qpvuntsm 96e699c6 commit 1
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    ");
+    insta::assert_snapshot!(other_dir.run_jj(["log", "-r", "all()"]), @r"
+    @  kkmpptxz test.user@example.com 2001-02-03 08:05:10 13a7175c
+    │  commit 2
+    ○  qpvuntsm test.user@example.com 2001-02-03 08:05:09 96e699c6
+    │  commit 1
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ");
+
+    // Commits created before the exported operations are also included
+    let bundle = test_env
+        .new_jj_cmd()
+        .current_dir(work_dir.root())
+        .args(["op", "export", "--range", "@"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    other_dir.write_file("../bundle", bundle);
+    test_env
+        .run_jj_in(".", ["git", "init", "another"])
+        .success();
+    let another_dir = test_env.work_dir("another");
+    let output = another_dir.run_jj(["op", "import", "../bundle"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Imported 1 operations and 3 commits as operation 51c3bdc9270b
+    Hint: Use `jj op restore 51c3bdc9270b` to restore the repo to the imported operation.
+    [EOF]
+    ");
+    let output = another_dir.run_jj(["--at-op", "51c3bdc9270b", "log", "-r", "all()"]);
+    insta::assert_snapshot!(output, @r"
+    @  kkmpptxz test.user@example.com 2001-02-03 08:05:10 13a7175c
+    │  commit 2
+    ○  qpvuntsm test.user@example.com 2001-02-03 08:05:09 96e699c6
+    │  commit 1
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ");
+
+    // Garbage can't be imported
+    other_dir.write_file("../garbage", "garbage");
+    let output = other_dir.run_jj(["op", "import", "../garbage"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Not an operation bundle
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_op_tag() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    // Tag the operation created by a command
+    work_dir
+        .run_jj(["new", "-m", "built", "--op-tag", "rc=3", "--op-tag=ci=yes"])
+        .success();
+    work_dir.run_jj(["new", "-m", "more"]).success();
+    let template = r#"id.short() ++ " " ++ description ++ " [" ++ self.tag("rc") ++ "]\n""#;
+    insta::assert_snapshot!(work_dir.run_jj(["op", "log", "-T", template]), @r"
+    @  803bd8a0a01b new empty commit []
+    ○  ec76e9c5aa3a new empty commit [3]
+    ○  8f47435a3990 add workspace 'default' []
+    ○  000000000000  []
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "op",
+        "log",
+        "--no-graph",
+        "-r",
+        "tags(rc=3)",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ec76e9c5aa3a new empty commit [3]
+    [EOF]
+    ");
+
+    // Tag an existing operation
+    let output = work_dir.run_jj(["op", "tag", "@", "rc=4", "release=true"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Tagged operation 803bd8a0a01b and reparented 0 descendant operations.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "tag", "@", "rc=4"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "tag", "tags(rc=3)", "rc=3.1"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Tagged operation ec76e9c5aa3a and reparented 1 descendant operations.
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["op", "log", "-T", template]), @r"
+    @  4a12b7435ffd new empty commit [4]
+    ○  ad21385adbc0 new empty commit [3.1]
+    ○  8f47435a3990 add workspace 'default' []
+    ○  000000000000  []
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["op", "show", "@-", "--no-op-diff"]), @r"
+    ad21385adbc0 test-username@host.example.com 2001-02-03 04:05:08.000 +07:00 - 2001-02-03 04:05:08.000 +07:00
+    new empty commit
+    args: jj new -m built --op-tag 'rc=3' '--op-tag=ci=yes'
+    ci: yes
+    rc: 3.1
+    [EOF]
+    ");
+
+    // The working copy is still up to date
+    let output = work_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output, @r"
+    The working copy has no changes.
+    Working copy  (@) : kkmpptxz 6d2721de (empty) more
+    Parent commit (@-): rlvkpnrz bc7ede7d (empty) built
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "tag", "@", "no-value"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: invalid value 'no-value' for '<KEY=VALUE>...': expected KEY=VALUE
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+    let output = work_dir.run_jj(["op", "tag", "@", "=value"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: invalid value '=value' for '<KEY=VALUE>...': tag name must not be empty
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+    let output = work_dir.run_jj(["op", "tag", "000000000000", "rc=0"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Cannot tag the root operation
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_op_compact() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    for contents in ["a", "b", "c"] {
+        work_dir.write_file("file", contents);
+        work_dir.run_jj(["debug", "snapshot"]).success();
+    }
+    work_dir.run_jj(["describe", "-m", "commit 1"]).success();
+    work_dir.write_file("file", "d");
+    work_dir.run_jj(["debug", "snapshot"]).success();
+    work_dir.write_file("file", "e");
+    work_dir.run_jj(["debug", "snapshot"]).success();
+    let template = r#"id.short() ++ " " ++ description ++ "\n""#;
+    insta::assert_snapshot!(work_dir.run_jj(["op", "log", "-T", template]), @r"
+    @  5372f89ef834 snapshot working copy
+    ○  6863787303e0 snapshot working copy
+    ○  971f52b9de4c describe commit 53b5f4a188e5d5c432a34acedcd5a95df6a070f7
+    ○  957bc6796e01 snapshot working copy
+    ○  f30e698011b5 snapshot working copy
+    ○  ccce446f2a03 snapshot working copy
+    ○  8f47435a3990 add workspace 'default'
+    ○  000000000000
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "compact", "--older-than", "2001-02-03T04:05:12+07:00"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Folded 2 snapshot operations.
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["op", "log", "-T", template]), @r"
+    @  fcc42af69a27 snapshot working copy
+    ○  f48e05586561 snapshot working copy
+    ○  7afc294a9630 describe commit 53b5f4a188e5d5c432a34acedcd5a95df6a070f7
+    ○  96e01876dbe9 snapshot working copy
+    ○  8f47435a3990 add workspace 'default'
+    ○  000000000000
+    [EOF]
+    ");
+
+    // The previous versions of the working-copy commit are preserved.
+    let output = work_dir.run_jj(["evolog", "-T", "commit.commit_id().short() ++ \"\\n\""]);
+    insta::assert_snapshot!(output, @r"
+    @  907b9614865d
+    ○  b28998884850
+    ○  357a41b759d3
+    ○  53b5f4a188e5
+    ○  422100d463c9
+    ○  bbb914dc3a91
+    ○  e8849ae12c70
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "compact"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Folded 1 snapshot operations.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "compact"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+
+    // Snapshot operations can be compacted by `jj util gc`.
+    work_dir.write_file("file", "f");
+    work_dir.run_jj(["debug", "snapshot"]).success();
+    work_dir.write_file("file", "g");
+    work_dir.run_jj(["debug", "snapshot"]).success();
+    let output = work_dir.run_jj([
+        "util",
+        "gc",
+        "--expire=now",
+        "--config=gc.compact-snapshots=true",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Folded 2 snapshot operations.
+    Reclaimed 0.0B of disk space.
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["op", "log", "-T", template]), @r"
+    @  6400caeb149d snapshot working copy
+    ○  7afc294a9630 describe commit 53b5f4a188e5d5c432a34acedcd5a95df6a070f7
+    ○  96e01876dbe9 snapshot working copy
+    ○  8f47435a3990 add workspace 'default'
+    ○  000000000000
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_op_abandon_without_updating_working_copy() {
     let test_env = TestEnvironment::default();
@@ -885,9 +1509,11 @@ fn test_op_abandon_multiple_heads() {
 
     let output = work_dir.run_jj(["op", "log"]);
     insta::assert_snapshot!(output, @r"
-    @    4bc6ca79dcdc test-username@host.example.com 2001-02-03 04:05:17.000 +07:00 - 2001-02-03 04:05:17.000 +07:00
+    @    5e438df8dfd7 test-username@host.example.com 2001-02-03 04:05:17.000 +07:00 - 2001-02-03 04:05:17.000 +07:00
     ├─╮  reconcile divergent operations
+    │ │  merged from f5e2d13c1aac, 56b918336386
     │ │  args: jj op log
+    │ │  conflicted-workspaces: default
     ○ │  f5e2d13c1aac test-username@host.example.com 2001-02-03 04:05:10.000 +07:00 - 2001-02-03 04:05:10.000 +07:00
     │ │  commit 4b087e94a5d14530c3953d617623d075a13294c8
     │ │  args: jj commit -m 'commit 3'
@@ -903,6 +1529,10 @@ fn test_op_abandon_multiple_heads() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation f5e2d13c1aac: commit 4b087e94a5d14530c3953d617623d075a13294c8
+    Merging operation 56b918336386: commit 4e0592f3dd52e7a4998a97d9a1f354e2727a856b
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation f5e2d13c1aac
     [EOF]
     ");
 }
@@ -1010,6 +1640,10 @@ fn test_op_recover_from_bad_gc() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 97087d6f3eb9: describe commit a053bc8736064a739ab73f2c775a6ac2851bf1a3
+    Merging operation 4b88b2540806: describe commit a053bc8736064a739ab73f2c775a6ac2851bf1a3
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation 97087d6f3eb9
     [EOF]
     ");
 }
@@ -1286,13 +1920,18 @@ fn test_op_diff() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 64f6f2454329: track remote bookmark bookmark-1@origin
+    Merging operation e932e147b07d: point bookmark bookmark-1 to commit 4ff6253913375c6ebdddd8423c11df3b3f17e331
+    Warning: Concurrent operations moved bookmarks to different commits: bookmark-1
     [EOF]
     ");
     let output = work_dir.run_jj(["op", "log"]);
     insta::assert_snapshot!(output, @r"
-    @    4ee6d5cabe2e test-username@host.example.com 2001-02-03 04:05:19.000 +07:00 - 2001-02-03 04:05:19.000 +07:00
+    @    1a9ed790e5b4 test-username@host.example.com 2001-02-03 04:05:19.000 +07:00 - 2001-02-03 04:05:19.000 +07:00
     ├─╮  reconcile divergent operations
+    │ │  merged from 64f6f2454329, e932e147b07d
     │ │  args: jj log
+    │ │  conflicted-bookmarks: bookmark-1
     ○ │  64f6f2454329 test-username@host.example.com 2001-02-03 04:05:10.000 +07:00 - 2001-02-03 04:05:10.000 +07:00
     │ │  track remote bookmark bookmark-1@origin
     │ │  args: jj bookmark track bookmark-1@origin
@@ -1318,36 +1957,28 @@ fn test_op_diff() {
     // Diff between the first parent of the merge operation and the merge operation.
     let output = work_dir.run_jj(["op", "diff", "--from", first_parent_id, "--to", op_id]);
     insta::assert_snapshot!(output, @r"
-    From operation: 64f6f2454329 (2001-02-03 08:05:10) track remote bookmark bookmark-1@origin
-      To operation: 4ee6d5cabe2e (2001-02-03 08:05:19) reconcile divergent operations
-
-    Changed local bookmarks:
-    bookmark-1:
-    + (added) pukowqtp 0cb7e07e bookmark-1?? bookmark-1@origin | Commit 1
-    + (added) rnnslrkn 4ff62539 bookmark-1?? bookmark-2@origin | Commit 2
-    - pukowqtp 0cb7e07e bookmark-1?? bookmark-1@origin | Commit 1
+    ------- stderr -------
+    Error: Failed to parse opset: Syntax error
+    Caused by:  --> 1:6
+      |
+    1 | args:
+      |      ^---
+      |
+      = expected <identifier>, <string_literal>, or <raw_string_literal>
+    Hint: See https://jj-vcs.github.io/jj/latest/operation-log/#operation-sets for opsets syntax.
     [EOF]
+    [exit status: 1]
     ");
 
     // Diff between the second parent of the merge operation and the merge
     // operation.
     let output = work_dir.run_jj(["op", "diff", "--from", second_parent_id, "--to", op_id]);
-    insta::assert_snapshot!(output, @r"
-    From operation: e932e147b07d (2001-02-03 08:05:18) point bookmark bookmark-1 to commit 4ff6253913375c6ebdddd8423c11df3b3f17e331
-      To operation: 4ee6d5cabe2e (2001-02-03 08:05:19) reconcile divergent operations
-
-    Changed local bookmarks:
-    bookmark-1:
-    + (added) pukowqtp 0cb7e07e bookmark-1?? bookmark-1@origin | Commit 1
-    + (added) rnnslrkn 4ff62539 bookmark-1?? bookmark-2@origin | Commit 2
-    - rnnslrkn 4ff62539 bookmark-1?? bookmark-2@origin | Commit 2
-
-    Changed remote bookmarks:
-    bookmark-1@origin:
-    + tracked pukowqtp 0cb7e07e bookmark-1?? bookmark-1@origin | Commit 1
-    - untracked pukowqtp 0cb7e07e bookmark-1?? bookmark-1@origin | Commit 1
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Operation ID "track" is not a valid hexadecimal prefix
     [EOF]
-    ");
+    [exit status: 1]
+    "#);
 
     // Test fetching from git remote.
     modify_git_repo(git_repo);
@@ -1362,8 +1993,8 @@ fn test_op_diff() {
     ");
     let output = work_dir.run_jj(["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: 4ee6d5cabe2e (2001-02-03 08:05:19) reconcile divergent operations
-      To operation: 37661c38b5a1 (2001-02-03 08:05:23) fetch from git remote(s) origin
+    From operation: 1a9ed790e5b4 (2001-02-03 08:05:19) reconcile divergent operations
+      To operation: b8d4d709cde1 (2001-02-03 08:05:23) fetch from git remote(s) origin
 
     Changed commits:
     ○  + kulxwnxm e1a239a5 bookmark-2@origin | Commit 5
@@ -1405,8 +2036,8 @@ fn test_op_diff() {
     ");
     let output = work_dir.run_jj(["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: 37661c38b5a1 (2001-02-03 08:05:23) fetch from git remote(s) origin
-      To operation: 83bf9eca7d31 (2001-02-03 08:05:25) create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
+    From operation: b8d4d709cde1 (2001-02-03 08:05:23) fetch from git remote(s) origin
+      To operation: 1c347093b3d7 (2001-02-03 08:05:25) create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
 
     Changed local bookmarks:
     bookmark-2:
@@ -1424,8 +2055,8 @@ fn test_op_diff() {
     ");
     let output = work_dir.run_jj(["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: 83bf9eca7d31 (2001-02-03 08:05:25) create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
-      To operation: 818db089870a (2001-02-03 08:05:27) track remote bookmark bookmark-2@origin
+    From operation: 1c347093b3d7 (2001-02-03 08:05:25) create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
+      To operation: e8b92266fff4 (2001-02-03 08:05:27) track remote bookmark bookmark-2@origin
 
     Changed remote bookmarks:
     bookmark-2@origin:
@@ -1445,8 +2076,8 @@ fn test_op_diff() {
     ");
     let output = work_dir.run_jj(["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: 83bf9eca7d31 (2001-02-03 08:05:25) create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
-      To operation: 818db089870a (2001-02-03 08:05:27) track remote bookmark bookmark-2@origin
+    From operation: 1c347093b3d7 (2001-02-03 08:05:25) create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
+      To operation: e8b92266fff4 (2001-02-03 08:05:27) track remote bookmark bookmark-2@origin
 
     Changed remote bookmarks:
     bookmark-2@origin:
@@ -1466,8 +2097,8 @@ fn test_op_diff() {
     ");
     let output = work_dir.run_jj(["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: 818db089870a (2001-02-03 08:05:27) track remote bookmark bookmark-2@origin
-      To operation: 263f1b7d2e97 (2001-02-03 08:05:31) new empty commit
+    From operation: e8b92266fff4 (2001-02-03 08:05:27) track remote bookmark bookmark-2@origin
+      To operation: 252fc2dc4b37 (2001-02-03 08:05:31) new empty commit
 
     Changed commits:
     ○  + xlzxqlsl 731ab199 (empty) new commit
@@ -1488,8 +2119,8 @@ fn test_op_diff() {
     ");
     let output = work_dir.run_jj(["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: 263f1b7d2e97 (2001-02-03 08:05:31) new empty commit
-      To operation: a90be14f7026 (2001-02-03 08:05:33) point bookmark bookmark-1 to commit 731ab19950fc6fc1199b9ea73cb8b9016f22e8f3
+    From operation: 252fc2dc4b37 (2001-02-03 08:05:31) new empty commit
+      To operation: f44fe672dab9 (2001-02-03 08:05:33) point bookmark bookmark-1 to commit 731ab19950fc6fc1199b9ea73cb8b9016f22e8f3
 
     Changed local bookmarks:
     bookmark-1:
@@ -1508,8 +2139,8 @@ fn test_op_diff() {
     ");
     let output = work_dir.run_jj(["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: a90be14f7026 (2001-02-03 08:05:33) point bookmark bookmark-1 to commit 731ab19950fc6fc1199b9ea73cb8b9016f22e8f3
-      To operation: 1b2b83317596 (2001-02-03 08:05:35) delete bookmark bookmark-2
+    From operation: f44fe672dab9 (2001-02-03 08:05:33) point bookmark bookmark-1 to commit 731ab19950fc6fc1199b9ea73cb8b9016f22e8f3
+      To operation: 38e562c89b24 (2001-02-03 08:05:35) delete bookmark bookmark-2
 
     Changed local bookmarks:
     bookmark-2:
@@ -1529,8 +2160,8 @@ fn test_op_diff() {
     ");
     let output = work_dir.run_jj(["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: 1b2b83317596 (2001-02-03 08:05:35) delete bookmark bookmark-2
-      To operation: b85f801e5384 (2001-02-03 08:05:37) push all tracked bookmarks to git remote origin
+    From operation: 38e562c89b24 (2001-02-03 08:05:35) delete bookmark bookmark-2
+      To operation: 42a0a4fab5a5 (2001-02-03 08:05:37) push all tracked bookmarks to git remote origin
 
     Changed remote bookmarks:
     bookmark-1@origin:
@@ -1679,9 +2310,11 @@ fn test_op_diff_sibling() {
 
     let output = work_dir.run_jj(["op", "log"]);
     insta::assert_snapshot!(output, @r"
-    @    d566adf20e48 test-username@host.example.com 2001-02-03 04:05:13.000 +07:00 - 2001-02-03 04:05:13.000 +07:00
+    @    df8a6792e4ac test-username@host.example.com 2001-02-03 04:05:13.000 +07:00 - 2001-02-03 04:05:13.000 +07:00
     ├─╮  reconcile divergent operations
+    │ │  merged from 7bba3a63b73b, 252ff3a5a0e6
     │ │  args: jj op log
+    │ │  conflicted-workspaces: default
     ○ │  7bba3a63b73b test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
     │ │  new empty commit
     │ │  args: jj new '@-+' -mA
@@ -1706,6 +2339,10 @@ fn test_op_diff_sibling() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 7bba3a63b73b: new empty commit
+    Merging operation 252ff3a5a0e6: describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation 7bba3a63b73b
     [EOF]
     ");
     let output = work_dir
@@ -1713,7 +2350,7 @@ fn test_op_diff_sibling() {
         .success();
     let [head_op_id, p1_op_id, _, _, _, _, p2_op_id] =
         output.stdout.raw().lines().next_array().unwrap();
-    insta::assert_snapshot!(head_op_id, @"d566adf20e48");
+    insta::assert_snapshot!(head_op_id, @"df8a6792e4ac");
     insta::assert_snapshot!(p1_op_id, @"7bba3a63b73b");
     insta::assert_snapshot!(p2_op_id, @"252ff3a5a0e6");
 
@@ -2064,6 +2701,10 @@ fn test_op_diff_at_merge_op_with_rebased_commits() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 69ec49158b0e: describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    Merging operation 0c5076ddf77d: describe commit ab92d1a87bebb4300165a16a753c5403bd7bc578
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation 69ec49158b0e
     Rebased 1 descendant commits onto commits rewritten by other operation
     [EOF]
     ");
@@ -2073,7 +2714,7 @@ fn test_op_diff_at_merge_op_with_rebased_commits() {
     insta::assert_snapshot!(output, @r"
     From operation: 69ec49158b0e (2001-02-03 08:05:09) describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
     From operation: 0c5076ddf77d (2001-02-03 08:05:10) describe commit ab92d1a87bebb4300165a16a753c5403bd7bc578
-      To operation: ac3c7e679e31 (2001-02-03 08:05:11) reconcile divergent operations
+      To operation: 5899fd6ba0d3 (2001-02-03 08:05:11) reconcile divergent operations
 
     Changed commits:
     ○  + rlvkpnrz?? 8f35f6a6 (empty) 2b
@@ -2083,17 +2724,21 @@ fn test_op_diff_at_merge_op_with_rebased_commits() {
 
     let output = work_dir.run_jj(["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    ac3c7e679e31 test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
+    5899fd6ba0d3 test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
     reconcile divergent operations
+    merged from 69ec49158b0e, 0c5076ddf77d
     args: jj log
+    conflicted-workspaces: default
     [EOF]
     ");
 
     let output = work_dir.run_jj(["op", "log", "--op-diff", "--limit=3"]);
     insta::assert_snapshot!(output, @r"
-    @    ac3c7e679e31 test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
+    @    5899fd6ba0d3 test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
     ├─╮  reconcile divergent operations
+    │ │  merged from 69ec49158b0e, 0c5076ddf77d
     │ │  args: jj log
+    │ │  conflicted-workspaces: default
     ○ │  69ec49158b0e test-username@host.example.com 2001-02-03 04:05:09.000 +07:00 - 2001-02-03 04:05:09.000 +07:00
     │ │  describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
     │ │  args: jj describe -r@- -m1
@@ -2122,6 +2767,176 @@ fn test_op_diff_at_merge_op_with_rebased_commits() {
     ");
 }
 
+#[test]
+fn test_op_diff_filters() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["new", "-mA"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "a"])
+        .success();
+    work_dir.run_jj(["new", "-mB"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "b"])
+        .success();
+    work_dir.run_jj(["new", "-mC"]).success();
+    let base_op_id = work_dir.current_operation_id();
+
+    // Rewrite A, B, and C, create D, and abandon B
+    work_dir.run_jj(["describe", "-ra", "-mA2"]).success();
+    work_dir.run_jj(["new", "root()", "-mD"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "d"])
+        .success();
+    work_dir.run_jj(["abandon", "b"]).success();
+
+    let output = work_dir.run_jj(["op", "diff", "--from", &base_op_id]);
+    insta::assert_snapshot!(output, @r"
+    From operation: b093485a47bc (2001-02-03 08:05:12) new empty commit
+      To operation: 8e0103ef9d9e (2001-02-03 08:05:16) abandon commit e369e0eb611f32d3881edc899030c26c5cfe0fda
+
+    Changed commits:
+    ○  + royxmykx 65a1c468 (empty) C
+    │  - royxmykx hidden 767f0776 (empty) C
+    ○  + rlvkpnrz b7c560ab a | (empty) A2
+       - rlvkpnrz hidden 8c12c2c0 (empty) A
+    ○  + vruxwmqv 521674f5 d | (empty) D
+    ○  - zsuskuln hidden 1d189e93 (empty) B
+
+    Changed working copy default@:
+    + vruxwmqv 521674f5 d | (empty) D
+    - royxmykx hidden 767f0776 (empty) C
+
+    Changed local bookmarks:
+    a:
+    + rlvkpnrz b7c560ab a | (empty) A2
+    - rlvkpnrz hidden 8c12c2c0 (empty) A
+    b:
+    + (absent)
+    - zsuskuln hidden 1d189e93 (empty) B
+    d:
+    + vruxwmqv 521674f5 d | (empty) D
+    - (absent)
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "op",
+        "diff",
+        "--from",
+        &base_op_id,
+        "-r",
+        "a | description(B)",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    From operation: b093485a47bc (2001-02-03 08:05:12) new empty commit
+      To operation: 8e0103ef9d9e (2001-02-03 08:05:16) abandon commit e369e0eb611f32d3881edc899030c26c5cfe0fda
+
+    Changed commits:
+    ○  + rlvkpnrz b7c560ab a | (empty) A2
+       - rlvkpnrz hidden 8c12c2c0 (empty) A
+    ○  - zsuskuln hidden 1d189e93 (empty) B
+
+    Changed working copy default@:
+    + vruxwmqv 521674f5 d | (empty) D
+    - royxmykx hidden 767f0776 (empty) C
+
+    Changed local bookmarks:
+    a:
+    + rlvkpnrz b7c560ab a | (empty) A2
+    - rlvkpnrz hidden 8c12c2c0 (empty) A
+    b:
+    + (absent)
+    - zsuskuln hidden 1d189e93 (empty) B
+    d:
+    + vruxwmqv 521674f5 d | (empty) D
+    - (absent)
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "op",
+        "diff",
+        "--from",
+        &base_op_id,
+        "-r",
+        "none()",
+        "--ref-pattern",
+        "glob:[ab]",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    From operation: b093485a47bc (2001-02-03 08:05:12) new empty commit
+      To operation: 8e0103ef9d9e (2001-02-03 08:05:16) abandon commit e369e0eb611f32d3881edc899030c26c5cfe0fda
+
+    Changed working copy default@:
+    + vruxwmqv 521674f5 d | (empty) D
+    - royxmykx hidden 767f0776 (empty) C
+
+    Changed local bookmarks:
+    a:
+    + rlvkpnrz b7c560ab a | (empty) A2
+    - rlvkpnrz hidden 8c12c2c0 (empty) A
+    b:
+    + (absent)
+    - zsuskuln hidden 1d189e93 (empty) B
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "diff", "--from", &base_op_id, "--commit-stat"]);
+    insta::assert_snapshot!(output, @r"
+    From operation: b093485a47bc (2001-02-03 08:05:12) new empty commit
+      To operation: 8e0103ef9d9e (2001-02-03 08:05:16) abandon commit e369e0eb611f32d3881edc899030c26c5cfe0fda
+
+    Changed commits: 1 new, 2 rewritten, 1 abandoned
+
+    Changed local bookmarks:
+    a: 0 new, 1 rewritten, 0 abandoned
+    b: 0 new, 0 rewritten, 1 abandoned
+    d: 1 new, 0 rewritten, 0 abandoned
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "op",
+        "diff",
+        "--from",
+        &base_op_id,
+        "--no-graph",
+        "-T",
+        r#"description.first_line()"#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    From operation: b093485a47bc (2001-02-03 08:05:12) new empty commit
+      To operation: 8e0103ef9d9e (2001-02-03 08:05:16) abandon commit e369e0eb611f32d3881edc899030c26c5cfe0fda
+
+    Changed commits:
+    + C
+    - C
+    + D
+    + A2
+    - A
+    - B
+
+    Changed working copy default@:
+    + D
+    - C
+
+    Changed local bookmarks:
+    a:
+    + A2
+    - A
+    b:
+    + (absent)
+    - B
+    d:
+    + D
+    - (absent)
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_op_diff_word_wrap() {
     let test_env = TestEnvironment::default();
@@ -2381,14 +3196,19 @@ fn test_op_show() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 64f6f2454329: track remote bookmark bookmark-1@origin
+    Merging operation b27c0ff6788d: point bookmark bookmark-1 to commit 4ff6253913375c6ebdddd8423c11df3b3f17e331
+    Warning: Concurrent operations moved bookmarks to different commits: bookmark-1
     [EOF]
     ");
     // Showing a merge operation is empty.
     let output = work_dir.run_jj(["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    f7f71a8bc2f4 test-username@host.example.com 2001-02-03 04:05:17.000 +07:00 - 2001-02-03 04:05:17.000 +07:00
+    369e688b39e8 test-username@host.example.com 2001-02-03 04:05:17.000 +07:00 - 2001-02-03 04:05:17.000 +07:00
     reconcile divergent operations
+    merged from 64f6f2454329, b27c0ff6788d
     args: jj log
+    conflicted-bookmarks: bookmark-1
     [EOF]
     ");
 
@@ -2405,7 +3225,7 @@ fn test_op_show() {
     ");
     let output = work_dir.run_jj(["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    a32821470ddb test-username@host.example.com 2001-02-03 04:05:19.000 +07:00 - 2001-02-03 04:05:19.000 +07:00
+    77a9e87f608c test-username@host.example.com 2001-02-03 04:05:19.000 +07:00 - 2001-02-03 04:05:19.000 +07:00
     fetch from git remote(s) origin
     args: jj git fetch
 
@@ -2449,7 +3269,7 @@ fn test_op_show() {
     ");
     let output = work_dir.run_jj(["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    2ce7ebded2de test-username@host.example.com 2001-02-03 04:05:21.000 +07:00 - 2001-02-03 04:05:21.000 +07:00
+    42e0a2c68812 test-username@host.example.com 2001-02-03 04:05:21.000 +07:00 - 2001-02-03 04:05:21.000 +07:00
     create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
     args: jj bookmark create bookmark-2 -r bookmark-2@origin
 
@@ -2469,7 +3289,7 @@ fn test_op_show() {
     ");
     let output = work_dir.run_jj(["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    9cc95f35ba27 test-username@host.example.com 2001-02-03 04:05:23.000 +07:00 - 2001-02-03 04:05:23.000 +07:00
+    9676dd81f590 test-username@host.example.com 2001-02-03 04:05:23.000 +07:00 - 2001-02-03 04:05:23.000 +07:00
     track remote bookmark bookmark-2@origin
     args: jj bookmark track bookmark-2@origin
 
@@ -2490,7 +3310,7 @@ fn test_op_show() {
     ");
     let output = work_dir.run_jj(["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    9cc95f35ba27 test-username@host.example.com 2001-02-03 04:05:23.000 +07:00 - 2001-02-03 04:05:23.000 +07:00
+    9676dd81f590 test-username@host.example.com 2001-02-03 04:05:23.000 +07:00 - 2001-02-03 04:05:23.000 +07:00
     track remote bookmark bookmark-2@origin
     args: jj bookmark track bookmark-2@origin
 
@@ -2512,7 +3332,7 @@ fn test_op_show() {
     ");
     let output = work_dir.run_jj(["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    8727dbd86fa3 test-username@host.example.com 2001-02-03 04:05:27.000 +07:00 - 2001-02-03 04:05:27.000 +07:00
+    f58ebd46184f test-username@host.example.com 2001-02-03 04:05:27.000 +07:00 - 2001-02-03 04:05:27.000 +07:00
     new empty commit
     args: jj new bookmark-1@origin -m 'new commit'
 
@@ -2535,7 +3355,7 @@ fn test_op_show() {
     ");
     let output = work_dir.run_jj(["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    f5d053abd6be test-username@host.example.com 2001-02-03 04:05:29.000 +07:00 - 2001-02-03 04:05:29.000 +07:00
+    88fdaa16ae83 test-username@host.example.com 2001-02-03 04:05:29.000 +07:00 - 2001-02-03 04:05:29.000 +07:00
     point bookmark bookmark-1 to commit 8f340dd76dc637e4deac17f30056eef7d8eaf682
     args: jj bookmark set bookmark-1 -r @
 
@@ -2556,7 +3376,7 @@ fn test_op_show() {
     ");
     let output = work_dir.run_jj(["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    97af2adb0e1f test-username@host.example.com 2001-02-03 04:05:31.000 +07:00 - 2001-02-03 04:05:31.000 +07:00
+    731de22aad1b test-username@host.example.com 2001-02-03 04:05:31.000 +07:00 - 2001-02-03 04:05:31.000 +07:00
     delete bookmark bookmark-2
     args: jj bookmark delete bookmark-2
 
@@ -2578,7 +3398,7 @@ fn test_op_show() {
     ");
     let output = work_dir.run_jj(["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    e2345a3aa1b3 test-username@host.example.com 2001-02-03 04:05:33.000 +07:00 - 2001-02-03 04:05:33.000 +07:00
+    8e62aacfade0 test-username@host.example.com 2001-02-03 04:05:33.000 +07:00 - 2001-02-03 04:05:33.000 +07:00
     push all tracked bookmarks to git remote origin
     args: jj git push --tracked --deleted
 
@@ -2594,20 +3414,12 @@ fn test_op_show() {
 
     // Showing a given operation, without graph
     let output = work_dir.run_jj(["op", "show", "--no-graph", "8727dbd86fa3"]);
-    insta::assert_snapshot!(output, @r"
-    8727dbd86fa3 test-username@host.example.com 2001-02-03 04:05:27.000 +07:00 - 2001-02-03 04:05:27.000 +07:00
-    new empty commit
-    args: jj new bookmark-1@origin -m 'new commit'
-
-    Changed commits:
-    + tlkvzzqu 8f340dd7 (empty) new commit
-    - qpvuntsm hidden e8849ae1 (empty) (no description set)
-
-    Changed working copy default@:
-    + tlkvzzqu 8f340dd7 (empty) new commit
-    - qpvuntsm hidden e8849ae1 (empty) (no description set)
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: No operation ID matching "8727dbd86fa3"
     [EOF]
-    ");
+    [exit status: 1]
+    "#);
 }
 
 #[test]
@@ -2879,8 +3691,8 @@ fn test_op_log_parents() {
         .success();
     let template = r#"id.short() ++ "\nP: " ++ parents.len() ++ " " ++ parents.map(|o| o.id().short()) ++ "\n""#;
     let output = work_dir.run_jj(["op", "log", "-T", template]);
-    insta::assert_snapshot!(output, @r###"
-    @    ea1c99c7c4a9
+    insta::assert_snapshot!(output, @r"
+    @    e51ef21a0c84
     ├─╮  P: 2 12f7cbba4278 dd1534c4b064
     ○ │  12f7cbba4278
     │ │  P: 1 8f47435a3990
@@ -2893,8 +3705,12 @@ fn test_op_log_parents() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 12f7cbba4278: describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    Merging operation dd1534c4b064: describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation 12f7cbba4278
     [EOF]
-    "###);
+    ");
 }
 
 #[test]
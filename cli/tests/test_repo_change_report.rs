@@ -126,6 +126,10 @@ fn test_report_conflicts_with_divergent_commits() {
     insta::assert_snapshot!(output, @r###"
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 615715c83e16: describe commit 5f6551b8a486f1b27498617217d864c4b545f734
+    Merging operation f120b6528b2b: describe commit 5f6551b8a486f1b27498617217d864c4b545f734
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation 615715c83e16
     Rebased 3 commits to destination
     Working copy  (@) now at: zsuskuln?? 08a31f4f (conflict) C2
     Parent commit (@-)      : kkmpptxz 099d6624 (conflict) B
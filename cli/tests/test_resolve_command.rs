@@ -14,12 +14,14 @@
 
 use std::path::Path;
 
+use indoc::formatdoc;
 use indoc::indoc;
 
 use crate::common::CommandOutput;
 use crate::common::TestEnvironment;
 use crate::common::TestWorkDir;
 use crate::common::create_commit_with_files;
+use crate::common::to_toml_value;
 
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
@@ -624,7 +626,8 @@ fn test_baseless_conflict_input_files() {
 
 #[test]
 fn test_too_many_parents() {
-    let test_env = TestEnvironment::default();
+    let mut test_env = TestEnvironment::default();
+    test_env.set_up_fake_editor();
     test_env.run_jj_in(".", ["git", "init", "repo"]).success();
     let work_dir = test_env.work_dir("repo");
 
@@ -643,16 +646,29 @@ fn test_too_many_parents() {
     [EOF]
     ");
 
+    // External merge tools only support 2-sided conflicts
     let output = work_dir.run_jj(["resolve"]);
     insta::assert_snapshot!(output, @r#"
     ------- stderr -------
-    Hint: Using default editor ':builtin'; run `jj config set --user ui.merge-editor :builtin` to disable this message.
     Error: Failed to resolve conflicts
-    Caused by: The conflict at "file" has 3 sides. At most 2 sides are supported.
-    Hint: Edit the conflict markers manually to resolve this.
+    Caused by: The conflict at "file" has 3 sides. At most 2 sides are supported by external merge tools.
+    Hint: Edit the conflict markers manually or use the built-in merge editor (`--tool=:builtin`) to resolve this.
     [EOF]
     [exit status: 1]
     "#);
+
+    // Builtin tools can pick a side of n-sided conflicts
+    let output = work_dir.run_jj(["resolve", "--tool=:theirs"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: znkkpsqq 0b8d4cad conflict | conflict
+    Parent commit (@-)      : zsuskuln 45537d53 a | a
+    Parent commit (@-)      : royxmykx 89d1b299 b | b
+    Parent commit (@-)      : vruxwmqv a87962b8 c | c
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.read_file("file"), @"b");
 }
 
 #[test]
@@ -2171,3 +2187,106 @@ fn test_resolve_with_contents_of_side() {
     [exit status: 2]
     "#);
 }
+
+#[test]
+fn test_resolve_auto() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let formatter_path = assert_cmd::cargo::cargo_bin!("fake-formatter");
+    test_env.add_config(formatdoc! {"
+        merge.same-change = 'keep'
+
+        [fix.tools.fake-formatter]
+        command = [{formatter}, '--uppercase']
+        patterns = ['glob:\"fix*\"']
+        ",
+        formatter = to_toml_value(formatter_path.to_str().unwrap()),
+    });
+
+    create_commit_with_files(
+        &work_dir,
+        "base",
+        &[],
+        &[
+            ("same", "base\n"),
+            ("whitespace", "a\nb\n"),
+            ("fix", "base\n"),
+            ("unresolvable", "base\n"),
+        ],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "left",
+        &["base"],
+        &[
+            ("same", "same\n"),
+            ("whitespace", "  a\nb\n"),
+            ("fix", "new\n"),
+            ("unresolvable", "left\n"),
+        ],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "right",
+        &["base"],
+        &[
+            ("same", "same\n"),
+            ("whitespace", "x\nb\n"),
+            ("fix", "NEW\n"),
+            ("unresolvable", "right\n"),
+        ],
+    );
+    work_dir
+        .run_jj(["new", "left", "right", "-mconflict"])
+        .success();
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list"]), @r"
+    fix             2-sided conflict
+    same            2-sided conflict
+    unresolvable    2-sided conflict
+    whitespace      2-sided conflict
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["resolve", "--auto"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Resolved conflicts automatically in:
+      fix (identical after running fix tools)
+      same (sides equal to base or to each other)
+      whitespace (whitespace-only changes)
+    Working copy  (@) now at: vruxwmqv eee34a0a (conflict) conflict
+    Parent commit (@-)      : zsuskuln da00a2ec left | left
+    Parent commit (@-)      : royxmykx 6edb8093 right | right
+    Added 0 files, modified 3 files, removed 0 files
+    Warning: There are unresolved conflicts at these paths:
+    unresolvable    2-sided conflict
+    New conflicts appeared in 1 commits:
+      vruxwmqv eee34a0a (conflict) conflict
+    Hint: To resolve the conflicts, start by creating a commit on top of
+    the conflicted commit:
+      jj new vruxwmqv
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you can inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.read_file("same"), @"same");
+    insta::assert_snapshot!(work_dir.read_file("whitespace"), @r"
+    x
+    b
+    ");
+    insta::assert_snapshot!(work_dir.read_file("fix"), @"NEW");
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list"]), @r"
+    unresolvable    2-sided conflict
+    [EOF]
+    ");
+
+    // Nothing else can be resolved
+    let output = work_dir.run_jj(["resolve", "--auto"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    No conflicts could be resolved automatically.
+    [EOF]
+    ");
+}
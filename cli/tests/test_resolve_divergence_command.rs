@@ -0,0 +1,178 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::CommandOutput;
+use crate::common::TestEnvironment;
+use crate::common::TestWorkDir;
+use crate::common::force_interactive;
+
+#[must_use]
+fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
+    let template =
+        r#"separate(" ", commit_id.short(), description.first_line(), if(divergent, "divergent"))"#;
+    work_dir.run_jj(["log", "-T", template])
+}
+
+/// Makes the working-copy commit divergent: one side modifies "file", and the
+/// other side is described and makes "file" executable.
+fn create_divergent_commit(work_dir: &TestWorkDir) {
+    work_dir.write_file("file", "a\nb\nc\n");
+    work_dir.run_jj(["describe", "-m", "base"]).success();
+    let base_opid = work_dir.current_operation_id();
+    work_dir.write_file("file", "a\nb\nc\nd\n");
+    work_dir.run_jj(["debug", "snapshot"]).success();
+    work_dir
+        .run_jj(["describe", "--at-op", &base_opid, "-m", "described"])
+        .success();
+    work_dir
+        .run_jj(["file", "chmod", "x", "file", "-r", "description(described)"])
+        .success();
+}
+
+#[test]
+fn test_resolve_divergence_keep() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    create_divergent_commit(&work_dir);
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  24465f007cea base divergent
+    │ ○  0654bc11e4f2 described divergent
+    ├─╯
+    ◆  000000000000
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["resolve-divergence", "--keep", "description(described)"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Resolved divergence with commit qpvuntsm 0654bc11 described
+    Working copy  (@) now at: qpvuntsm 0654bc11 described
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  0654bc11e4f2 described
+    ◆  000000000000
+    [EOF]
+    ");
+
+    // The change is no longer divergent
+    let output = work_dir.run_jj(["resolve-divergence"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Change qpvuntsmwlqt is not divergent
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The revisions must be of a single change
+    work_dir.run_jj(["undo"]).success();
+    let output = work_dir.run_jj(["resolve-divergence", "all()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: The revisions must belong to a single change
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The kept commit must be one of the divergent commits
+    let output = work_dir.run_jj(["resolve-divergence", "--keep", "root()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Commit 000000000000 is not one of the divergent commits
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_resolve_divergence_quit() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    create_divergent_commit(&work_dir);
+    let setup_opid = work_dir.current_operation_id();
+
+    let output = work_dir.run_jj_with(|cmd| {
+        force_interactive(cmd)
+            .args(["resolve-divergence", "description(described)"])
+            .write_stdin("q\n")
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    The change has 2 divergent commits:
+    1: qpvuntsm?? 0654bc11 described
+       rewritten from qpvuntsm hidden 30bce989 described
+    2: qpvuntsm?? 24465f00 base
+    Common predecessor: qpvuntsm hidden 0384bc15 base
+    m: merge the divergent commits
+    q: quit the prompt
+    enter the index of the commit to keep, or `m` to merge them: Nothing changed.
+    [EOF]
+    ");
+    assert_eq!(work_dir.current_operation_id(), setup_opid);
+}
+
+#[test]
+fn test_resolve_divergence_merge() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    create_divergent_commit(&work_dir);
+    work_dir.run_jj(["new", "-m", "child"]).success();
+
+    // The evolution of each side since the common predecessor is shown
+    let output = work_dir.run_jj_with(|cmd| {
+        force_interactive(cmd)
+            .args(["resolve-divergence", "description(described)"])
+            .write_stdin("m\n")
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    The change has 2 divergent commits:
+    1: qpvuntsm?? 0654bc11 described
+       rewritten from qpvuntsm hidden 30bce989 described
+    2: qpvuntsm?? 24465f00 base
+    Common predecessor: qpvuntsm hidden 0384bc15 base
+    m: merge the divergent commits
+    q: quit the prompt
+    enter the index of the commit to keep, or `m` to merge them: Resolved divergence with commit qpvuntsm 79a3a0d9 described
+    Rebased 1 descendant commits
+    Working copy  (@) now at: royxmykx 8f72fc2b (empty) child
+    Parent commit (@-)      : qpvuntsm 79a3a0d9 described
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  8f72fc2b014c child
+    ○  79a3a0d9d7a6 described
+    ◆  000000000000
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "show", "-r@-", "file"]);
+    insta::assert_snapshot!(output, @r"
+    a
+    b
+    c
+    d
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list", "-r@-", "-T", r#"executable ++ "\n""#]);
+    insta::assert_snapshot!(output, @r"
+    true
+    [EOF]
+    ");
+}
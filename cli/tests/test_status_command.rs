@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use regex::Regex;
+
 use crate::common::TestEnvironment;
 use crate::common::create_commit_with_files;
 
@@ -135,6 +137,11 @@ fn test_status_conflicted_bookmarks() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 3da397448351: describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    Merging operation c5189044ead5: describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    Warning: Concurrent operations moved bookmarks to different commits: local_bookmark
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation 3da397448351
     [EOF]
     ");
 
@@ -167,7 +174,13 @@ fn test_status_conflicted_bookmarks() {
 
     // create conflicted remote bookmark
     work_dir.run_jj(["git", "fetch", "--at-op", "@-"]).success();
-    let output = work_dir.run_jj(["status"]);
+    // The fetched operations aren't stable, so their ids are masked.
+    let op_id_regex = Regex::new(r"Merging operation [0-9a-f]{12}").unwrap();
+    let output = work_dir.run_jj(["status"]).normalize_stderr_with(|text| {
+        op_id_regex
+            .replace_all(&text, "Merging operation [op id]")
+            .into_owned()
+    });
     insta::assert_snapshot!(output, @r"
     The working copy has no changes.
     Working copy  (@) : qpvuntsm?? 99025a24 local_bookmark?? | (empty) a
@@ -181,6 +194,8 @@ fn test_status_conflicted_bookmarks() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation [op id]: fetch from git remote(s) origin
+    Merging operation [op id]: fetch from git remote(s) origin
     [EOF]
     ");
 }
@@ -259,6 +259,8 @@ fn test_tag_list() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 7bb8f7a15a6f: set tag conflicted_tag to commit 76abdd20c064838d0db5faced5480454e96d15e7
+    Merging operation 13f85c1bd91a: set tag conflicted_tag to commit 13c4e819ef358f4aae867ce1d868b4ea6450ee0b
     [EOF]
     ");
 
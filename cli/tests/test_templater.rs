@@ -151,6 +151,60 @@ fn test_templater_upper_lower() {
         render(r#""Hello".upper() ++ "Hello".lower()"#), @"HELLOhello[EOF]");
 }
 
+#[test]
+fn test_templater_hyperlink() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let template = r#"hyperlink("https://example.com/" ++ commit_id.short(), "link") ++ "\n""#;
+
+    // Output is not a terminal
+    let output = work_dir.run_jj(["log", "--color=always", "--no-graph", "-r@", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    link
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "log",
+        "--color=always",
+        "--config=ui.hyperlinks=always",
+        "--no-graph",
+        "-r@",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ]8;;https://example.com/e8849ae12c70\link]8;;\
+    [EOF]
+    ");
+
+    // Plain text output should never contain links
+    let output = work_dir.run_jj([
+        "log",
+        "--config=ui.hyperlinks=always",
+        "--no-graph",
+        "-r@",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    link
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["log", "--color=always", "--config=ui.hyperlinks=bad"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Config error: Invalid type or value for ui.hyperlinks
+    Caused by: unknown variant `bad`, expected one of `always`, `never`, `auto`
+
+    For help, see https://jj-vcs.github.io/jj/latest/config/ or use `jj help -k config`.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_templater_alias() {
     let test_env = TestEnvironment::default();
@@ -361,6 +415,47 @@ fn test_templater_alias() {
     ");
 }
 
+#[test]
+fn test_templater_let_binding() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["new", "-m", "first"]).success();
+    work_dir.run_jj(["new", "-m", "second"]).success();
+    let render = |template| get_template_output(&work_dir, "@", template);
+
+    test_env.add_config(
+        r#"
+    [template-aliases]
+    'short_desc(c)' = 'let d = c.description().first_line(); if(d, d, "(no description)")'
+    "#,
+    );
+
+    insta::assert_snapshot!(
+        render(r#"let id = change_id.short(4); id ++ " " ++ id.upper()"#),
+        @"kkmp KKMP[EOF]");
+    insta::assert_snapshot!(
+        render(r#"let f = |c| short_desc(c) ++ "\n"; f(self) ++ parents.map(f).join("")"#),
+        @r"
+    second
+    first
+    [EOF]
+    ");
+
+    insta::assert_snapshot!(render(r#"let f = |c| c.commit_id(); f"#), @r"
+    ------- stderr -------
+    Error: Failed to parse template: Lambda `f` must be called
+    Caused by:  --> 1:28
+      |
+    1 | let f = |c| c.commit_id(); f
+      |                            ^
+      |
+      = Lambda `f` must be called
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_templater_alias_override() {
     let test_env = TestEnvironment::default();
@@ -464,6 +559,45 @@ fn test_templater_config_function() {
     ");
 }
 
+#[test]
+fn test_templater_config_table() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config(
+        r#"
+        [team-owners]
+        "src/net" = "alice"
+        "docs" = "bob"
+        "#,
+    );
+    let work_dir = test_env.work_dir("repo");
+    work_dir.create_dir_all("src/net");
+    work_dir.create_dir("docs");
+    work_dir.write_file("src/net/lib.rs", "");
+    work_dir.write_file("src/main.rs", "");
+    work_dir.write_file("docs/index.md", "");
+    let render = |template| get_template_output(&work_dir, "@", template);
+
+    insta::assert_snapshot!(render(r#"config("team-owners").keys()"#), @"src/net docs[EOF]");
+
+    let template = r#"
+        let owners = config("team-owners");
+        diff.files().map(|e|
+          let dir = e.path().parent().display();
+          e.path().display() ++ ": " ++ if(owners.contains_key(dir), owners.get(dir).as_string(), "-")
+        ).join("\n")
+    "#;
+    insta::assert_snapshot!(render(template), @r"
+    docs/index.md: bob
+    src/main.rs: -
+    src/net/lib.rs: alice[EOF]
+    ");
+
+    insta::assert_snapshot!(render(r#"config("team-owners").get("docs")"#), @r#""bob"[EOF]"#);
+    insta::assert_snapshot!(render(r#"config("team-owners").get("src")"#), @"");
+    insta::assert_snapshot!(render(r#"config("user.name").keys()"#), @"<Error: Expected a table, but is string>[EOF]");
+}
+
 #[must_use]
 fn get_template_output(work_dir: &TestWorkDir, rev: &str, template: &str) -> CommandOutput {
     work_dir.run_jj(["log", "--no-graph", "-r", rev, "-T", template])
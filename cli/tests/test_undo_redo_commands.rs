@@ -48,6 +48,10 @@ fn test_undo_merge_operation() {
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation dbcb2561b6fe: new empty commit
+    Merging operation b6c93bcb296f: new empty commit
+    Warning: Concurrent operations changed the working-copy commit of workspaces: default
+    Hint: Kept the working-copy commit of the operation dbcb2561b6fe
     Error: Cannot undo a merge operation
     Hint: Consider using `jj op restore` instead
     [EOF]
@@ -73,6 +77,15 @@ fn test_undo_push_operation() {
     let output = work_dir.run_jj(["undo"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
+    Error: Refusing to undo a push operation since the state of the remote can't be undone
+    Hint: Use --force to undo it anyway
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["undo", "--force"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
     Warning: Undoing a push operation often leads to conflicted bookmarks.
     Hint: To avoid this, run `jj redo` now.
     Restored to operation: f9fd582ef03c (2001-02-03 08:05:09) commit 3850397cf31988d0657948307ad5bbe873d76a38
@@ -80,6 +93,168 @@ fn test_undo_push_operation() {
     ");
 }
 
+#[test]
+fn test_undo_push_operation_with_count() {
+    let test_env = TestEnvironment::default();
+
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "origin"])
+        .success();
+    test_env
+        .run_jj_in(".", ["git", "clone", "origin", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("foo", "foo");
+    work_dir.run_jj(["commit", "-mfoo"]).success();
+    work_dir.run_jj(["git", "push", "-c@-"]).success();
+    work_dir.run_jj(["new"]).success();
+
+    // The push operation is refused even if it isn't the latest operation
+    let output = work_dir.run_jj(["undo", "--steps=2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Refusing to undo a push operation since the state of the remote can't be undone
+    Hint: Use --force to undo it anyway
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_undo_multiple_operations() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-mA"]).success();
+    work_dir.write_file("state", "B");
+    work_dir.run_jj(["describe", "-mB"]).success();
+    work_dir.run_jj(["new", "-mC"]).success();
+    work_dir.write_file("state", "D");
+
+    // The snapshot operations are undone, but not counted. The working copy is
+    // restored to the snapshot taken before "describe -mB".
+    let output = work_dir.run_jj(["undo", "--steps=2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Restored to operation: c3232433fd89 (2001-02-03 08:05:09) snapshot working copy
+    Working copy  (@) now at: qpvuntsm d66f4fa8 A
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @  A
+    ◆
+    [EOF]
+    ");
+    assert_eq!(work_dir.read_file("state"), "B");
+
+    // Undoing multiple operations is recorded as a single undo-operation
+    work_dir.run_jj(["redo"]).success();
+    assert_eq!(work_dir.read_file("state"), "D");
+
+    work_dir.run_jj(["undo"]).success();
+    work_dir.run_jj(["undo"]).success();
+    let output = work_dir.run_jj(["log", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @  A
+    ◆
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["redo", "--steps=2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Restored to operation: 7f3d319c7e1d (2001-02-03 08:05:11) snapshot working copy
+    Working copy  (@) now at: zsuskuln 33b770a3 C
+    Parent commit (@-)      : qpvuntsm 1fc530ce B
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    ");
+    assert_eq!(work_dir.read_file("state"), "D");
+
+    let output = work_dir.run_jj(["redo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Nothing to redo
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["undo", "--steps=0"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: The number of operations to undo must be positive
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The number of steps can't be combined with the deprecated operation
+    let output = work_dir.run_jj(["undo", "--steps=1", "@"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the argument '--steps <N>' cannot be used with '[OPERATION]'
+
+    Usage: jj undo --steps <N> [OPERATION]
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
+#[test]
+fn test_undo_redo_preview() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-mA"]).success();
+    work_dir.run_jj(["new", "-mB"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "foo"])
+        .success();
+
+    let output = work_dir.run_jj(["undo", "--preview", "--steps=2"]);
+    insta::assert_snapshot!(output, @r"
+    Would restore to operation: 129e2d05f267 (2001-02-03 08:05:08) describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+
+    Changed commits:
+    ○  - kkmpptxz 807cecf2 (empty) B
+
+    Changed working copy default@:
+    + qpvuntsm 8777db25 foo | (empty) A
+    - kkmpptxz 807cecf2 (empty) B
+
+    Changed local bookmarks:
+    foo:
+    + (absent)
+    - qpvuntsm 8777db25 foo | (empty) A
+    [EOF]
+    ");
+
+    // Nothing was undone
+    let output = work_dir.run_jj(["op", "log", "-n1", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @  create bookmark foo pointing to commit 8777db25171cace71ad014598663d5ffc4fae6b1
+    [EOF]
+    ");
+
+    work_dir.run_jj(["undo"]).success();
+    let output = work_dir.run_jj(["redo", "--preview"]);
+    insta::assert_snapshot!(output, @r"
+    Would restore to operation: f206b5aac115 (2001-02-03 08:05:10) create bookmark foo pointing to commit 8777db25171cace71ad014598663d5ffc4fae6b1
+
+    Changed local bookmarks:
+    foo:
+    + qpvuntsm 8777db25 (empty) A
+    - (absent)
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_undo_jump_old_undo_stack() {
     let test_env = TestEnvironment::default();
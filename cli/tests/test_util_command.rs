@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use insta::assert_snapshot;
+use regex::Regex;
 
+use crate::common::CommandOutput;
 use crate::common::TestEnvironment;
 
 #[test]
@@ -45,7 +47,11 @@ fn test_gc_args() {
     let work_dir = test_env.work_dir("repo");
 
     let output = work_dir.run_jj(["util", "gc"]);
-    insta::assert_snapshot!(output, @"");
+    insta::assert_snapshot!(normalize_reclaimed_size(output), @r"
+    ------- stderr -------
+    Reclaimed [size] of disk space.
+    [EOF]
+    ");
 
     let output = work_dir.run_jj(["util", "gc", "--at-op=@-"]);
     insta::assert_snapshot!(output, @r"
@@ -103,6 +109,264 @@ fn test_gc_operation_log() {
     ");
 }
 
+#[test]
+fn test_gc_prune_operations() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["new", "-m", "A"]).success();
+    work_dir.run_jj(["new", "-m", "B"]).success();
+    work_dir.run_jj(["new", "-m", "C"]).success();
+    work_dir.run_jj(["op", "tag", "@-", "release=1"]).success();
+    work_dir.run_jj(["new", "-m", "D"]).success();
+    let op_log = || {
+        work_dir.run_jj([
+            "op",
+            "log",
+            "--no-graph",
+            "-T",
+            r#"separate(" ", description, self.tag("release")) ++ "\n""#,
+        ])
+    };
+
+    // Operations aren't pruned by default
+    let output = work_dir.run_jj(["util", "gc"]);
+    insta::assert_snapshot!(normalize_reclaimed_size(output), @r"
+    ------- stderr -------
+    Reclaimed [size] of disk space.
+    [EOF]
+    ");
+    insta::assert_snapshot!(op_log(), @r"
+    new empty commit
+    new empty commit
+    new empty commit 1
+    new empty commit
+    add workspace 'default'
+
+    [EOF]
+    ");
+
+    // Operations are recorded at the configured timestamp, which is within the
+    // retention period
+    let output = work_dir.run_jj(["util", "gc", "--prune-operations"]);
+    insta::assert_snapshot!(normalize_reclaimed_size(output), @r"
+    ------- stderr -------
+    Reclaimed [size] of disk space.
+    [EOF]
+    ");
+
+    // Without retention period, only the current and tagged operations are kept
+    let output = work_dir.run_jj([
+        "util",
+        "gc",
+        "--prune-operations",
+        "--config=gc.operations.keep-all-days=0",
+        "--config=gc.operations.keep-daily-weeks=0",
+    ]);
+    insta::assert_snapshot!(normalize_reclaimed_size(output), @r"
+    ------- stderr -------
+    Pruned 3 operations and reparented 2 descendant operations.
+    Reclaimed [size] of disk space.
+    [EOF]
+    ");
+    insta::assert_snapshot!(op_log(), @r"
+    new empty commit
+    new empty commit 1
+
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-T", "description"]);
+    insta::assert_snapshot!(output, @r"
+    @  D
+    ○  C
+    ○  B
+    ○  A
+    ○
+    ◆
+    [EOF]
+    ");
+
+    // Nothing to prune
+    let output = work_dir.run_jj([
+        "util",
+        "gc",
+        "--config=gc.operations.prune=true",
+        "--config=gc.operations.keep-all-days=0",
+        "--config=gc.operations.keep-daily-weeks=0",
+    ]);
+    insta::assert_snapshot!(normalize_reclaimed_size(output), @r"
+    ------- stderr -------
+    Reclaimed [size] of disk space.
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_gc_prune_operations_threshold() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["new", "-m", "A"]).success();
+    work_dir.run_jj(["new", "-m", "B"]).success();
+    work_dir.run_jj(["new", "-m", "C"]).success();
+    let op_log = || {
+        work_dir.run_jj([
+            "op",
+            "log",
+            "--no-graph",
+            "-T",
+            r#"separate(" ", time.end().format("%H:%M:%S"), description) ++ "\n""#,
+        ])
+    };
+    insta::assert_snapshot!(op_log(), @r"
+    04:05:10 new empty commit
+    04:05:09 new empty commit
+    04:05:08 new empty commit
+    04:05:07 add workspace 'default'
+    00:00:00
+    [EOF]
+    ");
+
+    // Operations which ended exactly one day before are kept
+    let output = work_dir.run_jj([
+        "util",
+        "gc",
+        "--prune-operations",
+        "--config=debug.operation-timestamp=2001-02-04T04:05:09+07:00",
+        "--config=gc.operations.keep-all-days=1",
+        "--config=gc.operations.keep-daily-weeks=0",
+    ]);
+    insta::assert_snapshot!(normalize_reclaimed_size(output), @r"
+    ------- stderr -------
+    Pruned 2 operations and reparented 2 descendant operations.
+    Reclaimed [size] of disk space.
+    [EOF]
+    ");
+    insta::assert_snapshot!(op_log(), @r"
+    04:05:10 new empty commit
+    04:05:09 new empty commit
+    00:00:00
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_gc_prune_operations_multiple_workspaces() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["new", "-m", "A"]).success();
+    work_dir
+        .run_jj(["workspace", "add", "../secondary"])
+        .success();
+    let prune_args = [
+        "--config=gc.operations.keep-all-days=0",
+        "--config=gc.operations.keep-daily-weeks=0",
+    ];
+
+    let output = work_dir.run_jj(
+        ["util", "gc", "--prune-operations"]
+            .iter()
+            .chain(&prune_args),
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Cannot prune operations in a repo with multiple workspaces
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(
+        ["util", "gc", "--config=gc.operations.prune=true"]
+            .iter()
+            .chain(&prune_args),
+    );
+    insta::assert_snapshot!(normalize_reclaimed_size(output), @r"
+    ------- stderr -------
+    Warning: Cannot prune operations in a repo with multiple workspaces
+    Reclaimed [size] of disk space.
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_util_migrate_op_store() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(
+            ".",
+            ["git", "init", "repo", "--config=operation.store=table"],
+        )
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    let store_type_path = work_dir.root().join(".jj/repo/op_store/type");
+    assert_eq!(
+        std::fs::read_to_string(&store_type_path).unwrap(),
+        "table_op_store"
+    );
+
+    work_dir.write_file("file", "a change\n");
+    work_dir.run_jj(["commit", "-m", "a change"]).success();
+    work_dir
+        .run_jj(["describe", "-m", "another change"])
+        .success();
+    let template = r#"id.short() ++ " " ++ description ++ "\n""#;
+    let op_log = work_dir.run_jj(["op", "log", "-T", template]).success();
+    insta::assert_snapshot!(op_log, @r"
+    @  b09fcd17d2a5 describe commit a44eb3e8c0c01f354a7e845615121a833ea906b4
+    ○  b50d0a8f111a commit 8b157fea292461303265e56568d4dd7db079c3fa
+    ○  cd78b9ace2f3 snapshot working copy
+    ○  8f47435a3990 add workspace 'default'
+    ○  000000000000
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["util", "migrate-op-store", "simple"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Converted 4 operations to the simple format.
+    [EOF]
+    ");
+    assert_eq!(
+        std::fs::read_to_string(&store_type_path).unwrap(),
+        "simple_op_store"
+    );
+    let output = work_dir.run_jj(["op", "log", "-T", template]).success();
+    assert_eq!(output.stdout.raw(), op_log.stdout.raw());
+
+    let output = work_dir.run_jj(["util", "migrate-op-store", "table"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Converted 4 operations to the table format.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["util", "migrate-op-store", "table"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+
+    // Unreachable operations can be garbage collected
+    work_dir.run_jj(["op", "abandon", "..@-"]).success();
+    work_dir.run_jj(["util", "gc", "--expire=now"]).success();
+    let output = work_dir.run_jj(["op", "log", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    @  a32cff8012a1 describe commit a44eb3e8c0c01f354a7e845615121a833ea906b4
+    ○  000000000000
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r", "all()"]);
+    insta::assert_snapshot!(output, @r"
+    @  rlvkpnrz test.user@example.com 2001-02-03 08:05:09 592aa0da
+    │  (empty) another change
+    ○  qpvuntsm test.user@example.com 2001-02-03 08:05:08 71c942ce
+    │  a change
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_shell_completions() {
     #[track_caller]
@@ -206,3 +470,8 @@ fn test_util_exec_sets_env() {
     [EOF]
     "###);
 }
+
+fn normalize_reclaimed_size(output: CommandOutput) -> CommandOutput {
+    let regex = Regex::new(r"Reclaimed [0-9.]+[A-Za-z]*B").unwrap();
+    output.normalize_stderr_with(|text| regex.replace_all(&text, "Reclaimed [size]").into_owned())
+}
@@ -232,6 +232,8 @@ fn test_workspaces_add_at_operation() {
     [EOF]
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation 07ec70db2f8c: commit 9152e822279787a168ddf4cede6440a21faa00d7
+    Merging operation 3a951ec0075c: create initial working-copy commit in workspace secondary
     [EOF]
     ");
 
@@ -551,6 +553,10 @@ fn test_workspaces_conflicting_edits() {
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation a24f51e22ff7: squash commits into 9a462e35578a347e6a3951bf7a58ad7146959a8b
+    Merging operation 3293dbf3c3c7: snapshot working copy
+    Warning: Concurrent operations changed the working-copy commit of workspaces: secondary
+    Hint: Kept the working-copy commit of the operation a24f51e22ff7
     Rebased 1 descendant commits onto commits rewritten by other operation
     Working copy  (@) now at: pmmvwywv?? 90f3d42e (empty) (no description set)
     Parent commit (@-)      : qpvuntsm b853f7c8 (no description set)
@@ -963,6 +969,8 @@ fn test_workspaces_update_stale_snapshot() {
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
+    Merging operation cae281640587: new empty commit
+    Merging operation f571d1306277: snapshot working copy
     Attempted recovery, but the working copy is not stale
     [EOF]
     ");
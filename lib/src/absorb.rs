@@ -37,6 +37,7 @@ use crate::conflicts::MaterializedTreeValue;
 use crate::conflicts::materialized_diff_stream;
 use crate::copies::CopyRecords;
 use crate::diff::ContentDiff;
+use crate::diff::DiffAlgorithm;
 use crate::diff::DiffHunkKind;
 use crate::matchers::Matcher;
 use crate::merge::Merge;
@@ -94,6 +95,7 @@ pub async fn split_hunks_to_trees(
     source: &AbsorbSource,
     destinations: &Arc<ResolvedRevsetExpression>,
     matcher: &dyn Matcher,
+    diff_algorithm: DiffAlgorithm,
 ) -> Result<SelectedTrees, AbsorbError> {
     let mut selected_trees = SelectedTrees::default();
 
@@ -136,13 +138,14 @@ pub async fn split_hunks_to_trees(
         // Compute annotation of parent (= left) content to map right hunks
         let mut annotator =
             FileAnnotator::with_file_content(source.commit.id(), left_path, left_text.clone());
+        annotator.set_diff_algorithm(diff_algorithm);
         annotator.compute(repo, destinations)?;
         let annotation = annotator.to_annotation();
         let annotation_ranges = annotation
             .compact_line_ranges()
             .filter_map(|(commit_id, range)| Some((commit_id.ok()?, range)))
             .collect_vec();
-        let diff = ContentDiff::by_line([&left_text, &right_text]);
+        let diff = ContentDiff::by_line_with_algorithm([&left_text, &right_text], diff_algorithm);
         let selected_ranges = split_file_hunks(&annotation_ranges, &diff);
         // Build trees containing parent (= left) contents + selected hunks
         for (&commit_id, ranges) in &selected_ranges {
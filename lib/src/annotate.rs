@@ -39,6 +39,7 @@ use crate::conflicts::MaterializedTreeValue;
 use crate::conflicts::materialize_merge_result_to_bytes;
 use crate::conflicts::materialize_tree_value;
 use crate::diff::ContentDiff;
+use crate::diff::DiffAlgorithm;
 use crate::diff::DiffHunkKind;
 use crate::files::FileMergeHunkLevel;
 use crate::fileset::FilesetExpression;
@@ -155,6 +156,7 @@ pub struct FileAnnotator {
     // If we add copy-tracing support, file_path might be tracked by state.
     file_path: RepoPathBuf,
     starting_text: BString,
+    diff_algorithm: DiffAlgorithm,
     state: AnnotationState,
 }
 
@@ -204,10 +206,17 @@ impl FileAnnotator {
         Self {
             file_path: file_path.to_owned(),
             starting_text,
+            diff_algorithm: DiffAlgorithm::Default,
             state,
         }
     }
 
+    /// Sets the algorithm to find lines in common between file revisions.
+    pub fn set_diff_algorithm(&mut self, algorithm: DiffAlgorithm) -> &mut Self {
+        self.diff_algorithm = algorithm;
+        self
+    }
+
     /// Computes line-by-line annotation within the `domain`.
     ///
     /// The `domain` expression narrows the range of ancestors to search. It
@@ -218,7 +227,13 @@ impl FileAnnotator {
         repo: &dyn Repo,
         domain: &Arc<ResolvedRevsetExpression>,
     ) -> Result<(), RevsetEvaluationError> {
-        process_commits(repo, &mut self.state, domain, &self.file_path)
+        process_commits(
+            repo,
+            &mut self.state,
+            domain,
+            &self.file_path,
+            self.diff_algorithm,
+        )
     }
 
     /// Remaining commit ids to visit from.
@@ -298,6 +313,7 @@ fn process_commits(
     state: &mut AnnotationState,
     domain: &Arc<ResolvedRevsetExpression>,
     file_name: &RepoPath,
+    diff_algorithm: DiffAlgorithm,
 ) -> Result<(), RevsetEvaluationError> {
     let predicate = RevsetFilterPredicate::File(FilesetExpression::file_path(file_name.to_owned()));
     // TODO: If the domain isn't a contiguous range, changes masked out by it
@@ -313,7 +329,14 @@ fn process_commits(
     state.num_unresolved_roots = 0;
     for node in revset.iter_graph() {
         let (commit_id, edge_list) = node?;
-        process_commit(repo, file_name, state, &commit_id, &edge_list)?;
+        process_commit(
+            repo,
+            file_name,
+            diff_algorithm,
+            state,
+            &commit_id,
+            &edge_list,
+        )?;
         if state.commit_source_map.len() == state.num_unresolved_roots {
             // No more lines to propagate to ancestors.
             break;
@@ -328,6 +351,7 @@ fn process_commits(
 fn process_commit(
     repo: &dyn Repo,
     file_name: &RepoPath,
+    diff_algorithm: DiffAlgorithm,
     state: &mut AnnotationState,
     current_commit_id: &CommitId,
     edges: &[GraphEdge<CommitId>],
@@ -360,6 +384,7 @@ fn process_commit(
         copy_same_lines_with(
             &current_source.text,
             &parent_source.text,
+            diff_algorithm,
             |current_start, parent_start, count| {
                 new_current_line_map
                     .extend(current_lines.peeking_take_while(|&(cur, _)| cur < current_start));
@@ -412,9 +437,10 @@ fn process_commit(
 fn copy_same_lines_with(
     current_contents: &[u8],
     parent_contents: &[u8],
+    algorithm: DiffAlgorithm,
     mut copy: impl FnMut(usize, usize, usize),
 ) {
-    let diff = ContentDiff::by_line([current_contents, parent_contents]);
+    let diff = ContentDiff::by_line_with_algorithm([current_contents, parent_contents], algorithm);
     let mut current_line_counter: usize = 0;
     let mut parent_line_counter: usize = 0;
     for hunk in diff.hunks() {
@@ -452,6 +478,7 @@ async fn get_file_contents(
                 merge: MergeOptions {
                     hunk_level: FileMergeHunkLevel::Line,
                     same_change: SameChange::Accept,
+                    diff_algorithm: store.merge_options().diff_algorithm,
                 },
             };
             Ok(materialize_merge_result_to_bytes(&file.contents, &options))
@@ -1412,7 +1412,10 @@ fn diff_match_lines(
         let rights: Merge<BString> = rights.map(|text| match_lines(text, matcher).collect());
         let lefts = files::merge(&lefts, merge_options);
         let rights = files::merge(&rights, merge_options);
-        let diff = ContentDiff::by_line(lefts.iter().chain(rights.iter()));
+        let diff = ContentDiff::by_line_with_algorithm(
+            lefts.iter().chain(rights.iter()),
+            merge_options.diff_algorithm,
+        );
         let different = files::conflict_diff_hunks(diff.hunks(), lefts.as_slice().len())
             .any(|hunk| hunk.kind == DiffHunkKind::Different);
         Ok(different)
@@ -1460,6 +1463,7 @@ mod tests {
     use super::*;
     use crate::default_index::DefaultMutableIndex;
     use crate::default_index::readonly::FieldLengths;
+    use crate::diff::DiffAlgorithm;
     use crate::files::FileMergeHunkLevel;
     use crate::merge::SameChange;
     use crate::str_util::StringPattern;
@@ -1853,6 +1857,7 @@ mod tests {
             let options = MergeOptions {
                 hunk_level: FileMergeHunkLevel::Line,
                 same_change: SameChange::Accept,
+                diff_algorithm: DiffAlgorithm::Default,
             };
             diff_match_lines(&left1, &left2, &matcher, &options).unwrap()
         };
@@ -1878,6 +1883,7 @@ mod tests {
             let options = MergeOptions {
                 hunk_level: FileMergeHunkLevel::Line,
                 same_change: SameChange::Accept,
+                diff_algorithm: DiffAlgorithm::Default,
             };
             diff_match_lines(&conflict1, &conflict2, &matcher, &options).unwrap()
         };
@@ -1907,6 +1913,7 @@ mod tests {
             let options = MergeOptions {
                 hunk_level: FileMergeHunkLevel::Line,
                 same_change: SameChange::Accept,
+                diff_algorithm: DiffAlgorithm::Default,
             };
             diff_match_lines(&base, &conflict2, &matcher, &options).unwrap()
         };
@@ -183,6 +183,25 @@ impl CompareBytes for CompareBytesIgnoreWhitespaceAmount {
     }
 }
 
+/// Algorithm to find unchanged words (or tokens) between inputs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffAlgorithm {
+    /// Anchors on the least frequent words shared by both sides, then matches
+    /// leading and trailing words.
+    #[default]
+    Default,
+    /// Anchors on words that occur exactly once on both sides. Falls back to
+    /// the Myers algorithm if there are no such words.
+    Patience,
+    /// Anchors on the common region containing the least frequent words,
+    /// similar to `git diff --histogram`. Falls back to the Myers algorithm if
+    /// all words occur too many times.
+    Histogram,
+    /// Myers algorithm, which finds the minimal number of changed words.
+    Minimal,
+}
+
 // Not implementing Eq because the text should be compared by WordComparator.
 #[derive(Clone, Copy, Debug)]
 struct HashedWord<'input> {
@@ -301,6 +320,12 @@ impl<'input> LocalDiffSource<'input, '_> {
             HashedWord { hash, text }
         })
     }
+
+    fn hashed_word_at(&self, position: LocalWordPosition) -> HashedWord<'input> {
+        let hash = self.hashes[position.0];
+        let text = &self.text[self.ranges[position.0].clone()];
+        HashedWord { hash, text }
+    }
 }
 
 struct Histogram<'input> {
@@ -547,6 +572,404 @@ fn collect_unchanged_words_lcs<C: CompareBytes, S: BuildHasher>(
     );
 }
 
+/// Finds unchanged word (or token) positions by the given `algorithm`.
+fn collect_unchanged_words_by<C: CompareBytes, S: BuildHasher>(
+    found_positions: &mut Vec<(WordPosition, WordPosition)>,
+    left: &LocalDiffSource,
+    right: &LocalDiffSource,
+    comp: &WordComparator<C, S>,
+    algorithm: DiffAlgorithm,
+) {
+    match algorithm {
+        DiffAlgorithm::Default => collect_unchanged_words(found_positions, left, right, comp),
+        DiffAlgorithm::Patience => {
+            collect_unchanged_words_patience(found_positions, left, right, comp);
+        }
+        DiffAlgorithm::Histogram => {
+            collect_unchanged_words_histogram(found_positions, left, right, comp);
+        }
+        DiffAlgorithm::Minimal => collect_unchanged_words_myers(found_positions, left, right, comp),
+    }
+}
+
+/// Returns the numbers of common leading and trailing words.
+fn count_common_affixes<C: CompareBytes, S: BuildHasher>(
+    left: &LocalDiffSource,
+    right: &LocalDiffSource,
+    comp: &WordComparator<C, S>,
+) -> (usize, usize) {
+    let common_leading_len = iter::zip(left.hashed_words(), right.hashed_words())
+        .take_while(|&(l, r)| comp.eq_hashed(l, r))
+        .count();
+    let common_trailing_len = iter::zip(
+        left.hashed_words().skip(common_leading_len).rev(),
+        right.hashed_words().skip(common_leading_len).rev(),
+    )
+    .take_while(|&(l, r)| comp.eq_hashed(l, r))
+    .count();
+    (common_leading_len, common_trailing_len)
+}
+
+/// Adds `len` unchanged words starting at the given local positions.
+fn push_unchanged_words(
+    found_positions: &mut Vec<(WordPosition, WordPosition)>,
+    left: &LocalDiffSource,
+    right: &LocalDiffSource,
+    [left_start, right_start]: [LocalWordPosition; 2],
+    len: usize,
+) {
+    found_positions.extend((0..len).map(|i| {
+        (
+            left.map_to_global(LocalWordPosition(left_start.0 + i)),
+            right.map_to_global(LocalWordPosition(right_start.0 + i)),
+        )
+    }));
+}
+
+type CollectUnchangedWordsFn<'input, C, S> = fn(
+    &mut Vec<(WordPosition, WordPosition)>,
+    &LocalDiffSource<'input, '_>,
+    &LocalDiffSource<'input, '_>,
+    &WordComparator<C, S>,
+);
+
+/// Trims common leading and trailing words, and calls `collect_middle` with
+/// the remaining words if both sides are non-empty.
+fn collect_unchanged_words_trimmed<'input, C: CompareBytes, S: BuildHasher>(
+    found_positions: &mut Vec<(WordPosition, WordPosition)>,
+    left: &LocalDiffSource<'input, '_>,
+    right: &LocalDiffSource<'input, '_>,
+    comp: &WordComparator<C, S>,
+    collect_middle: CollectUnchangedWordsFn<'input, C, S>,
+) {
+    let (common_leading_len, common_trailing_len) = count_common_affixes(left, right, comp);
+    let start = LocalWordPosition(common_leading_len);
+    let left_end = LocalWordPosition(left.ranges.len() - common_trailing_len);
+    let right_end = LocalWordPosition(right.ranges.len() - common_trailing_len);
+    push_unchanged_words(
+        found_positions,
+        left,
+        right,
+        [LocalWordPosition(0); 2],
+        common_leading_len,
+    );
+    if start < left_end && start < right_end {
+        collect_middle(
+            found_positions,
+            &left.narrowed(start..left_end),
+            &right.narrowed(start..right_end),
+            comp,
+        );
+    }
+    push_unchanged_words(
+        found_positions,
+        left,
+        right,
+        [left_end, right_end],
+        common_trailing_len,
+    );
+}
+
+/// Finds unchanged word positions by the Myers algorithm. The inputs are
+/// recursively bisected at the middle snake so the memory usage stays linear.
+fn collect_unchanged_words_myers<C: CompareBytes, S: BuildHasher>(
+    found_positions: &mut Vec<(WordPosition, WordPosition)>,
+    left: &LocalDiffSource,
+    right: &LocalDiffSource,
+    comp: &WordComparator<C, S>,
+) {
+    collect_unchanged_words_trimmed(
+        found_positions,
+        left,
+        right,
+        comp,
+        |found, left, right, comp| {
+            let Some((left_mid, right_mid)) = find_middle_snake(left, right, comp) else {
+                // Shouldn't happen, but don't lose the unchanged words if the
+                // search gave up.
+                collect_unchanged_words(found, left, right, comp);
+                return;
+            };
+            let left_len = LocalWordPosition(left.ranges.len());
+            let right_len = LocalWordPosition(right.ranges.len());
+            collect_unchanged_words_myers(
+                found,
+                &left.narrowed(LocalWordPosition(0)..left_mid),
+                &right.narrowed(LocalWordPosition(0)..right_mid),
+                comp,
+            );
+            collect_unchanged_words_myers(
+                found,
+                &left.narrowed(left_mid..left_len),
+                &right.narrowed(right_mid..right_len),
+                comp,
+            );
+        },
+    );
+}
+
+/// Finds the point where the forward and backward shortest edit paths meet.
+/// Both sides should be non-empty and start/end with different words.
+fn find_middle_snake<C: CompareBytes, S: BuildHasher>(
+    left: &LocalDiffSource,
+    right: &LocalDiffSource,
+    comp: &WordComparator<C, S>,
+) -> Option<(LocalWordPosition, LocalWordPosition)> {
+    let n = left.ranges.len() as isize;
+    let m = right.ranges.len() as isize;
+    let eq = |x: isize, y: isize| {
+        comp.eq_hashed(
+            left.hashed_word_at(LocalWordPosition(x as usize)),
+            right.hashed_word_at(LocalWordPosition(y as usize)),
+        )
+    };
+    let max_d = (n + m + 1) / 2;
+    let offset = max_d;
+    let v_len = 2 * max_d + 2;
+    // Furthest reaching x positions indexed by diagonal k = x - y (offset by
+    // max_d). The backward positions are counted from the ends.
+    let mut forward = vec![-1; v_len as usize];
+    let mut backward = vec![-1; v_len as usize];
+    forward[(offset + 1) as usize] = 0;
+    backward[(offset + 1) as usize] = 0;
+    let delta = n - m;
+    // If the total number of words is odd, the paths meet while extending
+    // the forward path. Otherwise, while extending the backward path.
+    let check_forward = delta % 2 != 0;
+    let (mut k1_start, mut k1_end, mut k2_start, mut k2_end) = (0, 0, 0, 0);
+    for d in 0..max_d {
+        let mut k1 = -d + k1_start;
+        while k1 <= d - k1_end {
+            let k1_index = (offset + k1) as usize;
+            let mut x1 = if k1 == -d || (k1 != d && forward[k1_index - 1] < forward[k1_index + 1]) {
+                forward[k1_index + 1]
+            } else {
+                forward[k1_index - 1] + 1
+            };
+            let mut y1 = x1 - k1;
+            while x1 < n && y1 < m && eq(x1, y1) {
+                x1 += 1;
+                y1 += 1;
+            }
+            forward[k1_index] = x1;
+            if x1 > n {
+                k1_end += 2;
+            } else if y1 > m {
+                k1_start += 2;
+            } else if check_forward {
+                let k2_index = offset + delta - k1;
+                if (0..v_len).contains(&k2_index) && backward[k2_index as usize] != -1 {
+                    let x2 = n - backward[k2_index as usize];
+                    if x1 >= x2 {
+                        return Some((
+                            LocalWordPosition(x1 as usize),
+                            LocalWordPosition(y1 as usize),
+                        ));
+                    }
+                }
+            }
+            k1 += 2;
+        }
+
+        let mut k2 = -d + k2_start;
+        while k2 <= d - k2_end {
+            let k2_index = (offset + k2) as usize;
+            let mut x2 = if k2 == -d || (k2 != d && backward[k2_index - 1] < backward[k2_index + 1])
+            {
+                backward[k2_index + 1]
+            } else {
+                backward[k2_index - 1] + 1
+            };
+            let mut y2 = x2 - k2;
+            while x2 < n && y2 < m && eq(n - x2 - 1, m - y2 - 1) {
+                x2 += 1;
+                y2 += 1;
+            }
+            backward[k2_index] = x2;
+            if x2 > n {
+                k2_end += 2;
+            } else if y2 > m {
+                k2_start += 2;
+            } else if !check_forward {
+                let k1_index = offset + delta - k2;
+                if (0..v_len).contains(&k1_index) && forward[k1_index as usize] != -1 {
+                    let x1 = forward[k1_index as usize];
+                    let y1 = offset + x1 - k1_index;
+                    if x1 >= n - x2 {
+                        return Some((
+                            LocalWordPosition(x1 as usize),
+                            LocalWordPosition(y1 as usize),
+                        ));
+                    }
+                }
+            }
+            k2 += 2;
+        }
+    }
+    None
+}
+
+/// Finds unchanged word positions by the patience algorithm.
+fn collect_unchanged_words_patience<C: CompareBytes, S: BuildHasher>(
+    found_positions: &mut Vec<(WordPosition, WordPosition)>,
+    left: &LocalDiffSource,
+    right: &LocalDiffSource,
+    comp: &WordComparator<C, S>,
+) {
+    collect_unchanged_words_trimmed(
+        found_positions,
+        left,
+        right,
+        comp,
+        |found, left, right, comp| {
+            let left_histogram = Histogram::calculate(left, comp, 1);
+            let right_histogram = Histogram::calculate(right, comp, 1);
+            // [(left_position, right_position)] of unique words, sorted by right
+            let unique_positions = right
+                .hashed_words()
+                .enumerate()
+                .filter_map(|(i, word)| {
+                    let &[left_position] = left_histogram.positions_by_word(word, comp)? else {
+                        return None;
+                    };
+                    let &[right_position] = right_histogram.positions_by_word(word, comp)? else {
+                        return None;
+                    };
+                    debug_assert_eq!(right_position, LocalWordPosition(i));
+                    Some((left_position, right_position))
+                })
+                .collect_vec();
+            if unique_positions.is_empty() {
+                collect_unchanged_words_myers(found, left, right, comp);
+                return;
+            }
+            let left_index_by_right_index = unique_positions
+                .iter()
+                .map(|(left_position, _)| left_position.0)
+                .collect_vec();
+            let mut previous_left_position = LocalWordPosition(0);
+            let mut previous_right_position = LocalWordPosition(0);
+            for (_, right_index) in find_lcs(&left_index_by_right_index) {
+                let (left_position, right_position) = unique_positions[right_index];
+                collect_unchanged_words_patience(
+                    found,
+                    &left.narrowed(previous_left_position..left_position),
+                    &right.narrowed(previous_right_position..right_position),
+                    comp,
+                );
+                found.push((
+                    left.map_to_global(left_position),
+                    right.map_to_global(right_position),
+                ));
+                previous_left_position = LocalWordPosition(left_position.0 + 1);
+                previous_right_position = LocalWordPosition(right_position.0 + 1);
+            }
+            collect_unchanged_words_patience(
+                found,
+                &left.narrowed(previous_left_position..LocalWordPosition(left.ranges.len())),
+                &right.narrowed(previous_right_position..LocalWordPosition(right.ranges.len())),
+                comp,
+            );
+        },
+    );
+}
+
+/// Finds unchanged word positions by the histogram algorithm.
+fn collect_unchanged_words_histogram<C: CompareBytes, S: BuildHasher>(
+    found_positions: &mut Vec<(WordPosition, WordPosition)>,
+    left: &LocalDiffSource,
+    right: &LocalDiffSource,
+    comp: &WordComparator<C, S>,
+) {
+    collect_unchanged_words_trimmed(
+        found_positions,
+        left,
+        right,
+        comp,
+        |found, left, right, comp| {
+            let Some(([left_start, right_start], len)) = find_histogram_anchor(left, right, comp)
+            else {
+                collect_unchanged_words_myers(found, left, right, comp);
+                return;
+            };
+            collect_unchanged_words_histogram(
+                found,
+                &left.narrowed(LocalWordPosition(0)..left_start),
+                &right.narrowed(LocalWordPosition(0)..right_start),
+                comp,
+            );
+            push_unchanged_words(found, left, right, [left_start, right_start], len);
+            collect_unchanged_words_histogram(
+                found,
+                &left.narrowed(
+                    LocalWordPosition(left_start.0 + len)..LocalWordPosition(left.ranges.len()),
+                ),
+                &right.narrowed(
+                    LocalWordPosition(right_start.0 + len)..LocalWordPosition(right.ranges.len()),
+                ),
+                comp,
+            );
+        },
+    );
+}
+
+/// Finds the common region containing the least frequent words in `left`.
+/// Among regions of the same frequency, the longest one is chosen. Returns
+/// the start positions and length of the region.
+fn find_histogram_anchor<C: CompareBytes, S: BuildHasher>(
+    left: &LocalDiffSource,
+    right: &LocalDiffSource,
+    comp: &WordComparator<C, S>,
+) -> Option<([LocalWordPosition; 2], usize)> {
+    let max_occurrences = 64;
+    let histogram = Histogram::calculate(left, comp, max_occurrences);
+    let left_counts = left
+        .hashed_words()
+        .map(|word| histogram.positions_by_word(word, comp).unwrap().len())
+        .collect_vec();
+    let eq = |l: usize, r: usize| {
+        comp.eq_hashed(
+            left.hashed_word_at(LocalWordPosition(l)),
+            right.hashed_word_at(LocalWordPosition(r)),
+        )
+    };
+    // (count, [left_start, right_start], len)
+    let mut best: Option<(usize, [usize; 2], usize)> = None;
+    let mut right_position = 0;
+    while right_position < right.ranges.len() {
+        let word = right.hashed_word_at(LocalWordPosition(right_position));
+        let mut next_right_position = right_position + 1;
+        if let Some(left_positions) = histogram.positions_by_word(word, comp)
+            && left_positions.len() <= max_occurrences
+        {
+            for &LocalWordPosition(left_position) in left_positions {
+                let mut start = [left_position, right_position];
+                while start[0] > 0 && start[1] > 0 && eq(start[0] - 1, start[1] - 1) {
+                    start = [start[0] - 1, start[1] - 1];
+                }
+                let mut end = [left_position + 1, right_position + 1];
+                while end[0] < left.ranges.len()
+                    && end[1] < right.ranges.len()
+                    && eq(end[0], end[1])
+                {
+                    end = [end[0] + 1, end[1] + 1];
+                }
+                let len = end[0] - start[0];
+                let count = *left_counts[start[0]..end[0]].iter().min().unwrap();
+                if best.is_none_or(|(best_count, _, best_len)| {
+                    count < best_count || (count == best_count && len > best_len)
+                }) {
+                    best = Some((count, start, len));
+                }
+                next_right_position = next_right_position.max(end[1]);
+            }
+        }
+        right_position = next_right_position;
+    }
+    let (_, start, len) = best?;
+    Some((start.map(LocalWordPosition), len))
+}
+
 /// Intersects two sorted sequences of `(base, other)` word positions by
 /// `base`. `base` positions should refer to the same source text.
 fn intersect_unchanged_words(
@@ -612,6 +1035,17 @@ impl<'input> ContentDiff<'input> {
         inputs: impl IntoIterator<Item = &'input T>,
         tokenizer: impl Fn(&[u8]) -> Vec<Range<usize>>,
         compare: impl CompareBytes,
+    ) -> Self {
+        Self::for_tokenizer_with_algorithm(inputs, tokenizer, compare, DiffAlgorithm::Default)
+    }
+
+    /// Like [`Self::for_tokenizer()`], but finds unchanged tokens by the
+    /// given `algorithm`.
+    pub fn for_tokenizer_with_algorithm<T: AsRef<[u8]> + ?Sized + 'input>(
+        inputs: impl IntoIterator<Item = &'input T>,
+        tokenizer: impl Fn(&[u8]) -> Vec<Range<usize>>,
+        compare: impl CompareBytes,
+        algorithm: DiffAlgorithm,
     ) -> Self {
         let mut inputs = inputs.into_iter().map(BStr::new);
         let base_input = inputs.next().expect("inputs must not be empty");
@@ -639,6 +1073,7 @@ impl<'input> ContentDiff<'input> {
             &base_token_ranges,
             &other_token_ranges,
             compare,
+            algorithm,
         )
     }
 
@@ -648,6 +1083,7 @@ impl<'input> ContentDiff<'input> {
         base_token_ranges: &[Range<usize>],
         other_token_ranges: &[Vec<Range<usize>>],
         compare: impl CompareBytes,
+        algorithm: DiffAlgorithm,
     ) -> Self {
         assert_eq!(other_inputs.len(), other_token_ranges.len());
         let comp = WordComparator::new(compare);
@@ -675,11 +1111,12 @@ impl<'input> ContentDiff<'input> {
                     others: smallvec![0..0; other_inputs.len()],
                 });
                 let mut first_positions = Vec::new();
-                collect_unchanged_words(
+                collect_unchanged_words_by(
                     &mut first_positions,
                     &base_source.local(),
                     &first_other_source.local(),
                     &comp,
+                    algorithm,
                 );
                 if tail_other_sources.is_empty() {
                     unchanged_regions.extend(first_positions.iter().map(
@@ -701,11 +1138,12 @@ impl<'input> ContentDiff<'input> {
                         first_positions,
                         |current_positions, other_source| {
                             let mut new_positions = Vec::new();
-                            collect_unchanged_words(
+                            collect_unchanged_words_by(
                                 &mut new_positions,
                                 &base_source.local(),
                                 &other_source.local(),
                                 &comp,
+                                algorithm,
                             );
                             intersect_unchanged_words(current_positions, &new_positions)
                         },
@@ -755,6 +1193,19 @@ impl<'input> ContentDiff<'input> {
         ContentDiff::for_tokenizer(inputs, find_line_ranges, CompareBytesExactly)
     }
 
+    /// Compares `inputs` line by line using the given `algorithm`.
+    pub fn by_line_with_algorithm<T: AsRef<[u8]> + ?Sized + 'input>(
+        inputs: impl IntoIterator<Item = &'input T>,
+        algorithm: DiffAlgorithm,
+    ) -> Self {
+        ContentDiff::for_tokenizer_with_algorithm(
+            inputs,
+            find_line_ranges,
+            CompareBytesExactly,
+            algorithm,
+        )
+    }
+
     /// Compares `inputs` word by word.
     ///
     /// The `inputs` is usually a changed hunk (e.g. a `DiffHunk::Different`)
@@ -1006,6 +1457,117 @@ impl Iterator for DiffHunkRangeIterator<'_> {
     }
 }
 
+/// Minimum number of alphanumeric characters in a run of lines to be detected
+/// as moved. Short runs such as `}` or blank lines are too common to be
+/// meaningful.
+const MIN_MOVED_LINES_ALNUM_COUNT: usize = 20;
+
+/// Maximum number of occurrences of a removed line to start a run of moved
+/// lines from. Lines such as `}` or blank lines can appear many times, and
+/// scanning runs from all of them would take quadratic time or worse.
+const MAX_MOVED_LINE_START_POSITIONS: usize = 16;
+
+/// Detects lines that are removed from one place and added to another.
+///
+/// `removed_blocks` and `added_blocks` are lists of contiguous changed lines,
+/// which may originate from different hunks or files. An added run of lines is
+/// detected as moved if the same run of lines is removed somewhere, and if it
+/// contains at least a certain number of alphanumeric characters. Line
+/// terminators aren't compared.
+///
+/// Returns `[removed, added]` flags indicating whether each line is moved, in
+/// the same shape as the input blocks.
+pub fn find_moved_lines<'input, C: CompareBytes>(
+    removed_blocks: &[Vec<&'input [u8]>],
+    added_blocks: &[Vec<&'input [u8]>],
+    compare: C,
+) -> [Vec<Vec<bool>>; 2] {
+    fn trim_line_terminator(line: &[u8]) -> &BStr {
+        BStr::new(line.strip_suffix(b"\n").unwrap_or(line))
+    }
+
+    let comp = WordComparator::new(compare);
+    let hashed_line = |line: &'input [u8]| {
+        let text = trim_line_terminator(line);
+        let hash = comp.hash_one(text);
+        HashedWord { hash, text }
+    };
+    let eq = |left: &[u8], right: &[u8]| {
+        comp.eq(trim_line_terminator(left), trim_line_terminator(right))
+    };
+    // Maps removed line to (block index, line index) positions.
+    let mut line_to_positions: HashTable<MovedLineEntry> = HashTable::new();
+    for (i, block) in removed_blocks.iter().enumerate() {
+        for (j, &line) in block.iter().enumerate() {
+            let line = hashed_line(line);
+            line_to_positions
+                .entry(
+                    line.hash,
+                    |&(l, _)| comp.eq_hashed(l, line),
+                    |(l, _)| l.hash,
+                )
+                .and_modify(|(_, positions)| positions.push((i, j)))
+                .or_insert_with(|| (line, vec![(i, j)]));
+        }
+    }
+
+    let mut removed_moved = removed_blocks
+        .iter()
+        .map(|block| vec![false; block.len()])
+        .collect_vec();
+    let mut added_moved = added_blocks
+        .iter()
+        .map(|block| vec![false; block.len()])
+        .collect_vec();
+    for (added_block, added_flags) in iter::zip(added_blocks, &mut added_moved) {
+        let mut j = 0;
+        while j < added_block.len() {
+            let line = hashed_line(added_block[j]);
+            let positions = line_to_positions
+                .find(line.hash, |&(l, _)| comp.eq_hashed(l, line))
+                .map_or(&[][..], |(_, positions)| positions);
+            let positions = if positions.len() <= MAX_MOVED_LINE_START_POSITIONS {
+                positions
+            } else {
+                &[]
+            };
+            // Pick the longest run of removed lines matching the added lines.
+            let longest = positions
+                .iter()
+                .map(|&(removed_index, start)| {
+                    let removed_block = &removed_blocks[removed_index];
+                    let len = iter::zip(&removed_block[start..], &added_block[j..])
+                        .take_while(|&(&left, &right)| eq(left, right))
+                        .count();
+                    (removed_index, start, len)
+                })
+                .rev()
+                .max_by_key(|&(_, _, len)| len);
+            match longest {
+                Some((removed_index, start, len))
+                    if count_alphanumeric(&added_block[j..j + len])
+                        >= MIN_MOVED_LINES_ALNUM_COUNT =>
+                {
+                    removed_moved[removed_index][start..start + len].fill(true);
+                    added_flags[j..j + len].fill(true);
+                    j += len;
+                }
+                _ => j += 1,
+            }
+        }
+    }
+    [removed_moved, added_moved]
+}
+
+type MovedLineEntry<'input> = (HashedWord<'input>, Vec<(usize, usize)>);
+
+fn count_alphanumeric(lines: &[&[u8]]) -> usize {
+    lines
+        .iter()
+        .map(|line| line.iter().filter(|b| b.is_ascii_alphanumeric()).count())
+        .sum()
+}
+
 /// Diffs slices of bytes.
 ///
 /// The returned diff hunks may be any length (may span many lines or
@@ -1193,14 +1755,28 @@ mod tests {
     }
 
     fn unchanged_ranges(
+        left: (&[u8], &[Range<usize>]),
+        right: (&[u8], &[Range<usize>]),
+    ) -> Vec<(Range<usize>, Range<usize>)> {
+        unchanged_ranges_by(left, right, DiffAlgorithm::Default)
+    }
+
+    fn unchanged_ranges_by(
         (left_text, left_ranges): (&[u8], &[Range<usize>]),
         (right_text, right_ranges): (&[u8], &[Range<usize>]),
+        algorithm: DiffAlgorithm,
     ) -> Vec<(Range<usize>, Range<usize>)> {
         let comp = WordComparator::new(CompareBytesExactly);
         let left = DiffSource::new(left_text, left_ranges, &comp);
         let right = DiffSource::new(right_text, right_ranges, &comp);
         let mut positions = Vec::new();
-        collect_unchanged_words(&mut positions, &left.local(), &right.local(), &comp);
+        collect_unchanged_words_by(
+            &mut positions,
+            &left.local(),
+            &right.local(),
+            &comp,
+            algorithm,
+        );
         positions
             .into_iter()
             .map(|(left_pos, right_pos)| (left.range_at(left_pos), right.range_at(right_pos)))
@@ -1335,6 +1911,124 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unchanged_ranges_by_algorithm() {
+        let unchanged_words = |left: &str, right: &str, algorithm| {
+            let left_ranges = find_word_ranges(left.as_bytes());
+            let right_ranges = find_word_ranges(right.as_bytes());
+            unchanged_ranges_by(
+                (left.as_bytes(), &left_ranges),
+                (right.as_bytes(), &right_ranges),
+                algorithm,
+            )
+            .into_iter()
+            .map(|(left_range, _)| &left[left_range])
+            .join(" ")
+        };
+
+        // Default anchors on the unique "b", so the leading "a" can't be matched.
+        let (left, right) = ("a a b x", "b a a x");
+        assert_eq!(unchanged_words(left, right, DiffAlgorithm::Default), "b x");
+        assert_eq!(unchanged_words(left, right, DiffAlgorithm::Patience), "b x");
+        assert_eq!(
+            unchanged_words(left, right, DiffAlgorithm::Histogram),
+            "b x"
+        );
+        assert_eq!(
+            unchanged_words(left, right, DiffAlgorithm::Minimal),
+            "a a x"
+        );
+
+        // No unique words, but the common region "x y" is the least frequent.
+        let (left, right) = ("a x y a b a", "b x y a a a");
+        assert_eq!(
+            unchanged_words(left, right, DiffAlgorithm::Patience),
+            "x y a a"
+        );
+        assert_eq!(
+            unchanged_words(left, right, DiffAlgorithm::Histogram),
+            "x y a a"
+        );
+        assert_eq!(
+            unchanged_words(left, right, DiffAlgorithm::Minimal),
+            "x y a a"
+        );
+    }
+
+    #[test]
+    fn test_unchanged_ranges_by_algorithm_against_lcs() {
+        fn lcs_len(left: &[u8], right: &[u8]) -> usize {
+            let mut table = vec![vec![0; right.len() + 1]; left.len() + 1];
+            for (i, l) in left.iter().enumerate() {
+                for (j, r) in right.iter().enumerate() {
+                    table[i + 1][j + 1] = if l == r {
+                        table[i][j] + 1
+                    } else {
+                        table[i][j + 1].max(table[i + 1][j])
+                    };
+                }
+            }
+            table[left.len()][right.len()]
+        }
+
+        // Generate inputs deterministically by xorshift. Small alphabets
+        // produce many repeated words, and large alphabets produce unique
+        // words to anchor on.
+        let mut state: u32 = 1;
+        let mut next_text = |alphabet: &[u8], max_len: u32| {
+            let mut next = || {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state
+            };
+            let len = next() % max_len;
+            (0..len)
+                .map(|_| alphabet[next() as usize % alphabet.len()])
+                .collect_vec()
+        };
+        let params = [
+            (&b"ab"[..], 16),
+            (b"abcd", 16),
+            (b"abcdefgh", 32),
+            (b"abcdefghijklmnopqrstuvwxyz", 48),
+        ];
+        for (alphabet, max_len) in params.into_iter().flat_map(|p| iter::repeat_n(p, 500)) {
+            let left = next_text(alphabet, max_len);
+            let right = next_text(alphabet, max_len);
+            let left_ranges = (0..left.len()).map(|i| i..i + 1).collect_vec();
+            let right_ranges = (0..right.len()).map(|i| i..i + 1).collect_vec();
+            let expected_len = lcs_len(&left, &right);
+            for algorithm in [
+                DiffAlgorithm::Default,
+                DiffAlgorithm::Patience,
+                DiffAlgorithm::Histogram,
+                DiffAlgorithm::Minimal,
+            ] {
+                let ranges =
+                    unchanged_ranges_by((&left, &left_ranges), (&right, &right_ranges), algorithm);
+                assert!(
+                    ranges
+                        .iter()
+                        .all(|(l, r)| left[l.clone()] == right[r.clone()])
+                );
+                assert!(
+                    ranges
+                        .iter()
+                        .tuple_windows()
+                        .all(|((l1, r1), (l2, r2))| l1.end <= l2.start && r1.end <= r2.start)
+                );
+                assert!(
+                    ranges.len() <= expected_len,
+                    "{algorithm:?} {left:?} {right:?}"
+                );
+                if algorithm == DiffAlgorithm::Minimal {
+                    assert_eq!(ranges.len(), expected_len, "{left:?} {right:?}");
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_diff_single_input() {
         assert_eq!(diff(["abc"]), vec![DiffHunk::matching(["abc"])]);
@@ -1778,4 +2472,93 @@ int main(int argc, char **argv)
             ]
         );
     }
+
+    #[test]
+    fn test_find_moved_lines() {
+        fn to_blocks<'a>(blocks: &[&[&'a str]]) -> Vec<Vec<&'a [u8]>> {
+            blocks
+                .iter()
+                .map(|block| block.iter().map(|line| line.as_bytes()).collect())
+                .collect()
+        }
+        let find = |removed: &[&[&str]], added: &[&[&str]]| {
+            find_moved_lines(&to_blocks(removed), &to_blocks(added), CompareBytesExactly)
+        };
+
+        // Nothing to match
+        assert_eq!(find(&[], &[]), [vec![], vec![]] as [Vec<Vec<bool>>; 2]);
+        assert_eq!(
+            find(&[&["removed line\n"]], &[&["added line\n"]]),
+            [vec![vec![false]], vec![vec![false]]]
+        );
+
+        // Run of lines moved to another block, with surrounding changes
+        assert_eq!(
+            find(
+                &[&["a\n", "fn moved() {\n", "    do_something_else();\n", "}\n"]],
+                &[
+                    &["b\n"],
+                    &["fn moved() {\n", "    do_something_else();\n", "}\n", "c\n"],
+                ],
+            ),
+            [
+                vec![vec![false, true, true, true]],
+                vec![vec![false], vec![true, true, true, false]],
+            ]
+        );
+
+        // Short runs aren't detected as moved
+        assert_eq!(
+            find(&[&["}\n", "\n"]], &[&["}\n", "\n"]]),
+            [vec![vec![false, false]], vec![vec![false, false]]]
+        );
+
+        // Line terminator isn't compared
+        assert_eq!(
+            find(
+                &[&["the quick brown fox jumps\n"]],
+                &[&["the quick brown fox jumps"]]
+            ),
+            [vec![vec![true]], vec![vec![true]]]
+        );
+
+        // Lines can be copied to multiple places
+        assert_eq!(
+            find(
+                &[&["the quick brown fox jumps\n"]],
+                &[
+                    &["the quick brown fox jumps\n"],
+                    &["the quick brown fox jumps\n"]
+                ]
+            ),
+            [vec![vec![true]], vec![vec![true], vec![true]]]
+        );
+
+        // Runs don't start from lines that are removed many times, but can
+        // contain them
+        let common_lines = vec!["}\n"; 10000];
+        let moved_lines = ["fn moved() {\n", "    do_something_else();\n", "}\n"];
+        let [removed_moved, added_moved] = find(
+            &[&common_lines, &["}\n"], &moved_lines],
+            &[&["}\n"], &moved_lines, &common_lines],
+        );
+        assert!(removed_moved[0].iter().all(|&moved| !moved));
+        assert_eq!(removed_moved[1..], [vec![false], vec![true, true, true]]);
+        assert_eq!(added_moved[..2], [vec![false], vec![true, true, true]]);
+        assert!(added_moved[2].iter().all(|&moved| !moved));
+    }
+
+    #[test]
+    fn test_find_moved_lines_whitespace() {
+        let removed = vec![vec![&b"    let reindented_value = 100;\n"[..]]];
+        let added = vec![vec![&b"let reindented_value = 100;\n"[..]]];
+        assert_eq!(
+            find_moved_lines(&removed, &added, CompareBytesExactly),
+            [vec![vec![false]], vec![vec![false]]]
+        );
+        assert_eq!(
+            find_moved_lines(&removed, &added, CompareBytesIgnoreAllWhitespace),
+            [vec![vec![true]], vec![vec![true]]]
+        );
+    }
 }
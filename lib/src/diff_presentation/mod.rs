@@ -29,9 +29,11 @@ use crate::diff::CompareBytesExactly;
 use crate::diff::CompareBytesIgnoreAllWhitespace;
 use crate::diff::CompareBytesIgnoreWhitespaceAmount;
 use crate::diff::ContentDiff;
+use crate::diff::DiffAlgorithm;
 use crate::diff::DiffHunk;
 use crate::diff::DiffHunkKind;
 use crate::diff::find_line_ranges;
+use crate::diff::find_moved_lines;
 use crate::merge::Merge;
 use crate::repo_path::RepoPath;
 
@@ -94,21 +96,55 @@ pub enum LineCompareMode {
 pub fn diff_by_line<'input, T: AsRef<[u8]> + ?Sized + 'input>(
     inputs: impl IntoIterator<Item = &'input T>,
     options: &LineCompareMode,
+    algorithm: DiffAlgorithm,
 ) -> ContentDiff<'input> {
     // TODO: If we add --ignore-blank-lines, its tokenizer will have to attach
     // blank lines to the preceding range. Maybe it can also be implemented as a
     // post-process (similar to refine_changed_regions()) that expands unchanged
     // regions across blank lines.
+    match options {
+        LineCompareMode::Exact => ContentDiff::for_tokenizer_with_algorithm(
+            inputs,
+            find_line_ranges,
+            CompareBytesExactly,
+            algorithm,
+        ),
+        LineCompareMode::IgnoreAllSpace => ContentDiff::for_tokenizer_with_algorithm(
+            inputs,
+            find_line_ranges,
+            CompareBytesIgnoreAllWhitespace,
+            algorithm,
+        ),
+        LineCompareMode::IgnoreSpaceChange => ContentDiff::for_tokenizer_with_algorithm(
+            inputs,
+            find_line_ranges,
+            CompareBytesIgnoreWhitespaceAmount,
+            algorithm,
+        ),
+    }
+}
+
+/// Detects lines that are removed from one block and added to another,
+/// comparing lines as specified by `options`. See [`find_moved_lines()`].
+pub fn find_moved_lines_by_mode(
+    removed_blocks: &[Vec<&[u8]>],
+    added_blocks: &[Vec<&[u8]>],
+    options: &LineCompareMode,
+) -> [Vec<Vec<bool>>; 2] {
     match options {
         LineCompareMode::Exact => {
-            ContentDiff::for_tokenizer(inputs, find_line_ranges, CompareBytesExactly)
-        }
-        LineCompareMode::IgnoreAllSpace => {
-            ContentDiff::for_tokenizer(inputs, find_line_ranges, CompareBytesIgnoreAllWhitespace)
-        }
-        LineCompareMode::IgnoreSpaceChange => {
-            ContentDiff::for_tokenizer(inputs, find_line_ranges, CompareBytesIgnoreWhitespaceAmount)
+            find_moved_lines(removed_blocks, added_blocks, CompareBytesExactly)
         }
+        LineCompareMode::IgnoreAllSpace => find_moved_lines(
+            removed_blocks,
+            added_blocks,
+            CompareBytesIgnoreAllWhitespace,
+        ),
+        LineCompareMode::IgnoreSpaceChange => find_moved_lines(
+            removed_blocks,
+            added_blocks,
+            CompareBytesIgnoreWhitespaceAmount,
+        ),
     }
 }
 
@@ -32,6 +32,7 @@ use crate::conflicts::ConflictMaterializeOptions;
 use crate::conflicts::MaterializedTreeValue;
 use crate::conflicts::materialize_merge_result_to_bytes;
 use crate::diff::ContentDiff;
+use crate::diff::DiffAlgorithm;
 use crate::diff::DiffHunkKind;
 use crate::object_id::ObjectId as _;
 use crate::repo_path::RepoPath;
@@ -178,6 +179,7 @@ pub fn unified_diff_hunks<'content>(
     contents: [&'content BStr; 2],
     context: usize,
     options: LineCompareMode,
+    algorithm: DiffAlgorithm,
 ) -> Vec<UnifiedDiffHunk<'content>> {
     let mut hunks = vec![];
     let mut current_hunk = UnifiedDiffHunk {
@@ -185,7 +187,7 @@ pub fn unified_diff_hunks<'content>(
         right_line_range: 0..0,
         lines: vec![],
     };
-    let diff = diff_by_line(contents, &options);
+    let diff = diff_by_line(contents, &options, algorithm);
     let mut diff_hunks = diff.hunks().peekable();
     while let Some(hunk) = diff_hunks.next() {
         match hunk.kind {
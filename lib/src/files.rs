@@ -24,9 +24,11 @@ use bstr::BString;
 use either::Either;
 use itertools::Itertools as _;
 
+use crate::diff::CompareBytes;
 use crate::diff::ContentDiff;
 use crate::diff::DiffHunk;
 use crate::diff::DiffHunkKind;
+use crate::diff::find_line_ranges;
 use crate::merge::Merge;
 use crate::merge::SameChange;
 use crate::tree_merge::MergeOptions;
@@ -289,6 +291,79 @@ pub fn merge_hunks<T: AsRef<[u8]>>(inputs: &Merge<T>, options: &MergeOptions) ->
     merge_inner(inputs, options)
 }
 
+/// Splits `inputs` into line hunks, then splits conflicting line hunks further
+/// into word hunks, and resolves trivial merge conflicts for each.
+///
+/// Unlike `merge_hunks()` with `FileMergeHunkLevel::Word`, partially-resolved
+/// line hunks are kept split into word hunks. This is useful for presenting
+/// intra-line differences to the user, but the returned hunks may not end
+/// with newline.
+pub fn merge_hunks_by_word<T: AsRef<[u8]>>(
+    inputs: &Merge<T>,
+    options: &MergeOptions,
+) -> MergeResult {
+    let num_diffs = inputs.removes().len();
+    let diff = ContentDiff::by_line_with_algorithm(
+        inputs.removes().chain(inputs.adds()),
+        options.diff_algorithm,
+    );
+    let hunks = resolve_diff_hunks(&diff, num_diffs, options.same_change).flat_map(|hunk| {
+        if hunk.is_resolved() {
+            return vec![hunk];
+        }
+        let diff = ContentDiff::by_word(hunk.removes().chain(hunk.adds()).copied());
+        resolve_diff_hunks(&diff, num_diffs, options.same_change).collect_vec()
+    });
+    collect_hunks(hunks.map(MergeHunk::Borrowed))
+}
+
+/// Splits `inputs` into line hunks by comparing lines with the given `compare`
+/// function, attempts to resolve merge conflicts for each.
+///
+/// Lines that are equivalent but not identical (e.g. ignoring whitespace) are
+/// resolved trivially if possible, or taken from the first side otherwise. If
+/// all input hunks can be merged successfully, returns the merged content.
+pub fn try_merge_with_compare<T: AsRef<[u8]>>(
+    inputs: &Merge<T>,
+    compare: impl CompareBytes,
+    same_change: SameChange,
+) -> Option<BString> {
+    let num_diffs = inputs.removes().len();
+    let diff = ContentDiff::for_tokenizer(
+        inputs.removes().chain(inputs.adds()),
+        find_line_ranges,
+        &compare,
+    );
+    let mut resolved_content = BString::default();
+    for diff_hunk in diff.hunks() {
+        let merge = Merge::from_removes_adds(
+            diff_hunk.contents[..num_diffs].iter().copied(),
+            diff_hunk.contents[num_diffs..].iter().copied(),
+        );
+        let content = match diff_hunk.kind {
+            // Equivalent hunks may still differ literally. Pick the changed
+            // side if any, or the first side.
+            DiffHunkKind::Matching => merge
+                .resolve_trivial(same_change)
+                .unwrap_or_else(|| merge.first()),
+            DiffHunkKind::Different => {
+                // Map each term to the index of the first equivalent term so
+                // that the trivial merge can be resolved up to equivalence.
+                let terms = merge.as_slice();
+                let indices = Merge::from_vec(
+                    terms
+                        .iter()
+                        .map(|&term| terms.iter().position(|&t| compare.eq(t, term)).unwrap())
+                        .collect_vec(),
+                );
+                &terms[*indices.resolve_trivial(same_change)?]
+            }
+        };
+        resolved_content.extend_from_slice(content);
+    }
+    Some(resolved_content)
+}
+
 /// Splits `inputs` into hunks, resolves trivial merge conflicts for each, then
 /// concatenates the outcome back to single `Merge` object.
 ///
@@ -315,7 +390,10 @@ where
     // usually done for 3-way conflicts. Are there better heuristics when there are
     // more than 3 parts?
     let num_diffs = inputs.removes().len();
-    let diff = ContentDiff::by_line(inputs.removes().chain(inputs.adds()));
+    let diff = ContentDiff::by_line_with_algorithm(
+        inputs.removes().chain(inputs.adds()),
+        options.diff_algorithm,
+    );
     let hunks = resolve_diff_hunks(&diff, num_diffs, options.same_change);
     match options.hunk_level {
         FileMergeHunkLevel::Line => B::from_hunks(hunks.map(MergeHunk::Borrowed)),
@@ -492,6 +570,8 @@ mod tests {
     use indoc::indoc;
 
     use super::*;
+    use crate::diff::CompareBytesIgnoreAllWhitespace;
+    use crate::diff::DiffAlgorithm;
 
     fn conflict<const N: usize>(values: [&[u8]; N]) -> Merge<BString> {
         Merge::from_vec(values.map(hunk).to_vec())
@@ -855,6 +935,7 @@ mod tests {
         let options = MergeOptions {
             hunk_level: FileMergeHunkLevel::Line,
             same_change: SameChange::Accept,
+            diff_algorithm: DiffAlgorithm::Default,
         };
         let merge_hunks = |inputs: &_| merge_hunks(inputs, &options);
         // Unchanged and empty on all sides
@@ -971,6 +1052,7 @@ mod tests {
         let options = MergeOptions {
             hunk_level: FileMergeHunkLevel::Line,
             same_change: SameChange::Accept,
+            diff_algorithm: DiffAlgorithm::Default,
         };
         let merge_hunks = |inputs: &_| merge_hunks(inputs, &options);
         let merge = |inputs: &_| merge(inputs, &options);
@@ -1078,11 +1160,32 @@ mod tests {
         assert_eq!(merge(&conflict([left, base, right])), resolved(merged));
     }
 
+    #[test]
+    fn test_merge_diff_algorithm() {
+        let options = |diff_algorithm| MergeOptions {
+            hunk_level: FileMergeHunkLevel::Line,
+            same_change: SameChange::Accept,
+            diff_algorithm,
+        };
+        let inputs = conflict([b"c\nc\nb\nb\n", b"a\nb\na\n", b"a\nb\n"]);
+        // Whether the changes overlap depends on the lines matched by the diff
+        // algorithm
+        assert_eq!(
+            merge(&inputs, &options(DiffAlgorithm::Default)),
+            conflict([b"c\nc\nb\nb\n", b"a\nb\na\n", b"a\nb\n"])
+        );
+        assert_eq!(
+            merge(&inputs, &options(DiffAlgorithm::Patience)),
+            resolved(b"c\nc\nb\nb\n")
+        );
+    }
+
     #[test]
     fn test_merge_hunk_by_word() {
         let options = MergeOptions {
             hunk_level: FileMergeHunkLevel::Word,
             same_change: SameChange::Accept,
+            diff_algorithm: DiffAlgorithm::Default,
         };
         let merge = |inputs: &_| merge(inputs, &options);
         // No context line in between, but "\n" is a context word
@@ -1106,4 +1209,76 @@ mod tests {
             conflict([b"a b", b"a", b"x a c"])
         );
     }
+
+    #[test]
+    fn test_merge_hunks_by_word() {
+        let options = MergeOptions {
+            hunk_level: FileMergeHunkLevel::Line,
+            same_change: SameChange::Accept,
+            diff_algorithm: DiffAlgorithm::Default,
+        };
+        let merge = |inputs: &_| merge_hunks_by_word(inputs, &options);
+        // Resolved line hunks are kept as is
+        assert_eq!(
+            merge(&conflict([b"a\nb\n", b"a\n", b"a\n"])),
+            MergeResult::Resolved(hunk(b"a\nb\n"))
+        );
+        // Conflicting line hunk is split into word hunks, and the unchanged
+        // words are resolved
+        assert_eq!(
+            merge(&conflict([b"a\nx b y\n", b"a\nb\n", b"a\nz b w\n"])),
+            MergeResult::Conflict(vec![
+                resolved(b"a\n"),
+                conflict([b"x ", b"", b"z "]),
+                resolved(b"b"),
+                conflict([b" y", b"", b" w"]),
+                resolved(b"\n"),
+            ])
+        );
+        // Word hunks can be resolved independently
+        assert_eq!(
+            merge(&conflict([b"x b\n", b"a b\n", b"a c\n"])),
+            MergeResult::Resolved(hunk(b"x c\n"))
+        );
+        // More than 2 sides
+        assert_eq!(
+            merge(&conflict([
+                b"x b\n", b"a b\n", b"y b\n", b"a b\n", b"a c\n"
+            ])),
+            MergeResult::Conflict(vec![
+                conflict([b"x", b"a", b"y", b"a", b"a"]),
+                resolved(b" c\n"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_try_merge_with_compare() {
+        let merge = |inputs: &_| {
+            try_merge_with_compare(inputs, CompareBytesIgnoreAllWhitespace, SameChange::Accept)
+        };
+        // Whitespace-only change on one side is overridden by the other side
+        assert_eq!(
+            merge(&conflict([b"  a\nb\n", b"a\nb\n", b"x\nb\n"])),
+            Some(hunk(b"x\nb\n"))
+        );
+        // Whitespace-only change is preserved if the other side didn't change
+        // the line
+        assert_eq!(
+            merge(&conflict([b"  a\nb\n", b"a\nb\n", b"a\ny\n"])),
+            Some(hunk(b"  a\ny\n"))
+        );
+        // Different whitespace-only changes are resolved to the first side
+        assert_eq!(
+            merge(&conflict([b"  a\n", b"a\n", b"\ta\n"])),
+            Some(hunk(b"  a\n"))
+        );
+        // Same change modulo whitespace
+        assert_eq!(
+            merge(&conflict([b"x  y\n", b"a\n", b"x y\n"])),
+            Some(hunk(b"x  y\n"))
+        );
+        // Conflicting changes can't be resolved
+        assert_eq!(merge(&conflict([b"x\n", b"a\n", b"y\n"])), None);
+    }
 }
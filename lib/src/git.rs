@@ -37,6 +37,7 @@ use crate::backend::BackendResult;
 use crate::backend::CommitId;
 use crate::backend::TreeValue;
 use crate::commit::Commit;
+use crate::evolution::walk_predecessors;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
 use crate::git_backend::GitBackend;
@@ -75,6 +76,9 @@ pub const REMOTE_NAME_FOR_LOCAL_GIT_REPO: &RemoteName = RemoteName::new("git");
 pub const RESERVED_REMOTE_REF_NAMESPACE: &str = "refs/remotes/git/";
 /// Ref name used as a placeholder to unset HEAD without a commit.
 const UNBORN_ROOT_REF_NAME: &str = "refs/jj/root";
+/// Git ref namespace of obsolescence markers exchanged with remotes.
+/// `refs/jj/obsolete/<commit id>` points to a successor of the commit.
+const OBSOLETE_REF_PREFIX: &str = "refs/jj/obsolete/";
 /// Dummy file to be added to the index to indicate that the user is editing a
 /// commit with a conflict that isn't represented in the Git index.
 const INDEX_DUMMY_CONFLICT_FILE: &str = ".jj-do-not-resolve-this-conflict";
@@ -403,6 +407,9 @@ impl GitImportError {
 pub struct GitImportStats {
     /// Commits superseded by newly imported commits.
     pub abandoned_commits: Vec<CommitId>,
+    /// Commit `(old_id, new_id)`s rewritten on the remote according to the
+    /// fetched obsolescence markers.
+    pub rewritten_commits: Vec<(CommitId, CommitId)>,
     /// Remote bookmark `(symbol, (old_remote_ref, new_target))`s to be merged
     /// in to the local bookmarks, sorted by `symbol`.
     pub changed_remote_bookmarks: Vec<(RemoteRefSymbolBuf, (RemoteRef, RefTarget))>,
@@ -565,6 +572,7 @@ pub fn import_some_refs(
     };
     let stats = GitImportStats {
         abandoned_commits,
+        rewritten_commits: vec![],
         changed_remote_bookmarks,
         changed_remote_tags,
         failed_ref_names,
@@ -1979,11 +1987,15 @@ fn remove_remote_git_refs(
     remote: &RemoteName,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
     let prefix = format!("refs/remotes/{remote}/", remote = remote.as_str());
-    let edits: Vec<_> = git_repo
+    let obsolete_prefix = remote_obsolete_ref_prefix(remote);
+    let mut edits: Vec<_> = git_repo
         .references()?
         .prefixed(prefix.as_str())?
         .map_ok(remove_ref)
         .try_collect()?;
+    for reference in git_repo.references()?.prefixed(obsolete_prefix.as_str())? {
+        edits.push(remove_ref(reference?));
+    }
     git_repo.edit_references(edits)?;
     Ok(())
 }
@@ -2479,6 +2491,12 @@ impl<'a> GitFetch<'a> {
             // Don't fall back to the base refspecs.
             return Ok(());
         }
+        if self.git_settings.exchange_obsolescence {
+            remaining_refspecs.push(RefSpec::forced(
+                format!("{OBSOLETE_REF_PREFIX}*"),
+                format!("{}*", remote_obsolete_ref_prefix(remote_name)),
+            ));
+        }
 
         let mut branches_to_prune = Vec::new();
         // git unfortunately errors out if one of the many refspecs is not found
@@ -2540,30 +2558,49 @@ impl<'a> GitFetch<'a> {
     /// and update jj's local branches. We also import local tags since remote
     /// tags should have been merged by Git.
     ///
+    /// If [`GitSettings::exchange_obsolescence`] is enabled, local commits
+    /// that were rewritten on the remote are replaced with their successors.
+    ///
     /// Clears all yet-to-be-imported {branch_names, remote_name} pairs after
     /// the import. If `fetch()` has not been called since the last time
     /// `import_refs()` was called then this will be a no-op.
     #[tracing::instrument(skip(self))]
     pub fn import_refs(&mut self) -> Result<GitImportStats, GitImportError> {
         tracing::debug!("import_refs");
-        let import_stats =
-            import_some_refs(
-                self.mut_repo,
-                self.git_settings,
-                |kind, symbol| match kind {
-                    GitRefKind::Bookmark => self
-                        .fetched
-                        .iter()
-                        .filter(|fetched| fetched.remote == symbol.remote)
-                        .any(|fetched| {
-                            fetched
-                                .branches
-                                .iter()
-                                .any(|pattern| pattern.is_match(symbol.name.as_str()))
-                        }),
-                    GitRefKind::Tag => true,
-                },
-            )?;
+        let mut import_stats = import_some_refs(
+            self.mut_repo,
+            self.git_settings,
+            |kind, symbol| match kind {
+                GitRefKind::Bookmark => self
+                    .fetched
+                    .iter()
+                    .filter(|fetched| fetched.remote == symbol.remote)
+                    .any(|fetched| {
+                        fetched
+                            .branches
+                            .iter()
+                            .any(|pattern| pattern.is_match(symbol.name.as_str()))
+                    }),
+                GitRefKind::Tag => true,
+            },
+        )?;
+
+        if self.git_settings.exchange_obsolescence {
+            let remotes: HashSet<&RemoteName> = self
+                .fetched
+                .iter()
+                .map(|fetched| fetched.remote.as_ref())
+                .collect();
+            for remote in remotes {
+                let rewritten = import_obsolescence_markers(self.mut_repo, remote)?;
+                // Descendants of rewritten commits are rebased onto the
+                // successors, not onto the parents.
+                import_stats
+                    .abandoned_commits
+                    .retain(|id| rewritten.iter().all(|(old_id, _)| old_id != id));
+                import_stats.rewritten_commits.extend(rewritten);
+            }
+        }
 
         self.fetched.clear();
 
@@ -2581,6 +2618,8 @@ pub enum GitPushError {
     Subprocess(#[from] GitSubprocessError),
     #[error(transparent)]
     UnexpectedBackend(#[from] UnexpectedGitBackendError),
+    #[error("Failed to update obsolescence markers")]
+    ObsolescenceMarkers(#[source] Box<dyn std::error::Error + Send + Sync>),
 }
 
 #[derive(Clone, Debug)]
@@ -2599,6 +2638,9 @@ pub struct GitRefUpdate {
 }
 
 /// Pushes the specified branches and updates the repo view accordingly.
+///
+/// If [`GitSettings::exchange_obsolescence`] is enabled, obsolescence markers
+/// of the pushed commits are pushed as well.
 pub fn push_branches(
     mut_repo: &mut MutableRepo,
     git_settings: &GitSettings,
@@ -2608,7 +2650,7 @@ pub fn push_branches(
 ) -> Result<GitPushStats, GitPushError> {
     validate_remote_name(remote)?;
 
-    let ref_updates = targets
+    let mut ref_updates = targets
         .branch_updates
         .iter()
         .map(|(name, update)| GitRefUpdate {
@@ -2617,9 +2659,19 @@ pub fn push_branches(
             new_target: update.new_target.clone(),
         })
         .collect_vec();
+    if git_settings.exchange_obsolescence {
+        ref_updates.extend(
+            obsolescence_ref_updates(mut_repo, remote, targets)
+                .map_err(GitPushError::ObsolescenceMarkers)?,
+        );
+    }
 
     let push_stats = push_updates(mut_repo, git_settings, remote, &ref_updates, callbacks)?;
     tracing::debug!(?push_stats);
+    if git_settings.exchange_obsolescence {
+        record_pushed_obsolescence_markers(mut_repo.store(), remote, &ref_updates, &push_stats)
+            .map_err(GitPushError::ObsolescenceMarkers)?;
+    }
 
     // TODO: add support for partially pushed refs? we could update the view
     // excluding rejected refs, but the transaction would be aborted anyway
@@ -2644,6 +2696,240 @@ pub fn push_branches(
     Ok(push_stats)
 }
 
+/// Returns the Git ref namespace in which obsolescence markers fetched from or
+/// pushed to the `remote` are recorded.
+fn remote_obsolete_ref_prefix(remote: &RemoteName) -> String {
+    format!(
+        "refs/jj/remotes/{remote}/obsolete/",
+        remote = remote.as_str()
+    )
+}
+
+/// Reads obsolescence markers `(old_id, new_id)` recorded in the Git refs
+/// under the `prefix`.
+fn read_obsolescence_markers(
+    git_repo: &gix::Repository,
+    prefix: &str,
+) -> Result<Vec<(CommitId, CommitId)>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut markers = vec![];
+    for reference in git_repo.references()?.prefixed(prefix)? {
+        let reference = reference?;
+        let Some(old_id) = CommitId::try_from_hex(&reference.name().as_bstr()[prefix.len()..])
+        else {
+            continue;
+        };
+        let Some(oid) = reference.inner.target.try_id() else {
+            continue;
+        };
+        markers.push((old_id, CommitId::from_bytes(oid.as_bytes())));
+    }
+    Ok(markers)
+}
+
+/// Replaces visible commits with their successors according to the
+/// obsolescence markers fetched from the `remote`. Returns the rewritten
+/// commit `(old_id, new_id)`s.
+fn import_obsolescence_markers(
+    mut_repo: &mut MutableRepo,
+    remote: &RemoteName,
+) -> Result<Vec<(CommitId, CommitId)>, GitImportError> {
+    let git_repo = get_git_repo(mut_repo.store())?;
+    let markers: HashMap<CommitId, CommitId> =
+        read_obsolescence_markers(&git_repo, &remote_obsolete_ref_prefix(remote))
+            .map_err(GitImportError::Git)?
+            .into_iter()
+            .collect();
+    if markers.is_empty() {
+        return Ok(vec![]);
+    }
+    let root_commit_id = mut_repo.store().root_commit_id();
+    let candidate_ids = markers
+        .keys()
+        .chain(markers.values())
+        .filter(|&id| id != root_commit_id)
+        .cloned()
+        .collect_vec();
+    let visible_ids: HashSet<CommitId> = RevsetExpression::commits(candidate_ids)
+        .intersection(&RevsetExpression::visible_heads().ancestors())
+        .evaluate(mut_repo)
+        .map_err(|err| err.into_backend_error())?
+        .iter()
+        .try_collect()
+        .map_err(|err| err.into_backend_error())?;
+
+    let index = mut_repo.index();
+    let mut rewritten = vec![];
+    for (old_id, new_id) in &markers {
+        if !visible_ids.contains(old_id) {
+            continue;
+        }
+        // Follow the markers in case the successor was rewritten again.
+        let mut new_id = new_id;
+        let mut visited = HashSet::from([old_id]);
+        while let Some(next_id) = markers.get(new_id) {
+            if !visited.insert(new_id) {
+                break;
+            }
+            new_id = next_id;
+        }
+        if !visible_ids.contains(new_id) || index.is_ancestor(old_id, new_id)? {
+            continue;
+        }
+        rewritten.push((old_id.clone(), new_id.clone()));
+    }
+    rewritten.sort();
+
+    let mut predecessors: HashMap<&CommitId, Vec<CommitId>> = HashMap::new();
+    for (old_id, new_id) in &rewritten {
+        mut_repo.set_rewritten_commit(old_id.clone(), new_id.clone());
+        predecessors.entry(new_id).or_default().push(old_id.clone());
+    }
+    for (new_id, old_ids) in predecessors {
+        mut_repo.set_predecessors(new_id.clone(), old_ids);
+    }
+    Ok(rewritten)
+}
+
+/// Returns updates of the obsolescence markers which record the commits to be
+/// pushed as successors of the commits already pushed to the `remote`.
+fn obsolescence_ref_updates(
+    mut_repo: &MutableRepo,
+    remote: &RemoteName,
+    targets: &GitBranchPushTargets,
+) -> Result<Vec<GitRefUpdate>, Box<dyn std::error::Error + Send + Sync>> {
+    let new_heads = targets
+        .branch_updates
+        .iter()
+        .filter_map(|(_, update)| update.new_target.clone())
+        .collect_vec();
+    let remote_heads = mut_repo
+        .view()
+        .remote_bookmarks(remote)
+        .flat_map(|(_, remote_ref)| remote_ref.target.added_ids())
+        .cloned()
+        .collect_vec();
+    if new_heads.is_empty() || remote_heads.is_empty() {
+        return Ok(vec![]);
+    }
+    let pushed_ids: Vec<CommitId> = RevsetExpression::commits(remote_heads.clone())
+        .range(&RevsetExpression::commits(new_heads))
+        .evaluate(mut_repo)
+        .map_err(|err| err.into_backend_error())?
+        .iter()
+        .try_collect()
+        .map_err(|err| err.into_backend_error())?;
+
+    // Commits rewritten in this transaction (e.g. signed on push) aren't
+    // recorded in the operation log yet.
+    let mut predecessors: HashMap<CommitId, Vec<CommitId>> = HashMap::new();
+    let mut start_ids = vec![];
+    for id in &pushed_ids {
+        if let Some(ids) = mut_repo.predecessors_in_transaction(id) {
+            predecessors.insert(id.clone(), ids.to_vec());
+            start_ids.extend(ids.iter().cloned());
+        } else {
+            start_ids.push(id.clone());
+        }
+    }
+    for entry in walk_predecessors(mut_repo.base_repo(), &start_ids) {
+        let entry = entry?;
+        predecessors
+            .entry(entry.commit.id().clone())
+            .or_insert_with(|| entry.predecessor_ids().to_vec());
+    }
+    let all_predecessor_ids = predecessors
+        .values()
+        .flatten()
+        .unique()
+        .cloned()
+        .collect_vec();
+    let remote_predecessor_ids: HashSet<CommitId> = RevsetExpression::commits(all_predecessor_ids)
+        .intersection(&RevsetExpression::commits(remote_heads).ancestors())
+        .evaluate(mut_repo)
+        .map_err(|err| err.into_backend_error())?
+        .iter()
+        .try_collect()
+        .map_err(|err| err.into_backend_error())?;
+
+    // Predecessors rewritten into multiple pushed commits are ambiguous, and
+    // are left out.
+    let mut successors: HashMap<&CommitId, Option<&CommitId>> = HashMap::new();
+    for new_id in &pushed_ids {
+        let mut to_visit = predecessors
+            .get(new_id)
+            .map_or(vec![], |ids| ids.iter().collect());
+        let mut visited = HashSet::new();
+        while let Some(id) = to_visit.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            if remote_predecessor_ids.contains(id) {
+                successors
+                    .entry(id)
+                    .and_modify(|successor| {
+                        if *successor != Some(new_id) {
+                            *successor = None;
+                        }
+                    })
+                    .or_insert(Some(new_id));
+            }
+            to_visit.extend(predecessors.get(id).into_iter().flatten());
+        }
+    }
+
+    let git_repo = get_git_repo(mut_repo.store())?;
+    let known_markers: HashMap<CommitId, CommitId> =
+        read_obsolescence_markers(&git_repo, &remote_obsolete_ref_prefix(remote))?
+            .into_iter()
+            .collect();
+    let updates = successors
+        .into_iter()
+        .filter_map(|(old_id, new_id)| Some((old_id, new_id?)))
+        .filter(|&(old_id, new_id)| known_markers.get(old_id) != Some(new_id))
+        .sorted()
+        .map(|(old_id, new_id)| GitRefUpdate {
+            qualified_name: format!("{OBSOLETE_REF_PREFIX}{}", old_id.hex()).into(),
+            expected_current_target: known_markers.get(old_id).cloned(),
+            new_target: Some(new_id.clone()),
+        })
+        .collect();
+    Ok(updates)
+}
+
+/// Records the successfully pushed obsolescence markers so they can be updated
+/// by later pushes.
+fn record_pushed_obsolescence_markers(
+    store: &Store,
+    remote: &RemoteName,
+    ref_updates: &[GitRefUpdate],
+    push_stats: &GitPushStats,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let git_repo = get_git_repo(store)?;
+    let remote_prefix = remote_obsolete_ref_prefix(remote);
+    for update in ref_updates {
+        let Some(old_hex) = update
+            .qualified_name
+            .as_str()
+            .strip_prefix(OBSOLETE_REF_PREFIX)
+        else {
+            continue;
+        };
+        let Some(new_id) = &update.new_target else {
+            continue;
+        };
+        if !push_stats.pushed.contains(&update.qualified_name) {
+            continue;
+        }
+        git_repo.reference(
+            format!("{remote_prefix}{old_hex}"),
+            gix::ObjectId::from_bytes_or_panic(new_id.as_bytes()),
+            gix::refs::transaction::PreviousValue::Any,
+            "push obsolescence marker from jj",
+        )?;
+    }
+    Ok(())
+}
+
 /// Pushes the specified Git refs without updating the repo view.
 pub fn push_updates(
     repo: &dyn Repo,
@@ -73,10 +73,13 @@ pub mod matchers;
 pub mod merge;
 pub mod merged_tree;
 pub mod object_id;
+pub mod op_bundle;
 pub mod op_heads_store;
 pub mod op_store;
 pub mod op_walk;
 pub mod operation;
+pub mod opset;
+mod opset_parser;
 #[expect(missing_docs)]
 pub mod protos;
 pub mod ref_name;
@@ -102,6 +105,7 @@ pub mod stacked_table;
 pub mod store;
 pub mod str_util;
 pub mod submodule_store;
+pub mod table_op_store;
 #[cfg(feature = "testing")]
 pub mod test_signing_backend;
 pub mod time_util;
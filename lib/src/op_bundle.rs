@@ -0,0 +1,449 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Portable bundle of operations and the objects they reference.
+//!
+//! A bundle contains operations, their views, and the commits, trees, files,
+//! and symlinks referenced by the views. It can be imported to another repo
+//! to reproduce the operation history.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use prost::Message as _;
+use thiserror::Error;
+use tokio::io::AsyncReadExt as _;
+
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::backend::CommitId;
+use crate::backend::FileId;
+use crate::backend::SymlinkId;
+use crate::backend::TreeId;
+use crate::backend::TreeValue;
+use crate::commit::Commit;
+use crate::dag_walk;
+use crate::hex_util::encode_hex;
+use crate::object_id::ObjectId as _;
+use crate::op_store::OpStoreError;
+use crate::op_store::OperationId;
+use crate::operation::Operation;
+use crate::protos::op_bundle as proto;
+use crate::repo::ReadonlyRepo;
+use crate::repo::Repo as _;
+use crate::repo::RepoLoader;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::revset::ResolvedRevsetExpression;
+use crate::revset::RevsetEvaluationError;
+use crate::simple_backend;
+use crate::simple_op_store;
+use crate::store::Store;
+use crate::view::View;
+
+const BUNDLE_HEADER: &[u8] = b"jj operation bundle v1\n";
+
+/// Error that may occur during export or import of operation bundle.
+#[derive(Debug, Error)]
+pub enum OpBundleError {
+    /// The data doesn't start with the bundle header.
+    #[error("Not an operation bundle")]
+    InvalidHeader,
+    /// The bundle is corrupted.
+    #[error("Failed to decode operation bundle")]
+    Decode(#[source] Box<dyn std::error::Error + Send + Sync>),
+    /// The bundle references a commit which exists neither in the bundle nor
+    /// in the repo, or the exported operations reference a commit which
+    /// doesn't exist in the repo.
+    #[error("Commit {} is referenced by the operations but doesn't exist in the repo", .0.hex())]
+    MissingCommit(CommitId),
+    /// The backend assigned a different id to the imported object.
+    #[error("Failed to reproduce {object_type} {expected} in the repo (got {actual})")]
+    IdMismatch {
+        /// Type of the object.
+        object_type: &'static str,
+        /// Object id in the bundle.
+        expected: String,
+        /// Object id assigned by the backend.
+        actual: String,
+    },
+    /// Failed to read or write objects.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    /// Failed to read or write operations.
+    #[error(transparent)]
+    OpStore(#[from] OpStoreError),
+    /// Failed to resolve the commits to export.
+    #[error(transparent)]
+    RevsetEvaluation(#[from] RevsetEvaluationError),
+}
+
+impl OpBundleError {
+    fn decode(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self::Decode(err.into())
+    }
+}
+
+/// Stats about `import_bundle()`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImportStats {
+    /// Heads of the imported operations.
+    pub head_ids: Vec<OperationId>,
+    /// The number of imported operations.
+    pub operation_count: usize,
+    /// The number of imported commits.
+    pub commit_count: usize,
+}
+
+/// Exports the `ops` and the objects they reference to a bundle.
+///
+/// All commits reachable from the views of `ops` and from the recorded
+/// predecessors are included in the bundle, so it can be imported to any repo.
+/// Fails if any of them doesn't exist in the repo.
+pub fn export_bundle(repo: &ReadonlyRepo, ops: &[Operation]) -> Result<Vec<u8>, OpBundleError> {
+    let op_store = repo.op_store();
+    let store = repo.store();
+    // The root operation exists in any repo.
+    let ops = ops
+        .iter()
+        .filter(|op| op.id() != op_store.root_operation_id())
+        .collect_vec();
+    let ops_by_id: HashMap<&OperationId, &Operation> =
+        ops.iter().map(|&op| (op.id(), op)).collect();
+    let sorted_ops = dag_walk::topo_order_forward(
+        ops.iter().copied(),
+        |op| op.id(),
+        |op| {
+            op.parent_ids()
+                .iter()
+                .filter_map(|id| ops_by_id.get(id).copied())
+                .collect_vec()
+        },
+        |op| OpStoreError::Other(format!("Cycle involving operation {}", op.id()).into()),
+    )?;
+
+    let mut builder = BundleBuilder::new(store);
+    let mut referenced_ids = HashSet::new();
+    for op in &sorted_ops {
+        let view = op_store.read_view(op.view_id()).block_on()?;
+        builder.bundle.operations.push(proto::OperationEntry {
+            id: op.id().to_bytes(),
+            operation: Some(simple_op_store::operation_to_proto(op.store_operation())),
+        });
+        builder.bundle.views.push(proto::ViewEntry {
+            id: op.view_id().to_bytes(),
+            view: Some(simple_op_store::view_to_proto(&view)),
+        });
+        referenced_ids.extend(op.all_referenced_commit_ids().cloned());
+        referenced_ids.extend(View::new(view).all_referenced_commit_ids().cloned());
+    }
+    // Predecessors may not be indexed, so their ancestors are walked until
+    // indexed commits are reached. The root commit exists in any repo.
+    let index = repo.index();
+    let root_commit_id = store.root_commit_id();
+    let mut commits: HashMap<CommitId, Commit> = HashMap::new();
+    let mut indexed_ids = vec![];
+    let mut pending_ids = referenced_ids.into_iter().collect_vec();
+    while let Some(id) = pending_ids.pop() {
+        if &id == root_commit_id || commits.contains_key(&id) {
+            continue;
+        }
+        if index.has_id(&id).unwrap_or(false) {
+            indexed_ids.push(id);
+            continue;
+        }
+        let commit = get_commit(store, &id)?;
+        pending_ids.extend(commit.parent_ids().iter().cloned());
+        commits.insert(id, commit);
+    }
+    let indexed_ids: Vec<CommitId> = ResolvedRevsetExpression::commits(indexed_ids)
+        .ancestors()
+        .minus(&ResolvedRevsetExpression::commits(vec![
+            root_commit_id.clone(),
+        ]))
+        .evaluate(repo)?
+        .iter()
+        .try_collect()?;
+    for id in indexed_ids {
+        if let Entry::Vacant(entry) = commits.entry(id) {
+            let commit = get_commit(store, entry.key())?;
+            entry.insert(commit);
+        }
+    }
+    let sorted_commits = dag_walk::topo_order_forward(
+        commits.keys().sorted().map(|id| &commits[id]),
+        |commit| commit.id(),
+        |commit| {
+            commit
+                .parent_ids()
+                .iter()
+                .filter_map(|id| commits.get(id))
+                .collect_vec()
+        },
+        |commit| BackendError::Other(format!("Cycle involving commit {}", commit.id()).into()),
+    )?;
+    for commit in sorted_commits {
+        for tree_id in commit.tree_id().as_merge().iter() {
+            builder.add_tree(RepoPath::root(), tree_id)?;
+        }
+        let data = commit.store_commit();
+        let mut commit_proto = simple_backend::commit_to_proto(data);
+        commit_proto.secure_sig = data.secure_sig.as_ref().map(|sig| sig.sig.clone());
+        builder.bundle.commits.push(proto::CommitEntry {
+            id: commit.id().to_bytes(),
+            commit: Some(commit_proto),
+        });
+    }
+
+    let mut data = BUNDLE_HEADER.to_vec();
+    builder.bundle.encode(&mut data).unwrap();
+    Ok(data)
+}
+
+struct BundleBuilder<'a> {
+    store: &'a Arc<Store>,
+    bundle: proto::Bundle,
+    tree_ids: HashSet<TreeId>,
+    file_ids: HashSet<FileId>,
+    symlink_ids: HashSet<SymlinkId>,
+}
+
+impl<'a> BundleBuilder<'a> {
+    fn new(store: &'a Arc<Store>) -> Self {
+        Self {
+            store,
+            bundle: proto::Bundle::default(),
+            tree_ids: HashSet::new(),
+            file_ids: HashSet::new(),
+            symlink_ids: HashSet::new(),
+        }
+    }
+
+    /// Adds the tree and its entries recursively. Subtrees are added before
+    /// the tree itself.
+    fn add_tree(&mut self, dir: &RepoPath, id: &TreeId) -> BackendResult<()> {
+        if !self.tree_ids.insert(id.clone()) {
+            return Ok(());
+        }
+        let tree = self.store.get_tree(dir.to_owned(), id)?;
+        for entry in tree.data().entries() {
+            let path = dir.join(entry.name());
+            match entry.value() {
+                TreeValue::File { id, .. } => self.add_file(&path, id)?,
+                TreeValue::Symlink(id) => self.add_symlink(&path, id)?,
+                TreeValue::Tree(id) => self.add_tree(&path, id)?,
+                TreeValue::GitSubmodule(_) => {}
+            }
+        }
+        self.bundle.trees.push(proto::TreeEntry {
+            id: id.to_bytes(),
+            path: dir.as_internal_file_string().to_owned(),
+            tree: Some(simple_backend::tree_to_proto(tree.data())),
+        });
+        Ok(())
+    }
+
+    fn add_file(&mut self, path: &RepoPath, id: &FileId) -> BackendResult<()> {
+        if !self.file_ids.insert(id.clone()) {
+            return Ok(());
+        }
+        let mut reader = self.store.read_file(path, id).block_on()?;
+        let mut content = vec![];
+        reader
+            .read_to_end(&mut content)
+            .block_on()
+            .map_err(|err| BackendError::ReadFile {
+                path: path.to_owned(),
+                id: id.clone(),
+                source: err.into(),
+            })?;
+        self.bundle.files.push(proto::FileEntry {
+            id: id.to_bytes(),
+            path: path.as_internal_file_string().to_owned(),
+            content,
+        });
+        Ok(())
+    }
+
+    fn add_symlink(&mut self, path: &RepoPath, id: &SymlinkId) -> BackendResult<()> {
+        if !self.symlink_ids.insert(id.clone()) {
+            return Ok(());
+        }
+        let target = self.store.read_symlink(path, id).block_on()?;
+        self.bundle.symlinks.push(proto::SymlinkEntry {
+            id: id.to_bytes(),
+            path: path.as_internal_file_string().to_owned(),
+            target,
+        });
+        Ok(())
+    }
+}
+
+/// Imports the operations and the objects from the bundle `data`.
+///
+/// Operations whose parents aren't included in the bundle are grafted onto the
+/// root operation. The imported operations aren't added to the operation
+/// heads, so the current repo state isn't changed. They can be looked up by
+/// the returned head ids.
+pub fn import_bundle(repo_loader: &RepoLoader, data: &[u8]) -> Result<ImportStats, OpBundleError> {
+    let data = data
+        .strip_prefix(BUNDLE_HEADER)
+        .ok_or(OpBundleError::InvalidHeader)?;
+    let bundle = proto::Bundle::decode(data).map_err(OpBundleError::decode)?;
+    let store = repo_loader.store();
+    let op_store = repo_loader.op_store();
+
+    let operations: Vec<_> = bundle
+        .operations
+        .into_iter()
+        .map(|entry| {
+            let data = simple_op_store::operation_from_proto(entry.operation.unwrap_or_default())
+                .map_err(OpBundleError::decode)?;
+            Ok::<_, OpBundleError>((OperationId::new(entry.id), data))
+        })
+        .try_collect()?;
+    let views: Vec<_> = bundle
+        .views
+        .into_iter()
+        .map(|entry| {
+            let view = simple_op_store::view_from_proto(entry.view.unwrap_or_default())
+                .map_err(OpBundleError::decode)?;
+            Ok::<_, OpBundleError>((entry.id, View::new(view)))
+        })
+        .try_collect()?;
+
+    // Check that all referenced commits will exist before writing anything.
+    let bundled_commit_ids: HashSet<CommitId> = bundle
+        .commits
+        .iter()
+        .map(|entry| CommitId::new(entry.id.clone()))
+        .collect();
+    let referenced_ids = itertools::chain!(
+        bundle
+            .commits
+            .iter()
+            .flat_map(|entry| entry.commit.iter().flat_map(|commit| &commit.parents))
+            .map(|id| CommitId::new(id.clone())),
+        views
+            .iter()
+            .flat_map(|(_, view)| view.all_referenced_commit_ids().cloned()),
+        operations.iter().flat_map(|(_, data)| {
+            data.commit_predecessors
+                .iter()
+                .flatten()
+                .flat_map(|(new_id, old_ids)| itertools::chain([new_id], old_ids).cloned())
+        }),
+    );
+    for id in referenced_ids.unique() {
+        if bundled_commit_ids.contains(&id) {
+            continue;
+        }
+        get_commit(store, &id)?;
+    }
+
+    for entry in bundle.files {
+        let path = RepoPathBuf::from_internal_string(entry.path).map_err(OpBundleError::decode)?;
+        let id = store
+            .write_file(&path, &mut entry.content.as_slice())
+            .block_on()?;
+        check_id("file", &entry.id, &id.to_bytes())?;
+    }
+    for entry in bundle.symlinks {
+        let path = RepoPathBuf::from_internal_string(entry.path).map_err(OpBundleError::decode)?;
+        let id = store.write_symlink(&path, &entry.target).block_on()?;
+        check_id("symlink", &entry.id, &id.to_bytes())?;
+    }
+    for entry in bundle.trees {
+        let path = RepoPathBuf::from_internal_string(entry.path).map_err(OpBundleError::decode)?;
+        let tree = simple_backend::tree_from_proto(entry.tree.unwrap_or_default());
+        let tree = store.write_tree(&path, tree).block_on()?;
+        check_id("tree", &entry.id, &tree.id().to_bytes())?;
+    }
+    let commit_count = bundle.commits.len();
+    for entry in bundle.commits {
+        let mut commit_proto = entry.commit.unwrap_or_default();
+        let secure_sig = commit_proto.secure_sig.take();
+        let commit = simple_backend::commit_from_proto(commit_proto);
+        // Reproduce the original signature instead of signing the commit again.
+        let mut sign = |_: &[u8]| Ok(secure_sig.clone().unwrap_or_default());
+        let sign_with = secure_sig.is_some().then_some(&mut sign as _);
+        let commit = store.write_commit(commit, sign_with).block_on()?;
+        check_id("commit", &entry.id, &commit.id().to_bytes())?;
+    }
+    for (id, view) in views {
+        let new_id = op_store.write_view(view.store_view()).block_on()?;
+        check_id("view", &id, &new_id.to_bytes())?;
+    }
+
+    let parent_ids: HashSet<&OperationId> = operations
+        .iter()
+        .flat_map(|(_, data)| &data.parents)
+        .collect();
+    let head_ids = operations
+        .iter()
+        .map(|(id, _)| id)
+        .filter(|id| !parent_ids.contains(id))
+        .cloned()
+        .collect_vec();
+    let mut new_ids: HashMap<OperationId, OperationId> = HashMap::new();
+    for (id, mut data) in operations.iter().cloned() {
+        data.parents = data
+            .parents
+            .iter()
+            .map(|parent_id| {
+                new_ids
+                    .get(parent_id)
+                    .unwrap_or(op_store.root_operation_id())
+                    .clone()
+            })
+            .unique()
+            .collect();
+        let new_id = op_store.write_operation(&data).block_on()?;
+        new_ids.insert(id, new_id);
+    }
+    let head_ids = head_ids.iter().map(|id| new_ids[id].clone()).collect_vec();
+    Ok(ImportStats {
+        head_ids,
+        operation_count: operations.len(),
+        commit_count,
+    })
+}
+
+fn get_commit(store: &Arc<Store>, id: &CommitId) -> Result<Commit, OpBundleError> {
+    match store.get_commit(id) {
+        Ok(commit) => Ok(commit),
+        Err(BackendError::ObjectNotFound { .. }) => Err(OpBundleError::MissingCommit(id.clone())),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn check_id(
+    object_type: &'static str,
+    expected: &[u8],
+    actual: &[u8],
+) -> Result<(), OpBundleError> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(OpBundleError::IdMismatch {
+            object_type,
+            expected: encode_hex(expected),
+            actual: encode_hex(actual),
+        })
+    }
+}
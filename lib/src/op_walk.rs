@@ -24,6 +24,7 @@ use itertools::Itertools as _;
 use pollster::FutureExt as _;
 use thiserror::Error;
 
+use crate::backend::MillisSinceEpoch;
 use crate::dag_walk;
 use crate::object_id::HexPrefix;
 use crate::object_id::PrefixResolution;
@@ -31,18 +32,25 @@ use crate::op_heads_store;
 use crate::op_heads_store::OpHeadResolutionError;
 use crate::op_heads_store::OpHeadsStore;
 use crate::op_heads_store::OpHeadsStoreError;
+use crate::op_store;
 use crate::op_store::OpStore;
 use crate::op_store::OpStoreError;
 use crate::op_store::OpStoreResult;
 use crate::op_store::OperationId;
 use crate::operation::Operation;
+use crate::opset;
+use crate::opset::OpsetParseError;
 use crate::repo::ReadonlyRepo;
 use crate::repo::Repo as _;
 use crate::repo::RepoLoader;
+use crate::time_util::DatePatternContext;
 
 /// Error that may occur during evaluation of operation set expression.
 #[derive(Debug, Error)]
 pub enum OpsetEvaluationError {
+    /// Failed to parse operation set expression.
+    #[error(transparent)]
+    OpsetParse(#[from] OpsetParseError),
     /// Failed to resolve operation set expression.
     #[error(transparent)]
     OpsetResolution(#[from] OpsetResolutionError),
@@ -89,6 +97,7 @@ pub enum OpsetResolutionError {
 pub fn resolve_op_for_load(
     repo_loader: &RepoLoader,
     op_str: &str,
+    date_pattern_context: &DatePatternContext,
 ) -> Result<Operation, OpsetEvaluationError> {
     let op_store = repo_loader.op_store();
     let op_heads_store = repo_loader.op_heads_store().as_ref();
@@ -102,7 +111,13 @@ pub fn resolve_op_for_load(
         })
     };
     let get_head_ops = || get_current_head_ops(op_store, op_heads_store);
-    resolve_single_op(op_store, get_current_op, get_head_ops, op_str)
+    resolve_single_op(
+        op_store,
+        get_current_op,
+        get_head_ops,
+        op_str,
+        date_pattern_context,
+    )
 }
 
 /// Resolves operation set expression against the loaded repo.
@@ -111,8 +126,14 @@ pub fn resolve_op_for_load(
 pub fn resolve_op_with_repo(
     repo: &ReadonlyRepo,
     op_str: &str,
+    date_pattern_context: &DatePatternContext,
 ) -> Result<Operation, OpsetEvaluationError> {
-    resolve_op_at(repo.op_store(), slice::from_ref(repo.operation()), op_str)
+    resolve_op_at(
+        repo.op_store(),
+        slice::from_ref(repo.operation()),
+        op_str,
+        date_pattern_context,
+    )
 }
 
 /// Resolves operation set expression at the given head operations.
@@ -120,6 +141,7 @@ pub fn resolve_op_at(
     op_store: &Arc<dyn OpStore>,
     head_ops: &[Operation],
     op_str: &str,
+    date_pattern_context: &DatePatternContext,
 ) -> Result<Operation, OpsetEvaluationError> {
     let get_current_op = || match head_ops {
         [head_op] => Ok(head_op.clone()),
@@ -131,7 +153,13 @@ pub fn resolve_op_at(
         .into()),
     };
     let get_head_ops = || Ok(head_ops.to_vec());
-    resolve_single_op(op_store, get_current_op, get_head_ops, op_str)
+    resolve_single_op(
+        op_store,
+        get_current_op,
+        get_head_ops,
+        op_str,
+        date_pattern_context,
+    )
 }
 
 /// Resolves operation set expression with the given "@" symbol resolution
@@ -141,7 +169,12 @@ fn resolve_single_op(
     get_current_op: impl FnOnce() -> Result<Operation, OpsetEvaluationError>,
     get_head_ops: impl FnOnce() -> Result<Vec<Operation>, OpsetEvaluationError>,
     op_str: &str,
+    date_pattern_context: &DatePatternContext,
 ) -> Result<Operation, OpsetEvaluationError> {
+    if !is_simple_op_expression(op_str) {
+        let head_ops = get_head_ops()?;
+        return resolve_single_op_from_opset(op_store, &head_ops, op_str, date_pattern_context);
+    }
     let op_symbol = op_str.trim_end_matches(['-', '+']);
     let op_postfix = &op_str[op_symbol.len()..];
     let head_ops = op_postfix.contains('+').then(get_head_ops).transpose()?;
@@ -177,7 +210,47 @@ fn resolve_single_op(
     Ok(operation)
 }
 
-fn resolve_single_op_from_store(
+/// Returns true if the `op_str` is a plain operation ID or "@" followed by
+/// `-`/`+` operators, which can be resolved without evaluating an operation
+/// set expression.
+pub fn is_simple_op_expression(op_str: &str) -> bool {
+    let op_symbol = op_str.trim_end_matches(['-', '+']);
+    op_symbol == "@" || op_symbol.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Resolves operation set expression other than a plain operation ID or "@"
+/// followed by `-`/`+` operators.
+fn resolve_single_op_from_opset(
+    op_store: &Arc<dyn OpStore>,
+    head_ops: &[Operation],
+    op_str: &str,
+    date_pattern_context: &DatePatternContext,
+) -> Result<Operation, OpsetEvaluationError> {
+    let expression = opset::parse(op_str, date_pattern_context)?;
+    let opset = expression.resolve(op_store, head_ops)?;
+    // Avoid walking the whole operation history if the set is small.
+    let mut ops: Vec<_> = match opset.explicit_ids() {
+        Some(ids) if ids.len() <= 1 => ids
+            .iter()
+            .map(|id| -> OpStoreResult<_> {
+                let data = op_store.read_operation(id).block_on()?;
+                Ok(Operation::new(op_store.clone(), id.clone(), data))
+            })
+            .try_collect()?,
+        _ => opset.evaluate().try_collect()?,
+    };
+    match ops.len() {
+        0 => Err(OpsetResolutionError::EmptyOperations(op_str.to_owned()).into()),
+        1 => Ok(ops.pop().unwrap()),
+        _ => Err(OpsetResolutionError::MultipleOperations {
+            expr: op_str.to_owned(),
+            candidates: ops.iter().map(|op| op.id().clone()).collect(),
+        }
+        .into()),
+    }
+}
+
+pub(crate) fn resolve_single_op_from_store(
     op_store: &Arc<dyn OpStore>,
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
@@ -393,3 +466,316 @@ pub fn reparent_range(
         unreachable_count,
     })
 }
+
+/// Removes the `abandoned_ids` from the ancestors of the `head_ops`.
+///
+/// The operations descended from the abandoned operations are rewritten so
+/// their parents are the nearest ancestors which aren't abandoned. The root
+/// operation and the `head_ops` must not be abandoned.
+///
+/// Returns the new head operation ids in order of the old `head_ops` as well as
+/// some stats.
+pub fn abandon_operations(
+    op_store: &dyn OpStore,
+    abandoned_ids: &HashSet<OperationId>,
+    head_ops: &[Operation],
+) -> OpStoreResult<ReparentStats> {
+    assert!(
+        !abandoned_ids.contains(op_store.root_operation_id()),
+        "root operation cannot be abandoned"
+    );
+    assert!(
+        head_ops.iter().all(|op| !abandoned_ids.contains(op.id())),
+        "head operations cannot be abandoned"
+    );
+    let ops: Vec<_> = walk_ancestors(head_ops).try_collect()?;
+    // Maps abandoned operations to their new parents, and rewritten operations
+    // to their new ids.
+    let mut new_parent_ids: HashMap<OperationId, Vec<OperationId>> = HashMap::new();
+    let mut rewritten_ids = HashMap::new();
+    let mut unreachable_count = 0;
+    for old_op in ops.into_iter().rev() {
+        let mut data = old_op.store_operation().clone();
+        data.parents = data
+            .parents
+            .iter()
+            .flat_map(|id| {
+                new_parent_ids
+                    .get(id)
+                    .cloned()
+                    .unwrap_or_else(|| vec![rewritten_ids.get(id).unwrap_or(id).clone()])
+            })
+            .unique()
+            .collect();
+        if abandoned_ids.contains(old_op.id()) {
+            new_parent_ids.insert(old_op.id().clone(), data.parents);
+            unreachable_count += 1;
+        } else if data.parents != old_op.parent_ids() {
+            let new_id = op_store.write_operation(&data).block_on()?;
+            rewritten_ids.insert(old_op.id().clone(), new_id);
+        }
+    }
+
+    let new_head_ids = head_ops
+        .iter()
+        .map(|op| rewritten_ids.get(op.id()).unwrap_or(op.id()).clone())
+        .collect();
+    Ok(ReparentStats {
+        new_head_ids,
+        rewritten_count: rewritten_ids.len(),
+        unreachable_count,
+    })
+}
+
+/// Rewrites the operation `op` with the `tags` added to its metadata.
+///
+/// The descendants of `op` are rewritten onto the new operation. Existing tags
+/// of the same names are overwritten. The root operation can't be rewritten.
+///
+/// Returns the new head operation ids in order of the old `head_ops` as well as
+/// some stats. The old operation is counted as unreachable if it was rewritten.
+pub fn set_operation_tags(
+    op_store: &dyn OpStore,
+    op: &Operation,
+    tags: &HashMap<String, String>,
+    head_ops: &[Operation],
+) -> OpStoreResult<ReparentStats> {
+    assert_ne!(
+        op.id(),
+        op_store.root_operation_id(),
+        "root operation cannot be rewritten"
+    );
+    let parent_ops: Vec<_> = op.parents().try_collect()?;
+    let ops: Vec<_> = walk_ancestors_range(head_ops, &parent_ops).try_collect()?;
+    let mut rewritten_ids = HashMap::new();
+    for old_op in ops.iter().rev() {
+        let mut data = old_op.store_operation().clone();
+        data.parents = data
+            .parents
+            .iter()
+            .map(|id| rewritten_ids.get(id).unwrap_or(id).clone())
+            .collect();
+        if old_op.id() == op.id() {
+            data.metadata
+                .tags
+                .extend(tags.iter().map(|(key, value)| (key.clone(), value.clone())));
+        }
+        if data != *old_op.store_operation() {
+            let new_id = op_store.write_operation(&data).block_on()?;
+            rewritten_ids.insert(old_op.id().clone(), new_id);
+        }
+    }
+
+    let new_head_ids = head_ops
+        .iter()
+        .map(|op| rewritten_ids.get(op.id()).unwrap_or(op.id()).clone())
+        .collect();
+    Ok(ReparentStats {
+        new_head_ids,
+        rewritten_count: rewritten_ids.len(),
+        unreachable_count: usize::from(rewritten_ids.contains_key(op.id())),
+    })
+}
+
+/// Stats about `copy_operations()`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CopyStats {
+    /// New head operation ids in order of the `head_ops`.
+    pub new_head_ids: Vec<OperationId>,
+    /// The number of copied operations.
+    pub copied_count: usize,
+}
+
+/// Copies the operations reachable from the `head_ops` and their views to the
+/// `dest_store`.
+///
+/// Operation ids are preserved unless the source store normalized the data
+/// on read, in which case the descendants are rewritten accordingly.
+pub fn copy_operations(
+    head_ops: &[Operation],
+    dest_store: &dyn OpStore,
+) -> OpStoreResult<CopyStats> {
+    let ops_to_copy: Vec<_> = walk_ancestors(head_ops).try_collect()?;
+    let mut copied_ids = HashMap::new();
+    let mut copied_count = 0;
+    for op in ops_to_copy.into_iter().rev() {
+        if op.parent_ids().is_empty() {
+            // The root operation exists in any store.
+            copied_ids.insert(op.id().clone(), dest_store.root_operation_id().clone());
+            continue;
+        }
+        let view = op.op_store().read_view(op.view_id()).block_on()?;
+        let view_id = dest_store.write_view(&view).block_on()?;
+        let mut data = op.store_operation().clone();
+        data.view_id = view_id;
+        data.parents = data
+            .parents
+            .iter()
+            .map(|id| copied_ids[id].clone())
+            .collect();
+        let new_id = dest_store.write_operation(&data).block_on()?;
+        copied_ids.insert(op.id().clone(), new_id);
+        copied_count += 1;
+    }
+    let new_head_ids = head_ops
+        .iter()
+        .map(|op| copied_ids[op.id()].clone())
+        .collect();
+    Ok(CopyStats {
+        new_head_ids,
+        copied_count,
+    })
+}
+
+/// Folds runs of consecutive snapshot operations which ended before the
+/// `cutoff` into single operations.
+///
+/// Each run is replaced by an operation which has the view and metadata of the
+/// last operation of the run, and the start time of the first. The commit
+/// predecessors recorded by the folded operations are merged into the new
+/// operation so the evolution of the working-copy commits can still be
+/// traversed. The descendants of the runs are rewritten onto the new
+/// operations.
+///
+/// Returns the new head operation ids in order of the old `head_ops` as well as
+/// some stats. The folded operations are counted as unreachable.
+pub fn compact_snapshots(
+    op_store: &dyn OpStore,
+    head_ops: &[Operation],
+    cutoff: MillisSinceEpoch,
+) -> OpStoreResult<ReparentStats> {
+    let ops: Vec<_> = walk_ancestors(head_ops).try_collect()?;
+    let mut children: HashMap<&OperationId, Vec<&Operation>> = HashMap::new();
+    for op in &ops {
+        for parent_id in op.parent_ids() {
+            children.entry(parent_id).or_default().push(op);
+        }
+    }
+    let is_compactable = |op: &Operation| {
+        let metadata = op.metadata();
+        metadata.is_snapshot && metadata.time.end.timestamp < cutoff && op.parent_ids().len() == 1
+    };
+    // Snapshot operations to be folded into their only child.
+    let folded_ids: HashSet<&OperationId> = ops
+        .iter()
+        .filter(|op| is_compactable(op))
+        .filter(|op| match children.get(op.id()).map(Vec::as_slice) {
+            Some([child]) => is_compactable(child),
+            _ => false,
+        })
+        .map(|op| op.id())
+        .collect();
+
+    // Maps folded operations to the data accumulated from the runs.
+    let mut folded_data: HashMap<&OperationId, op_store::Operation> = HashMap::new();
+    let mut rewritten_ids = HashMap::new();
+    for old_op in ops.iter().rev() {
+        let mut data = old_op.store_operation().clone();
+        data.parents = data
+            .parents
+            .iter()
+            .map(|id| rewritten_ids.get(id).unwrap_or(id).clone())
+            .collect();
+        if let [parent_id] = old_op.parent_ids()
+            && let Some(parent_data) = folded_data.remove(parent_id)
+        {
+            data.parents = parent_data.parents;
+            data.metadata.time.start = parent_data.metadata.time.start;
+            // Keep the recorded predecessors even if some of the operations
+            // didn't record them.
+            data.commit_predecessors =
+                match (parent_data.commit_predecessors, data.commit_predecessors) {
+                    (Some(mut map), Some(new_map)) => {
+                        map.extend(new_map);
+                        Some(map)
+                    }
+                    (map, None) | (None, map) => map,
+                };
+        }
+        if folded_ids.contains(old_op.id()) {
+            folded_data.insert(old_op.id(), data);
+        } else if data != *old_op.store_operation() {
+            let new_id = op_store.write_operation(&data).block_on()?;
+            rewritten_ids.insert(old_op.id().clone(), new_id);
+        }
+    }
+
+    let new_head_ids = head_ops
+        .iter()
+        .map(|op| rewritten_ids.get(op.id()).unwrap_or(op.id()).clone())
+        .collect();
+    Ok(ReparentStats {
+        new_head_ids,
+        rewritten_count: rewritten_ids.len(),
+        unreachable_count: folded_ids.len(),
+    })
+}
+
+/// Rules to select old operations to be pruned by `select_expired_operations()`.
+#[derive(Clone, Debug)]
+pub struct OperationRetentionPolicy {
+    /// Operations which ended at or after this time are kept.
+    pub keep_all_since: MillisSinceEpoch,
+    /// Of the operations which ended at or after this time, the last operation
+    /// of each day is kept. Days are counted in the time zone the operations
+    /// were recorded in.
+    pub keep_daily_since: MillisSinceEpoch,
+    /// Operations which have tags not listed here are kept.
+    pub ignored_tag_names: HashSet<String>,
+    /// Operations which are kept regardless of their age.
+    pub kept_ids: HashSet<OperationId>,
+}
+
+/// Selects the ancestors of the `head_ops` which aren't retained by the
+/// `policy`.
+///
+/// The root operation and the `head_ops` are always retained. The returned
+/// operations can be passed to `abandon_operations()`.
+pub fn select_expired_operations(
+    op_store: &dyn OpStore,
+    head_ops: &[Operation],
+    policy: &OperationRetentionPolicy,
+) -> OpStoreResult<HashSet<OperationId>> {
+    const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+    let day_of = |op: &Operation| {
+        let end = &op.metadata().time.end;
+        let offset_millis = i64::from(end.tz_offset) * 60 * 1000;
+        (end.timestamp.0 + offset_millis).div_euclid(MILLIS_PER_DAY)
+    };
+    let is_kept = |op: &Operation| {
+        let metadata = op.metadata();
+        op.id() == op_store.root_operation_id()
+            || head_ops.contains(op)
+            || policy.kept_ids.contains(op.id())
+            || metadata.time.end.timestamp >= policy.keep_all_since
+            || metadata
+                .tags
+                .keys()
+                .any(|name| !policy.ignored_tag_names.contains(name))
+    };
+    let ops: Vec<_> = walk_ancestors(head_ops).try_collect()?;
+    // The last operation of each day, which may also be retained by the other
+    // rules.
+    let mut daily_ops: HashMap<i64, &Operation> = HashMap::new();
+    for op in &ops {
+        let end = op.metadata().time.end.timestamp;
+        if end < policy.keep_daily_since {
+            continue;
+        }
+        let latest_op = daily_ops.entry(day_of(op)).or_insert(op);
+        if latest_op.metadata().time.end.timestamp < end {
+            *latest_op = op;
+        }
+    }
+    let expired_ids = ops
+        .iter()
+        .filter(|op| !is_kept(op))
+        .filter(|op| {
+            daily_ops
+                .get(&day_of(op))
+                .is_none_or(|latest_op| latest_op != op)
+        })
+        .map(|op| op.id().clone())
+        .collect();
+    Ok(expired_ids)
+}
@@ -0,0 +1,712 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Functional language for selecting a set of operations.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::LazyLock;
+
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+
+use crate::backend::MillisSinceEpoch;
+use crate::dsl_util::InvalidArguments;
+use crate::dsl_util::collect_similar;
+use crate::op_store::OpStore;
+use crate::op_store::OpStoreResult;
+use crate::op_store::OperationId;
+use crate::op_walk;
+use crate::op_walk::OpsetEvaluationError;
+use crate::op_walk::OpsetResolutionError;
+use crate::operation::Operation;
+use crate::opset_parser;
+use crate::opset_parser::BinaryOp;
+use crate::opset_parser::ExpressionKind;
+use crate::opset_parser::ExpressionNode;
+use crate::opset_parser::FunctionCallNode;
+pub use crate::opset_parser::OpsetParseError;
+pub use crate::opset_parser::OpsetParseErrorKind;
+pub use crate::opset_parser::OpsetParseResult;
+use crate::opset_parser::UnaryOp;
+use crate::str_util::StringPattern;
+use crate::time_util::DatePattern;
+use crate::time_util::DatePatternContext;
+
+/// Predicate on operation metadata.
+#[derive(Clone, Debug)]
+pub enum OperationFilter {
+    /// Operations that snapshotted the working copy.
+    Snapshot,
+    /// Operations whose description matches the pattern.
+    Description(StringPattern),
+    /// Operations run on a host whose name matches the pattern.
+    Hostname(StringPattern),
+    /// Operations run by a user whose name matches the pattern.
+    Username(StringPattern),
+    /// Operations which ended within the date range.
+    Time(DatePattern),
+    /// Operations with a tag `key` whose value matches the pattern.
+    Tag {
+        /// Tag name.
+        key: String,
+        /// Pattern to match the tag value.
+        value: StringPattern,
+    },
+}
+
+impl OperationFilter {
+    fn matches(&self, op: &Operation) -> bool {
+        let metadata = op.metadata();
+        match self {
+            Self::Snapshot => metadata.is_snapshot,
+            Self::Description(pattern) => pattern.is_match(&metadata.description),
+            Self::Hostname(pattern) => pattern.is_match(&metadata.hostname),
+            Self::Username(pattern) => pattern.is_match(&metadata.username),
+            Self::Time(pattern) => pattern.matches(&metadata.time.end),
+            Self::Tag { key, value } => metadata
+                .tags
+                .get(key)
+                .is_some_and(|tag_value| value.is_match(tag_value)),
+        }
+    }
+}
+
+/// Operation set expression.
+#[derive(Clone, Debug)]
+pub enum OpsetExpression {
+    /// Empty set.
+    None,
+    /// All operations reachable from the head operations.
+    All,
+    /// The current operation, `@`.
+    Current,
+    /// Operation ID or unique prefix.
+    Id(String),
+    /// Parents of the operations.
+    Parents(Arc<Self>),
+    /// Children of the operations.
+    Children(Arc<Self>),
+    /// Ancestors of the operations, including themselves.
+    Ancestors(Arc<Self>),
+    /// Ancestors of `heads` excluding ancestors of `roots`.
+    Range {
+        /// Operations whose ancestors are excluded.
+        roots: Arc<Self>,
+        /// Operations whose ancestors are included.
+        heads: Arc<Self>,
+    },
+    /// Operations matching the predicate.
+    Filter(OperationFilter),
+    /// The `count` most recent operations of the `candidates`.
+    Latest {
+        /// Operations to select from.
+        candidates: Arc<Self>,
+        /// Maximum number of operations to select.
+        count: usize,
+    },
+    /// Union of the two sets.
+    Union(Arc<Self>, Arc<Self>),
+    /// Intersection of the two sets.
+    Intersection(Arc<Self>, Arc<Self>),
+    /// Operations in the first set but not in the second.
+    Difference(Arc<Self>, Arc<Self>),
+}
+
+impl OpsetExpression {
+    /// Empty set.
+    pub fn none() -> Arc<Self> {
+        Arc::new(Self::None)
+    }
+
+    /// All operations reachable from the head operations.
+    pub fn all() -> Arc<Self> {
+        Arc::new(Self::All)
+    }
+
+    /// Operations matching the predicate.
+    pub fn filter(predicate: OperationFilter) -> Arc<Self> {
+        Arc::new(Self::Filter(predicate))
+    }
+
+    /// The latest operation which completed at or before the given `time`.
+    pub fn at_time(time: MillisSinceEpoch) -> Arc<Self> {
+        // DatePattern::Before excludes the given instant.
+        let before = DatePattern::Before(MillisSinceEpoch(time.0 + 1));
+        Self::filter(OperationFilter::Time(before)).latest(1)
+    }
+
+    /// Parents of `self`.
+    pub fn parents(self: &Arc<Self>) -> Arc<Self> {
+        Arc::new(Self::Parents(self.clone()))
+    }
+
+    /// Children of `self`.
+    pub fn children(self: &Arc<Self>) -> Arc<Self> {
+        Arc::new(Self::Children(self.clone()))
+    }
+
+    /// Ancestors of `self`, including themselves.
+    pub fn ancestors(self: &Arc<Self>) -> Arc<Self> {
+        Arc::new(Self::Ancestors(self.clone()))
+    }
+
+    /// Ancestors of `heads` excluding ancestors of `self`.
+    pub fn range(self: &Arc<Self>, heads: &Arc<Self>) -> Arc<Self> {
+        Arc::new(Self::Range {
+            roots: self.clone(),
+            heads: heads.clone(),
+        })
+    }
+
+    /// The `count` most recent operations of `self`.
+    pub fn latest(self: &Arc<Self>, count: usize) -> Arc<Self> {
+        Arc::new(Self::Latest {
+            candidates: self.clone(),
+            count,
+        })
+    }
+
+    /// Union of `self` and `other`.
+    pub fn union(self: &Arc<Self>, other: &Arc<Self>) -> Arc<Self> {
+        Arc::new(Self::Union(self.clone(), other.clone()))
+    }
+
+    /// Intersection of `self` and `other`.
+    pub fn intersection(self: &Arc<Self>, other: &Arc<Self>) -> Arc<Self> {
+        Arc::new(Self::Intersection(self.clone(), other.clone()))
+    }
+
+    /// Operations in `self` but not in `other`.
+    pub fn minus(self: &Arc<Self>, other: &Arc<Self>) -> Arc<Self> {
+        Arc::new(Self::Difference(self.clone(), other.clone()))
+    }
+
+    /// Resolves operation IDs and ranges against the `head_ops`.
+    ///
+    /// The "@" symbol will be resolved to the head operation, which must be
+    /// unique.
+    pub fn resolve(
+        &self,
+        op_store: &Arc<dyn OpStore>,
+        head_ops: &[Operation],
+    ) -> Result<ResolvedOpset, OpsetEvaluationError> {
+        let context = EvaluationContext { op_store, head_ops };
+        let expression = context.resolve(self)?;
+        Ok(ResolvedOpset {
+            expression,
+            head_ops: head_ops.to_vec(),
+        })
+    }
+}
+
+/// Operation set expression of which symbols and ranges are resolved.
+#[derive(Clone, Debug)]
+enum ResolvedExpression {
+    All,
+    Operations(HashSet<OperationId>),
+    Filter(OperationFilter),
+    Union(Box<Self>, Box<Self>),
+    Intersection(Box<Self>, Box<Self>),
+    Difference(Box<Self>, Box<Self>),
+}
+
+impl ResolvedExpression {
+    fn contains(&self, op: &Operation) -> bool {
+        match self {
+            Self::All => true,
+            Self::Operations(ids) => ids.contains(op.id()),
+            Self::Filter(predicate) => predicate.matches(op),
+            Self::Union(expression1, expression2) => {
+                expression1.contains(op) || expression2.contains(op)
+            }
+            Self::Intersection(expression1, expression2) => {
+                expression1.contains(op) && expression2.contains(op)
+            }
+            Self::Difference(expression1, expression2) => {
+                expression1.contains(op) && !expression2.contains(op)
+            }
+        }
+    }
+}
+
+/// Operation set resolved against head operations.
+#[derive(Clone, Debug)]
+pub struct ResolvedOpset {
+    expression: ResolvedExpression,
+    head_ops: Vec<Operation>,
+}
+
+impl ResolvedOpset {
+    /// Returns true if the set contains the operation.
+    pub fn contains(&self, op: &Operation) -> bool {
+        self.expression.contains(op)
+    }
+
+    /// Walks operations in the set in reverse topological order. Only
+    /// ancestors of the head operations are visited.
+    pub fn evaluate(&self) -> impl Iterator<Item = OpStoreResult<Operation>> + use<'_> {
+        op_walk::walk_ancestors(&self.head_ops).filter_ok(|op| self.contains(op))
+    }
+
+    /// Returns the operation IDs if the set was resolved to a fixed list of
+    /// operations.
+    pub(crate) fn explicit_ids(&self) -> Option<&HashSet<OperationId>> {
+        match &self.expression {
+            ResolvedExpression::Operations(ids) => Some(ids),
+            _ => None,
+        }
+    }
+}
+
+struct EvaluationContext<'a> {
+    op_store: &'a Arc<dyn OpStore>,
+    head_ops: &'a [Operation],
+}
+
+impl EvaluationContext<'_> {
+    fn resolve(
+        &self,
+        expression: &OpsetExpression,
+    ) -> Result<ResolvedExpression, OpsetEvaluationError> {
+        let ids = match expression {
+            OpsetExpression::None => HashSet::new(),
+            OpsetExpression::All => return Ok(ResolvedExpression::All),
+            OpsetExpression::Current => match self.head_ops {
+                [head_op] => HashSet::from([head_op.id().clone()]),
+                [] => Err(OpsetResolutionError::EmptyOperations("@".to_owned()))?,
+                _ => Err(OpsetResolutionError::MultipleOperations {
+                    expr: "@".to_owned(),
+                    candidates: self.head_ops.iter().map(|op| op.id().clone()).collect(),
+                })?,
+            },
+            OpsetExpression::Id(prefix) => {
+                let op = op_walk::resolve_single_op_from_store(self.op_store, prefix)?;
+                HashSet::from([op.id().clone()])
+            }
+            OpsetExpression::Parents(expression) => self
+                .evaluate(expression)?
+                .iter()
+                .flat_map(|op| op.parent_ids())
+                .cloned()
+                .collect(),
+            OpsetExpression::Children(expression) => {
+                let parent_ids = self.evaluate_ids(expression)?;
+                op_walk::walk_ancestors(self.head_ops)
+                    .filter_ok(|op| op.parent_ids().iter().any(|id| parent_ids.contains(id)))
+                    .map_ok(|op| op.id().clone())
+                    .try_collect()?
+            }
+            OpsetExpression::Ancestors(expression) => {
+                op_walk::walk_ancestors(&self.evaluate(expression)?)
+                    .map_ok(|op| op.id().clone())
+                    .try_collect()?
+            }
+            OpsetExpression::Range { roots, heads } => {
+                let root_ops = self.evaluate(roots)?;
+                let head_ops = self.evaluate(heads)?;
+                op_walk::walk_ancestors_range(&head_ops, &root_ops)
+                    .map_ok(|op| op.id().clone())
+                    .try_collect()?
+            }
+            OpsetExpression::Filter(predicate) => {
+                return Ok(ResolvedExpression::Filter(predicate.clone()));
+            }
+            OpsetExpression::Latest { candidates, count } => {
+                let mut ops = self.evaluate(candidates)?;
+                // Sort by ID as well to stabilize the order of concurrent ops.
+                ops.sort_by(|op1, op2| {
+                    let time1 = &op1.metadata().time.end.timestamp;
+                    let time2 = &op2.metadata().time.end.timestamp;
+                    time2.cmp(time1).then_with(|| op2.id().cmp(op1.id()))
+                });
+                ops.iter().take(*count).map(|op| op.id().clone()).collect()
+            }
+            OpsetExpression::Union(expression1, expression2) => {
+                return Ok(ResolvedExpression::Union(
+                    Box::new(self.resolve(expression1)?),
+                    Box::new(self.resolve(expression2)?),
+                ));
+            }
+            OpsetExpression::Intersection(expression1, expression2) => {
+                return Ok(ResolvedExpression::Intersection(
+                    Box::new(self.resolve(expression1)?),
+                    Box::new(self.resolve(expression2)?),
+                ));
+            }
+            OpsetExpression::Difference(expression1, expression2) => {
+                return Ok(ResolvedExpression::Difference(
+                    Box::new(self.resolve(expression1)?),
+                    Box::new(self.resolve(expression2)?),
+                ));
+            }
+        };
+        Ok(ResolvedExpression::Operations(ids))
+    }
+
+    /// Evaluates the `expression` to operations in no particular order.
+    fn evaluate(
+        &self,
+        expression: &OpsetExpression,
+    ) -> Result<Vec<Operation>, OpsetEvaluationError> {
+        match self.resolve(expression)? {
+            ResolvedExpression::Operations(ids) => Ok(ids
+                .into_iter()
+                .map(|id| {
+                    let data = self.op_store.read_operation(&id).block_on()?;
+                    Ok::<_, OpsetEvaluationError>(Operation::new(self.op_store.clone(), id, data))
+                })
+                .try_collect()?),
+            expression => Ok(op_walk::walk_ancestors(self.head_ops)
+                .filter_ok(|op| expression.contains(op))
+                .try_collect()?),
+        }
+    }
+
+    fn evaluate_ids(
+        &self,
+        expression: &OpsetExpression,
+    ) -> Result<HashSet<OperationId>, OpsetEvaluationError> {
+        match self.resolve(expression)? {
+            ResolvedExpression::Operations(ids) => Ok(ids),
+            expression => Ok(op_walk::walk_ancestors(self.head_ops)
+                .filter_ok(|op| expression.contains(op))
+                .map_ok(|op| op.id().clone())
+                .try_collect()?),
+        }
+    }
+}
+
+type OpsetFunction =
+    fn(&FunctionCallNode, &DatePatternContext) -> OpsetParseResult<Arc<OpsetExpression>>;
+
+static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, OpsetFunction>> = LazyLock::new(|| {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map: HashMap<&str, OpsetFunction> = HashMap::new();
+    map.insert("none", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(OpsetExpression::none())
+    });
+    map.insert("all", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(OpsetExpression::all())
+    });
+    map.insert("snapshots", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(OpsetExpression::filter(OperationFilter::Snapshot))
+    });
+    map.insert("description", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg, StringPattern::substring)?;
+        Ok(OpsetExpression::filter(OperationFilter::Description(
+            pattern,
+        )))
+    });
+    map.insert("hostname", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg, StringPattern::substring)?;
+        Ok(OpsetExpression::filter(OperationFilter::Hostname(pattern)))
+    });
+    map.insert("user", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg, StringPattern::substring)?;
+        Ok(OpsetExpression::filter(OperationFilter::Username(pattern)))
+    });
+    map.insert("time", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_date_pattern(arg, context)?;
+        Ok(OpsetExpression::filter(OperationFilter::Time(pattern)))
+    });
+    map.insert("tags", |function, _context| {
+        if function.keyword_args.is_empty() {
+            return Err(InvalidArguments {
+                name: function.name,
+                message: "Expected tag name and value as keyword arguments".to_owned(),
+                span: function.args_span,
+            }
+            .into());
+        }
+        if let Some(arg) = function.args.first() {
+            return Err(InvalidArguments {
+                name: function.name,
+                message: "Unexpected positional arguments".to_owned(),
+                span: arg.span,
+            }
+            .into());
+        }
+        let mut expression = OpsetExpression::all();
+        for arg in &function.keyword_args {
+            let value = expect_string_pattern(&arg.value, StringPattern::exact)?;
+            let predicate = OperationFilter::Tag {
+                key: arg.name.to_owned(),
+                value,
+            };
+            expression = expression.intersection(&OpsetExpression::filter(predicate));
+        }
+        Ok(expression)
+    });
+    map.insert("latest", |function, context| {
+        let ([candidates_arg], [count_opt_arg]) = function.expect_arguments()?;
+        let candidates = resolve_expression(candidates_arg, context)?;
+        let count = if let Some(count_arg) = count_opt_arg {
+            expect_literal("integer", count_arg)?
+        } else {
+            1
+        };
+        Ok(candidates.latest(count))
+    });
+    map
+});
+
+fn expect_string_pattern(
+    node: &ExpressionNode,
+    default_pattern: fn(String) -> StringPattern,
+) -> OpsetParseResult<StringPattern> {
+    match &node.kind {
+        ExpressionKind::Identifier(value) => Ok(default_pattern((*value).to_owned())),
+        ExpressionKind::String(value) => Ok(default_pattern(value.clone())),
+        ExpressionKind::StringPattern { kind, value } => StringPattern::from_str_kind(value, kind)
+            .map_err(|err| {
+                OpsetParseError::expression("Invalid string pattern", node.span).with_source(err)
+            }),
+        _ => Err(OpsetParseError::expression(
+            "Expected string pattern",
+            node.span,
+        )),
+    }
+}
+
+fn expect_date_pattern(
+    node: &ExpressionNode,
+    context: &DatePatternContext,
+) -> OpsetParseResult<DatePattern> {
+    match &node.kind {
+        ExpressionKind::StringPattern { kind, value } => {
+            context.parse_relative(value, kind).map_err(|err| {
+                OpsetParseError::expression("Invalid date pattern", node.span).with_source(err)
+            })
+        }
+        _ => Err(OpsetParseError::expression(
+            "Date pattern must specify 'after' or 'before'",
+            node.span,
+        )),
+    }
+}
+
+fn expect_literal<T: std::str::FromStr>(
+    type_name: &str,
+    node: &ExpressionNode,
+) -> OpsetParseResult<T> {
+    let value = match &node.kind {
+        ExpressionKind::Identifier(value) => *value,
+        ExpressionKind::String(value) => value.as_str(),
+        _ => "",
+    };
+    value
+        .parse()
+        .map_err(|_| OpsetParseError::expression(format!("Expected {type_name}"), node.span))
+}
+
+fn resolve_function(
+    function: &FunctionCallNode,
+    context: &DatePatternContext,
+) -> OpsetParseResult<Arc<OpsetExpression>> {
+    if let Some(func) = BUILTIN_FUNCTION_MAP.get(function.name) {
+        func(function, context)
+    } else {
+        Err(OpsetParseError::new(
+            OpsetParseErrorKind::NoSuchFunction {
+                name: function.name.to_owned(),
+                candidates: collect_similar(function.name, BUILTIN_FUNCTION_MAP.keys()),
+            },
+            function.name_span,
+        ))
+    }
+}
+
+fn resolve_expression(
+    node: &ExpressionNode,
+    context: &DatePatternContext,
+) -> OpsetParseResult<Arc<OpsetExpression>> {
+    match &node.kind {
+        ExpressionKind::Identifier(name) => Ok(Arc::new(OpsetExpression::Id((*name).to_owned()))),
+        ExpressionKind::String(name) => Ok(Arc::new(OpsetExpression::Id(name.clone()))),
+        ExpressionKind::StringPattern { kind: "at", value } => {
+            let time = context.parse_instant(value).map_err(|err| {
+                OpsetParseError::expression("Invalid date", node.span).with_source(err)
+            })?;
+            Ok(OpsetExpression::at_time(time))
+        }
+        ExpressionKind::StringPattern { .. } => Err(OpsetParseError::expression(
+            "String patterns other than `at:` may only be used as function arguments",
+            node.span,
+        )),
+        ExpressionKind::AtOperation => Ok(Arc::new(OpsetExpression::Current)),
+        ExpressionKind::RangeAll => Ok(OpsetExpression::all()),
+        ExpressionKind::Unary(op, arg_node) => {
+            let arg = resolve_expression(arg_node, context)?;
+            match op {
+                UnaryOp::Negate => Ok(OpsetExpression::all().minus(&arg)),
+                UnaryOp::RangePre => Ok(arg.ancestors()),
+                UnaryOp::RangePost => Ok(OpsetExpression::all().minus(&arg.ancestors())),
+                UnaryOp::Parents => Ok(arg.parents()),
+                UnaryOp::Children => Ok(arg.children()),
+            }
+        }
+        ExpressionKind::Binary(op, lhs_node, rhs_node) => {
+            let lhs = resolve_expression(lhs_node, context)?;
+            let rhs = resolve_expression(rhs_node, context)?;
+            match op {
+                BinaryOp::Intersection => Ok(lhs.intersection(&rhs)),
+                BinaryOp::Difference => Ok(lhs.minus(&rhs)),
+                BinaryOp::Range => Ok(lhs.range(&rhs)),
+            }
+        }
+        ExpressionKind::UnionAll(nodes) => {
+            let expressions: Vec<_> = nodes
+                .iter()
+                .map(|node| resolve_expression(node, context))
+                .try_collect()?;
+            Ok(expressions
+                .iter()
+                .skip(1)
+                .fold(expressions[0].clone(), |acc, expression| {
+                    acc.union(expression)
+                }))
+        }
+        ExpressionKind::FunctionCall(function) => resolve_function(function, context),
+    }
+}
+
+/// Parses text into `OpsetExpression`.
+///
+/// Relative dates such as "yesterday" are resolved against the `context`.
+pub fn parse(text: &str, context: &DatePatternContext) -> OpsetParseResult<Arc<OpsetExpression>> {
+    let node = opset_parser::parse_program(text)?;
+    resolve_expression(&node, context)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    fn parse_with_epoch(text: &str) -> OpsetParseResult<Arc<OpsetExpression>> {
+        let context = chrono::DateTime::UNIX_EPOCH.fixed_offset().into();
+        parse(text, &context)
+    }
+
+    #[test]
+    fn test_parse_symbols() {
+        assert_matches!(*parse_with_epoch("@").unwrap(), OpsetExpression::Current);
+        assert_matches!(
+            &*parse_with_epoch("abc123").unwrap(),
+            OpsetExpression::Id(id) if id == "abc123"
+        );
+        assert_matches!(
+            &*parse_with_epoch("@-").unwrap(),
+            OpsetExpression::Parents(expression)
+                if matches!(**expression, OpsetExpression::Current)
+        );
+        assert_matches!(&*parse_with_epoch("..").unwrap(), OpsetExpression::All);
+        assert_matches!(
+            parse_with_epoch(r#"glob:"foo*""#).map_err(|err| err.kind().clone()),
+            Err(OpsetParseErrorKind::Expression(_))
+        );
+    }
+
+    #[test]
+    fn test_parse_functions() {
+        assert_matches!(
+            &*parse_with_epoch("snapshots()").unwrap(),
+            OpsetExpression::Filter(OperationFilter::Snapshot)
+        );
+        assert_matches!(
+            &*parse_with_epoch(r#"description(glob:"git fetch*")"#).unwrap(),
+            OpsetExpression::Filter(OperationFilter::Description(pattern))
+                if pattern.to_glob().as_deref() == Some("git fetch*")
+        );
+        assert_matches!(
+            parse_with_epoch("time(after:1970-01-02)").map_err(|err| err.kind().clone()),
+            Err(OpsetParseErrorKind::SyntaxError)
+        );
+        assert_matches!(
+            &*parse_with_epoch(r#"time(after:"1970-01-02")"#).unwrap(),
+            OpsetExpression::Filter(OperationFilter::Time(DatePattern::AtOrAfter(_)))
+        );
+        assert_matches!(
+            parse_with_epoch("time(yesterday)").map_err(|err| err.kind().clone()),
+            Err(OpsetParseErrorKind::Expression(_))
+        );
+        assert_matches!(
+            &*parse_with_epoch("latest(snapshots(), 3)").unwrap(),
+            OpsetExpression::Latest { count: 3, .. }
+        );
+        assert_matches!(
+            parse_with_epoch("latest(snapshots(), x)").map_err(|err| err.kind().clone()),
+            Err(OpsetParseErrorKind::Expression(_))
+        );
+        assert_matches!(
+            parse_with_epoch("snapshot()").map_err(|err| err.kind().clone()),
+            Err(OpsetParseErrorKind::NoSuchFunction { candidates, .. })
+                if candidates == ["snapshots"]
+        );
+    }
+
+    #[test]
+    fn test_parse_at_time() {
+        let expression = parse_with_epoch(r#"at:"1970-01-02T00:00:00Z""#).unwrap();
+        let OpsetExpression::Latest { candidates, count } = &*expression else {
+            panic!("unexpected expression: {expression:?}");
+        };
+        assert_eq!(*count, 1);
+        assert_matches!(
+            &**candidates,
+            OpsetExpression::Filter(OperationFilter::Time(DatePattern::Before(time)))
+                if time.0 == 86_400_000 + 1
+        );
+        assert_matches!(
+            parse_with_epoch(r#"at:"not a date""#).map_err(|err| err.kind().clone()),
+            Err(OpsetParseErrorKind::Expression(_))
+        );
+        assert_matches!(
+            &*parse_with_epoch(r#"at:"1970-01-02"-"#).unwrap(),
+            OpsetExpression::Parents(_)
+        );
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        let expression = parse_with_epoch("tags(host=ci)").unwrap();
+        let OpsetExpression::Intersection(_, filter) = &*expression else {
+            panic!("unexpected expression: {expression:?}");
+        };
+        assert_matches!(
+            &**filter,
+            OpsetExpression::Filter(OperationFilter::Tag { key, value })
+                if key == "host" && value.as_exact() == Some("ci")
+        );
+        assert_matches!(
+            parse_with_epoch("tags()").map_err(|err| err.kind().clone()),
+            Err(OpsetParseErrorKind::InvalidArguments { .. })
+        );
+        assert_matches!(
+            parse_with_epoch("tags(ci)").map_err(|err| err.kind().clone()),
+            Err(OpsetParseErrorKind::InvalidArguments { .. })
+        );
+    }
+}
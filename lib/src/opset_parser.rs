@@ -0,0 +1,573 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parser for the operation set language.
+
+use std::error;
+use std::sync::LazyLock;
+
+use itertools::Itertools as _;
+use pest::Parser as _;
+use pest::iterators::Pair;
+use pest::pratt_parser::Assoc;
+use pest::pratt_parser::Op;
+use pest::pratt_parser::PrattParser;
+use pest_derive::Parser;
+use thiserror::Error;
+
+use crate::dsl_util;
+use crate::dsl_util::FunctionCallParser;
+use crate::dsl_util::InvalidArguments;
+use crate::dsl_util::StringLiteralParser;
+
+#[derive(Parser)]
+#[grammar = "opset.pest"]
+struct OpsetParser;
+
+const STRING_LITERAL_PARSER: StringLiteralParser<Rule> = StringLiteralParser {
+    content_rule: Rule::string_content,
+    escape_rule: Rule::string_escape,
+};
+const FUNCTION_CALL_PARSER: FunctionCallParser<Rule> = FunctionCallParser {
+    function_name_rule: Rule::function_name,
+    function_arguments_rule: Rule::function_arguments,
+    keyword_argument_rule: Rule::keyword_argument,
+    argument_name_rule: Rule::strict_identifier,
+    argument_value_rule: Rule::expression,
+};
+
+impl Rule {
+    fn to_symbol(self) -> Option<&'static str> {
+        match self {
+            Self::EOI => None,
+            Self::whitespace => None,
+            Self::identifier => None,
+            Self::strict_identifier => None,
+            Self::string_escape => None,
+            Self::string_content_char => None,
+            Self::string_content => None,
+            Self::string_literal => None,
+            Self::raw_string_content => None,
+            Self::raw_string_literal => None,
+            Self::at_op => Some("@"),
+            Self::pattern_kind_op => Some(":"),
+            Self::parents_op => Some("-"),
+            Self::children_op => Some("+"),
+            Self::range_op => Some(".."),
+            Self::range_pre_op => Some(".."),
+            Self::range_post_op => Some(".."),
+            Self::range_all_op => Some(".."),
+            Self::negate_op => Some("~"),
+            Self::union_op => Some("|"),
+            Self::intersection_op => Some("&"),
+            Self::difference_op => Some("~"),
+            Self::infix_op => None,
+            Self::function => None,
+            Self::function_name => None,
+            Self::keyword_argument => None,
+            Self::argument => None,
+            Self::function_arguments => None,
+            Self::string_pattern => None,
+            Self::primary => None,
+            Self::neighbors_expression => None,
+            Self::range_expression => None,
+            Self::expression => None,
+            Self::program => None,
+        }
+    }
+}
+
+/// Result of opset parsing and name resolution.
+pub type OpsetParseResult<T> = Result<T, OpsetParseError>;
+
+/// Error occurred during opset parsing and name resolution.
+#[derive(Debug, Error)]
+#[error("{pest_error}")]
+pub struct OpsetParseError {
+    kind: OpsetParseErrorKind,
+    pest_error: Box<pest::error::Error<Rule>>,
+    source: Option<Box<dyn error::Error + Send + Sync>>,
+}
+
+/// Categories of opset parsing and name resolution error.
+#[expect(missing_docs)]
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum OpsetParseErrorKind {
+    #[error("Syntax error")]
+    SyntaxError,
+    #[error("Function `{name}` doesn't exist")]
+    NoSuchFunction {
+        name: String,
+        candidates: Vec<String>,
+    },
+    #[error("Function `{name}`: {message}")]
+    InvalidArguments { name: String, message: String },
+    #[error("{0}")]
+    Expression(String),
+}
+
+impl OpsetParseError {
+    pub(super) fn new(kind: OpsetParseErrorKind, span: pest::Span<'_>) -> Self {
+        let message = kind.to_string();
+        let pest_error = Box::new(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message },
+            span,
+        ));
+        Self {
+            kind,
+            pest_error,
+            source: None,
+        }
+    }
+
+    pub(super) fn with_source(
+        mut self,
+        source: impl Into<Box<dyn error::Error + Send + Sync>>,
+    ) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Some other expression error.
+    pub(super) fn expression(message: impl Into<String>, span: pest::Span<'_>) -> Self {
+        Self::new(OpsetParseErrorKind::Expression(message.into()), span)
+    }
+
+    /// Category of the underlying error.
+    pub fn kind(&self) -> &OpsetParseErrorKind {
+        &self.kind
+    }
+}
+
+impl From<pest::error::Error<Rule>> for OpsetParseError {
+    fn from(err: pest::error::Error<Rule>) -> Self {
+        Self {
+            kind: OpsetParseErrorKind::SyntaxError,
+            pest_error: Box::new(rename_rules_in_pest_error(err)),
+            source: None,
+        }
+    }
+}
+
+impl From<InvalidArguments<'_>> for OpsetParseError {
+    fn from(err: InvalidArguments<'_>) -> Self {
+        let kind = OpsetParseErrorKind::InvalidArguments {
+            name: err.name.to_owned(),
+            message: err.message,
+        };
+        Self::new(kind, err.span)
+    }
+}
+
+fn rename_rules_in_pest_error(err: pest::error::Error<Rule>) -> pest::error::Error<Rule> {
+    err.renamed_rules(|rule| {
+        rule.to_symbol()
+            .map(|sym| format!("`{sym}`"))
+            .unwrap_or_else(|| format!("<{rule:?}>"))
+    })
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExpressionKind<'i> {
+    /// Operation ID prefix, or bare function argument.
+    Identifier(&'i str),
+    String(String),
+    StringPattern {
+        kind: &'i str,
+        value: String,
+    },
+    /// `@`
+    AtOperation,
+    /// `..`
+    RangeAll,
+    Unary(UnaryOp, Box<ExpressionNode<'i>>),
+    Binary(BinaryOp, Box<ExpressionNode<'i>>, Box<ExpressionNode<'i>>),
+    /// `x | y | ..`
+    UnionAll(Vec<ExpressionNode<'i>>),
+    FunctionCall(Box<FunctionCallNode<'i>>),
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum UnaryOp {
+    /// `~x`
+    Negate,
+    /// `..x`
+    RangePre,
+    /// `x..`
+    RangePost,
+    /// `x-`
+    Parents,
+    /// `x+`
+    Children,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BinaryOp {
+    /// `&`
+    Intersection,
+    /// `~`
+    Difference,
+    /// `..`
+    Range,
+}
+
+pub type ExpressionNode<'i> = dsl_util::ExpressionNode<'i, ExpressionKind<'i>>;
+pub type FunctionCallNode<'i> = dsl_util::FunctionCallNode<'i, ExpressionKind<'i>>;
+
+fn union_nodes<'i>(lhs: ExpressionNode<'i>, rhs: ExpressionNode<'i>) -> ExpressionNode<'i> {
+    let span = lhs.span.start_pos().span(&rhs.span.end_pos());
+    let expr = match lhs.kind {
+        // Flatten "x | y | z" to save recursion stack.
+        ExpressionKind::UnionAll(mut nodes) => {
+            nodes.push(rhs);
+            ExpressionKind::UnionAll(nodes)
+        }
+        _ => ExpressionKind::UnionAll(vec![lhs, rhs]),
+    };
+    ExpressionNode::new(expr, span)
+}
+
+fn parse_as_string_literal(pair: Pair<Rule>) -> String {
+    match pair.as_rule() {
+        Rule::identifier => pair.as_str().to_owned(),
+        Rule::string_literal => STRING_LITERAL_PARSER.parse(pair.into_inner()),
+        Rule::raw_string_literal => {
+            let [content] = pair.into_inner().collect_array().unwrap();
+            assert_eq!(content.as_rule(), Rule::raw_string_content);
+            content.as_str().to_owned()
+        }
+        r => panic!("unexpected string literal rule: {r:?}"),
+    }
+}
+
+fn parse_primary_node(pair: Pair<Rule>) -> OpsetParseResult<ExpressionNode> {
+    assert_eq!(pair.as_rule(), Rule::primary);
+    let span = pair.as_span();
+    let first = pair.into_inner().next().unwrap();
+    let expr = match first.as_rule() {
+        // Ignore inner span to preserve parenthesized expression as such.
+        Rule::expression => parse_expression_node(first)?.kind,
+        Rule::function => {
+            let function = Box::new(FUNCTION_CALL_PARSER.parse(
+                first,
+                |pair| Ok(pair.as_str()),
+                |pair| parse_expression_node(pair),
+            )?);
+            ExpressionKind::FunctionCall(function)
+        }
+        Rule::string_pattern => {
+            let [lhs, op, rhs] = first.into_inner().collect_array().unwrap();
+            assert_eq!(lhs.as_rule(), Rule::strict_identifier);
+            assert_eq!(op.as_rule(), Rule::pattern_kind_op);
+            let kind = lhs.as_str();
+            let value = parse_as_string_literal(rhs);
+            ExpressionKind::StringPattern { kind, value }
+        }
+        Rule::identifier => ExpressionKind::Identifier(first.as_str()),
+        Rule::string_literal | Rule::raw_string_literal => {
+            ExpressionKind::String(parse_as_string_literal(first))
+        }
+        Rule::at_op => ExpressionKind::AtOperation,
+        r => panic!("unexpected primary rule: {r:?}"),
+    };
+    Ok(ExpressionNode::new(expr, span))
+}
+
+fn parse_expression_node(pair: Pair<Rule>) -> OpsetParseResult<ExpressionNode> {
+    assert_eq!(pair.as_rule(), Rule::expression);
+    static PRATT: LazyLock<PrattParser<Rule>> = LazyLock::new(|| {
+        PrattParser::new()
+            .op(Op::infix(Rule::union_op, Assoc::Left))
+            .op(Op::infix(Rule::intersection_op, Assoc::Left)
+                | Op::infix(Rule::difference_op, Assoc::Left))
+            .op(Op::prefix(Rule::negate_op))
+            // Ranges can't be nested without parentheses. Associativity doesn't matter.
+            .op(Op::infix(Rule::range_op, Assoc::Left))
+            .op(Op::prefix(Rule::range_pre_op))
+            .op(Op::postfix(Rule::range_post_op))
+            // Neighbors
+            .op(Op::postfix(Rule::parents_op) | Op::postfix(Rule::children_op))
+    });
+    PRATT
+        .map_primary(|primary| {
+            let expr = match primary.as_rule() {
+                Rule::primary => return parse_primary_node(primary),
+                Rule::range_all_op => ExpressionKind::RangeAll,
+                r => panic!("unexpected primary rule {r:?}"),
+            };
+            Ok(ExpressionNode::new(expr, primary.as_span()))
+        })
+        .map_prefix(|op, rhs| {
+            let op_kind = match op.as_rule() {
+                Rule::negate_op => UnaryOp::Negate,
+                Rule::range_pre_op => UnaryOp::RangePre,
+                r => panic!("unexpected prefix operator rule {r:?}"),
+            };
+            let rhs = Box::new(rhs?);
+            let span = op.as_span().start_pos().span(&rhs.span.end_pos());
+            let expr = ExpressionKind::Unary(op_kind, rhs);
+            Ok(ExpressionNode::new(expr, span))
+        })
+        .map_postfix(|lhs, op| {
+            let op_kind = match op.as_rule() {
+                Rule::range_post_op => UnaryOp::RangePost,
+                Rule::parents_op => UnaryOp::Parents,
+                Rule::children_op => UnaryOp::Children,
+                r => panic!("unexpected postfix operator rule {r:?}"),
+            };
+            let lhs = Box::new(lhs?);
+            let span = lhs.span.start_pos().span(&op.as_span().end_pos());
+            let expr = ExpressionKind::Unary(op_kind, lhs);
+            Ok(ExpressionNode::new(expr, span))
+        })
+        .map_infix(|lhs, op, rhs| {
+            let op_kind = match op.as_rule() {
+                Rule::union_op => return Ok(union_nodes(lhs?, rhs?)),
+                Rule::intersection_op => BinaryOp::Intersection,
+                Rule::difference_op => BinaryOp::Difference,
+                Rule::range_op => BinaryOp::Range,
+                r => panic!("unexpected infix operator rule {r:?}"),
+            };
+            let lhs = Box::new(lhs?);
+            let rhs = Box::new(rhs?);
+            let span = lhs.span.start_pos().span(&rhs.span.end_pos());
+            let expr = ExpressionKind::Binary(op_kind, lhs, rhs);
+            Ok(ExpressionNode::new(expr, span))
+        })
+        .parse(pair.into_inner())
+}
+
+/// Parses text into expression tree. No name resolution is made at this stage.
+pub fn parse_program(text: &str) -> OpsetParseResult<ExpressionNode<'_>> {
+    let mut pairs = OpsetParser::parse(Rule::program, text)?;
+    let first = pairs.next().unwrap();
+    parse_expression_node(first)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::dsl_util::KeywordArgument;
+
+    fn parse_into_kind(text: &str) -> Result<ExpressionKind<'_>, OpsetParseErrorKind> {
+        parse_program(text)
+            .map(|node| node.kind)
+            .map_err(|err| err.kind)
+    }
+
+    fn parse_normalized(text: &str) -> ExpressionNode<'_> {
+        normalize_tree(parse_program(text).unwrap())
+    }
+
+    /// Drops auxiliary data from parsed tree so it can be compared with other.
+    fn normalize_tree(node: ExpressionNode) -> ExpressionNode {
+        fn empty_span() -> pest::Span<'static> {
+            pest::Span::new("", 0, 0).unwrap()
+        }
+
+        fn normalize_list(nodes: Vec<ExpressionNode>) -> Vec<ExpressionNode> {
+            nodes.into_iter().map(normalize_tree).collect()
+        }
+
+        fn normalize_function_call(function: FunctionCallNode) -> FunctionCallNode {
+            FunctionCallNode {
+                name: function.name,
+                name_span: empty_span(),
+                args: normalize_list(function.args),
+                keyword_args: function
+                    .keyword_args
+                    .into_iter()
+                    .map(|arg| KeywordArgument {
+                        name: arg.name,
+                        name_span: empty_span(),
+                        value: normalize_tree(arg.value),
+                    })
+                    .collect(),
+                args_span: empty_span(),
+            }
+        }
+
+        let normalized_kind = match node.kind {
+            ExpressionKind::Identifier(_)
+            | ExpressionKind::String(_)
+            | ExpressionKind::StringPattern { .. }
+            | ExpressionKind::AtOperation
+            | ExpressionKind::RangeAll => node.kind,
+            ExpressionKind::Unary(op, arg) => {
+                let arg = Box::new(normalize_tree(*arg));
+                ExpressionKind::Unary(op, arg)
+            }
+            ExpressionKind::Binary(op, lhs, rhs) => {
+                let lhs = Box::new(normalize_tree(*lhs));
+                let rhs = Box::new(normalize_tree(*rhs));
+                ExpressionKind::Binary(op, lhs, rhs)
+            }
+            ExpressionKind::UnionAll(nodes) => {
+                let nodes = normalize_list(nodes);
+                ExpressionKind::UnionAll(nodes)
+            }
+            ExpressionKind::FunctionCall(function) => {
+                let function = Box::new(normalize_function_call(*function));
+                ExpressionKind::FunctionCall(function)
+            }
+        };
+        ExpressionNode {
+            kind: normalized_kind,
+            span: empty_span(),
+        }
+    }
+
+    #[test]
+    fn test_parse_tree_eq() {
+        assert_eq!(
+            parse_normalized(r#" foo( x ) | ~bar:"baz" "#),
+            parse_normalized(r#"(foo(x))|(~(bar:"baz"))"#)
+        );
+        assert_eq!(
+            parse_normalized(" @--..abc+ "),
+            parse_normalized("((@-)-)..(abc+)")
+        );
+        assert_ne!(parse_normalized(r#" foo "#), parse_normalized(r#" "foo" "#));
+    }
+
+    #[test]
+    fn test_parse_symbol() {
+        assert_eq!(parse_into_kind("@"), Ok(ExpressionKind::AtOperation));
+        assert_eq!(
+            parse_into_kind("0123abcd"),
+            Ok(ExpressionKind::Identifier("0123abcd"))
+        );
+        assert_eq!(
+            parse_into_kind(r#" "0123abcd" "#),
+            Ok(ExpressionKind::String("0123abcd".to_owned()))
+        );
+        assert_eq!(
+            parse_into_kind("@abc"),
+            Err(OpsetParseErrorKind::SyntaxError)
+        );
+        assert_eq!(
+            parse_into_kind("a.b"),
+            Err(OpsetParseErrorKind::SyntaxError)
+        );
+    }
+
+    #[test]
+    fn test_parse_neighbors() {
+        assert_matches!(
+            parse_into_kind("@-"),
+            Ok(ExpressionKind::Unary(UnaryOp::Parents, _))
+        );
+        assert_matches!(
+            parse_into_kind("abc+"),
+            Ok(ExpressionKind::Unary(UnaryOp::Children, _))
+        );
+        assert_eq!(parse_normalized("@-+"), parse_normalized("(@-)+"),);
+        // Neighbor operators can't be prefixed.
+        assert_eq!(parse_into_kind("-@"), Err(OpsetParseErrorKind::SyntaxError));
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_matches!(
+            parse_into_kind("@--..@"),
+            Ok(ExpressionKind::Binary(BinaryOp::Range, _, _))
+        );
+        assert_matches!(
+            parse_into_kind("..@-"),
+            Ok(ExpressionKind::Unary(UnaryOp::RangePre, _))
+        );
+        assert_matches!(
+            parse_into_kind("abc.."),
+            Ok(ExpressionKind::Unary(UnaryOp::RangePost, _))
+        );
+        assert_eq!(parse_into_kind(".."), Ok(ExpressionKind::RangeAll));
+        // Ranges can't be nested without parentheses.
+        assert_eq!(
+            parse_into_kind("a..b..c"),
+            Err(OpsetParseErrorKind::SyntaxError)
+        );
+        assert_eq!(parse_normalized("~a..b"), parse_normalized("~(a..b)"),);
+    }
+
+    #[test]
+    fn test_parse_set_operators() {
+        assert_eq!(
+            parse_normalized("a | b & c ~ d"),
+            parse_normalized("a | ((b & c) ~ d)"),
+        );
+        assert_matches!(
+            parse_into_kind("a | b | c"),
+            Ok(ExpressionKind::UnionAll(nodes)) if nodes.len() == 3
+        );
+    }
+
+    #[test]
+    fn test_parse_string_pattern() {
+        assert_eq!(
+            parse_into_kind(r#" glob:"git fetch*" "#),
+            Ok(ExpressionKind::StringPattern {
+                kind: "glob",
+                value: "git fetch*".to_owned()
+            })
+        );
+        assert_eq!(
+            parse_into_kind(" after:yesterday "),
+            Ok(ExpressionKind::StringPattern {
+                kind: "after",
+                value: "yesterday".to_owned()
+            })
+        );
+        assert_eq!(
+            parse_into_kind(r#" exact:'a\b' "#),
+            Ok(ExpressionKind::StringPattern {
+                kind: "exact",
+                value: r"a\b".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_function_call() {
+        let function = assert_matches!(
+            parse_into_kind(r#"tags(host = ci, "x")"#),
+            Err(OpsetParseErrorKind::InvalidArguments { name, .. }) => name
+        );
+        assert_eq!(function, "tags");
+
+        let function = assert_matches!(
+            parse_normalized("tags(host = ci, user=alice,)").kind,
+            ExpressionKind::FunctionCall(function) => function
+        );
+        assert_eq!(function.name, "tags");
+        assert!(function.args.is_empty());
+        assert_eq!(
+            function
+                .keyword_args
+                .iter()
+                .map(|arg| (arg.name, arg.value.kind.clone()))
+                .collect_vec(),
+            [
+                ("host", ExpressionKind::Identifier("ci")),
+                ("user", ExpressionKind::Identifier("alice")),
+            ]
+        );
+
+        assert_eq!(
+            parse_into_kind("5foo(x)"),
+            Err(OpsetParseErrorKind::SyntaxError)
+        );
+    }
+}
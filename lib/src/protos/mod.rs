@@ -8,6 +8,9 @@ pub mod git_store {
 pub mod local_working_copy {
     include!("local_working_copy.rs");
 }
+pub mod op_bundle {
+    include!("op_bundle.rs");
+}
 pub mod simple_op_store {
     include!("simple_op_store.rs");
 }
@@ -0,0 +1,67 @@
+// This file is @generated by prost-build.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OperationEntry {
+    #[prost(bytes = "vec", tag = "1")]
+    pub id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub operation: ::core::option::Option<super::simple_op_store::Operation>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ViewEntry {
+    #[prost(bytes = "vec", tag = "1")]
+    pub id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub view: ::core::option::Option<super::simple_op_store::View>,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct CommitEntry {
+    #[prost(bytes = "vec", tag = "1")]
+    pub id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub commit: ::core::option::Option<super::simple_store::Commit>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TreeEntry {
+    #[prost(bytes = "vec", tag = "1")]
+    pub id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag = "2")]
+    pub path: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub tree: ::core::option::Option<super::simple_store::Tree>,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct FileEntry {
+    #[prost(bytes = "vec", tag = "1")]
+    pub id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag = "2")]
+    pub path: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "3")]
+    pub content: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SymlinkEntry {
+    #[prost(bytes = "vec", tag = "1")]
+    pub id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag = "2")]
+    pub path: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub target: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Bundle {
+    /// Operations in topological order, parents first.
+    #[prost(message, repeated, tag = "1")]
+    pub operations: ::prost::alloc::vec::Vec<OperationEntry>,
+    #[prost(message, repeated, tag = "2")]
+    pub views: ::prost::alloc::vec::Vec<ViewEntry>,
+    /// Commits in topological order, parents first.
+    #[prost(message, repeated, tag = "3")]
+    pub commits: ::prost::alloc::vec::Vec<CommitEntry>,
+    /// Trees in post order, subtrees first.
+    #[prost(message, repeated, tag = "4")]
+    pub trees: ::prost::alloc::vec::Vec<TreeEntry>,
+    #[prost(message, repeated, tag = "5")]
+    pub files: ::prost::alloc::vec::Vec<FileEntry>,
+    #[prost(message, repeated, tag = "6")]
+    pub symlinks: ::prost::alloc::vec::Vec<SymlinkEntry>,
+}
@@ -106,6 +106,7 @@ use crate::simple_op_heads_store::SimpleOpHeadsStore;
 use crate::simple_op_store::SimpleOpStore;
 use crate::store::Store;
 use crate::submodule_store::SubmoduleStore;
+use crate::table_op_store::TableOpStore;
 use crate::transaction::Transaction;
 use crate::transaction::TransactionCommitError;
 use crate::tree_merge::MergeOptions;
@@ -176,9 +177,20 @@ pub enum RepoInitError {
 }
 
 impl ReadonlyRepo {
+    /// Returns initializer of the operation store selected by the
+    /// `operation.store` setting.
     pub fn default_op_store_initializer() -> &'static OpStoreInitializer<'static> {
-        &|_settings, store_path, root_data| {
-            Ok(Box::new(SimpleOpStore::init(store_path, root_data)?))
+        &|settings, store_path, root_data| {
+            let store_format = settings
+                .get_string("operation.store")
+                .map_err(|err| BackendInitError(err.into()))?;
+            match store_format.as_str() {
+                "simple" => Ok(Box::new(SimpleOpStore::init(store_path, root_data)?)),
+                "table" => Ok(Box::new(TableOpStore::init(store_path, root_data)?)),
+                _ => Err(BackendInitError(
+                    format!("Unknown operation store format: {store_format}").into(),
+                )),
+            }
         }
     }
 
@@ -452,6 +464,12 @@ impl Default for StoreFactories {
                 Ok(Box::new(SimpleOpStore::load(store_path, root_data)))
             }),
         );
+        factories.add_op_store(
+            TableOpStore::name(),
+            Box::new(|_settings, store_path, root_data| {
+                Ok(Box::new(TableOpStore::load(store_path, root_data)))
+            }),
+        );
 
         // OpHeadsStores
         factories.add_op_heads_store(
@@ -636,6 +654,74 @@ pub fn read_store_type(
         .map_err(|source| StoreLoadError::ReadError { store, source })
 }
 
+/// Finishes or rolls back the replacement of the operation store which was
+/// interrupted.
+///
+/// The operation store is replaced by populating a new store at
+/// `op_store.new`, moving the current store to `op_store.old`, and moving the
+/// new store in place. The operation heads are then updated to the new store
+/// before `op_store.old` is removed.
+fn recover_op_store_swap(
+    settings: &UserSettings,
+    store_factories: &StoreFactories,
+    repo_path: &Path,
+    root_op_data: &RootOperationData,
+    op_heads_store: &dyn OpHeadsStore,
+) -> Result<(), StoreLoadError> {
+    let store_path = repo_path.join("op_store");
+    let new_store_path = repo_path.join("op_store.new");
+    let old_store_path = repo_path.join("op_store.old");
+    if !new_store_path.exists() && !old_store_path.exists() {
+        return Ok(());
+    }
+    let to_load_error = |err: PathError| BackendLoadError(err.into());
+    // Wait for the replacement in progress, if any.
+    let _lock = op_heads_store
+        .lock()
+        .block_on()
+        .map_err(|err| BackendLoadError(err.into()))?;
+    if new_store_path.exists() {
+        // The new store may be incomplete, so roll back.
+        if !store_path.exists() {
+            fs::rename(&old_store_path, &store_path)
+                .context(&old_store_path)
+                .map_err(to_load_error)?;
+        }
+        fs::remove_dir_all(&new_store_path)
+            .context(&new_store_path)
+            .map_err(to_load_error)?;
+    } else if old_store_path.exists() {
+        // The stores were swapped. Roll back unless the operation heads have
+        // been updated to the new store.
+        let op_store =
+            store_factories.load_op_store(settings, &store_path, root_op_data.clone())?;
+        let op_head_ids = op_heads_store
+            .get_op_heads()
+            .block_on()
+            .map_err(|err| BackendLoadError(err.into()))?;
+        let heads_updated = op_head_ids
+            .iter()
+            .all(|id| op_store.read_operation(id).block_on().is_ok());
+        drop(op_store);
+        if !heads_updated {
+            fs::rename(&store_path, &new_store_path)
+                .context(&store_path)
+                .map_err(to_load_error)?;
+            fs::rename(&old_store_path, &store_path)
+                .context(&old_store_path)
+                .map_err(to_load_error)?;
+            fs::remove_dir_all(&new_store_path)
+                .context(&new_store_path)
+                .map_err(to_load_error)?;
+        } else {
+            fs::remove_dir_all(&old_store_path)
+                .context(&old_store_path)
+                .map_err(to_load_error)?;
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, Error)]
 pub enum RepoLoaderError {
     #[error(transparent)]
@@ -703,13 +789,20 @@ impl RepoLoader {
         let root_op_data = RootOperationData {
             root_commit_id: store.root_commit_id().clone(),
         };
+        let op_heads_store: Arc<dyn OpHeadsStore> =
+            Arc::from(store_factories.load_op_heads_store(settings, &repo_path.join("op_heads"))?);
+        recover_op_store_swap(
+            settings,
+            store_factories,
+            repo_path,
+            &root_op_data,
+            op_heads_store.as_ref(),
+        )?;
         let op_store = Arc::from(store_factories.load_op_store(
             settings,
             &repo_path.join("op_store"),
             root_op_data,
         )?);
-        let op_heads_store =
-            Arc::from(store_factories.load_op_heads_store(settings, &repo_path.join("op_heads"))?);
         let index_store =
             Arc::from(store_factories.load_index_store(settings, &repo_path.join("index"))?);
         let submodule_store = Arc::from(
@@ -962,6 +1055,12 @@ impl MutableRepo {
         self.commit_predecessors.insert(id, predecessors);
     }
 
+    /// Returns the predecessors of the commit if it was created in this
+    /// transaction.
+    pub(crate) fn predecessors_in_transaction(&self, id: &CommitId) -> Option<&[CommitId]> {
+        self.commit_predecessors.get(id).map(|ids| ids.as_slice())
+    }
+
     /// Record a commit as having been rewritten to another commit in this
     /// transaction.
     ///
@@ -2529,12 +2529,13 @@ pub fn walk_revs<'index>(
 fn reload_repo_at_operation(
     repo: &dyn Repo,
     op_str: &str,
+    date_pattern_context: &DatePatternContext,
 ) -> Result<Arc<ReadonlyRepo>, RevsetResolutionError> {
     // TODO: Maybe we should ensure that the resolved operation is an ancestor
     // of the current operation. If it weren't, there might be commits unknown
     // to the outer repo.
     let base_repo = repo.base_repo();
-    let operation = op_walk::resolve_op_with_repo(base_repo, op_str)
+    let operation = op_walk::resolve_op_with_repo(base_repo, op_str, date_pattern_context)
         .map_err(|err| RevsetResolutionError::Other(err.into()))?;
     base_repo.reload_at(&operation).map_err(|err| match err {
         RepoLoaderError::Backend(err) => RevsetResolutionError::Backend(err),
@@ -2779,6 +2780,7 @@ pub struct SymbolResolver<'a> {
     commit_id_resolver: CommitPrefixResolver<'a>,
     change_id_resolver: ChangePrefixResolver<'a>,
     extensions: Vec<Box<dyn PartialSymbolResolver + 'a>>,
+    date_pattern_context: DatePatternContext,
 }
 
 impl<'a> SymbolResolver<'a> {
@@ -2801,9 +2803,17 @@ impl<'a> SymbolResolver<'a> {
                 .iter()
                 .flat_map(|ext| ext.as_ref().new_resolvers(context_repo))
                 .collect(),
+            date_pattern_context: chrono::Local::now().into(),
         }
     }
 
+    /// Sets the context to resolve date patterns in operation expressions of
+    /// `at_operation()`. Defaults to the current local time.
+    pub fn with_date_pattern_context(mut self, date_pattern_context: DatePatternContext) -> Self {
+        self.date_pattern_context = date_pattern_context;
+        self
+    }
+
     pub fn with_id_prefix_context(mut self, id_prefix_context: &'a IdPrefixContext) -> Self {
         self.commit_id_resolver.context = Some(id_prefix_context);
         self.change_id_resolver.context = Some(id_prefix_context);
@@ -2986,7 +2996,11 @@ impl ExpressionStateFolder<UserExpressionState, ResolvedExpressionState>
         operation: &String,
         candidates: &UserRevsetExpression,
     ) -> Result<Arc<ResolvedRevsetExpression>, Self::Error> {
-        let repo = reload_repo_at_operation(self.repo(), operation)?;
+        let repo = reload_repo_at_operation(
+            self.repo(),
+            operation,
+            &self.symbol_resolver.date_pattern_context,
+        )?;
         self.repo_stack.push(repo);
         let candidates = self.fold_expression(candidates)?;
         let visible_heads = self.repo().view().heads().iter().cloned().collect();
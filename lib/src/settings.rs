@@ -63,6 +63,7 @@ pub struct GitSettings {
     pub executable_path: PathBuf,
     pub write_change_id_header: bool,
     pub colocate: bool,
+    pub exchange_obsolescence: bool,
 }
 
 impl GitSettings {
@@ -73,6 +74,7 @@ impl GitSettings {
             executable_path: settings.get("git.executable-path")?,
             write_change_id_header: settings.get("git.write-change-id-header")?,
             colocate: settings.get("git.colocate")?,
+            exchange_obsolescence: settings.get_bool("git.exchange-obsolescence")?,
         })
     }
 }
@@ -392,7 +392,7 @@ fn io_to_write_error(err: PathError, object_type: &'static str) -> OpStoreError
 }
 
 #[derive(Debug, Error)]
-enum PostDecodeError {
+pub(crate) enum PostDecodeError {
     #[error("Invalid hash length (expected {expected} bytes, got {actual} bytes)")]
     InvalidHashLength { expected: usize, actual: usize },
     #[error("Invalid remote ref state value {0}")]
@@ -498,7 +498,9 @@ fn commit_predecessors_map_from_proto(
         .collect()
 }
 
-fn operation_to_proto(operation: &Operation) -> crate::protos::simple_op_store::Operation {
+pub(crate) fn operation_to_proto(
+    operation: &Operation,
+) -> crate::protos::simple_op_store::Operation {
     let (commit_predecessors, stores_commit_predecessors) = match &operation.commit_predecessors {
         Some(map) => (commit_predecessors_map_to_proto(map), true),
         None => (vec![], false),
@@ -513,7 +515,7 @@ fn operation_to_proto(operation: &Operation) -> crate::protos::simple_op_store::
     }
 }
 
-fn operation_from_proto(
+pub(crate) fn operation_from_proto(
     proto: crate::protos::simple_op_store::Operation,
 ) -> Result<Operation, PostDecodeError> {
     let parents = proto
@@ -534,7 +536,7 @@ fn operation_from_proto(
     })
 }
 
-fn view_to_proto(view: &View) -> crate::protos::simple_op_store::View {
+pub(crate) fn view_to_proto(view: &View) -> crate::protos::simple_op_store::View {
     let wc_commit_ids = view
         .wc_commit_ids
         .iter()
@@ -586,7 +588,9 @@ fn view_to_proto(view: &View) -> crate::protos::simple_op_store::View {
     }
 }
 
-fn view_from_proto(proto: crate::protos::simple_op_store::View) -> Result<View, PostDecodeError> {
+pub(crate) fn view_from_proto(
+    proto: crate::protos::simple_op_store::View,
+) -> Result<View, PostDecodeError> {
     // TODO: validate commit id length?
     // For compatibility with old repos before we had support for multiple working
     // copies
@@ -29,9 +29,11 @@ use std::fs::File;
 use std::io;
 use std::io::Read;
 use std::io::Write as _;
+use std::iter;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::time::SystemTime;
 
 use blake2::Blake2b512;
 use blake2::Digest as _;
@@ -121,6 +123,17 @@ impl ReadonlyTable {
         MutableTable::incremental(self.clone())
     }
 
+    /// Iterates over the entries of this table and its ancestors. A key may
+    /// be yielded more than once if it was added to multiple segments.
+    pub fn entries(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        iter::successors(Some(self), |table| table.parent_file.as_deref()).flat_map(|table| {
+            (0..table.num_local_entries).map(move |pos| {
+                let entry = ReadonlyTableIndexEntry::new(table, pos);
+                (entry.key(), table.segment_value_by_pos(pos))
+            })
+        })
+    }
+
     fn segment_value_offset_by_pos(&self, pos: usize) -> usize {
         if pos == self.num_local_entries {
             self.values.len()
@@ -448,6 +461,59 @@ impl TableStore {
         Ok(table)
     }
 
+    /// Replaces the head `table` with a new table containing the entries for
+    /// which `keep` returns true, and deletes the segment files which are no
+    /// longer needed and aren't newer than `keep_newer`.
+    ///
+    /// The `table` should be the head obtained by `get_head_locked()`, and
+    /// the lock should be held until this function returns. Heads and segment
+    /// files saved concurrently without the lock are preserved as long as they
+    /// are newer than `keep_newer`.
+    pub fn retain_entries(
+        &self,
+        table: &Arc<ReadonlyTable>,
+        mut keep: impl FnMut(&[u8], &[u8]) -> bool,
+        keep_newer: SystemTime,
+        _lock: &FileLock,
+    ) -> TableStoreResult<Arc<ReadonlyTable>> {
+        let mut mut_table = MutableTable::full(self.key_size);
+        for (key, value) in table.entries() {
+            if keep(key, value) {
+                mut_table.add_entry(key.to_vec(), value.to_vec());
+            }
+        }
+        let new_table = mut_table.save_in(self)?;
+        self.add_head(&new_table)?;
+        if table.name != new_table.name {
+            self.remove_head(table);
+        }
+        // The new table has no parent, so the other segment files are
+        // unreferenced unless they were saved concurrently.
+        let to_remove_err = |name: String| move |err| TableStoreError::SaveSegment { name, err };
+        for entry in std::fs::read_dir(&self.dir).map_err(TableStoreError::LoadHeads)? {
+            let entry = entry.map_err(TableStoreError::LoadHeads)?;
+            let Ok(name) = entry.file_name().into_string() else {
+                continue;
+            };
+            if name == new_table.name || hex_util::decode_hex(&name).is_none_or(|id| id.len() != 64)
+            {
+                continue;
+            }
+            let metadata = entry.metadata().map_err(TableStoreError::LoadHeads)?;
+            let mtime = metadata.modified().expect("unsupported platform?");
+            if mtime > keep_newer {
+                continue;
+            }
+            std::fs::remove_file(entry.path()).map_err(to_remove_err(name))?;
+        }
+        {
+            let mut locked_cache = self.cached_tables.write().unwrap();
+            locked_cache.clear();
+            locked_cache.insert(new_table.name.clone(), new_table.clone());
+        }
+        Ok(new_table)
+    }
+
     fn add_head(&self, table: &Arc<ReadonlyTable>) -> TableStoreResult<()> {
         std::fs::write(self.dir.join("heads").join(&table.name), "")
             .map_err(TableStoreError::SaveHeads)
@@ -548,6 +614,9 @@ impl TableStore {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use itertools::Itertools as _;
     use test_case::test_case;
 
     use super::*;
@@ -737,4 +806,78 @@ mod tests {
         let table = store.get_head().unwrap();
         assert_eq!(table.get_value(b"abc"), Some(b"value".as_slice()));
     }
+
+    #[test]
+    fn stacked_table_retain_entries() {
+        let temp_dir = new_temp_dir();
+        let store = TableStore::init(temp_dir.path().to_path_buf(), 3);
+        let mut mut_table = store.get_head().unwrap().start_mutation();
+        mut_table.add_entry(b"abc".to_vec(), b"value1".to_vec());
+        mut_table.add_entry(b"abd".to_vec(), b"value2".to_vec());
+        mut_table.add_entry(b"abe".to_vec(), b"value2".to_vec());
+        mut_table.add_entry(b"abf".to_vec(), b"value2".to_vec());
+        let table = store.save_table(mut_table).unwrap();
+        // The parent segment is large enough that the child isn't squashed
+        let mut mut_table = table.start_mutation();
+        mut_table.add_entry(b"zzz".to_vec(), b"value3".to_vec());
+        store.save_table(mut_table).unwrap();
+
+        let (table, lock) = store.get_head_locked().unwrap();
+        assert!(table.segment_parent_file().is_some());
+        assert_eq!(table.entries().count(), 5);
+        let future = SystemTime::now() + Duration::from_secs(3600);
+        let new_table = store
+            .retain_entries(&table, |_key, value| value != b"value2", future, &lock)
+            .unwrap();
+        drop(lock);
+        assert_eq!(
+            new_table.entries().sorted().collect_vec(),
+            [
+                (b"abc".as_slice(), b"value1".as_slice()),
+                (b"zzz".as_slice(), b"value3".as_slice()),
+            ]
+        );
+
+        // The old segments are removed
+        let store = TableStore::load(temp_dir.path().to_path_buf(), 3);
+        let table = store.get_head().unwrap();
+        assert_eq!(table.get_value(b"abc"), Some(b"value1".as_slice()));
+        assert_eq!(table.get_value(b"abd"), None);
+        assert_eq!(table.get_value(b"zzz"), Some(b"value3".as_slice()));
+        let segment_count = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .filter(|entry| entry.as_ref().unwrap().path().is_file())
+            .filter(|entry| entry.as_ref().unwrap().file_name() != "lock")
+            .count();
+        assert_eq!(segment_count, 1);
+    }
+
+    #[test]
+    fn stacked_table_retain_entries_concurrent_save() {
+        let temp_dir = new_temp_dir();
+        let store = TableStore::init(temp_dir.path().to_path_buf(), 3);
+        let mut mut_table = store.get_head().unwrap().start_mutation();
+        mut_table.add_entry(b"abc".to_vec(), b"value1".to_vec());
+        mut_table.add_entry(b"abd".to_vec(), b"value2".to_vec());
+        store.save_table(mut_table).unwrap();
+        let other_store = TableStore::load(temp_dir.path().to_path_buf(), 3);
+        let other_table = other_store.get_head().unwrap();
+
+        // Another process saves a table without taking the lock
+        let (table, lock) = store.get_head_locked().unwrap();
+        let mut mut_table = other_table.start_mutation();
+        mut_table.add_entry(b"zzz".to_vec(), b"value3".to_vec());
+        other_store.save_table(mut_table).unwrap();
+        let past = SystemTime::now() - Duration::from_secs(3600);
+        store
+            .retain_entries(&table, |_key, value| value != b"value2", past, &lock)
+            .unwrap();
+        drop(lock);
+
+        // The concurrently saved table and its parent segment are preserved
+        let store = TableStore::load(temp_dir.path().to_path_buf(), 3);
+        let table = store.get_head().unwrap();
+        assert_eq!(table.get_value(b"abc"), Some(b"value1".as_slice()));
+        assert_eq!(table.get_value(b"zzz"), Some(b"value3".as_slice()));
+    }
 }
@@ -0,0 +1,512 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Operation store which stores operations and views in stacked tables.
+//!
+//! Unlike [`SimpleOpStore`](crate::simple_op_store::SimpleOpStore), which
+//! writes one file per object, this store appends objects to segment files
+//! managed by [`TableStore`]. The number of segment files grows
+//! logarithmically with the number of objects, which is faster on file
+//! systems where creating and listing many small files is slow.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use prost::Message as _;
+use thiserror::Error;
+
+use crate::backend::BackendInitError;
+use crate::backend::MillisSinceEpoch;
+use crate::content_hash::blake2b_hash;
+use crate::dag_walk;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::lock::FileLock;
+use crate::object_id::HexPrefix;
+use crate::object_id::ObjectId;
+use crate::object_id::PrefixResolution;
+use crate::op_store::OpStore;
+use crate::op_store::OpStoreError;
+use crate::op_store::OpStoreResult;
+use crate::op_store::Operation;
+use crate::op_store::OperationId;
+use crate::op_store::RootOperationData;
+use crate::op_store::View;
+use crate::op_store::ViewId;
+use crate::simple_op_store::operation_from_proto;
+use crate::simple_op_store::operation_to_proto;
+use crate::simple_op_store::view_from_proto;
+use crate::simple_op_store::view_to_proto;
+use crate::stacked_table::MutableTable;
+use crate::stacked_table::ReadonlyTable;
+use crate::stacked_table::TableSegment as _;
+use crate::stacked_table::TableStore;
+use crate::stacked_table::TableStoreError;
+
+// BLAKE2b-512 hash length in bytes
+const OPERATION_ID_LENGTH: usize = 64;
+const VIEW_ID_LENGTH: usize = 64;
+// Each value is prefixed with the time it was written, which is used to
+// preserve recently written objects in gc().
+const TIMESTAMP_LENGTH: usize = 8;
+
+/// Error that may occur during [`TableOpStore`] initialization.
+#[derive(Debug, Error)]
+#[error("Failed to initialize table operation store")]
+pub struct TableOpStoreInitError(#[from] pub PathError);
+
+impl From<TableOpStoreInitError> for BackendInitError {
+    fn from(err: TableOpStoreInitError) -> Self {
+        Self(err.into())
+    }
+}
+
+/// Operation store backed by stacked tables of operations and views.
+pub struct TableOpStore {
+    root_data: RootOperationData,
+    root_operation_id: OperationId,
+    root_view_id: ViewId,
+    operations: ObjectTable,
+    views: ObjectTable,
+}
+
+impl Debug for TableOpStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("TableOpStore")
+            .field("path", &self.operations.store_dir)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TableOpStore {
+    /// Returns the name of the store type.
+    pub fn name() -> &'static str {
+        "table_op_store"
+    }
+
+    /// Creates an empty OpStore. Returns error if it already exists.
+    pub fn init(
+        store_path: &Path,
+        root_data: RootOperationData,
+    ) -> Result<Self, TableOpStoreInitError> {
+        let operations_dir = store_path.join("operations");
+        let views_dir = store_path.join("views");
+        for dir in [&operations_dir, &views_dir] {
+            fs::create_dir(dir).context(dir)?;
+        }
+        let operations = TableStore::init(operations_dir, OPERATION_ID_LENGTH);
+        let views = TableStore::init(views_dir, VIEW_ID_LENGTH);
+        Ok(Self::new(store_path, root_data, operations, views))
+    }
+
+    /// Load an existing OpStore
+    pub fn load(store_path: &Path, root_data: RootOperationData) -> Self {
+        let operations = TableStore::load(store_path.join("operations"), OPERATION_ID_LENGTH);
+        let views = TableStore::load(store_path.join("views"), VIEW_ID_LENGTH);
+        Self::new(store_path, root_data, operations, views)
+    }
+
+    fn new(
+        store_path: &Path,
+        root_data: RootOperationData,
+        operations: TableStore,
+        views: TableStore,
+    ) -> Self {
+        Self {
+            root_data,
+            root_operation_id: OperationId::from_bytes(&[0; OPERATION_ID_LENGTH]),
+            root_view_id: ViewId::from_bytes(&[0; VIEW_ID_LENGTH]),
+            operations: ObjectTable::new("operation", store_path.join("operations"), operations),
+            views: ObjectTable::new("view", store_path.join("views"), views),
+        }
+    }
+}
+
+#[async_trait]
+impl OpStore for TableOpStore {
+    fn name(&self) -> &str {
+        Self::name()
+    }
+
+    fn root_operation_id(&self) -> &OperationId {
+        &self.root_operation_id
+    }
+
+    async fn read_view(&self, id: &ViewId) -> OpStoreResult<View> {
+        if *id == self.root_view_id {
+            return Ok(View::make_root(self.root_data.root_commit_id.clone()));
+        }
+
+        let buf = self.views.read(id)?;
+        let proto = crate::protos::simple_op_store::View::decode(&*buf)
+            .map_err(|err| to_read_error(err.into(), id))?;
+        view_from_proto(proto).map_err(|err| to_read_error(err.into(), id))
+    }
+
+    async fn write_view(&self, view: &View) -> OpStoreResult<ViewId> {
+        let id = ViewId::new(blake2b_hash(view).to_vec());
+        let proto = view_to_proto(view);
+        self.views.write(&id, &proto.encode_to_vec())?;
+        Ok(id)
+    }
+
+    async fn read_operation(&self, id: &OperationId) -> OpStoreResult<Operation> {
+        if *id == self.root_operation_id {
+            return Ok(Operation::make_root(self.root_view_id.clone()));
+        }
+
+        let buf = self.operations.read(id)?;
+        let proto = crate::protos::simple_op_store::Operation::decode(&*buf)
+            .map_err(|err| to_read_error(err.into(), id))?;
+        operation_from_proto(proto).map_err(|err| to_read_error(err.into(), id))
+    }
+
+    async fn write_operation(&self, operation: &Operation) -> OpStoreResult<OperationId> {
+        assert!(!operation.parents.is_empty());
+        let id = OperationId::new(blake2b_hash(operation).to_vec());
+        let proto = operation_to_proto(operation);
+        self.operations.write(&id, &proto.encode_to_vec())?;
+        Ok(id)
+    }
+
+    async fn resolve_operation_id_prefix(
+        &self,
+        prefix: &HexPrefix,
+    ) -> OpStoreResult<PrefixResolution<OperationId>> {
+        let matches_root = prefix.matches(&self.root_operation_id);
+        let table = self.operations.reload_head()?;
+        let mut matched = matches_root.then(|| self.root_operation_id.clone());
+        for key in table.entries().map(|(key, _)| key).unique() {
+            let id = OperationId::from_bytes(key);
+            if !prefix.matches(&id) {
+                continue;
+            }
+            if matched.is_some() {
+                return Ok(PrefixResolution::AmbiguousMatch);
+            }
+            matched = Some(id);
+        }
+        if let Some(id) = matched {
+            Ok(PrefixResolution::SingleMatch(id))
+        } else {
+            Ok(PrefixResolution::NoMatch)
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn gc(&self, head_ids: &[OperationId], keep_newer: SystemTime) -> OpStoreResult<()> {
+        // Reachable objects are resolved without taking the lock. Objects
+        // added concurrently should be newer than keep_newer.
+        let read_op = |id: &OperationId| {
+            self.read_operation(id)
+                .block_on()
+                .map(|data| (id.clone(), data))
+        };
+        let reachable_ops: HashMap<OperationId, Operation> = dag_walk::dfs_ok(
+            head_ids.iter().map(read_op),
+            |(id, _)| id.clone(),
+            |(_, data)| data.parents.iter().map(read_op).collect_vec(),
+        )
+        .try_collect()?;
+        let reachable_views: HashSet<&ViewId> =
+            reachable_ops.values().map(|data| &data.view_id).collect();
+        tracing::info!(
+            reachable_op_count = reachable_ops.len(),
+            reachable_view_count = reachable_views.len(),
+            "collected reachable objects"
+        );
+
+        let keep_newer_millis = to_millis(keep_newer);
+        self.operations.retain(keep_newer, |key, time| {
+            time > keep_newer_millis || reachable_ops.contains_key(&OperationId::from_bytes(key))
+        })?;
+        self.views.retain(keep_newer, |key, time| {
+            time > keep_newer_millis || reachable_views.contains(&ViewId::from_bytes(key))
+        })?;
+        Ok(())
+    }
+}
+
+/// Table of content-addressed objects of one type.
+struct ObjectTable {
+    object_type: &'static str,
+    store_dir: PathBuf,
+    store: TableStore,
+    cached_head: Mutex<Option<Arc<ReadonlyTable>>>,
+}
+
+impl ObjectTable {
+    fn new(object_type: &'static str, store_dir: PathBuf, store: TableStore) -> Self {
+        Self {
+            object_type,
+            store_dir,
+            store,
+            cached_head: Mutex::new(None),
+        }
+    }
+
+    fn cached_head(&self) -> OpStoreResult<Arc<ReadonlyTable>> {
+        let mut locked_head = self.cached_head.lock().unwrap();
+        match locked_head.as_ref() {
+            Some(head) => Ok(head.clone()),
+            None => {
+                let table = self.store.get_head().map_err(to_other_error)?;
+                *locked_head = Some(table.clone());
+                Ok(table)
+            }
+        }
+    }
+
+    fn reload_head(&self) -> OpStoreResult<Arc<ReadonlyTable>> {
+        let table = self.store.get_head().map_err(to_other_error)?;
+        *self.cached_head.lock().unwrap() = Some(table.clone());
+        Ok(table)
+    }
+
+    fn read(&self, id: &impl ObjectId) -> OpStoreResult<Vec<u8>> {
+        let lookup = |table: &ReadonlyTable| {
+            table
+                .get_value(id.as_bytes())
+                .map(|value| value[TIMESTAMP_LENGTH..].to_vec())
+        };
+        if let Some(buf) = lookup(&*self.cached_head()?) {
+            return Ok(buf);
+        }
+        // The object may have been written by another process.
+        lookup(&*self.reload_head()?).ok_or_else(|| OpStoreError::ObjectNotFound {
+            object_type: id.object_type(),
+            hash: id.hex(),
+            source: format!(
+                "Object {} not found in {}",
+                id.hex(),
+                self.store_dir.display()
+            )
+            .into(),
+        })
+    }
+
+    fn write(&self, id: &impl ObjectId, buf: &[u8]) -> OpStoreResult<()> {
+        let to_write_error = |err: TableStoreError| OpStoreError::WriteObject {
+            object_type: self.object_type,
+            source: err.into(),
+        };
+        if self.cached_head()?.get_value(id.as_bytes()).is_some() {
+            return Ok(());
+        }
+        let (table, lock) = self.store.get_head_locked().map_err(to_write_error)?;
+        if table.get_value(id.as_bytes()).is_some() {
+            *self.cached_head.lock().unwrap() = Some(table);
+            return Ok(());
+        }
+        let mut value = to_millis(SystemTime::now()).0.to_le_bytes().to_vec();
+        value.extend_from_slice(buf);
+        let mut mut_table = table.start_mutation();
+        mut_table.add_entry(id.to_bytes(), value);
+        self.save_table(mut_table, &lock).map_err(to_write_error)?;
+        Ok(())
+    }
+
+    fn save_table(&self, mut_table: MutableTable, _lock: &FileLock) -> Result<(), TableStoreError> {
+        let table = self.store.save_table(mut_table)?;
+        *self.cached_head.lock().unwrap() = Some(table);
+        Ok(())
+    }
+
+    /// Removes objects for which `keep(id, write_time)` returns false. Segment
+    /// files newer than `keep_newer` are preserved.
+    fn retain(
+        &self,
+        keep_newer: SystemTime,
+        mut keep: impl FnMut(&[u8], MillisSinceEpoch) -> bool,
+    ) -> OpStoreResult<()> {
+        let (table, lock) = self.store.get_head_locked().map_err(to_other_error)?;
+        let table = self
+            .store
+            .retain_entries(
+                &table,
+                |key, value| {
+                    let time = value[..TIMESTAMP_LENGTH].try_into().unwrap();
+                    keep(key, MillisSinceEpoch(i64::from_le_bytes(time)))
+                },
+                keep_newer,
+                &lock,
+            )
+            .map_err(to_other_error)?;
+        *self.cached_head.lock().unwrap() = Some(table);
+        Ok(())
+    }
+}
+
+fn to_millis(time: SystemTime) -> MillisSinceEpoch {
+    let millis = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| {
+            duration.as_millis().try_into().unwrap_or(i64::MAX)
+        });
+    MillisSinceEpoch(millis)
+}
+
+fn to_read_error(
+    source: Box<dyn std::error::Error + Send + Sync>,
+    id: &impl ObjectId,
+) -> OpStoreError {
+    OpStoreError::ReadObject {
+        object_type: id.object_type(),
+        hash: id.hex(),
+        source,
+    }
+}
+
+fn to_other_error(err: TableStoreError) -> OpStoreError {
+    OpStoreError::Other(err.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+    use std::time::Duration;
+
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::backend::CommitId;
+    use crate::backend::Timestamp;
+    use crate::op_store::OperationMetadata;
+    use crate::op_store::TimestampRange;
+    use crate::tests::new_temp_dir;
+
+    fn root_data() -> RootOperationData {
+        RootOperationData {
+            root_commit_id: CommitId::from_hex("000000"),
+        }
+    }
+
+    fn create_operation(view_id: &ViewId, parent_id: &OperationId, description: &str) -> Operation {
+        let timestamp = Timestamp {
+            timestamp: MillisSinceEpoch(123456789),
+            tz_offset: 3600,
+        };
+        Operation {
+            view_id: view_id.clone(),
+            parents: vec![parent_id.clone()],
+            metadata: OperationMetadata {
+                time: TimestampRange {
+                    start: timestamp,
+                    end: timestamp,
+                },
+                description: description.to_owned(),
+                hostname: "some.host.example.com".to_owned(),
+                username: "someone".to_owned(),
+                is_snapshot: false,
+                tags: Default::default(),
+            },
+            commit_predecessors: None,
+        }
+    }
+
+    #[test]
+    fn test_read_write_operation() {
+        let temp_dir = new_temp_dir();
+        let store = TableOpStore::init(temp_dir.path(), root_data()).unwrap();
+        let view = View::make_root(CommitId::from_hex("111111"));
+        let view_id = store.write_view(&view).block_on().unwrap();
+        assert_eq!(store.read_view(&view_id).block_on().unwrap(), view);
+        let operation = create_operation(&view_id, store.root_operation_id(), "op 1");
+        let op_id = store.write_operation(&operation).block_on().unwrap();
+        assert_eq!(store.read_operation(&op_id).block_on().unwrap(), operation);
+        // Writing the same object again is a no-op
+        assert_eq!(store.write_operation(&operation).block_on().unwrap(), op_id);
+
+        // Objects written by another instance can be read
+        let other_store = TableOpStore::load(temp_dir.path(), root_data());
+        let operation2 = create_operation(&view_id, &op_id, "op 2");
+        let op_id2 = other_store.write_operation(&operation2).block_on().unwrap();
+        assert_eq!(
+            store.read_operation(&op_id2).block_on().unwrap(),
+            operation2
+        );
+        assert_matches!(
+            store
+                .read_operation(&OperationId::from_bytes(&[1; 64]))
+                .block_on(),
+            Err(OpStoreError::ObjectNotFound { .. })
+        );
+
+        let prefix = HexPrefix::try_from_hex(&op_id.hex()[..10]).unwrap();
+        assert_eq!(
+            store
+                .resolve_operation_id_prefix(&prefix)
+                .block_on()
+                .unwrap(),
+            PrefixResolution::SingleMatch(op_id.clone())
+        );
+        let prefix = HexPrefix::try_from_hex("").unwrap();
+        assert_eq!(
+            store
+                .resolve_operation_id_prefix(&prefix)
+                .block_on()
+                .unwrap(),
+            PrefixResolution::AmbiguousMatch
+        );
+    }
+
+    #[test]
+    fn test_gc() {
+        let temp_dir = new_temp_dir();
+        let store = TableOpStore::init(temp_dir.path(), root_data()).unwrap();
+        let view_id1 = store
+            .write_view(&View::make_root(CommitId::from_hex("111111")))
+            .block_on()
+            .unwrap();
+        let view_id2 = store
+            .write_view(&View::make_root(CommitId::from_hex("222222")))
+            .block_on()
+            .unwrap();
+        let op1 = create_operation(&view_id1, store.root_operation_id(), "op 1");
+        let op_id1 = store.write_operation(&op1).block_on().unwrap();
+        let op2 = create_operation(&view_id2, store.root_operation_id(), "op 2");
+        let op_id2 = store.write_operation(&op2).block_on().unwrap();
+
+        // Nothing is removed if all objects are new
+        let past = SystemTime::now() - Duration::from_secs(3600);
+        store.gc(slice::from_ref(&op_id1), past).unwrap();
+        assert!(store.read_operation(&op_id2).block_on().is_ok());
+
+        // Unreachable objects are removed
+        let future = SystemTime::now() + Duration::from_secs(3600);
+        store.gc(slice::from_ref(&op_id1), future).unwrap();
+        let store = TableOpStore::load(temp_dir.path(), root_data());
+        assert!(store.read_operation(&op_id1).block_on().is_ok());
+        assert!(store.read_view(&view_id1).block_on().is_ok());
+        assert_matches!(
+            store.read_operation(&op_id2).block_on(),
+            Err(OpStoreError::ObjectNotFound { .. })
+        );
+        assert_matches!(
+            store.read_view(&view_id2).block_on(),
+            Err(OpStoreError::ObjectNotFound { .. })
+        );
+    }
+}
@@ -47,6 +47,14 @@ impl DatePatternContext {
             Self::Fixed(dt) => DatePattern::from_str_kind(s, kind, dt),
         }
     }
+
+    /// Parses the given string into an instant.
+    pub fn parse_instant(&self, s: &str) -> Result<MillisSinceEpoch, DateError> {
+        match *self {
+            Self::Local(dt) => parse_instant(s, dt),
+            Self::Fixed(dt) => parse_instant(s, dt),
+        }
+    }
 }
 
 impl From<DateTime<Local>> for DatePatternContext {
@@ -103,9 +111,7 @@ impl DatePattern {
     where
         Tz::Offset: Copy,
     {
-        let d =
-            parse_date_string(s, now, Dialect::Us).map_err(DatePatternParseError::ParseError)?;
-        let millis_since_epoch = MillisSinceEpoch(d.timestamp_millis());
+        let millis_since_epoch = parse_instant(s, now)?;
         match kind {
             "after" => Ok(Self::AtOrAfter(millis_since_epoch)),
             "before" => Ok(Self::Before(millis_since_epoch)),
@@ -122,6 +128,14 @@ impl DatePattern {
     }
 }
 
+fn parse_instant<Tz: TimeZone>(s: &str, now: DateTime<Tz>) -> Result<MillisSinceEpoch, DateError>
+where
+    Tz::Offset: Copy,
+{
+    let d = parse_date_string(s, now, Dialect::Us)?;
+    Ok(MillisSinceEpoch(d.timestamp_millis()))
+}
+
 // @TODO ideally we would have this unified with the other parsing code. However
 // we use the interim crate which does not handle explicitly given time zone
 // information
@@ -14,6 +14,8 @@
 
 #![expect(missing_docs)]
 
+use std::collections::HashMap;
+use std::mem;
 use std::sync::Arc;
 
 use itertools::Itertools as _;
@@ -31,6 +33,8 @@ use crate::op_store::OpStoreError;
 use crate::op_store::OperationMetadata;
 use crate::op_store::TimestampRange;
 use crate::operation::Operation;
+use crate::ref_name::RefNameBuf;
+use crate::ref_name::WorkspaceNameBuf;
 use crate::repo::MutableRepo;
 use crate::repo::ReadonlyRepo;
 use crate::repo::Repo as _;
@@ -39,6 +43,31 @@ use crate::repo::RepoLoaderError;
 use crate::settings::UserSettings;
 use crate::view::View;
 
+/// Operation tag listing the bookmarks which became conflicted by merging
+/// concurrent operations.
+pub const CONFLICTED_BOOKMARKS_TAG: &str = "conflicted-bookmarks";
+/// Operation tag listing the workspaces whose working-copy commits were
+/// changed by concurrent operations.
+pub const CONFLICTED_WORKSPACES_TAG: &str = "conflicted-workspaces";
+
+/// Bookmarks and working-copy commits which were changed by both sides of
+/// merged operations.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OperationMergeConflicts {
+    /// Bookmarks which became conflicted.
+    pub bookmarks: Vec<RefNameBuf>,
+    /// Workspaces whose working-copy commits were changed on both sides. The
+    /// working-copy commit of the first operation is kept.
+    pub workspaces: Vec<WorkspaceNameBuf>,
+}
+
+impl OperationMergeConflicts {
+    /// Returns true if nothing conflicted.
+    pub fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty() && self.workspaces.is_empty()
+    }
+}
+
 /// Error from attempts to write and publish transaction.
 #[derive(Debug, Error)]
 #[error("Failed to commit new operation")]
@@ -95,7 +124,14 @@ impl Transaction {
         &mut self.mut_repo
     }
 
-    pub fn merge_operation(&mut self, other_op: Operation) -> Result<(), RepoLoaderError> {
+    /// Merges the `other_op` into this transaction.
+    ///
+    /// Returns the bookmarks and working-copy commits which were changed on
+    /// both sides. They are also recorded in the operation tags.
+    pub fn merge_operation(
+        &mut self,
+        other_op: Operation,
+    ) -> Result<OperationMergeConflicts, RepoLoaderError> {
         let ancestor_op = dag_walk::closest_common_node_ok(
             self.parent_ops.iter().cloned().map(Ok),
             [Ok(other_op.clone())],
@@ -108,8 +144,50 @@ impl Transaction {
         let other_repo = repo_loader.load_at(&other_op)?;
         self.parent_ops.push(other_op);
         let merged_repo = self.repo_mut();
+        let self_view = merged_repo.view().store_view().clone();
         merged_repo.merge(&base_repo, &other_repo)?;
-        Ok(())
+
+        let (base_view, other_view) = (base_repo.view(), other_repo.view());
+        let bookmarks = merged_repo
+            .view()
+            .local_bookmarks()
+            .filter(|(name, target)| {
+                target.has_conflict()
+                    && !self_view
+                        .local_bookmarks
+                        .get(*name)
+                        .is_some_and(|target| target.has_conflict())
+                    && !other_view.get_local_bookmark(name).has_conflict()
+            })
+            .map(|(name, _)| name.to_owned())
+            .collect_vec();
+        // The working-copy commit of the self side is kept if both sides
+        // changed it.
+        let workspaces = other_view
+            .wc_commit_ids()
+            .iter()
+            .filter(|&(name, other_id)| {
+                let base_id = base_view.get_wc_commit_id(name);
+                self_view.wc_commit_ids.get(name).is_some_and(|self_id| {
+                    self_id != other_id && Some(self_id) != base_id && Some(other_id) != base_id
+                })
+            })
+            .map(|(name, _)| name.clone())
+            .collect_vec();
+        let conflicts = OperationMergeConflicts {
+            bookmarks,
+            workspaces,
+        };
+        self.record_merge_conflicts(&conflicts);
+        Ok(conflicts)
+    }
+
+    fn record_merge_conflicts(&mut self, conflicts: &OperationMergeConflicts) {
+        let tags = &mut self.op_metadata.tags;
+        let bookmarks = conflicts.bookmarks.iter().map(|name| name.as_str());
+        add_names_to_tag(tags, CONFLICTED_BOOKMARKS_TAG, bookmarks);
+        let workspaces = conflicts.workspaces.iter().map(|name| name.as_str());
+        add_names_to_tag(tags, CONFLICTED_WORKSPACES_TAG, workspaces);
     }
 
     pub fn set_is_snapshot(&mut self, is_snapshot: bool) {
@@ -167,6 +245,52 @@ impl Transaction {
     }
 }
 
+/// Adds the `names` to the comma-separated list of names in the tag.
+fn add_names_to_tag<'a>(
+    tags: &mut HashMap<String, String>,
+    key: &str,
+    names: impl Iterator<Item = &'a str>,
+) {
+    let recorded = tags.remove(key).unwrap_or_default();
+    let value = parse_names_tag(&recorded)
+        .into_iter()
+        .chain(names.map(str::to_owned))
+        .sorted()
+        .dedup()
+        .map(|name| name.replace('\\', "\\\\").replace(',', "\\,"))
+        .join(", ");
+    if !value.is_empty() {
+        tags.insert(key.to_owned(), value);
+    }
+}
+
+/// Parses the comma-separated list of names recorded in the
+/// [`CONFLICTED_BOOKMARKS_TAG`] or [`CONFLICTED_WORKSPACES_TAG`] tag. Commas
+/// and backslashes within names are escaped by backslash.
+pub fn parse_names_tag(value: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut name = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => name.extend(chars.next()),
+            ',' => {
+                names.push(mem::take(&mut name));
+                chars = chars
+                    .as_str()
+                    .strip_prefix(' ')
+                    .unwrap_or(chars.as_str())
+                    .chars();
+            }
+            _ => name.push(c),
+        }
+    }
+    if !name.is_empty() {
+        names.push(name);
+    }
+    names
+}
+
 pub fn create_op_metadata(
     user_settings: &UserSettings,
     description: String,
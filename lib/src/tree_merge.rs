@@ -33,6 +33,7 @@ use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::TreeValue;
 use crate::config::ConfigGetError;
+use crate::diff::DiffAlgorithm;
 use crate::files;
 use crate::files::FileMergeHunkLevel;
 use crate::merge::Merge;
@@ -55,6 +56,8 @@ pub struct MergeOptions {
     pub hunk_level: FileMergeHunkLevel,
     /// Whether to resolve conflict that makes the same change at all sides.
     pub same_change: SameChange,
+    /// Algorithm to find unchanged lines between file contents.
+    pub diff_algorithm: DiffAlgorithm,
 }
 
 impl MergeOptions {
@@ -65,6 +68,7 @@ impl MergeOptions {
             // needed. It wouldn't be translated to FileMergeHunkLevel.
             hunk_level: settings.get("merge.hunk-level")?,
             same_change: settings.get("merge.same-change")?,
+            diff_algorithm: settings.get("merge.diff-algorithm")?,
         })
     }
 }
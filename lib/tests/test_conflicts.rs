@@ -23,6 +23,7 @@ use jj_lib::conflicts::extract_as_single_hunk;
 use jj_lib::conflicts::materialize_merge_result_to_bytes;
 use jj_lib::conflicts::parse_conflict;
 use jj_lib::conflicts::update_from_content;
+use jj_lib::diff::DiffAlgorithm;
 use jj_lib::files::FileMergeHunkLevel;
 use jj_lib::merge::Merge;
 use jj_lib::merge::SameChange;
@@ -2416,6 +2417,7 @@ fn materialize_conflict_string(
         merge: MergeOptions {
             hunk_level: FileMergeHunkLevel::Line,
             same_change: SameChange::Accept,
+            diff_algorithm: DiffAlgorithm::Default,
         },
     };
     String::from_utf8(materialize_merge_result_to_bytes(&contents, &options).into()).unwrap()
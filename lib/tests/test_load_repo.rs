@@ -12,7 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fs;
+use std::path::Path;
+use std::slice;
+
+use jj_lib::op_store::OpStore as _;
+use jj_lib::op_store::RootOperationData;
+use jj_lib::op_walk;
+use jj_lib::repo::Repo as _;
 use jj_lib::repo::RepoLoader;
+use jj_lib::simple_op_store::SimpleOpStore;
 use testutils::TestRepo;
 use testutils::write_random_commit;
 
@@ -52,3 +61,63 @@ fn test_load_at_operation() {
     let old_repo = loader.load_at(repo.operation()).unwrap();
     assert!(old_repo.view().heads().contains(commit.id()));
 }
+
+#[test]
+fn test_load_recovers_op_store_swap() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo_path = test_repo.repo_path();
+    let mut tx = test_repo.repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let repo = tx.commit("add commit").unwrap();
+    let store_path = repo_path.join("op_store");
+    let new_store_path = repo_path.join("op_store.new");
+    let old_store_path = repo_path.join("op_store.old");
+
+    // Creates a new store at `path`, optionally with the current operations
+    let init_store = |path: &Path, copy: bool| {
+        let root_data = RootOperationData {
+            root_commit_id: repo.store().root_commit_id().clone(),
+        };
+        fs::create_dir(path).unwrap();
+        let store = SimpleOpStore::init(path, root_data).unwrap();
+        fs::write(path.join("type"), store.name()).unwrap();
+        if copy {
+            op_walk::copy_operations(slice::from_ref(repo.operation()), &store).unwrap();
+        }
+    };
+    let load_head_op_id = || {
+        let loader = RepoLoader::init_from_file_system(
+            &settings,
+            repo_path,
+            &test_repo.env.default_store_factories(),
+        )
+        .unwrap();
+        let head_repo = loader.load_at_head().unwrap();
+        assert!(!new_store_path.exists());
+        assert!(!old_store_path.exists());
+        head_repo.op_id().clone()
+    };
+
+    // The new store may be incomplete
+    init_store(&new_store_path, false);
+    assert_eq!(&load_head_op_id(), repo.op_id());
+
+    // Interrupted before the new store is moved in place
+    init_store(&new_store_path, true);
+    fs::rename(&store_path, &old_store_path).unwrap();
+    assert_eq!(&load_head_op_id(), repo.op_id());
+
+    // Interrupted before the operation heads are updated
+    init_store(&new_store_path, false);
+    fs::rename(&store_path, &old_store_path).unwrap();
+    fs::rename(&new_store_path, &store_path).unwrap();
+    assert_eq!(&load_head_op_id(), repo.op_id());
+    assert!(store_path.join("operations").read_dir().unwrap().count() > 0);
+
+    // Interrupted before the old store is removed
+    init_store(&new_store_path, true);
+    fs::rename(&store_path, &old_store_path).unwrap();
+    fs::rename(&new_store_path, &store_path).unwrap();
+    assert_eq!(&load_head_op_id(), repo.op_id());
+}
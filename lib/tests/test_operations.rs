@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::path::Path;
 use std::slice;
 use std::sync::Arc;
@@ -20,22 +22,40 @@ use std::time::SystemTime;
 use assert_matches::assert_matches;
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
+use jj_lib::backend::MillisSinceEpoch;
+use jj_lib::backend::Timestamp;
 use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigSource;
 use jj_lib::evolution::walk_predecessors;
 use jj_lib::index::Index;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_bundle;
+use jj_lib::op_bundle::OpBundleError;
+use jj_lib::op_store::OpStore as _;
 use jj_lib::op_store::OperationId;
+use jj_lib::op_store::RefTarget;
+use jj_lib::op_store::RootOperationData;
+use jj_lib::op_store::TimestampRange;
 use jj_lib::op_walk;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
 use jj_lib::operation::Operation;
+use jj_lib::ref_name::RefNameBuf;
+use jj_lib::ref_name::WorkspaceName;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::settings::UserSettings;
+use jj_lib::table_op_store::TableOpStore;
+use jj_lib::time_util::DatePatternContext;
+use jj_lib::transaction::CONFLICTED_BOOKMARKS_TAG;
+use jj_lib::transaction::CONFLICTED_WORKSPACES_TAG;
+use jj_lib::transaction::parse_names_tag;
 use pollster::FutureExt as _;
 use test_case::test_case;
 use testutils::TestRepo;
+use testutils::TestRepoBackend;
+use testutils::create_tree;
+use testutils::repo_path;
 use testutils::write_random_commit;
 use testutils::write_random_commit_with_parents;
 
@@ -57,6 +77,10 @@ fn list_dir(dir: &Path) -> Vec<String> {
         .collect()
 }
 
+fn now() -> DatePatternContext {
+    chrono::Local::now().into()
+}
+
 fn index_has_id(index: &dyn Index, commit_id: &CommitId) -> bool {
     index.has_id(commit_id).unwrap()
 }
@@ -171,6 +195,94 @@ fn test_concurrent_operations() {
     assert_eq!(list_dir(&op_heads_dir), vec![merged_op_id.hex()]);
 }
 
+#[test]
+fn test_concurrent_operations_conflicts() {
+    let test_repo = TestRepo::init();
+    let repo_0 = test_repo.repo;
+    let ws_name = WorkspaceName::DEFAULT.to_owned();
+
+    let mut tx = repo_0.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    tx.repo_mut()
+        .set_local_bookmark_target("main".as_ref(), RefTarget::normal(commit_a.id().clone()));
+    tx.repo_mut()
+        .set_local_bookmark_target("other".as_ref(), RefTarget::normal(commit_a.id().clone()));
+    tx.repo_mut()
+        .set_wc_commit(ws_name.clone(), commit_a.id().clone())
+        .unwrap();
+    let repo_a = tx.commit("op A").unwrap();
+
+    // Both operations move "main" and the working-copy commit. Only one
+    // operation moves "other".
+    let concurrent_tx = |description: &str, move_other: bool| {
+        let mut tx = repo_a.start_transaction();
+        let commit = write_random_commit(tx.repo_mut());
+        tx.repo_mut()
+            .set_local_bookmark_target("main".as_ref(), RefTarget::normal(commit.id().clone()));
+        if move_other {
+            tx.repo_mut().set_local_bookmark_target(
+                "other".as_ref(),
+                RefTarget::normal(commit.id().clone()),
+            );
+        }
+        tx.repo_mut()
+            .set_wc_commit(ws_name.clone(), commit.id().clone())
+            .unwrap();
+        tx.commit(description).unwrap()
+    };
+    let repo_b = concurrent_tx("op B", true);
+    let repo_c = concurrent_tx("op C", false);
+
+    let mut tx = repo_b.start_transaction();
+    let conflicts = tx.merge_operation(repo_c.operation().clone()).unwrap();
+    assert_eq!(conflicts.bookmarks, [RefNameBuf::from("main")]);
+    assert_eq!(conflicts.workspaces, [WorkspaceName::DEFAULT]);
+    // The working-copy commit of the first operation is kept
+    assert_eq!(
+        tx.repo().view().get_wc_commit_id(&ws_name),
+        repo_b.view().get_wc_commit_id(&ws_name)
+    );
+    let merged_repo = tx.commit("merge").unwrap();
+    let tags = &merged_repo.operation().metadata().tags;
+    assert_eq!(
+        tags.get(CONFLICTED_BOOKMARKS_TAG).map(String::as_str),
+        Some("main")
+    );
+    assert_eq!(
+        tags.get(CONFLICTED_WORKSPACES_TAG).map(String::as_str),
+        Some("default")
+    );
+
+    // Names containing the separator are escaped
+    let concurrent_tx = |description: &str, name: &str| {
+        let mut tx = repo_a.start_transaction();
+        let commit = write_random_commit(tx.repo_mut());
+        tx.repo_mut()
+            .set_local_bookmark_target(name.as_ref(), RefTarget::normal(commit.id().clone()));
+        tx.commit(description).unwrap()
+    };
+    let repo_d = concurrent_tx("op D", "a, b");
+    let repo_e = concurrent_tx("op E", "a, b");
+    let repo_f = concurrent_tx("op F", "a");
+    let repo_g = concurrent_tx("op G", "a");
+    let mut tx = repo_d.start_transaction();
+    tx.merge_operation(repo_e.operation().clone()).unwrap();
+    tx.merge_operation(repo_f.operation().clone()).unwrap();
+    tx.merge_operation(repo_g.operation().clone()).unwrap();
+    let merged_repo = tx.commit("merge").unwrap();
+    let tag = &merged_repo.operation().metadata().tags[CONFLICTED_BOOKMARKS_TAG];
+    assert_eq!(tag, r"a, a\, b");
+    assert_eq!(parse_names_tag(tag), ["a", "a, b"]);
+    assert_eq!(parse_names_tag(r"a\\, b\\\,c"), [r"a\", r"b\,c"]);
+
+    // Merging operations which don't conflict records nothing
+    let mut tx = repo_a.start_transaction();
+    let conflicts = tx.merge_operation(repo_b.operation().clone()).unwrap();
+    assert!(conflicts.is_empty());
+    let merged_repo = tx.commit("merge").unwrap();
+    assert!(merged_repo.operation().metadata().tags.is_empty());
+}
+
 fn assert_heads(repo: &dyn Repo, expected: Vec<&CommitId>) {
     let expected = expected.iter().cloned().cloned().collect();
     assert_eq!(*repo.view().heads(), expected);
@@ -682,7 +794,7 @@ fn test_resolve_op_id() {
     "#);
 
     let repo_loader = repo.loader();
-    let resolve = |op_str: &str| op_walk::resolve_op_for_load(repo_loader, op_str);
+    let resolve = |op_str: &str| op_walk::resolve_op_for_load(repo_loader, op_str, &now());
 
     // Full id
     assert_eq!(resolve(&operations[0].id().hex()).unwrap(), operations[0]);
@@ -737,7 +849,7 @@ fn test_resolve_current_op() {
     let repo = test_repo.repo;
 
     assert_eq!(
-        op_walk::resolve_op_with_repo(&repo, "@").unwrap(),
+        op_walk::resolve_op_with_repo(&repo, "@", &now()).unwrap(),
         *repo.operation()
     );
 }
@@ -760,16 +872,16 @@ fn test_resolve_op_parents_children() {
     // Parent
     let op2_id_hex = operations[2].id().hex();
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}-")).unwrap(),
+        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}-"), &now()).unwrap(),
         *operations[1]
     );
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}--")).unwrap(),
+        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}--"), &now()).unwrap(),
         *operations[0]
     );
     // "{op2_id_hex}----" is the root operation
     assert_matches!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}-----")),
+        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}-----"), &now()),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::EmptyOperations(_)
         ))
@@ -778,15 +890,15 @@ fn test_resolve_op_parents_children() {
     // Child
     let op0_id_hex = operations[0].id().hex();
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}+")).unwrap(),
+        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}+"), &now()).unwrap(),
         *operations[1]
     );
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}++")).unwrap(),
+        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}++"), &now()).unwrap(),
         *operations[2]
     );
     assert_matches!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}+++")),
+        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}+++"), &now()),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::EmptyOperations(_)
         ))
@@ -794,17 +906,17 @@ fn test_resolve_op_parents_children() {
 
     // Child of parent
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}--+")).unwrap(),
+        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}--+"), &now()).unwrap(),
         *operations[1]
     );
 
     // Child at old repo: new operations shouldn't be visible
     assert_eq!(
-        op_walk::resolve_op_with_repo(&repos[1], &format!("{op0_id_hex}+")).unwrap(),
+        op_walk::resolve_op_with_repo(&repos[1], &format!("{op0_id_hex}+"), &now()).unwrap(),
         *operations[1]
     );
     assert_matches!(
-        op_walk::resolve_op_with_repo(&repos[0], &format!("{op0_id_hex}+")),
+        op_walk::resolve_op_with_repo(&repos[0], &format!("{op0_id_hex}+"), &now()),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::EmptyOperations(_)
         ))
@@ -820,19 +932,20 @@ fn test_resolve_op_parents_children() {
     // op ids) should be reported, not the full expression provided by the user.
     let op5_id_hex = repo.operation().id().hex();
     let parents_op_str = format!("{op5_id_hex}-");
-    let error = op_walk::resolve_op_with_repo(&repo, &parents_op_str).unwrap_err();
+    let error = op_walk::resolve_op_with_repo(&repo, &parents_op_str, &now()).unwrap_err();
     assert_eq!(
         extract_multiple_operations_error(&error).unwrap(),
         (&parents_op_str, parent_op_ids)
     );
     let grandparents_op_str = format!("{op5_id_hex}--");
-    let error = op_walk::resolve_op_with_repo(&repo, &grandparents_op_str).unwrap_err();
+    let error = op_walk::resolve_op_with_repo(&repo, &grandparents_op_str, &now()).unwrap_err();
     assert_eq!(
         extract_multiple_operations_error(&error).unwrap(),
         (&parents_op_str, parent_op_ids)
     );
     let children_of_parents_op_str = format!("{op5_id_hex}-+");
-    let error = op_walk::resolve_op_with_repo(&repo, &children_of_parents_op_str).unwrap_err();
+    let error =
+        op_walk::resolve_op_with_repo(&repo, &children_of_parents_op_str, &now()).unwrap_err();
     assert_eq!(
         extract_multiple_operations_error(&error).unwrap(),
         (&parents_op_str, parent_op_ids)
@@ -840,13 +953,565 @@ fn test_resolve_op_parents_children() {
 
     let op2_id_hex = operations[2].id().hex();
     let op_str = format!("{op2_id_hex}+");
-    let error = op_walk::resolve_op_with_repo(&repo, &op_str).unwrap_err();
+    let error = op_walk::resolve_op_with_repo(&repo, &op_str, &now()).unwrap_err();
     assert_eq!(
         extract_multiple_operations_error(&error).unwrap(),
         (&op_str, parent_op_ids)
     );
 }
 
+#[test]
+fn test_resolve_op_opset() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo_0 = test_repo.repo;
+    let repo_a = repo_0.start_transaction().commit("op A").unwrap();
+    let repo_b = repo_a.start_transaction().commit("op B").unwrap();
+    let repo = repo_b.start_transaction().commit("op C").unwrap();
+
+    assert_eq!(
+        op_walk::resolve_op_with_repo(&repo, r#"description("op B")"#, &now()).unwrap(),
+        *repo_b.operation()
+    );
+    assert_eq!(
+        op_walk::resolve_op_with_repo(&repo, r#"latest(description(glob:"op *"))"#, &now())
+            .unwrap(),
+        *repo.operation()
+    );
+    assert_eq!(
+        op_walk::resolve_op_with_repo(&repo, r#"@-- & description("op")"#, &now()).unwrap(),
+        *repo_a.operation()
+    );
+    let op_str = format!("{}..@-", repo_0.op_id().hex());
+    let error = op_walk::resolve_op_with_repo(&repo, &op_str, &now()).unwrap_err();
+    assert_eq!(
+        extract_multiple_operations_error(&error).unwrap(),
+        (
+            &op_str,
+            &[repo_b.op_id().clone(), repo_a.op_id().clone()][..]
+        )
+    );
+    assert_matches!(
+        op_walk::resolve_op_with_repo(&repo, "snapshots()", &now()),
+        Err(OpsetEvaluationError::OpsetResolution(
+            OpsetResolutionError::EmptyOperations(_)
+        ))
+    );
+    assert_matches!(
+        op_walk::resolve_op_with_repo(&repo, "snapshot()", &now()),
+        Err(OpsetEvaluationError::OpsetParse(_))
+    );
+}
+
+#[test]
+fn test_abandon_operations() {
+    let test_repo = TestRepo::init();
+    let repo_0 = test_repo.repo;
+    let loader = repo_0.loader();
+    let op_store = repo_0.op_store();
+
+    let read_op = |id| loader.load_operation(id).unwrap();
+
+    // Set up linear operation graph:
+    // D
+    // C
+    // B
+    // A
+    // 0 (initial)
+    let random_tx = |repo: &Arc<ReadonlyRepo>| {
+        let mut tx = repo.start_transaction();
+        write_random_commit(tx.repo_mut());
+        tx
+    };
+    let repo_a = random_tx(&repo_0).commit("op A").unwrap();
+    let repo_b = random_tx(&repo_a).commit("op B").unwrap();
+    let repo_c = random_tx(&repo_b).commit("op C").unwrap();
+    let repo_d = random_tx(&repo_c).commit("op D").unwrap();
+
+    // Abandon A and C:
+    // D'
+    // B'
+    // 0 (initial)
+    let abandoned_ids = HashSet::from([repo_a.op_id().clone(), repo_c.op_id().clone()]);
+    let stats = op_walk::abandon_operations(
+        op_store.as_ref(),
+        &abandoned_ids,
+        slice::from_ref(repo_d.operation()),
+    )
+    .unwrap();
+    assert_eq!(stats.new_head_ids.len(), 1);
+    assert_eq!(stats.rewritten_count, 2);
+    assert_eq!(stats.unreachable_count, 2);
+    let new_op_d = read_op(&stats.new_head_ids[0]);
+    assert_eq!(new_op_d.metadata(), repo_d.operation().metadata());
+    let [new_op_b] = new_op_d.parents().collect_array().unwrap();
+    let new_op_b = new_op_b.unwrap();
+    assert_eq!(new_op_b.metadata(), repo_b.operation().metadata());
+    assert_eq!(new_op_b.parent_ids(), slice::from_ref(repo_0.op_id()));
+
+    // Nothing to abandon
+    let stats = op_walk::abandon_operations(
+        op_store.as_ref(),
+        &HashSet::new(),
+        slice::from_ref(repo_d.operation()),
+    )
+    .unwrap();
+    assert_eq!(stats.new_head_ids, vec![repo_d.op_id().clone()]);
+    assert_eq!(stats.rewritten_count, 0);
+    assert_eq!(stats.unreachable_count, 0);
+}
+
+#[test]
+fn test_compact_snapshots() {
+    let test_repo = TestRepo::init();
+    let repo_0 = test_repo.repo;
+    let loader = repo_0.loader();
+    let op_store = repo_0.op_store();
+
+    let read_op = |id| loader.load_operation(id).unwrap();
+
+    // Set up linear operation graph, where S1, S2, and S3 are snapshots
+    // rewriting the same commit:
+    // D
+    // S3
+    // S2
+    // S1
+    // A
+    // 0 (initial)
+    let mut tx = repo_0.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let repo_a = tx.commit("op A").unwrap();
+    let snapshot_tx = |repo: &Arc<ReadonlyRepo>, commit, description: &str| {
+        let mut tx = repo.start_transaction();
+        tx.set_is_snapshot(true);
+        let new_commit = tx
+            .repo_mut()
+            .rewrite_commit(commit)
+            .set_description(description)
+            .write()
+            .unwrap();
+        tx.repo_mut().rebase_descendants().unwrap();
+        (tx.commit("snapshot working copy").unwrap(), new_commit)
+    };
+    let (repo_s1, commit_s1) = snapshot_tx(&repo_a, &commit_a, "1");
+    let (repo_s2, commit_s2) = snapshot_tx(&repo_s1, &commit_s1, "2");
+    let (repo_s3, commit_s3) = snapshot_tx(&repo_s2, &commit_s2, "3");
+    let repo_d = repo_s3.start_transaction().commit("op D").unwrap();
+
+    // Nothing is older than the cutoff
+    let stats = op_walk::compact_snapshots(
+        op_store.as_ref(),
+        slice::from_ref(repo_d.operation()),
+        MillisSinceEpoch(0),
+    )
+    .unwrap();
+    assert_eq!(stats.new_head_ids, vec![repo_d.op_id().clone()]);
+    assert_eq!(stats.rewritten_count, 0);
+    assert_eq!(stats.unreachable_count, 0);
+
+    // Fold S1 and S2 into S3:
+    // D'
+    // S3'
+    // A
+    // 0 (initial)
+    let stats = op_walk::compact_snapshots(
+        op_store.as_ref(),
+        slice::from_ref(repo_d.operation()),
+        MillisSinceEpoch(i64::MAX),
+    )
+    .unwrap();
+    assert_eq!(stats.new_head_ids.len(), 1);
+    assert_eq!(stats.rewritten_count, 2);
+    assert_eq!(stats.unreachable_count, 2);
+    let new_op_d = read_op(&stats.new_head_ids[0]);
+    assert_eq!(new_op_d.metadata(), repo_d.operation().metadata());
+    let [new_op_s3] = new_op_d.parents().collect_array().unwrap();
+    let new_op_s3 = new_op_s3.unwrap();
+    assert_eq!(new_op_s3.view_id(), repo_s3.operation().view_id());
+    assert_eq!(new_op_s3.parent_ids(), slice::from_ref(repo_a.op_id()));
+    assert_eq!(
+        new_op_s3.metadata().time.start,
+        repo_s1.operation().metadata().time.start
+    );
+    assert_eq!(
+        new_op_s3.metadata().time.end,
+        repo_s3.operation().metadata().time.end
+    );
+
+    // The evolution of the working-copy commit is preserved.
+    let new_repo = loader.load_at(&new_op_d).unwrap();
+    assert_eq!(
+        get_predecessors(&new_repo, commit_s3.id()),
+        [commit_s2.id().clone()]
+    );
+    assert_eq!(
+        get_predecessors(&new_repo, commit_s2.id()),
+        [commit_s1.id().clone()]
+    );
+    assert_eq!(
+        get_predecessors(&new_repo, commit_s1.id()),
+        [commit_a.id().clone()]
+    );
+}
+
+#[test]
+fn test_compact_snapshots_partially_recorded_predecessors() {
+    let test_repo = TestRepo::init();
+    let repo_0 = test_repo.repo;
+    let loader = repo_0.loader();
+    let op_store = repo_0.op_store();
+
+    // Set up linear operation graph, where only S2 records predecessors:
+    // D
+    // S3 (no predecessors)
+    // S2
+    // S1 (no predecessors)
+    // A
+    // 0 (initial)
+    let mut tx = repo_0.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let repo_a = tx.commit("op A").unwrap();
+    let snapshot_tx = |repo: &Arc<ReadonlyRepo>, commit, description: &str| {
+        let mut tx = repo.start_transaction();
+        tx.set_is_snapshot(true);
+        let new_commit = tx
+            .repo_mut()
+            .rewrite_commit(commit)
+            .set_description(description)
+            .write()
+            .unwrap();
+        tx.repo_mut().rebase_descendants().unwrap();
+        (tx.commit("snapshot working copy").unwrap(), new_commit)
+    };
+    let (repo_s1, commit_s1) = snapshot_tx(&repo_a, &commit_a, "1");
+    let (repo_s2, commit_s2) = snapshot_tx(&repo_s1, &commit_s1, "2");
+    let (repo_s3, _commit_s3) = snapshot_tx(&repo_s2, &commit_s2, "3");
+    let repo_d = repo_s3.start_transaction().commit("op D").unwrap();
+    let rewrite_op = |op: &Operation, parent_id: &OperationId, has_predecessors: bool| {
+        let mut data = op.store_operation().clone();
+        data.parents = vec![parent_id.clone()];
+        if !has_predecessors {
+            data.commit_predecessors = None;
+        }
+        op_store.write_operation(&data).block_on().unwrap()
+    };
+    let op_s1_id = rewrite_op(repo_s1.operation(), repo_a.op_id(), false);
+    let op_s2_id = rewrite_op(repo_s2.operation(), &op_s1_id, true);
+    let op_s3_id = rewrite_op(repo_s3.operation(), &op_s2_id, false);
+    let op_d_id = rewrite_op(repo_d.operation(), &op_s3_id, true);
+    let op_d = loader.load_operation(&op_d_id).unwrap();
+
+    let stats = op_walk::compact_snapshots(
+        op_store.as_ref(),
+        slice::from_ref(&op_d),
+        MillisSinceEpoch(i64::MAX),
+    )
+    .unwrap();
+    assert_eq!(stats.unreachable_count, 2);
+    let new_op_d = loader.load_operation(&stats.new_head_ids[0]).unwrap();
+    let [new_op_s3] = new_op_d.parents().collect_array().unwrap();
+    let new_op_s3 = new_op_s3.unwrap();
+    assert_eq!(new_op_s3.parent_ids(), slice::from_ref(repo_a.op_id()));
+    // The predecessors recorded by S2 are preserved.
+    assert_eq!(
+        new_op_s3.store_operation().commit_predecessors,
+        repo_s2.operation().store_operation().commit_predecessors
+    );
+}
+
+#[test]
+fn test_set_operation_tags() {
+    let test_repo = TestRepo::init();
+    let repo_0 = test_repo.repo;
+    let loader = repo_0.loader();
+    let op_store = repo_0.op_store();
+
+    let read_op = |id| loader.load_operation(id).unwrap();
+
+    // Set up linear operation graph:
+    // C
+    // B
+    // A
+    // 0 (initial)
+    let random_tx = |repo: &Arc<ReadonlyRepo>| {
+        let mut tx = repo.start_transaction();
+        write_random_commit(tx.repo_mut());
+        tx
+    };
+    let repo_a = random_tx(&repo_0).commit("op A").unwrap();
+    let repo_b = random_tx(&repo_a).commit("op B").unwrap();
+    let repo_c = random_tx(&repo_b).commit("op C").unwrap();
+
+    // Tag B:
+    // C'
+    // B'
+    // A
+    // 0 (initial)
+    let tags = HashMap::from([("rc".to_owned(), "3".to_owned())]);
+    let stats = op_walk::set_operation_tags(
+        op_store.as_ref(),
+        repo_b.operation(),
+        &tags,
+        slice::from_ref(repo_c.operation()),
+    )
+    .unwrap();
+    assert_eq!(stats.new_head_ids.len(), 1);
+    assert_eq!(stats.rewritten_count, 2);
+    assert_eq!(stats.unreachable_count, 1);
+    let new_op_c = read_op(&stats.new_head_ids[0]);
+    assert_eq!(new_op_c.metadata(), repo_c.operation().metadata());
+    assert_eq!(new_op_c.view_id(), repo_c.operation().view_id());
+    let [new_op_b] = new_op_c.parents().collect_array().unwrap();
+    let new_op_b = new_op_b.unwrap();
+    assert_eq!(new_op_b.view_id(), repo_b.operation().view_id());
+    assert_eq!(new_op_b.parent_ids(), slice::from_ref(repo_a.op_id()));
+    assert_eq!(
+        new_op_b.metadata().tags.get("rc").map(String::as_str),
+        Some("3")
+    );
+
+    // Setting the same tags again doesn't rewrite anything
+    let stats = op_walk::set_operation_tags(
+        op_store.as_ref(),
+        &new_op_b,
+        &tags,
+        slice::from_ref(&new_op_c),
+    )
+    .unwrap();
+    assert_eq!(stats.new_head_ids, vec![new_op_c.id().clone()]);
+    assert_eq!(stats.rewritten_count, 0);
+    assert_eq!(stats.unreachable_count, 0);
+}
+
+#[test]
+fn test_select_expired_operations() {
+    let test_repo = TestRepo::init();
+    let repo_0 = test_repo.repo;
+    let loader = repo_0.loader();
+    let op_store = repo_0.op_store();
+
+    const HOUR: i64 = 60 * 60 * 1000;
+    const DAY: i64 = 24 * HOUR;
+    let write_op = |parent: &Operation, end: i64, tags: &[&str]| {
+        let mut data = parent.store_operation().clone();
+        data.parents = vec![parent.id().clone()];
+        let timestamp = Timestamp {
+            timestamp: MillisSinceEpoch(end),
+            tz_offset: 0,
+        };
+        data.metadata.time = TimestampRange {
+            start: timestamp,
+            end: timestamp,
+        };
+        data.metadata.tags = tags
+            .iter()
+            .map(|&name| (name.to_owned(), String::new()))
+            .collect();
+        let id = op_store.write_operation(&data).block_on().unwrap();
+        loader.load_operation(&id).unwrap()
+    };
+
+    // Set up linear operation graph:
+    // F (day 20)
+    // E (day 10)
+    // D (day 2, 18:00)
+    // C (day 2, 12:00)
+    // B (day 1, tagged)
+    // A (day 1)
+    // 0 (initial)
+    let op_a = write_op(repo_0.operation(), DAY + HOUR, &["args"]);
+    let op_b = write_op(&op_a, DAY + 2 * HOUR, &["args", "release"]);
+    let op_c = write_op(&op_b, 2 * DAY + 12 * HOUR, &["args"]);
+    let op_d = write_op(&op_c, 2 * DAY + 18 * HOUR, &["args"]);
+    let op_e = write_op(&op_d, 10 * DAY, &["args"]);
+    let op_f = write_op(&op_e, 20 * DAY, &["args"]);
+    let head_ops = slice::from_ref(&op_f);
+
+    let select = |policy: &op_walk::OperationRetentionPolicy| {
+        op_walk::select_expired_operations(op_store.as_ref(), head_ops, policy)
+            .unwrap()
+            .into_iter()
+            .sorted()
+            .collect_vec()
+    };
+    let sorted_ids =
+        |ops: &[&Operation]| ops.iter().map(|op| op.id().clone()).sorted().collect_vec();
+    let mut policy = op_walk::OperationRetentionPolicy {
+        keep_all_since: MillisSinceEpoch(0),
+        keep_daily_since: MillisSinceEpoch(0),
+        ignored_tag_names: HashSet::from(["args".to_owned()]),
+        kept_ids: HashSet::new(),
+    };
+
+    // Everything is newer than the threshold
+    assert_eq!(select(&policy), vec![]);
+
+    // Keep operations since day 5, and the last operation of each day. B is
+    // the last operation of day 1.
+    policy.keep_all_since = MillisSinceEpoch(5 * DAY);
+    assert_eq!(select(&policy), sorted_ids(&[&op_a, &op_c]));
+
+    // Keep the last operation of each day since day 3
+    policy.keep_daily_since = MillisSinceEpoch(3 * DAY);
+    assert_eq!(select(&policy), sorted_ids(&[&op_a, &op_c, &op_d]));
+
+    // Nothing is kept but the head, tagged, and explicitly kept operations
+    policy.keep_all_since = MillisSinceEpoch(i64::MAX);
+    policy.keep_daily_since = MillisSinceEpoch(i64::MAX);
+    policy.kept_ids = HashSet::from([op_d.id().clone()]);
+    assert_eq!(select(&policy), sorted_ids(&[&op_a, &op_c, &op_e]));
+
+    // Abandon the selected operations:
+    // F'
+    // D'
+    // B'
+    // 0 (initial)
+    let expired_ids =
+        op_walk::select_expired_operations(op_store.as_ref(), head_ops, &policy).unwrap();
+    let stats = op_walk::abandon_operations(op_store.as_ref(), &expired_ids, head_ops).unwrap();
+    assert_eq!(stats.rewritten_count, 3);
+    assert_eq!(stats.unreachable_count, 3);
+    let new_op_f = loader.load_operation(&stats.new_head_ids[0]).unwrap();
+    let [new_op_d] = new_op_f.parents().collect_array().unwrap();
+    let new_op_d = new_op_d.unwrap();
+    assert_eq!(new_op_d.metadata(), op_d.metadata());
+    let [new_op_b] = new_op_d.parents().collect_array().unwrap();
+    let new_op_b = new_op_b.unwrap();
+    assert_eq!(new_op_b.metadata(), op_b.metadata());
+    assert_eq!(new_op_b.parent_ids(), slice::from_ref(repo_0.op_id()));
+}
+
+#[test_case(TestRepoBackend::Simple; "simple backend")]
+#[test_case(TestRepoBackend::Git; "git backend")]
+fn test_export_import_bundle(backend: TestRepoBackend) {
+    let test_repo = TestRepo::init_with_backend(backend);
+    let repo_0 = test_repo.repo;
+
+    // Set up linear operation graph:
+    // B (rewrites commit A)
+    // A
+    // 0 (initial)
+    let mut tx = repo_0.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let repo_a = tx.commit("op A").unwrap();
+    let mut tx = repo_a.start_transaction();
+    let tree = create_tree(&repo_a, &[(repo_path("dir/file"), "contents")]);
+    let commit_b = tx
+        .repo_mut()
+        .rewrite_commit(&commit_a)
+        .set_tree_id(tree.id())
+        .write()
+        .unwrap();
+    tx.repo_mut().rebase_descendants().unwrap();
+    let repo_b = tx.commit("op B").unwrap();
+
+    let ops = [repo_a.operation().clone(), repo_b.operation().clone()];
+    let data = op_bundle::export_bundle(&repo_b, &ops).unwrap();
+
+    // Import the bundle to another repo
+    let other_test_repo = TestRepo::init_with_backend(backend);
+    let other_repo = &other_test_repo.repo;
+    let loader = other_repo.loader();
+    let stats = op_bundle::import_bundle(loader, &data).unwrap();
+    assert_eq!(stats.operation_count, 2);
+    assert_eq!(stats.commit_count, 2);
+    assert_eq!(stats.head_ids, vec![repo_b.op_id().clone()]);
+    // The operation heads aren't changed
+    assert_eq!(
+        loader.op_heads_store().get_op_heads().block_on().unwrap(),
+        vec![other_repo.op_id().clone()]
+    );
+
+    // The imported operation reproduces the exported repo state
+    let imported_op = loader.load_operation(repo_b.op_id()).unwrap();
+    assert_eq!(imported_op.metadata(), repo_b.operation().metadata());
+    let imported_repo = loader.load_at(&imported_op).unwrap();
+    assert_eq!(
+        imported_repo.view().store_view(),
+        repo_b.view().store_view()
+    );
+    let imported_commit = imported_repo.store().get_commit(commit_b.id()).unwrap();
+    assert_eq!(imported_commit.tree_id(), &tree.id());
+    assert_eq!(
+        get_predecessors(&imported_repo, commit_b.id()),
+        [commit_a.id().clone()]
+    );
+
+    // The predecessors are included even if the operation which created them
+    // isn't exported
+    let data = op_bundle::export_bundle(&repo_b, slice::from_ref(repo_b.operation())).unwrap();
+    let other_test_repo = TestRepo::init_with_backend(backend);
+    let other_repo = &other_test_repo.repo;
+    let loader = other_repo.loader();
+    let stats = op_bundle::import_bundle(loader, &data).unwrap();
+    assert_eq!(stats.operation_count, 1);
+    assert_eq!(stats.commit_count, 2);
+    let imported_op = loader.load_operation(&stats.head_ids[0]).unwrap();
+    let imported_repo = loader.load_at(&imported_op).unwrap();
+    assert_eq!(
+        get_predecessors(&imported_repo, commit_b.id()),
+        [commit_a.id().clone()]
+    );
+
+    // Operations referencing commits which don't exist can't be exported
+    let missing_id = CommitId::new(vec![0xab; commit_b.id().as_bytes().len()]);
+    let op_store = repo_b.op_store();
+    let mut view = repo_b.view().store_view().clone();
+    view.head_ids.insert(missing_id.clone());
+    let mut data = repo_b.operation().store_operation().clone();
+    data.view_id = op_store.write_view(&view).block_on().unwrap();
+    let op_id = op_store.write_operation(&data).block_on().unwrap();
+    let op = repo_b.loader().load_operation(&op_id).unwrap();
+    assert_matches!(
+        op_bundle::export_bundle(&repo_b, slice::from_ref(&op)),
+        Err(OpBundleError::MissingCommit(id)) if id == missing_id
+    );
+    assert_matches!(
+        op_bundle::import_bundle(other_repo.loader(), b"garbage"),
+        Err(OpBundleError::InvalidHeader)
+    );
+}
+
+#[test]
+fn test_copy_operations() {
+    let test_repo = TestRepo::init();
+    let repo_0 = test_repo.repo;
+    let root_data = RootOperationData {
+        root_commit_id: repo_0.store().root_commit_id().clone(),
+    };
+
+    // Set up operation graph:
+    //   C
+    // A B
+    // 0
+    let mut tx = repo_0.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let repo_a = tx.commit("op A").unwrap();
+    let mut tx = repo_0.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let repo_b = tx.commit("op B").unwrap();
+    let repo_c = repo_b.start_transaction().commit("op C").unwrap();
+
+    let temp_dir = testutils::new_temp_dir();
+    let dest_store = TableOpStore::init(temp_dir.path(), root_data).unwrap();
+    let head_ops = [repo_a.operation().clone(), repo_c.operation().clone()];
+    let stats = op_walk::copy_operations(&head_ops, &dest_store).unwrap();
+    // The ids are preserved
+    assert_eq!(
+        stats.new_head_ids,
+        vec![repo_a.op_id().clone(), repo_c.op_id().clone()]
+    );
+    // The root operation isn't counted
+    assert_eq!(stats.copied_count, 3);
+    for repo in [&repo_a, &repo_b, &repo_c] {
+        let op = repo.operation();
+        let data = dest_store.read_operation(op.id()).block_on().unwrap();
+        assert_eq!(&data, op.store_operation());
+        let view = dest_store.read_view(op.view_id()).block_on().unwrap();
+        assert_eq!(&view, repo.view().store_view());
+    }
+}
+
 #[test]
 fn test_walk_ancestors() {
     let test_repo = TestRepo::init();